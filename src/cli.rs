@@ -7,6 +7,26 @@ use clap::{Parser, Subcommand};
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(about = "Claude Code配置管理CLI工具", long_about = None)]
 pub struct Cli {
+    /// 纯文本输出: 不使用颜色与 emoji (管道输出或非 UTF-8 终端会自动启用)
+    #[arg(long, global = true, visible_alias = "plain")]
+    pub no_color: bool,
+
+    /// 安静模式: 只输出必要结果 (如切换后的配置名)，警告与错误写到标准错误
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// 输出调试日志到标准错误 (HTTP 请求、文件写入等，密钥会被隐藏)；也可通过 RUST_LOG 设置
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// 自动确认: 确认提示直接采用默认值，删除等危险操作自动确认 (便于脚本使用)
+    #[arg(short = 'y', long, global = true)]
+    pub yes: bool,
+
+    /// 数据目录 (默认 ~/.ca-switch)；优先于 CA_SWITCH_PROFILE 环境变量 (~/.ca-switch/profiles/<名称>)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub config_dir: Option<std::path::PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -14,34 +34,262 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Claude API 配置管理
-    Claude,
+    Claude {
+        /// 仅预览切换将写入的内容，不修改任何文件
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(subcommand)]
+        action: Option<ApiAction>,
+    },
 
     /// Codex API 配置管理
-    Codex,
+    Codex {
+        /// 仅预览切换将写入的内容，不修改任何文件
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(subcommand)]
+        action: Option<ApiAction>,
+    },
 
     /// Gemini CLI 配置管理
-    Gemini,
+    Gemini {
+        /// 仅预览切换将写入的内容，不修改任何文件
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(subcommand)]
+        action: Option<ApiAction>,
+    },
 
     /// OpenCode 配置管理
     #[command(name = "opencode")]
-    OpenCode,
+    OpenCode {
+        #[command(subcommand)]
+        action: Option<OpenCodeAction>,
+    },
 
     /// 备份与恢复
-    Backup,
+    Backup {
+        /// 强制备份所有选中的类别，即使内容与上次备份相同
+        #[arg(long)]
+        force: bool,
+
+        #[command(subcommand)]
+        action: Option<BackupAction>,
+    },
+
+    /// Prompt 管理 (不带子命令时进入交互式菜单)
+    Prompt {
+        #[command(subcommand)]
+        action: Option<PromptAction>,
+    },
 
     /// 查看当前状态
     Status,
 
+    /// 将 Claude/Codex/Gemini 都切换到同名的 Token/API Key (没有该密钥的工具跳过)
+    Switch {
+        /// 密钥名称 (如 work)
+        key: String,
+    },
+
+    /// 命名的激活配置组合 (profile)，可一次切换所有工具
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// ca-switch 自身的配置
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
     /// 导出配置
     Export {
         /// 要导出的配置类型
-        #[arg(value_name = "TYPE")]
-        config_type: ExportType,
+        #[arg(value_name = "TYPE", required_unless_present = "provider")]
+        config_type: Option<ExportType>,
+
+        /// 导出单个站点: 站点所属的供应商 (claude/codex/gemini/opencode)
+        #[arg(long, requires = "site", conflicts_with = "config_type")]
+        provider: Option<ExportType>,
+
+        /// 导出单个站点: 站点 / Provider 名称
+        #[arg(long, requires = "provider")]
+        site: Option<String>,
+
+        /// 导出时移除 Token/API Key
+        #[arg(long, requires = "provider")]
+        redact: bool,
+
+        /// 目标文件已存在时直接覆盖，不再确认
+        #[arg(long, visible_alias = "force-overwrite")]
+        force: bool,
+    },
+
+    /// 从导出文件导入站点配置
+    Import {
+        /// 导出的 JSON 文件路径
+        #[arg(value_name = "FILE")]
+        file: std::path::PathBuf,
+    },
+}
+
+/// Claude / Codex / Gemini 的非交互操作 (不带子命令时进入交互式菜单)
+#[derive(Subcommand)]
+pub enum ApiAction {
+    /// 删除站点 (配合 --yes 可跳过确认)
+    Delete {
+        /// 站点名称
+        site: String,
+    },
+
+    /// 重新写入当前激活的配置 (配合 --dry-run 只预览)
+    Apply {
+        /// 写入当前目录下的项目级配置 (如 ./.claude/settings.json)，而不是主目录
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// 列出所有站点 (密钥已脱敏)
+    List {
+        /// 以 JSON 输出站点与当前激活的引用
+        #[arg(long)]
+        json: bool,
+
+        /// 只列出带有该标签的站点
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// 跳过按名称排序后的前 N 个站点
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// 最多列出 N 个站点
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// 选择站点与密钥并切换 (配合 --dry-run 只预览)
+    Switch {
+        /// 只在带有该标签的站点中选择，只有一个站点匹配时直接使用
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// 单行输出当前激活的站点、密钥名称与模型 (没有激活的配置时返回非零退出码)
+    Current,
+
+    /// 输出当前激活配置的环境变量，如 eval "$(ca-switch claude env)"
+    Env {
+        /// 输出 fish 语法 (set -gx)
+        #[arg(long)]
+        fish: bool,
+    },
+
+    /// 从工具现有的配置文件导入站点 (目前支持 claude: ~/.claude/settings.json；codex: ~/.codex/config.toml 与 auth.json)
+    ImportExisting,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// 显示各配置文件的实际路径及是否存在
+    Path {
+        /// 以 JSON 输出 (名称 -> {path, exists})
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// 将当前所有工具的激活配置保存为 profile (同名覆盖)
+    Save {
+        /// Profile 名称
+        name: String,
+    },
+
+    /// 切换到 profile: 重新激活其中记录的所有工具配置并同步
+    Use {
+        /// Profile 名称
+        name: String,
+    },
+
+    /// 列出已保存的 profile
+    List {
+        /// 以 JSON 输出 (名称 -> 各工具的激活引用)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 删除 profile (不影响当前的激活配置)
+    Delete {
+        /// Profile 名称
+        name: String,
+    },
+}
+
+/// OpenCode 的非交互操作 (不带子命令时进入交互式菜单)
+#[derive(Subcommand)]
+pub enum OpenCodeAction {
+    /// 单行输出当前激活的 Provider (没有激活的配置时返回非零退出码)
+    Current,
+}
+
+/// 备份的非交互操作 (不带子命令时进入交互式菜单)
+#[derive(Subcommand)]
+pub enum BackupAction {
+    /// 测试已保存的 WebDAV 连接，失败时以非零状态退出 (适合 CI/cron 健康检查)
+    Test,
+
+    /// 清理云端旧备份，每个类别保留最新的若干个 (删除前列出文件与可释放的空间)
+    Cleanup {
+        /// 每个类别保留的备份数量
+        #[arg(long, default_value_t = 5)]
+        keep: usize,
+
+        /// 只列出将被删除的文件与可释放的空间，不删除
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// 非交互地备份指定类别 (配合 --yes 跳过确认，适合 cron 定时备份)
+    Run {
+        /// 要备份的类别，逗号分隔 (ccCli, claudeCode, codex, gemini, opencode)
+        #[arg(long, value_delimiter = ',', required = true)]
+        categories: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PromptAction {
+    /// 输出 prompt 原始内容到标准输出 (便于脚本使用)
+    Get {
+        /// Prompt 名称 (支持不区分大小写的唯一前缀)
+        name: String,
+    },
+
+    /// 将整个 prompt 库导出为单个 JSON 文件
+    Export {
+        #[arg(value_name = "FILE")]
+        file: std::path::PathBuf,
+    },
+
+    /// 从导出文件导入 prompt 库
+    Import {
+        #[arg(value_name = "FILE")]
+        file: std::path::PathBuf,
     },
 }
 
 #[derive(Clone, Debug)]
 pub enum ExportType {
+    Claude,
+    Codex,
+    Gemini,
     OpenCode,
 }
 
@@ -50,6 +298,9 @@ impl std::str::FromStr for ExportType {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
+            "claude" => Ok(ExportType::Claude),
+            "codex" => Ok(ExportType::Codex),
+            "gemini" => Ok(ExportType::Gemini),
             "opencode" => Ok(ExportType::OpenCode),
             _ => Err(format!("不支持的配置类型: {}", s)),
         }
@@ -59,6 +310,9 @@ impl std::str::FromStr for ExportType {
 impl std::fmt::Display for ExportType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ExportType::Claude => write!(f, "claude"),
+            ExportType::Codex => write!(f, "codex"),
+            ExportType::Gemini => write!(f, "gemini"),
             ExportType::OpenCode => write!(f, "opencode"),
         }
     }
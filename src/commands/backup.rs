@@ -1,9 +1,68 @@
-use crate::error::Result;
-use crate::config::file_manager::FileManager;
-use crate::ui::{show_error, show_info, show_success, show_warning};
-use crate::config::webdav::WebDAVClient;
+use crate::error::{CliError, Result};
+use crate::config::file_manager::{
+    current_hostname, current_os, BackupData, BackupFileContent, CategoryBackupState, ConflictDecision,
+    ConflictPolicy, FileManager, PendingUpload, RestoreOutcome, UPLOAD_CHUNK_SIZE,
+};
+use crate::ui::{
+    confirm, confirm_destructive, is_quiet_mode, plain_text, select_item, show_action_error, show_error, show_info, show_line_diff, show_result, show_success, show_warning, spinner,
+    wait_for_back,
+};
+use crate::config::webdav::{part_path, WebDAVClient};
+use crate::config::ConfigManager;
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect, Select};
+use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
+use std::collections::HashMap;
+
+/// 云端剩余空间低于该值时提示 (100 MB)
+const LOW_QUOTA_WARNING_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 校验命令行传入的类别名称并去重，`known` 为内置与自定义类别，存在未知类别时返回错误
+fn parse_categories(names: &[String], known: &[String]) -> Result<Vec<String>> {
+    let mut categories: Vec<String> = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !known.iter().any(|k| k == name) {
+            unknown.push(name.to_string());
+        } else if !categories.iter().any(|c| c == name) {
+            categories.push(name.to_string());
+        }
+    }
+
+    if !unknown.is_empty() {
+        return Err(CliError::NotFound(format!(
+            "未知的备份类别: {} (可选: {})",
+            unknown.join(", "),
+            known.join(", ")
+        )));
+    }
+    if categories.is_empty() {
+        return Err(CliError::Config("未指定要备份的类别".to_string()));
+    }
+    Ok(categories)
+}
+
+/// 切换成功后按 settings.auto_backup_on_switch 自动备份对应类别
+///
+/// 切换命令是同步的，这里在当前 tokio 运行时中阻塞执行备份；失败只提示警告，不影响切换结果
+pub fn auto_backup_after_switch(config_manager: &ConfigManager, category: &str) {
+    let enabled = config_manager
+        .read_global_config()
+        .is_ok_and(|config| config.settings.auto_backup_on_switch);
+    if !enabled {
+        return;
+    }
+
+    let result = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(async {
+            let mut cmd = BackupCommand::new()?;
+            cmd.auto_backup(category).await
+        })
+    });
+
+    if let Err(e) = result {
+        show_warning(&format!("自动备份失败: {e}"));
+    }
+}
 
 /// 备份类别
 #[derive(Debug, Clone)]
@@ -23,10 +82,19 @@ impl BackupCategory {
     }
 }
 
+/// 单个类别的备份结果
+enum BackupOutcome {
+    /// 已上传，附带远程文件名与被过滤规则跳过的文件数
+    Uploaded { file_name: String, skipped_files: usize },
+    /// 内容与上次备份相同，未上传
+    Unchanged,
+}
+
 /// 备份命令
 pub struct BackupCommand {
     file_manager: FileManager,
     webdav_client: WebDAVClient,
+    force: bool,
 }
 
 impl BackupCommand {
@@ -34,9 +102,128 @@ impl BackupCommand {
         Ok(Self {
             file_manager: FileManager::new()?,
             webdav_client: WebDAVClient::new()?,
+            force: false,
         })
     }
 
+    /// 设置是否强制备份 (不跳过内容未变化的类别)
+    pub fn set_force(&mut self, force: bool) {
+        self.force = force;
+    }
+
+    /// 测试 WebDAV 连接（命令行 `backup test`），连接失败时返回错误
+    pub async fn test_webdav(&mut self) -> Result<()> {
+        self.webdav_client.load_existing().await?;
+
+        let loading = spinner("正在测试连接...");
+        let health = self.webdav_client.health_check().await;
+        loading.finish();
+        let health = health?;
+
+        if let Some((url, username, _)) = self.webdav_client.get_server_info() {
+            println!("  {} {}", style("地址:").dim(), url);
+            println!("  {} {}", style("用户:").dim(), username);
+        }
+        println!("  {} {}", style("类型:").dim(), health.server_type);
+        if let Some(latency) = health.latency_ms {
+            println!("  {} {} ms", style("延迟:").dim(), latency);
+        }
+
+        if !health.connected {
+            println!("  {} {}", style("状态:").dim(), style("❌ 连接失败").red());
+            return Err(CliError::WebDav(health.error_message.unwrap_or_else(|| "连接失败".to_string())));
+        }
+
+        println!("  {} {}", style("状态:").dim(), style("✅ 已连接").green());
+        show_result(&health.latency_ms.map_or("ok".to_string(), |latency| format!("{latency}ms")));
+        Ok(())
+    }
+
+    /// 清理云端旧备份及遗留的分块目录（命令行 `backup cleanup [--keep N] [--dry-run]`），未配置 WebDAV 时返回错误
+    pub async fn cleanup(&mut self, keep_per_category: usize, dry_run: bool) -> Result<()> {
+        self.webdav_client.load_existing().await?;
+        self.webdav_client.test_connection().await?;
+        let pending: Vec<String> = self
+            .file_manager
+            .load_upload_manifest()
+            .await
+            .uploads
+            .into_values()
+            .map(|upload| upload.file_name)
+            .collect();
+        self.webdav_client
+            .cleanup_old_backups(keep_per_category, dry_run, &pending)
+            .await
+    }
+
+    /// 非交互地备份指定类别（命令行 `backup run --categories a,b`），跳过类别选择
+    ///
+    /// 确认提示可用 --yes 跳过；安静模式下每个类别输出一行 `类别<TAB>uploaded|unchanged|failed<TAB>文件名或错误`，
+    /// 任一类别失败时返回错误
+    pub async fn run(&mut self, names: &[String]) -> Result<()> {
+        self.show_category_warnings();
+        let categories = parse_categories(names, &self.file_manager.get_categories())?;
+
+        self.webdav_client.load_existing().await?;
+        self.webdav_client.test_connection().await?;
+
+        let loading = spinner("📦 正在收集备份文件...");
+        let mut collected = Vec::new();
+        let mut failures = Vec::new();
+        for category in &categories {
+            match self.file_manager.collect_backup_data(category).await {
+                Ok(data) => collected.push((category.clone(), data)),
+                Err(e) => failures.push((category.clone(), format!("收集失败: {e}"))),
+            }
+        }
+        loading.finish();
+
+        if !collected.is_empty() && !self.confirm_backup(&collected)? {
+            return Err(CliError::UserCancelled);
+        }
+
+        let mut uploaded = 0;
+        let mut unchanged = 0;
+        for (category, backup_data) in &collected {
+            match self.backup_category(category, backup_data).await {
+                Ok(BackupOutcome::Uploaded { file_name, .. }) => {
+                    show_result(&format!("{category}\tuploaded\t{file_name}"));
+                    uploaded += 1;
+                }
+                Ok(BackupOutcome::Unchanged) => {
+                    show_result(&format!("{category}\tunchanged\t"));
+                    unchanged += 1;
+                }
+                Err(e) => failures.push((category.clone(), e.to_string())),
+            }
+        }
+
+        for (category, error) in &failures {
+            show_error(&format!("备份 {category} 失败: {error}"));
+            show_result(&format!("{category}\tfailed\t{}", error.replace(['\t', '\n'], " ")));
+        }
+
+        show_info(&format!(
+            "备份完成: 上传 {uploaded} 个，未变化 {unchanged} 个，失败 {} 个",
+            failures.len()
+        ));
+
+        if !failures.is_empty() {
+            return Err(CliError::WebDav(format!("{} 个类别备份失败", failures.len())));
+        }
+        Ok(())
+    }
+
+    /// 非交互地备份单个类别（用于切换后的自动备份），未配置 WebDAV 时返回错误
+    async fn auto_backup(&mut self, category: &str) -> Result<()> {
+        self.webdav_client.load_existing().await?;
+        self.webdav_client.test_connection().await?;
+
+        let backup_data = self.file_manager.collect_backup_data(category).await?;
+        self.backup_category(category, &backup_data).await?;
+        Ok(())
+    }
+
     /// 执行备份命令
     pub async fn execute(&mut self) -> Result<()> {
         loop {
@@ -45,26 +232,22 @@ impl BackupCommand {
             match choice.as_str() {
                 "backup" => {
                     if let Err(e) = self.handle_backup().await {
-                        show_error(&format!("备份失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("备份失败", &e);
                     }
                 }
                 "restore" => {
                     if let Err(e) = self.handle_restore().await {
-                        show_error(&format!("恢复失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("恢复失败", &e);
                     }
                 }
                 "status" => {
                     if let Err(e) = self.handle_status().await {
-                        show_error(&format!("获取状态失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("获取状态失败", &e);
                     }
                 }
                 "config" => {
                     if let Err(e) = self.handle_config().await {
-                        show_error(&format!("配置失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("配置失败", &e);
                     }
                 }
                 "back" => break,
@@ -112,6 +295,8 @@ impl BackupCommand {
         println!("\n{}", style("📤 配置备份向导").cyan().bold());
         println!();
 
+        self.show_category_warnings();
+
         // 选择备份类别
         let categories = self.select_backup_categories()?;
 
@@ -120,8 +305,28 @@ impl BackupCommand {
             return Ok(());
         }
 
+        // 先收集所有类别的数据，以便在确认前显示总大小
+        let loading = spinner("📦 正在收集备份文件...");
+        let mut collected = Vec::new();
+        let mut collect_errors = Vec::new();
+        for category in &categories {
+            match self.file_manager.collect_backup_data(category).await {
+                Ok(data) => collected.push((category.clone(), data)),
+                Err(e) => collect_errors.push(format!("收集 {category} 失败: {e}")),
+            }
+        }
+        loading.finish();
+        for error in &collect_errors {
+            show_error(error);
+        }
+
+        if collected.is_empty() {
+            show_error("没有可备份的数据");
+            return Ok(());
+        }
+
         // 确认备份
-        let confirmed = self.confirm_backup(&categories)?;
+        let confirmed = self.confirm_backup(&collected)?;
 
         if !confirmed {
             show_info("用户取消备份");
@@ -137,12 +342,19 @@ impl BackupCommand {
         println!();
 
         let mut success_count = 0;
-        let mut fail_count = 0;
+        let mut unchanged_count = 0;
+        let mut skipped_file_count = 0;
+        let mut fail_count = collect_errors.len();
 
         // 执行备份
-        for category in &categories {
-            match self.backup_category(category).await {
-                Ok(_) => success_count += 1,
+        for (category, backup_data) in &collected {
+            match self.backup_category(category, backup_data).await {
+                Ok(BackupOutcome::Uploaded { file_name, skipped_files }) => {
+                    show_result(&file_name);
+                    success_count += 1;
+                    skipped_file_count += skipped_files;
+                }
+                Ok(BackupOutcome::Unchanged) => unchanged_count += 1,
                 Err(e) => {
                     show_error(&format!("备份 {category} 失败: {e}"));
                     fail_count += 1;
@@ -155,44 +367,59 @@ impl BackupCommand {
         println!("{}", style("📊 备份完成统计").white().bold());
         println!();
         println!("  {} {} 个配置类别", style("✅ 成功:").green(), success_count);
+        if unchanged_count > 0 {
+            println!("  {} {} 个配置类别", style("⏭️  未变化:").dim(), unchanged_count);
+        }
+        if skipped_file_count > 0 {
+            println!("  {} {} 个文件", style("🚫 已排除:").yellow(), skipped_file_count);
+        }
         if fail_count > 0 {
             println!("  {} {} 个配置类别", style("❌ 失败:").red(), fail_count);
         }
         println!();
 
-        self.wait_for_back()?;
+        wait_for_back();
 
         Ok(())
     }
 
     /// 备份单个类别
-    async fn backup_category(&mut self, category: &str) -> Result<()> {
-        let category_name = match category {
-            "ccCli" => "CC-CLI配置",
-            "claudeCode" => "Claude Code配置",
-            "codex" => "Codex配置",
-            "gemini" => "Gemini配置",
-            "opencode" => "OpenCode配置",
-            _ => category,
-        };
-
-        show_info(&format!("📦 正在收集 {category_name} 的文件..."));
-
-        // 收集备份数据
-        let backup_data = self.file_manager.collect_backup_data(category).await?;
-
-        // 生成文件名
-        let file_name = format!(
-            "{}-{}.json",
-            category,
-            chrono::Local::now().format("%Y-%m-%d-%H-%M-%S")
-        );
-
-        // 序列化为 JSON
-        let json_data = serde_json::to_value(&backup_data)?;
+    ///
+    /// 内容与上次成功备份相同时跳过上传 (除非使用 --force)
+    async fn backup_category(&mut self, category: &str, backup_data: &BackupData) -> Result<BackupOutcome> {
+        let category_name = self.file_manager.category_name(category);
+
+        let loading = spinner(&format!("📦 正在准备 {category_name}..."));
+
+        // 与上次成功备份比较
+        let hash = FileManager::content_hash(backup_data);
+        let mut state = self.file_manager.load_backup_state().await;
+        let unchanged = state
+            .categories
+            .get(category)
+            .is_some_and(|last| last.hash == hash);
+
+        if unchanged && !self.force {
+            loading.finish();
+            show_info(&format!("⏭️  {category_name} 未变化，已跳过"));
+            return Ok(BackupOutcome::Unchanged);
+        }
 
-        // 上传到 WebDAV
-        self.webdav_client.upload_backup(&file_name, &json_data).await?;
+        // 上传到 WebDAV，超过分块大小时改为分块上传
+        loading.set_message(&format!("☁️  正在上传 {category_name}..."));
+        let parts = FileManager::split_into_parts(backup_data, UPLOAD_CHUNK_SIZE);
+        let file_name = if parts.len() > 1 {
+            self.upload_in_parts(category, &hash, backup_data, parts).await?
+        } else {
+            let file_name = self
+                .webdav_client
+                .naming()
+                .file_name(category, &chrono::Local::now());
+            let json_data = serde_json::to_value(backup_data)?;
+            self.webdav_client.upload_backup(&file_name, &json_data).await?;
+            file_name
+        };
+        loading.finish();
 
         show_success(&format!(
             "✅ {} 备份成功 ({} 个文件, {})",
@@ -201,12 +428,120 @@ impl BackupCommand {
             self.file_manager.format_file_size(backup_data.metadata.total_size)
         ));
 
-        Ok(())
+        let skipped = &backup_data.metadata.skipped_files;
+        if !skipped.is_empty() {
+            show_warning(&format!(
+                "{} 个文件因排除规则或大小限制未包含 (规则见 ~/.ca-switch/backup-filter.json):",
+                skipped.len()
+            ));
+            for file in skipped.iter().take(10) {
+                println!("  {}", style(file).dim());
+            }
+            if skipped.len() > 10 {
+                println!("  {}", style(format!("... 另有 {} 个", skipped.len() - 10)).dim());
+            }
+        }
+
+        // 记录本次备份状态
+        state.categories.insert(
+            category.to_string(),
+            CategoryBackupState {
+                hash,
+                file_name: file_name.clone(),
+                backed_up_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        if let Err(e) = self.file_manager.save_backup_state(&state).await {
+            show_warning(&format!("保存备份状态失败: {e}"));
+        }
+
+        Ok(BackupOutcome::Uploaded {
+            file_name,
+            skipped_files: backup_data.metadata.skipped_files.len(),
+        })
+    }
+
+    /// 分块上传备份
+    ///
+    /// 每个分块上传成功后写入 ~/.ca-switch/upload-manifest.json，中断后重新备份时若内容未变，
+    /// 沿用原文件名并跳过已上传的分块。全部分块完成后才上传索引文件，因此备份列表中只会出现完整的备份
+    async fn upload_in_parts(
+        &mut self,
+        category: &str,
+        hash: &str,
+        backup_data: &BackupData,
+        parts: Vec<HashMap<String, BackupFileContent>>,
+    ) -> Result<String> {
+        let total = parts.len();
+        let mut manifest = self.file_manager.load_upload_manifest().await;
+        let mut pending = match manifest.resumable(category, hash, total) {
+            Some(pending) => {
+                show_info(&format!(
+                    "🔁 检测到未完成的上传 ({}/{} 个分块已完成)，继续上传剩余分块",
+                    pending.uploaded.len(),
+                    total
+                ));
+                pending
+            }
+            None => PendingUpload {
+                hash: hash.to_string(),
+                file_name: self
+                    .webdav_client
+                    .naming()
+                    .file_name(category, &chrono::Local::now()),
+                total_parts: total,
+                uploaded: Vec::new(),
+            },
+        };
+
+        self.webdav_client.ensure_parts_directory(&pending.file_name).await?;
+        for (index, files) in parts.into_iter().enumerate() {
+            if pending.uploaded.contains(&index) {
+                continue;
+            }
+
+            let path = part_path(&pending.file_name, index);
+            show_info(&format!("📤 上传分块 [{}/{}]: {}", index + 1, total, path));
+            self.webdav_client
+                .upload_part(&path, &serde_json::json!({ "files": files }))
+                .await?;
+
+            pending.uploaded.push(index);
+            manifest.uploads.insert(category.to_string(), pending.clone());
+            if let Err(e) = self.file_manager.save_upload_manifest(&manifest).await {
+                show_warning(&format!("保存上传进度失败，中断后将无法续传: {e}"));
+            }
+        }
+
+        let index_data = BackupData {
+            category: backup_data.category.clone(),
+            timestamp: backup_data.timestamp.clone(),
+            files: HashMap::new(),
+            metadata: backup_data.metadata.clone(),
+            parts: (0..total).map(|index| part_path(&pending.file_name, index)).collect(),
+        };
+        self.webdav_client
+            .upload_backup(&pending.file_name, &serde_json::to_value(&index_data)?)
+            .await?;
+
+        manifest.uploads.remove(category);
+        if let Err(e) = self.file_manager.save_upload_manifest(&manifest).await {
+            show_warning(&format!("清理上传进度记录失败: {e}"));
+        }
+
+        Ok(pending.file_name)
+    }
+
+    /// 提示自定义备份类别中被忽略的条目
+    fn show_category_warnings(&self) {
+        for warning in self.file_manager.category_warnings() {
+            show_warning(warning);
+        }
     }
 
     /// 选择备份类别
     fn select_backup_categories(&self) -> Result<Vec<String>> {
-        let categories = vec![
+        let mut categories = vec![
             BackupCategory::new(
                 "🔧 CA-Switch配置 (.ca-switch/)",
                 "ccCli",
@@ -234,6 +569,22 @@ impl BackupCommand {
             ),
         ];
 
+        // 自定义类别 (~/.ca-switch/backup-categories.json)
+        for (key, paths) in self.file_manager.custom_categories() {
+            let mut entries: Vec<String> = paths
+                .files
+                .keys()
+                .cloned()
+                .chain(paths.directories.keys().map(|dir| format!("{dir}/")))
+                .collect();
+            entries.sort();
+            categories.push(BackupCategory::new(
+                format!("📁 {} ({})", paths.name, entries.join(", ")),
+                key.clone(),
+                false,
+            ));
+        }
+
         let items: Vec<String> = categories.iter().map(|c| c.name.clone()).collect();
         let defaults: Vec<bool> = categories.iter().map(|c| c.checked).collect();
 
@@ -253,29 +604,60 @@ impl BackupCommand {
     }
 
     /// 确认备份
-    fn confirm_backup(&self, categories: &[String]) -> Result<bool> {
-        println!("\n{}", style("📋 备份信息确认").white());
+    ///
+    /// 总大小超过 `warn_total_size` 时给出警告，需要明确确认才会上传
+    fn confirm_backup(&self, collected: &[(String, BackupData)]) -> Result<bool> {
+        let total_size: u64 = collected.iter().map(|(_, data)| data.metadata.total_size).sum();
+        if !is_quiet_mode() {
+            self.show_backup_summary(collected, total_size);
+        }
+
+        let filter = self.file_manager.filter();
+        if filter.exceeds_total_warning(total_size) {
+            show_warning(&format!(
+                "备份总大小 {} 超过提示阈值 {} (可在 ~/.ca-switch/backup-filter.json 的 warn_total_size 中调整)",
+                self.file_manager.format_file_size(total_size),
+                self.file_manager.format_file_size(filter.warn_total_size)
+            ));
+            return confirm_destructive("备份内容较大，确认继续上传");
+        }
+
+        confirm("确认执行备份", true)
+    }
+
+    /// 打印待备份类别及大小汇总
+    fn show_backup_summary(&self, collected: &[(String, BackupData)], total_size: u64) {
+        println!("\n{}", style(plain_text("📋 备份信息确认")).white());
         println!("{}", style("─".repeat(40)).dim());
 
-        for category in categories {
+        for (category, data) in collected {
             let display = match category.as_str() {
-                "ccCli" => "🔧 CC-CLI配置",
-                "claudeCode" => "🎯 Claude Code配置",
-                "codex" => "⚙️  Codex配置",
-                "gemini" => "🌟 Gemini配置",
-                "opencode" => "🚀 OpenCode配置",
-                _ => category,
+                "ccCli" => "🔧 CC-CLI配置".to_string(),
+                "claudeCode" => "🎯 Claude Code配置".to_string(),
+                "codex" => "⚙️  Codex配置".to_string(),
+                "gemini" => "🌟 Gemini配置".to_string(),
+                "opencode" => "🚀 OpenCode配置".to_string(),
+                _ => format!("📁 {}", self.file_manager.category_name(category)),
             };
-            println!("  ✓ {display}");
+            println!(
+                "  {} {}",
+                plain_text(&format!("✓ {display}")),
+                style(format!(
+                    "({} 个文件, {})",
+                    data.metadata.total_files,
+                    self.file_manager.format_file_size(data.metadata.total_size)
+                ))
+                .dim()
+            );
         }
 
         println!();
-
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("确认执行备份")
-            .default(true)
-            .interact()
-            .map_err(|_| crate::error::CliError::UserCancelled)
+        println!(
+            "  {} {}",
+            style("合计:").white(),
+            style(self.file_manager.format_file_size(total_size)).cyan()
+        );
+        println!();
     }
 
     /// 处理恢复数据
@@ -288,26 +670,219 @@ impl BackupCommand {
         self.webdav_client.initialize().await?;
 
         // 获取备份列表
+        let loading = spinner("正在获取备份列表...");
         let backups = self.webdav_client.list_backups().await?;
+        loading.finish();
 
         if backups.is_empty() {
             show_warning("云端没有找到任何备份文件");
-            self.wait_for_back()?;
+            wait_for_back();
             return Ok(());
         }
 
         println!();
         show_info(&format!("找到 {} 个备份文件", backups.len()));
 
-        // TODO: 实现备份文件选择和恢复逻辑
-        show_info("完整的恢复功能正在开发中...");
-        show_info("当前已支持：列出远程备份文件");
+        // 选择要恢复的备份
+        let backups = WebDAVClient::sort_by_time_desc(backups);
+        let mut items: Vec<String> = backups
+            .iter()
+            .map(|b| {
+                format!(
+                    "{} ({}, {})",
+                    b.name,
+                    WebDAVClient::format_size(b.size),
+                    b.last_modified
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                )
+            })
+            .collect();
+        items.push("⬅️  返回上一级菜单".to_string());
+
+        let selection = select_item("选择要恢复的备份", &items, 0)
+            .map_err(|_| crate::error::CliError::UserCancelled)?;
+        let Some(backup) = backups.get(selection) else {
+            return Ok(());
+        };
+
+        let loading = spinner("正在下载备份文件...");
+        let data = self
+            .webdav_client
+            .download_backup(&backup.path)
+            .await?;
+        loading.finish();
+
+        let backup_data: BackupData = serde_json::from_value(data)
+            .map_err(|e| crate::error::CliError::Parse(format!("备份文件格式无效: {e}")))?;
+
+        let metadata = &backup_data.metadata;
+        let created_at = chrono::DateTime::parse_from_rfc3339(&metadata.created_at)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| metadata.created_at.clone());
+
+        println!();
+        println!("  {} {}", style("类别:").dim(), style(&backup_data.category).white());
+        println!("  {} {}", style("时间:").dim(), style(created_at).white());
+        println!("  {} {}", style("主机:").dim(), style(&metadata.hostname).white());
+        if !metadata.os.is_empty() {
+            println!("  {} {}", style("系统:").dim(), style(&metadata.os).white());
+        }
+        println!("  {} {}", style("文件:").dim(), backup_data.files.len());
+        println!();
+
+        // 来自其他机器的备份可能包含不适用于本机的路径
+        let hostname = current_hostname();
+        if metadata.hostname != hostname {
+            show_warning(&format!(
+                "该备份来自主机 '{}'，与当前主机 '{}' 不同，其中的路径配置可能不适用于本机",
+                metadata.hostname, hostname
+            ));
+            if !metadata.os.is_empty() && metadata.os != current_os() {
+                show_warning(&format!("备份系统为 {}，当前系统为 {}", metadata.os, current_os()));
+            }
+            if !confirm_destructive("仍要恢复该备份吗")? {
+                show_info("用户取消恢复");
+                return Ok(());
+            }
+        }
+
+        // 选择恢复范围：全部文件 (None) 或其中的部分文件
+        let mut file_names: Vec<&str> = backup_data.files.keys().map(String::as_str).collect();
+        file_names.sort();
+        let scopes = ["📦 恢复全部文件", "📄 选择要恢复的文件", "⬅️  取消"];
+        let selected_files: Option<Vec<&str>> = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("恢复范围")
+            .items(&scopes)
+            .default(0)
+            .interact()
+            .map_err(|_| crate::error::CliError::UserCancelled)?
+        {
+            0 => None,
+            1 => {
+                let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+                    .with_prompt("选择要恢复的文件 (空格选择，回车确认)")
+                    .items(&file_names)
+                    .interact()
+                    .map_err(|_| crate::error::CliError::UserCancelled)?;
+                if chosen.is_empty() {
+                    show_info("未选择任何文件，恢复已取消");
+                    return Ok(());
+                }
+                Some(chosen.into_iter().map(|i| file_names[i]).collect())
+            }
+            _ => return Ok(()),
+        };
+
+        // 选择冲突处理方式
+        let policies = [
+            "🔍 逐个确认 - 本地文件不同时询问",
+            "📝 全部覆盖 - 覆盖前保存 .bak 备份",
+            "⏭️  跳过冲突 - 只恢复本地不存在的文件",
+            "⬅️  取消",
+        ];
+        let policy = match Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("本地文件已存在时如何处理")
+            .items(&policies)
+            .default(0)
+            .interact()
+            .map_err(|_| crate::error::CliError::UserCancelled)?
+        {
+            0 => ConflictPolicy::Ask,
+            1 => ConflictPolicy::Overwrite,
+            2 => ConflictPolicy::Skip,
+            _ => return Ok(()),
+        };
+
+        let ask = |path: &std::path::Path, old: &[u8], new: &[u8]| Self::ask_conflict(path, old, new);
+        let outcomes = match selected_files {
+            Some(files) => {
+                self.file_manager
+                    .restore_selected(&backup_data.category, &backup_data, &files, policy, ask)
+                    .await?
+            }
+            None => {
+                self.file_manager
+                    .restore_backup_data(&backup_data.category, &backup_data, policy, ask)
+                    .await?
+            }
+        };
 
-        self.wait_for_back()?;
+        self.show_restore_report(&outcomes);
+        wait_for_back();
 
         Ok(())
     }
 
+    /// 询问单个冲突文件的处理方式
+    fn ask_conflict(path: &std::path::Path, old: &[u8], new: &[u8]) -> Result<ConflictDecision> {
+        println!();
+        show_warning(&format!("本地文件与备份不同: {}", path.display()));
+
+        let items = ["跳过", "覆盖", "覆盖全部", "查看差异"];
+        loop {
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("请选择")
+                .items(&items)
+                .default(0)
+                .interact()
+                .map_err(|_| crate::error::CliError::UserCancelled)?;
+
+            match selection {
+                0 => return Ok(ConflictDecision::Skip),
+                1 => return Ok(ConflictDecision::Overwrite),
+                2 => return Ok(ConflictDecision::OverwriteAll),
+                _ => match (std::str::from_utf8(old), std::str::from_utf8(new)) {
+                    (Ok(old), Ok(new)) => {
+                        println!();
+                        show_line_diff(old, new);
+                        println!();
+                    }
+                    _ => show_info("二进制文件，无法显示差异"),
+                },
+            }
+        }
+    }
+
+    /// 显示恢复结果
+    fn show_restore_report(&self, outcomes: &[(std::path::PathBuf, RestoreOutcome)]) {
+        println!();
+        println!("{}", style("═".repeat(40)).dim());
+        println!("{}", style("📊 恢复结果").white().bold());
+        println!();
+
+        let mut restored = 0;
+        let mut skipped = 0;
+        for (path, outcome) in outcomes {
+            match outcome {
+                RestoreOutcome::Created => {
+                    restored += 1;
+                    println!("  {} {}", style("➕ 新建").green(), path.display());
+                }
+                RestoreOutcome::Overwritten { backup } => {
+                    restored += 1;
+                    println!(
+                        "  {} {} {}",
+                        style("📝 覆盖").yellow(),
+                        path.display(),
+                        style(format!("(原文件: {})", backup.display())).dim()
+                    );
+                }
+                RestoreOutcome::Unchanged => {
+                    println!("  {} {}", style("✔️  相同").dim(), style(path.display()).dim());
+                }
+                RestoreOutcome::Skipped => {
+                    skipped += 1;
+                    println!("  {} {}", style("⏭️  跳过").dim(), path.display());
+                }
+            }
+        }
+
+        println!();
+        show_success(&format!("恢复完成: {} 个文件已写入，{} 个文件已跳过", restored, skipped));
+        show_result(&format!("restored={} skipped={}", restored, skipped));
+    }
+
     /// 处理备份状态
     async fn handle_status(&mut self) -> Result<()> {
         println!("\n{}", style("📊 备份状态报告").cyan().bold());
@@ -317,10 +892,10 @@ impl BackupCommand {
         println!("{}", style("🔍 本地配置文件状态：").white().bold());
         println!();
 
-        let categories = vec!["ccCli", "claudeCode", "codex", "gemini", "opencode"];
+        self.show_category_warnings();
 
-        for category in categories {
-            match self.file_manager.check_category_files(category).await {
+        for category in self.file_manager.get_categories() {
+            match self.file_manager.check_category_files(&category).await {
                 Ok(result) => {
                     let status_icon = if result.total_exists == result.total_count {
                         "✅"
@@ -377,9 +952,14 @@ impl BackupCommand {
             println!("  {} {}", style("地址:").dim(), style(url).white());
             println!("  {} {}", style("用户:").dim(), style(username).white());
 
-            match self.webdav_client.test_connection().await {
+            let loading = spinner("正在测试连接...");
+            let connection = self.webdav_client.test_connection().await;
+            loading.finish();
+
+            match connection {
                 Ok(_) => {
                     println!("  {} {}", style("状态:").dim(), style("✅ 已连接").green());
+                    self.show_storage_info().await;
                 }
                 Err(_) => {
                     println!("  {} {}", style("状态:").dim(), style("❌ 连接失败").red());
@@ -391,11 +971,43 @@ impl BackupCommand {
         }
 
         println!();
-        self.wait_for_back()?;
+        wait_for_back();
 
         Ok(())
     }
 
+    /// 显示云端备份占用与账户配额
+    async fn show_storage_info(&self) {
+        let loading = spinner("正在获取存储信息...");
+        let info = self.webdav_client.get_storage_info().await;
+        loading.finish();
+
+        let info = match info {
+            Ok(info) => info,
+            Err(e) => {
+                println!("  {} {}", style("存储:").dim(), style(format!("获取失败: {e}")).red());
+                return;
+            }
+        };
+
+        println!(
+            "  {} {} 个文件，共 {}",
+            style("备份:").dim(),
+            info.total_files,
+            WebDAVClient::format_size(info.total_size_bytes)
+        );
+
+        let format_quota = |bytes: Option<u64>| bytes.map_or("未知".to_string(), WebDAVClient::format_size);
+        println!("  {} {}", style("已用空间:").dim(), format_quota(info.quota_used_bytes));
+        match info.quota_available_bytes {
+            Some(available) if available < LOW_QUOTA_WARNING_BYTES => {
+                println!("  {} {}", style("剩余空间:").dim(), style(format_quota(Some(available))).red());
+                show_warning("云端剩余空间不足，后续备份可能失败");
+            }
+            available => println!("  {} {}", style("剩余空间:").dim(), format_quota(available)),
+        }
+    }
+
     /// 处理 WebDAV 配置
     async fn handle_config(&mut self) -> Result<()> {
         println!("\n{}", style("⚙️  WebDAV 配置管理").cyan().bold());
@@ -422,45 +1034,42 @@ impl BackupCommand {
             }
             1 => {
                 // 测试连接
-                show_info("🧪 测试 WebDAV 连接...");
-                match self.webdav_client.test_connection().await {
+                let loading = spinner("🧪 测试 WebDAV 连接...");
+                let connection = self.webdav_client.test_connection().await;
+                loading.finish();
+
+                match connection {
                     Ok(_) => show_success("✅ WebDAV 连接正常"),
                     Err(e) => show_error(&format!("❌ WebDAV 连接失败: {e}")),
                 }
             }
-            2 => {
+            2 if confirm_destructive("确认清除 WebDAV 配置？")? =>
+            {
                 // 清除配置
-                if Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt("确认清除 WebDAV 配置？")
-                    .default(false)
-                    .interact()?
-                {
-                    self.webdav_client.clear_config().await?;
-                }
+                self.webdav_client.clear_config().await?;
             }
             _ => {}
         }
 
-        self.wait_for_back()?;
-
-        Ok(())
-    }
+        wait_for_back();
 
-    /// 等待用户返回
-    fn wait_for_back(&self) -> Result<()> {
-        let items = vec!["⬅️  返回上一级菜单"];
-        Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("操作完成")
-            .items(&items)
-            .default(0)
-            .interact()
-            .map_err(|_| crate::error::CliError::UserCancelled)?;
         Ok(())
     }
 }
 
-impl Default for BackupCommand {
-    fn default() -> Self {
-        Self::new().expect("Failed to create BackupCommand")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_categories() {
+        let known: Vec<String> = ["ccCli", "claudeCode", "codex", "zed"].iter().map(|k| k.to_string()).collect();
+        let names = vec!["codex".to_string(), " zed".to_string(), "codex".to_string()];
+        assert_eq!(parse_categories(&names, &known).unwrap(), vec!["codex", "zed"]);
+
+        let err = parse_categories(&["codex".to_string(), "vscode".to_string()], &known).unwrap_err();
+        assert!(matches!(err, CliError::NotFound(ref msg) if msg.contains("vscode")));
+
+        assert!(parse_categories(&[String::new()], &known).is_err());
     }
 }
@@ -1,58 +1,127 @@
 // Claude 命令模块 - 基于新架构重构
 // 支持新的配置文件结构：claude.json + config.json
 
-use crate::config::{ClaudeSite, ConfigManager};
-use crate::ui::{confirm, show_error, show_info, show_success, show_warning, ApiMenuChoice};
+use crate::commands::backup::auto_backup_after_switch;
+use crate::commands::key_import::{parse_key_batch, read_key_batch, report_key_batch};
+use crate::commands::verify::{select_auth_header_style, select_base_url, verify_before_switch};
+use crate::error::{CliError, Result};
+use crate::config::claude_manager::{vertex_warnings, IMPORTED_TOKEN_NAME};
+use crate::config::{page_sites, project_dir, BedrockConfig, ClaudeSite, ClaudeSiteConfig, ClaudeActiveReference, ConfigManager, SiteSummary, SiteMetadata, VertexConfig};
+use crate::ui::{
+    confirm, confirm_destructive, input_tags, select_key, select_named, select_site, select_site_with_tag, show_action_error, show_error, show_file_diffs, show_info, show_success, show_warning, show_written_paths, show_written_paths_with_copy, warn_duplicate_base_url,
+    copy_to_clipboard, current_line, env_export_lines, redact, show_overwrite_diffs, show_result, sorted_keys,
+    is_assume_yes, wait_for_back, ApiMenuChoice, SELECT_PAGE_SIZE,
+};
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Input, Password, Select};
+use dialoguer::{theme::ColorfulTheme, Input, MultiSelect, Password, Select};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// `claude list --json` 的输出 (Token 已脱敏)
+#[derive(Serialize)]
+struct ClaudeListView<'a> {
+    active: Option<ClaudeActiveReference>,
+    sites: Vec<ClaudeSiteView<'a>>,
+}
+
+/// 单个站点的 JSON 视图
+#[derive(Serialize)]
+struct ClaudeSiteView<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    metadata: &'a SiteMetadata,
+    config: &'a ClaudeSiteConfig,
+    /// Token 名称 -> 脱敏后的值
+    tokens: BTreeMap<&'a str, String>,
+}
+
+impl<'a> ClaudeSiteView<'a> {
+    fn new(name: &'a str, site: &'a ClaudeSite) -> Self {
+        Self {
+            name,
+            metadata: &site.metadata,
+            config: &site.config,
+            tokens: site.tokens.iter().map(|(token_name, token)| (token_name.as_str(), redact(token))).collect(),
+        }
+    }
+}
 
 /// Claude API 管理命令
 pub struct ClaudeCommand {
     config_manager: ConfigManager,
+    dry_run: bool,
+    /// 切换前是否先验证 Token
+    verify_before_switch: bool,
+    /// 切换时只更新激活记录，不写入工具配置文件
+    record_only: bool,
 }
 
 impl ClaudeCommand {
     /// 创建新的 Claude 命令实例
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> Result<Self> {
         Ok(Self {
             config_manager: ConfigManager::new()?,
+            dry_run: false,
+            verify_before_switch: false,
+            record_only: false,
         })
     }
 
+    /// 设置 dry-run 模式（切换时只预览，不写入文件）
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     /// 执行 Claude API 管理命令
-    pub fn execute(&mut self) -> Result<(), String> {
+    pub fn execute(&mut self) -> Result<()> {
         loop {
-            let choice = crate::ui::show_api_menu("📡 Claude配置管理").map_err(|e| e.to_string())?;
+            let choice = crate::ui::show_api_menu("📡 Claude配置管理")?;
 
             match choice {
                 ApiMenuChoice::Switch => {
                     if let Err(e) = self.handle_switch() {
-                        show_error(&format!("切换配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("切换配置失败", &e);
                     }
                 }
                 ApiMenuChoice::List => {
                     if let Err(e) = self.handle_list() {
-                        show_error(&format!("查看配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("查看配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Add => {
                     if let Err(e) = self.handle_add() {
-                        show_error(&format!("添加配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("添加配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Edit => {
                     if let Err(e) = self.handle_edit() {
-                        show_error(&format!("编辑配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("编辑配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Delete => {
                     if let Err(e) = self.handle_delete() {
-                        show_error(&format!("删除配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("删除配置失败", &e);
+                    }
+                }
+                ApiMenuChoice::CopyKey => {
+                    if let Err(e) = self.handle_copy_key() {
+                        show_action_error("复制密钥失败", &e);
+                    }
+                }
+                ApiMenuChoice::ToggleVerify => {
+                    self.verify_before_switch = !self.verify_before_switch;
+                    if self.verify_before_switch {
+                        show_info("已开启切换前验证");
+                    } else {
+                        show_info("已关闭切换前验证");
+                    }
+                }
+                ApiMenuChoice::ToggleRecordOnly => {
+                    self.record_only = !self.record_only;
+                    if self.record_only {
+                        show_info("已开启仅记录模式: 切换时不写入 settings.json");
+                    } else {
+                        show_info("已关闭仅记录模式");
                     }
                 }
                 ApiMenuChoice::Back => break,
@@ -66,7 +135,14 @@ impl ClaudeCommand {
     // 切换配置
     // ========================================================================
 
-    fn handle_switch(&mut self) -> Result<(), String> {
+    fn handle_switch(&mut self) -> Result<()> {
+        self.switch_site(None)?;
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 选择站点与密钥并切换（命令行 `claude switch [--tag <标签>]`），指定标签时只在带该标签的站点中选择
+    pub fn switch_site(&mut self, tag: Option<&str>) -> Result<()> {
         println!("\n{}", style("🔄 切换 Claude API 配置").cyan().bold());
         println!();
 
@@ -79,23 +155,7 @@ impl ClaudeCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names
-            .iter()
-            .map(|name| {
-                let site = sites.get(name).unwrap();
-                format!("🌐 {} ({})", name, site.metadata.url)
-            })
-            .collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site_with_tag("选择站点", &sites, tag)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         // 检查是否有 tokens
@@ -105,30 +165,15 @@ impl ClaudeCommand {
         }
 
         // 选择 Token
-        let token_names: Vec<String> = selected_site.tokens.keys().cloned().collect();
-        let token_items: Vec<String> = token_names
-            .iter()
-            .map(|name| {
-                let token = selected_site.tokens.get(name).unwrap();
-                let preview = if token.len() > 20 {
-                    format!("{}...", &token[..20])
-                } else {
-                    token.clone()
-                };
-                format!("🔑 {} ({})", name, preview)
-            })
-            .collect();
-
-        let token_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择 Token")
-            .items(&token_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_token_name = &token_names[token_idx];
+        let selected_token_name = &select_key("选择 Token", &selected_site.tokens, &selected_site.usage)?;
         let selected_token = selected_site.tokens.get(selected_token_name).unwrap();
 
+        // 按磁盘上的最新配置校验，站点或 Token 可能已在别处被删除
+        if let Err(e) = self.config_manager.resolve_claude_switch(selected_site_name, selected_token_name) {
+            show_error(&format!("无法切换到 {} - {}: {}", selected_site_name, selected_token_name, e));
+            return Ok(());
+        }
+
         // 显示将要切换到的配置
         println!("\n{}", style("📋 即将切换到以下配置：").white());
         println!();
@@ -143,36 +188,195 @@ impl ClaudeCommand {
         println!(
             "  {} {}",
             style("Token:").white(),
-            style(format!("{}...", &selected_token[..20.min(selected_token.len())])).cyan()
+            style(redact(selected_token)).cyan()
         );
         println!();
 
+        // Vertex AI 模式下缺少项目 ID 或凭据时，写入的 settings.json 无法使用
+        let vertex_issues = vertex_warnings(&selected_site.config.vertex);
+        for issue in &vertex_issues {
+            show_warning(issue);
+        }
+
+        // dry-run: 只显示将要写入的变更
+        if self.dry_run && self.record_only {
+            show_info("dry-run 模式: 仅记录模式下只会更新 config.json 中的激活记录，未修改任何文件");
+            return Ok(());
+        }
+        if self.dry_run {
+            let files = self
+                .config_manager
+                .preview_claude_switch(selected_site_name, selected_token_name)?;
+
+            show_info("dry-run 模式: 以下为将要写入的变更，未修改任何文件");
+            show_file_diffs(&files);
+            return Ok(());
+        }
+
+        // 可选: 切换前验证 Token
+        if self.verify_before_switch {
+            let base_url = selected_site
+                .config
+                .base_url
+                .as_deref()
+                .unwrap_or(&selected_site.metadata.url);
+            if !verify_before_switch(&self.config_manager, base_url, selected_token, selected_site.auth_header_style())? {
+                show_info("用户取消切换");
+                return Ok(());
+            }
+        }
+
+        // 可选: 覆盖已有文件前先显示 diff（config.json 中的 settings.diff_before_overwrite）
+        let overwrites = !self.record_only
+            && self.config_manager.read_global_config()?.settings.diff_before_overwrite
+            && show_overwrite_diffs(
+                &self
+                    .config_manager
+                    .preview_claude_switch(selected_site_name, selected_token_name)?,
+            );
+
         // 确认切换
-        let confirmed = confirm("确认切换配置", true).map_err(|e| e.to_string())?;
+        let confirmed = if overwrites {
+            confirm_destructive("确认覆盖以上变更并切换")?
+        } else if !vertex_issues.is_empty() {
+            confirm("Vertex AI 配置不完整，仍要切换", false)?
+        } else {
+            confirm("确认切换配置", true)?
+        };
 
         if !confirmed {
             show_info("用户取消切换");
             return Ok(());
         }
 
-        // 执行切换
-        self.config_manager
-            .switch_claude_config(selected_site_name, selected_token_name)?;
+        // 执行切换（仅记录模式下只更新 config.json 中的激活引用）
+        let written_paths = if self.record_only {
+            self.config_manager.set_active_claude(selected_site_name, selected_token_name)?;
+            Vec::new()
+        } else {
+            self.config_manager.switch_claude_config(selected_site_name, selected_token_name)?
+        };
 
         show_success(&format!(
             "✨ 成功切换到配置: {} - {}",
             selected_site_name, selected_token_name
         ));
-        self.wait_for_back();
+        if self.record_only {
+            show_info("仅记录模式: 未写入 settings.json");
+        }
+        show_result(&format!("{}/{}", selected_site_name, selected_token_name));
+        show_written_paths_with_copy(&written_paths);
+        if !self.record_only {
+            auto_backup_after_switch(&self.config_manager, "claudeCode");
+        }
+        Ok(())
+    }
+
+    /// 将当前激活的 Token 复制到剪贴板
+    fn handle_copy_key(&self) -> Result<()> {
+        let Some(active_config) = self.config_manager.get_active_claude_config()? else {
+            show_error("当前没有激活的 Claude 配置，请先切换");
+            wait_for_back();
+            return Ok(());
+        };
+
+        show_warning("剪贴板将保存完整的 Token，其他程序也能读取剪贴板内容");
+        let prompt = format!(
+            "复制 {} - {} 的 Token 到剪贴板",
+            active_config.site, active_config.token_name
+        );
+        if !confirm(&prompt, false)? {
+            show_info("操作已取消");
+            wait_for_back();
+            return Ok(());
+        }
+
+        copy_to_clipboard(&active_config.token)?;
+        show_success("Token 已复制到剪贴板");
+        show_info("使用后请及时清空剪贴板 (例如复制其他内容覆盖)");
+        wait_for_back();
 
         Ok(())
     }
 
+    /// 重新写入当前激活的配置（命令行 `claude apply [--project]`）
+    pub fn apply_active(&self, project: bool) -> Result<()> {
+        if self.dry_run {
+            let files = self.config_manager.preview_claude_apply(project)?;
+            show_info("dry-run 模式: 以下为将要写入的变更，未修改任何文件");
+            show_file_diffs(&files);
+            return Ok(());
+        }
+
+        let written_paths = self.config_manager.apply_claude(project)?;
+
+        if project {
+            let dir = project_dir(".claude")?;
+            show_success(&format!("✨ 已应用到当前项目: {}", dir.display()));
+            show_result(&dir.display().to_string());
+        } else {
+            show_success("✨ 已重新写入当前激活的 Claude 配置");
+        }
+        show_written_paths(&written_paths);
+
+        Ok(())
+    }
+
+    /// 输出当前激活配置的环境变量（命令行 `claude env [--fish]`，不写入任何文件）
+    pub fn print_env(&self, fish: bool) -> Result<()> {
+        let vars = self.config_manager.claude_env_vars()?;
+        print!("{}", env_export_lines(&vars, fish));
+        Ok(())
+    }
+
+    /// 单行输出当前激活的配置（命令行 `claude current`），没有激活的配置时返回错误
+    pub fn print_current(&self) -> Result<()> {
+        let active = self.config_manager.require_active_claude()?;
+        println!("{}", current_line("claude", &active.site, &active.token_name, active.model.as_deref()));
+        Ok(())
+    }
+
     // ========================================================================
     // 查看配置
     // ========================================================================
 
-    fn handle_list(&self) -> Result<(), String> {
+    /// 列表中显示的站点: 按名称排序，指定标签时先按标签过滤，再取 offset/limit 指定的一页
+    fn listed_sites(&self, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<Vec<(String, ClaudeSite)>> {
+        let claude = self.config_manager.claude();
+        let Some(tag) = tag else {
+            return claude.get_sites_page(offset, limit);
+        };
+
+        let tagged = claude.get_sites_sorted()?.into_iter().filter(|(_, site)| site.has_tag(tag)).collect();
+        Ok(page_sites(tagged, offset, limit))
+    }
+
+    fn handle_list(&self) -> Result<()> {
+        self.print_list(None, 0, None)?;
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 列出所有站点（命令行 `claude list [--json]`）
+    pub fn list(&self, json: bool, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<()> {
+        if !json {
+            return self.print_list(tag, offset, limit);
+        }
+
+        let sites = self.listed_sites(tag, offset, limit)?;
+        let view = ClaudeListView {
+            active: self.config_manager.read_global_config()?.active.claude,
+            sites: sites.iter().map(|(name, site)| ClaudeSiteView::new(name, site)).collect(),
+        };
+
+        let content = serde_json::to_string_pretty(&view)
+            .map_err(|e| format!("序列化站点列表失败: {}", e))?;
+        println!("{}", content);
+        Ok(())
+    }
+
+    /// 以文本形式输出所有站点与当前激活的配置，指定标签时只列出带该标签的站点
+    fn print_list(&self, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<()> {
         println!("\n{}", style("📋 Claude API 配置列表").cyan().bold());
         println!();
 
@@ -206,6 +410,15 @@ impl ClaudeCommand {
                     );
                 }
             }
+            if active_config.bedrock.enabled {
+                println!("  {} {}", style("Bedrock:").white(), style("启用").green());
+                if let Some(ref region) = active_config.bedrock.region {
+                    println!("    {} {}", style("Region:").white(), style(region).dim());
+                }
+                if let Some(ref profile) = active_config.bedrock.profile {
+                    println!("    {} {}", style("Profile:").white(), style(profile).dim());
+                }
+            }
             println!();
         } else {
             println!("{}", style("⚠️  当前没有激活的配置").yellow());
@@ -213,11 +426,10 @@ impl ClaudeCommand {
         }
 
         // 显示所有站点
-        let sites = self.config_manager.claude().get_all_sites()?;
+        let sites = self.listed_sites(tag, offset, limit)?;
 
         if sites.is_empty() {
             show_info("没有可用的站点配置");
-            self.wait_for_back();
             return Ok(());
         }
 
@@ -232,6 +444,10 @@ impl ClaudeCommand {
                 println!("  {} {}", style("描述:").white(), style(desc).dim());
             }
 
+            if !site.metadata.tags.is_empty() {
+                println!("  {} {}", style("标签:").white(), style(site.metadata.tags.join(", ")).magenta());
+            }
+
             if let Some(ref base_url) = site.config.base_url {
                 println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
             }
@@ -241,19 +457,14 @@ impl ClaudeCommand {
             }
 
             println!("  {} {}", style("Tokens:").white(), style(site.tokens.len()).yellow());
-            for (token_name, token) in &site.tokens {
-                let preview = if token.len() > 20 {
-                    format!("{}...", &token[..20])
-                } else {
-                    token.clone()
-                };
-                println!("    - {} ({})", style(token_name).cyan(), style(preview).dim());
+            for token_name in sorted_keys(&site.tokens) {
+                let token = &site.tokens[token_name];
+                println!("    - {} ({})", style(token_name).cyan(), style(redact(token)).dim());
             }
 
             println!();
         }
 
-        self.wait_for_back();
         Ok(())
     }
 
@@ -261,30 +472,35 @@ impl ClaudeCommand {
     // 添加配置
     // ========================================================================
 
-    fn handle_add(&mut self) -> Result<(), String> {
+    fn handle_add(&mut self) -> Result<()> {
         println!("\n{}", style("➕ 添加 Claude API 配置").cyan().bold());
         println!();
 
-        // 选择操作类型
-        let choices = vec!["添加新站点", "在已有站点中添加 Token", "返回"];
+        // 选择操作类型（存在 ~/.claude/settings.json 时可从中导入）
+        let mut choices = vec!["添加新站点", "在已有站点中添加 Token", "批量导入 Token"];
+        if self.config_manager.claude().get_settings_file_path().exists() {
+            choices.push("从现有配置导入");
+        }
+        choices.push("返回");
 
         let choice = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("选择操作")
             .items(&choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        match choice {
-            0 => self.add_new_site(),
-            1 => self.add_token_to_existing_site(),
-            2 => Ok(()),
-            _ => Ok(()),
+            .map_err(|_| CliError::UserCancelled)?;
+
+        match choices[choice] {
+            "添加新站点" => self.add_new_site(),
+            "在已有站点中添加 Token" => self.add_token_to_existing_site(),
+            "批量导入 Token" => self.import_keys_to_existing_site(),
+            "返回" => Ok(()),
+            _ => self.import_existing(),
         }
     }
 
     /// 添加新站点
-    fn add_new_site(&mut self) -> Result<(), String> {
+    fn add_new_site(&mut self) -> Result<()> {
         println!("\n{}", style("创建新站点").cyan().bold());
         println!();
 
@@ -292,7 +508,7 @@ impl ClaudeCommand {
         let site_name: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("站点名称")
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // 检查站点是否已存在
         if self
@@ -301,22 +517,19 @@ impl ClaudeCommand {
             .get_site(&site_name)?
             .is_some()
         {
-            return Err(format!("站点 '{}' 已存在", site_name));
+            return Err(format!("站点 '{}' 已存在", site_name).into());
         }
 
-        // 输入 Base URL
-        let base_url: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("API Base URL")
-            .default("https://api.anthropic.com".to_string())
-            .interact_text()
-            .map_err(|_| "用户取消操作")?;
+        // 选择或输入 Base URL
+        let base_url = select_base_url(ClaudeSite::BASE_URL_PRESETS)?;
+        warn_duplicate_base_url(&base_url, &self.config_manager.claude().find_sites_with_base_url(&base_url, None)?);
 
         // 输入描述（可选）
         let description: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("站点描述（可选）")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let description = if description.is_empty() {
             None
@@ -324,12 +537,15 @@ impl ClaudeCommand {
             Some(description)
         };
 
+        // 输入标签（可选）
+        let tags = input_tags(&[])?;
+
         // 输入 Model（可选）
         let model: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("默认模型（可选）")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let model = if model.is_empty() {
             None
@@ -337,58 +553,85 @@ impl ClaudeCommand {
             Some(model)
         };
 
+        // 选择认证头格式（预选该 provider 的默认值）
+        let auth_header_style = select_auth_header_style(ClaudeSite::DEFAULT_AUTH_HEADER_STYLE)?;
+
         // 创建站点（使用 base_url 作为 url）
         self.config_manager
             .claude_mut()
             .add_site(site_name.clone(), base_url.clone(), description)?;
+        if !tags.is_empty() {
+            self.config_manager.claude_mut().set_site_tags(&site_name, tags)?;
+        }
 
-        // 配置 Vertex AI
+        // 选择后端模式
         println!();
-        let use_vertex = confirm("是否使用 Vertex AI", false).map_err(|e| e.to_string())?;
-
-        if use_vertex {
-            // Vertex 模式
-            let project_id: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("Vertex Project ID")
-                .interact_text()
-                .map_err(|_| "用户取消操作")?;
-
-            let skip_auth = confirm("跳过 Vertex 认证", false).map_err(|e| e.to_string())?;
-
-            let vertex_config = crate::config::VertexConfig {
-                enabled: true,
-                project_id: Some(project_id),
-                base_url: Some(base_url.clone()),
-                skip_auth,
-            };
+        match self.select_backend(0)? {
+            1 => {
+                // Vertex 模式
+                let project_id: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Vertex Project ID")
+                    .interact_text()
+                    .map_err(|_| CliError::UserCancelled)?;
+
+                let skip_auth = confirm("跳过 Vertex 认证", false)?;
+
+                let vertex_config = VertexConfig {
+                    enabled: true,
+                    project_id: Some(project_id),
+                    base_url: Some(base_url.clone()),
+                    skip_auth,
+                };
 
-            // 更新站点配置：设置 model 和 vertex
-            self.config_manager
-                .claude_mut()
-                .update_site_config(&site_name, None, model, Some(vertex_config))?;
-        } else {
-            // 普通模式：设置 base_url 和 model
-            self.config_manager
-                .claude_mut()
-                .update_site_config(&site_name, Some(base_url), model, None)?;
+                // 更新站点配置：设置 model 和 vertex
+                self.config_manager.claude_mut().update_site_config(
+                    &site_name,
+                    None,
+                    model,
+                    Some(vertex_config),
+                    None,
+                )?;
+            }
+            2 => {
+                // Bedrock 模式
+                let bedrock_config = self.input_bedrock_config(&BedrockConfig::default())?;
+
+                self.config_manager.claude_mut().update_site_config(
+                    &site_name,
+                    None,
+                    model,
+                    None,
+                    Some(bedrock_config),
+                )?;
+            }
+            _ => {
+                // 普通模式：设置 base_url 和 model
+                self.config_manager
+                    .claude_mut()
+                    .update_site_config(&site_name, Some(base_url), model, None, None)?;
+            }
         }
 
+        self.config_manager
+            .claude_mut()
+            .set_auth_header_style(&site_name, auth_header_style)?;
+
         show_success(&format!("成功创建站点: {}", site_name));
 
         // 询问是否立即添加 Token
         println!();
-        let add_token = confirm("是否立即添加 Token", true).map_err(|e| e.to_string())?;
+        let add_token = confirm("是否立即添加 Token", true)?;
 
         if add_token {
             self.add_token_to_site(&site_name)?;
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 在已有站点中添加 Token
-    fn add_token_to_existing_site(&mut self) -> Result<(), String> {
+    fn add_token_to_existing_site(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.claude().get_all_sites()?;
 
@@ -398,25 +641,51 @@ impl ClaudeCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
+        let selected_site = &select_site("选择站点", &sites)?;
 
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        self.add_token_to_site(selected_site)?;
+        wait_for_back();
+        Ok(())
+    }
 
-        let selected_site = &site_names[site_idx];
+    /// 批量导入 Token 到已有站点（粘贴列表或从文件读取）
+    fn import_keys_to_existing_site(&mut self) -> Result<()> {
+        let sites = self.config_manager.claude().get_all_sites()?;
 
-        self.add_token_to_site(selected_site)?;
-        self.wait_for_back();
+        if sites.is_empty() {
+            show_error("没有可用的站点，请先添加站点");
+            return Ok(());
+        }
+
+        let site_name = select_site("选择站点", &sites)?;
+        let existing: Vec<String> = sites[&site_name].tokens.keys().cloned().collect();
+
+        let text = read_key_batch("Token")?;
+        let mut batch = parse_key_batch(&text, &existing, "Token");
+
+        let mut added = Vec::new();
+        for (key_name, value) in batch.keys {
+            match self.config_manager.claude_mut().add_token(&site_name, key_name.clone(), value) {
+                Ok(()) => added.push(key_name),
+                Err(e) => batch.skipped.push(format!("Token '{}' 添加失败: {}", key_name, e)),
+            }
+        }
+
+        report_key_batch("Token", added.len(), &batch.skipped);
+
+        // 站点原本没有 Token: 询问是否立即激活导入的第一个
+        if existing.is_empty() {
+            if let Some(first) = added.first() {
+                self.activate_new_key(&site_name, first)?;
+            }
+        }
+
+        wait_for_back();
         Ok(())
     }
 
     /// 添加 Token 到指定站点
-    fn add_token_to_site(&mut self, site_name: &str) -> Result<(), String> {
+    fn add_token_to_site(&mut self, site_name: &str) -> Result<()> {
         println!("\n{}", style(format!("为站点 '{}' 添加 Token", site_name)).cyan());
         println!();
 
@@ -425,16 +694,16 @@ impl ClaudeCommand {
             .with_prompt("Token 名称")
             .default("主账号".to_string())
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // 输入 Token 值
         let token: String = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("Token 值（输入不可见）")
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         if token.is_empty() {
-            return Err("Token 值不能为空".to_string());
+            return Err("Token 值不能为空".into());
         }
 
         // 添加 Token
@@ -444,6 +713,38 @@ impl ClaudeCommand {
 
         show_success(&format!("成功添加 Token: {}", token_name));
 
+        // 站点的第一个 Token: 询问是否立即激活
+        let key_count = self.config_manager.claude().get_site(site_name)?.map_or(0, |site| site.tokens.len());
+        if key_count == 1 {
+            self.activate_new_key(site_name, &token_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// 新添加的 Token 询问后立即激活（dry-run 下不询问）
+    fn activate_new_key(&mut self, site_name: &str, token_name: &str) -> Result<()> {
+        if self.dry_run || !confirm("是否立即激活此配置?", true)? {
+            return Ok(());
+        }
+
+        let written_paths = if self.record_only {
+            self.config_manager.set_active_claude(site_name, token_name)?;
+            Vec::new()
+        } else {
+            self.config_manager.switch_claude_config(site_name, token_name)?
+        };
+
+        show_success(&format!("✨ 成功切换到配置: {} - {}", site_name, token_name));
+        if self.record_only {
+            show_info("仅记录模式: 未写入 settings.json");
+        }
+        show_result(&format!("{}/{}", site_name, token_name));
+        show_written_paths_with_copy(&written_paths);
+        if !self.record_only {
+            auto_backup_after_switch(&self.config_manager, "claudeCode");
+        }
+
         Ok(())
     }
 
@@ -451,7 +752,7 @@ impl ClaudeCommand {
     // 编辑配置
     // ========================================================================
 
-    fn handle_edit(&mut self) -> Result<(), String> {
+    fn handle_edit(&mut self) -> Result<()> {
         println!("\n{}", style("✏️  编辑 Claude API 配置").cyan().bold());
         println!();
 
@@ -464,22 +765,12 @@ impl ClaudeCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要编辑的站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site("选择要编辑的站点", &sites)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         // 选择编辑类型
         let edit_choices = vec![
-            "编辑站点元数据（URL、描述）",
+            "编辑站点元数据（URL、描述、标签）",
             "编辑站点配置（Base URL、Model等）",
             "编辑 Token",
             "返回",
@@ -490,7 +781,7 @@ impl ClaudeCommand {
             .items(&edit_choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match edit_choice {
             0 => self.edit_site_metadata(selected_site_name, selected_site)?,
@@ -500,12 +791,12 @@ impl ClaudeCommand {
             _ => return Ok(()),
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 编辑站点元数据
-    fn edit_site_metadata(&mut self, site_name: &str, site: &ClaudeSite) -> Result<(), String> {
+    fn edit_site_metadata(&mut self, site_name: &str, site: &ClaudeSite) -> Result<()> {
         println!("\n{}", style("编辑站点元数据").cyan());
         println!();
 
@@ -514,7 +805,10 @@ impl ClaudeCommand {
             .with_prompt("站点 URL")
             .default(site.metadata.url.clone())
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
+        if site.config.base_url.is_none() {
+            warn_duplicate_base_url(&new_url, &self.config_manager.claude().find_sites_with_base_url(&new_url, Some(site_name))?);
+        }
 
         // 编辑描述
         let current_desc = site.metadata.description.clone().unwrap_or_default();
@@ -523,7 +817,7 @@ impl ClaudeCommand {
             .default(current_desc)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_description = if new_description.is_empty() {
             None
@@ -531,10 +825,14 @@ impl ClaudeCommand {
             Some(new_description)
         };
 
+        // 编辑标签
+        let new_tags = input_tags(&site.metadata.tags)?;
+
         // 更新站点元数据
         self.config_manager
             .claude_mut()
             .update_site_metadata(site_name, Some(new_url), new_description)?;
+        self.config_manager.claude_mut().set_site_tags(site_name, new_tags)?;
 
         show_success("成功更新站点元数据");
 
@@ -542,7 +840,7 @@ impl ClaudeCommand {
     }
 
     /// 编辑站点配置
-    fn edit_site_config(&mut self, site_name: &str, site: &ClaudeSite) -> Result<(), String> {
+    fn edit_site_config(&mut self, site_name: &str, site: &ClaudeSite) -> Result<()> {
         println!("\n{}", style("编辑站点配置").cyan());
         println!();
 
@@ -553,13 +851,16 @@ impl ClaudeCommand {
             .default(current_base_url)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_base_url = if new_base_url.is_empty() {
             None
         } else {
             Some(new_base_url)
         };
+        if let Some(ref base_url) = new_base_url {
+            warn_duplicate_base_url(base_url, &self.config_manager.claude().find_sites_with_base_url(base_url, Some(site_name))?);
+        }
 
         // 编辑 Model
         let current_model = site.config.model.clone().unwrap_or_default();
@@ -568,7 +869,7 @@ impl ClaudeCommand {
             .default(current_model)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_model = if new_model.is_empty() {
             None
@@ -576,16 +877,20 @@ impl ClaudeCommand {
             Some(new_model)
         };
 
-        // 编辑 Vertex AI 配置
+        // 编辑后端模式（Vertex AI / Bedrock 互斥）
         println!();
-        println!("{}", style("🔷 Vertex AI 配置").cyan());
+        let current_backend = if site.config.bedrock.enabled {
+            2
+        } else if site.config.vertex.enabled {
+            1
+        } else {
+            0
+        };
+        let backend = self.select_backend(current_backend)?;
 
-        let vertex_enabled = confirm(
-            &format!("是否启用 Vertex AI (当前: {})", if site.config.vertex.enabled { "已启用" } else { "未启用" }),
-            site.config.vertex.enabled
-        ).map_err(|e| e.to_string())?;
+        let vertex_config = if backend == 1 {
+            println!("{}", style("🔷 Vertex AI 配置").cyan());
 
-        let vertex_config = if vertex_enabled {
             // Vertex Project ID
             let current_project_id = site.config.vertex.project_id.clone().unwrap_or_default();
             let project_id: String = Input::with_theme(&ColorfulTheme::default())
@@ -593,7 +898,7 @@ impl ClaudeCommand {
                 .default(current_project_id)
                 .allow_empty(true)
                 .interact_text()
-                .map_err(|_| "用户取消操作")?;
+                .map_err(|_| CliError::UserCancelled)?;
 
             let project_id = if project_id.is_empty() {
                 None
@@ -608,7 +913,7 @@ impl ClaudeCommand {
                 .default(current_vertex_url)
                 .allow_empty(true)
                 .interact_text()
-                .map_err(|_| "用户取消操作")?;
+                .map_err(|_| CliError::UserCancelled)?;
 
             let vertex_url = if vertex_url.is_empty() {
                 None
@@ -620,35 +925,76 @@ impl ClaudeCommand {
             let skip_auth = confirm(
                 &format!("是否跳过 Vertex 认证 (当前: {})", if site.config.vertex.skip_auth { "是" } else { "否" }),
                 site.config.vertex.skip_auth
-            ).map_err(|e| e.to_string())?;
+            )?;
 
-            Some(crate::config::VertexConfig {
+            Some(VertexConfig {
                 enabled: true,
                 project_id,
                 base_url: vertex_url,
                 skip_auth,
             })
         } else {
-            Some(crate::config::VertexConfig {
-                enabled: false,
-                project_id: None,
-                base_url: None,
-                skip_auth: false,
-            })
+            Some(VertexConfig::default())
+        };
+
+        let bedrock_config = if backend == 2 {
+            Some(self.input_bedrock_config(&site.config.bedrock)?)
+        } else {
+            Some(BedrockConfig::default())
         };
 
         // 更新站点配置
-        self.config_manager
-            .claude_mut()
-            .update_site_config(site_name, new_base_url, new_model, vertex_config)?;
+        self.config_manager.claude_mut().update_site_config(
+            site_name,
+            new_base_url,
+            new_model,
+            vertex_config,
+            bedrock_config,
+        )?;
 
         show_success("成功更新站点配置");
 
         Ok(())
     }
 
+    /// 选择后端模式: 0 = Anthropic API, 1 = Vertex AI, 2 = AWS Bedrock
+    fn select_backend(&self, current: usize) -> Result<usize> {
+        let backends = vec!["Anthropic API（默认）", "Vertex AI", "AWS Bedrock"];
+
+        Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择后端模式")
+            .items(&backends)
+            .default(current)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)
+    }
+
+    /// 输入 Bedrock 配置
+    fn input_bedrock_config(&self, current: &BedrockConfig) -> Result<BedrockConfig> {
+        println!("{}", style("🟧 AWS Bedrock 配置").cyan());
+
+        let region: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("AWS Region")
+            .default(current.region.clone().unwrap_or_else(|| "us-east-1".to_string()))
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let profile: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("AWS Profile（可选）")
+            .default(current.profile.clone().unwrap_or_default())
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        Ok(BedrockConfig {
+            enabled: true,
+            region: Some(region),
+            profile: if profile.is_empty() { None } else { Some(profile) },
+        })
+    }
+
     /// 编辑 Token
-    fn edit_token(&mut self, site_name: &str, site: &ClaudeSite) -> Result<(), String> {
+    fn edit_token(&mut self, site_name: &str, site: &ClaudeSite) -> Result<()> {
         if site.tokens.is_empty() {
             show_error("该站点没有 Token");
             return Ok(());
@@ -658,29 +1004,16 @@ impl ClaudeCommand {
         println!();
 
         // 选择 Token
-        let token_names: Vec<String> = site.tokens.keys().cloned().collect();
-        let token_items: Vec<String> = token_names
-            .iter()
-            .map(|name| format!("🔑 {}", name))
-            .collect();
-
-        let token_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要编辑的 Token")
-            .items(&token_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let token_name = &token_names[token_idx];
+        let token_name = &select_named("选择要编辑的 Token", &site.tokens, |name, _| format!("🔑 {}", name))?;
 
         // 输入新的 Token 值
         let new_token: String = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("新的 Token 值（输入不可见）")
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         if new_token.is_empty() {
-            return Err("Token 值不能为空".to_string());
+            return Err("Token 值不能为空".into());
         }
 
         // 更新 Token
@@ -690,6 +1023,17 @@ impl ClaudeCommand {
 
         show_success(&format!("成功更新 Token: {}", token_name));
 
+        // 编辑的是当前激活的 Token 时，重新写入工具配置文件使新密钥生效
+        if self.config_manager.is_active_claude_token(site_name, token_name)? {
+            if self.record_only {
+                show_info("仅记录模式: 未重新写入工具配置文件，可稍后使用 ca-switch claude apply 写入");
+            } else if confirm("该 Token 正在使用中，是否重新写入配置文件使其生效", true)? {
+                let written_paths = self.config_manager.apply_claude(false)?;
+                show_success("✨ 已重新写入当前激活的 Claude 配置");
+                show_written_paths_with_copy(&written_paths);
+            }
+        }
+
         Ok(())
     }
 
@@ -697,7 +1041,7 @@ impl ClaudeCommand {
     // 删除配置
     // ========================================================================
 
-    fn handle_delete(&mut self) -> Result<(), String> {
+    fn handle_delete(&mut self) -> Result<()> {
         println!("\n{}", style("🗑️  删除 Claude API 配置").cyan().bold());
         println!();
 
@@ -717,7 +1061,7 @@ impl ClaudeCommand {
             .items(&delete_choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match delete_choice {
             0 => self.delete_site()?,
@@ -726,62 +1070,126 @@ impl ClaudeCommand {
             _ => return Ok(()),
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 删除站点
-    fn delete_site(&mut self) -> Result<(), String> {
+    fn delete_site(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.claude().get_all_sites()?;
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
+        let selected_site = select_site("选择要删除的站点", &sites)?;
 
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        self.delete_site_by_name(&selected_site)
+    }
 
-        let selected_site = &site_names[site_idx];
+    /// 从现有的 ~/.claude/settings.json 导入站点与 Token（同名站点覆盖前确认）
+    pub fn import_existing(&mut self) -> Result<()> {
+        let Some(site) = self.config_manager.claude().read_existing_site()? else {
+            show_warning("未在 ~/.claude/settings.json 中找到可导入的 Token 或 Base URL");
+            return Ok(());
+        };
 
-        // 确认删除
-        show_warning(&format!("⚠️  警告：即将删除站点 '{}'", selected_site));
-        let confirmed = confirm("确认删除", false).map_err(|e| e.to_string())?;
+        println!("\n{}", style("📥 从 ~/.claude/settings.json 导入").cyan().bold());
+        println!();
+        println!("  {} {}", style("URL:").white(), style(&site.metadata.url).dim());
+        if let Some(ref base_url) = site.config.base_url {
+            println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
+        }
+        if let Some(ref model) = site.config.model {
+            println!("  {} {}", style("Model:").white(), style(model).yellow());
+        }
+        if site.config.vertex.enabled {
+            println!("  {} {}", style("Vertex AI:").white(), style("启用").green());
+            if let Some(ref project_id) = site.config.vertex.project_id {
+                println!("  {} {}", style("Project ID:").white(), style(project_id).dim());
+            }
+        }
+        if site.config.bedrock.enabled {
+            println!("  {} {}", style("Bedrock:").white(), style("启用").green());
+        }
+        match site.tokens.get(IMPORTED_TOKEN_NAME) {
+            Some(token) => println!("  {} {}", style("Token:").white(), style(redact(token)).cyan()),
+            None => println!("  {} {}", style("Token:").white(), style("未找到，导入后可手动添加").dim()),
+        }
+        println!();
 
-        if !confirmed {
+        if self.dry_run {
+            show_info("dry-run 模式: 仅显示将要导入的站点，未修改任何文件");
+            return Ok(());
+        }
+
+        // 默认使用 URL 的主机名作为站点名称
+        let default_name = site
+            .metadata
+            .url
+            .split("://")
+            .last()
+            .and_then(|rest| rest.split('/').next())
+            .filter(|host| !host.is_empty())
+            .unwrap_or("imported")
+            .to_string();
+        let site_name = if is_assume_yes() {
+            show_info(&format!("站点名称: {} (--yes)", default_name));
+            default_name
+        } else {
+            let site_name: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("站点名称")
+                .default(default_name)
+                .interact_text()
+                .map_err(|_| CliError::UserCancelled)?;
+            site_name.trim().to_string()
+        };
+
+        if self.config_manager.claude().get_site(&site_name)?.is_some()
+            && !confirm(&format!("站点 '{}' 已存在，是否覆盖?", site_name), false)?
+        {
+            show_info("用户取消导入");
+            return Ok(());
+        }
+
+        self.config_manager.claude().upsert_site(&site_name, site)?;
+
+        show_success(&format!("成功导入站点: {}", site_name));
+        show_result(&site_name);
+
+        Ok(())
+    }
+
+    /// 按名称删除站点（命令行 `claude delete <站点>` 也使用此方法）
+    pub fn delete_site_by_name(&mut self, site_name: &str) -> Result<()> {
+        if self.config_manager.claude().get_site(site_name)?.is_none() {
+            return Err(CliError::NotFound(format!("站点 '{}' 不存在", site_name)));
+        }
+
+        // 确认删除
+        show_warning(&format!("⚠️  警告：即将删除站点 '{}'", site_name));
+        if !confirm_destructive(&format!("确认删除站点 '{}'", site_name))? {
             show_info("用户取消删除");
             return Ok(());
         }
 
         // 执行删除
-        self.config_manager.claude_mut().remove_site(selected_site)?;
+        let cleared = self.config_manager.delete_claude_site(site_name)?;
 
-        show_success(&format!("成功删除站点: {}", selected_site));
+        show_success(&format!("成功删除站点: {}", site_name));
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
+        show_result(site_name);
 
         Ok(())
     }
 
-    /// 删除 Token
-    fn delete_token(&mut self) -> Result<(), String> {
+    /// 删除 Token（可多选批量删除）
+    fn delete_token(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.claude().get_all_sites()?;
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site("选择站点", &sites)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         if selected_site.tokens.is_empty() {
@@ -789,28 +1197,58 @@ impl ClaudeCommand {
             return Ok(());
         }
 
-        // 选择 Token
-        let token_names: Vec<String> = selected_site.tokens.keys().cloned().collect();
-        let token_items: Vec<String> = token_names
+        // 当前激活的 Token（仅当属于该站点时）
+        let active_name = self
+            .config_manager
+            .read_global_config()?
+            .active
+            .claude
+            .filter(|r| &r.site == selected_site_name)
+            .map(|r| r.token_name);
+
+        // 多选 Token
+        let names: Vec<String> = sorted_keys(&selected_site.tokens).into_iter().cloned().collect();
+        let items: Vec<String> = names
             .iter()
-            .map(|name| format!("🔑 {}", name))
+            .map(|name| {
+                if active_name.as_ref() == Some(name) {
+                    format!("🔑 {} (当前激活)", name)
+                } else {
+                    format!("🔑 {}", name)
+                }
+            })
             .collect();
 
-        let token_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的 Token")
-            .items(&token_items)
-            .default(0)
+        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择要删除的 Token (空格选择,回车确认)")
+            .items(&items)
+            .max_length(SELECT_PAGE_SIZE)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
+
+        if selections.is_empty() {
+            show_info("未选择任何 Token");
+            return Ok(());
+        }
 
-        let selected_token = &token_names[token_idx];
+        let selected_names: Vec<&String> = selections.iter().map(|&i| &names[i]).collect();
+        let deletes_active = selected_names
+            .iter()
+            .any(|name| active_name.as_ref() == Some(*name));
 
         // 确认删除
         show_warning(&format!(
-            "⚠️  警告：即将删除站点 '{}' 的 Token '{}'",
-            selected_site_name, selected_token
+            "⚠️  警告：即将删除站点 '{}' 的 {} 个 Token:",
+            selected_site_name,
+            selected_names.len()
         ));
-        let confirmed = confirm("确认删除", false).map_err(|e| e.to_string())?;
+        for name in &selected_names {
+            println!("  - {}", name);
+        }
+        if deletes_active {
+            show_warning("其中包含当前激活的 Token，删除后将清除当前激活配置");
+        }
+        let confirmed = confirm_destructive("确认删除")?;
 
         if !confirmed {
             show_info("用户取消删除");
@@ -818,11 +1256,18 @@ impl ClaudeCommand {
         }
 
         // 执行删除
-        self.config_manager
-            .claude_mut()
-            .remove_token(selected_site_name, selected_token)?;
+        let mut cleared = false;
+        for name in &selected_names {
+            cleared |= self
+                .config_manager
+                .delete_claude_token(selected_site_name, name)?;
+        }
+
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
 
-        show_success(&format!("成功删除 Token: {}", selected_token));
+        show_success(&format!("成功删除 {} 个 Token", selected_names.len()));
 
         Ok(())
     }
@@ -830,10 +1275,4 @@ impl ClaudeCommand {
     // ========================================================================
     // 辅助方法
     // ========================================================================
-
-    fn wait_for_back(&self) {
-        println!();
-        println!("{}", style("按回车键返回...").dim());
-        let _ = std::io::stdin().read_line(&mut String::new());
-    }
 }
@@ -1,58 +1,129 @@
 // Codex 命令模块 - 基于新架构重构
 // 支持新的配置文件结构：codex.json + config.json
 
-use crate::config::{CodexSite, ConfigManager};
-use crate::ui::{confirm, show_error, show_info, show_success, show_warning, ApiMenuChoice};
+use crate::commands::backup::auto_backup_after_switch;
+use crate::commands::key_import::{parse_key_batch, read_key_batch, report_key_batch};
+use crate::commands::verify::{select_auth_header_style, select_base_url, verify_before_switch};
+use crate::error::{CliError, Result};
+use crate::config::codex_manager::IMPORTED_KEY_NAME;
+use crate::config::{
+    page_sites, project_dir, CodexSite, CodexSiteConfig, CodexActiveReference, ConfigManager, SiteMetadata, SiteSummary, CODEX_APPROVAL_POLICIES, CODEX_NETWORK_ACCESS_OPTIONS, CODEX_SANDBOX_MODES,
+};
+use crate::ui::{
+    confirm, confirm_destructive, input_tags, select_key, select_named, select_site, select_site_with_tag, show_action_error, show_error, show_file_diffs, show_info, show_success, show_warning, show_written_paths, show_written_paths_with_copy, warn_duplicate_base_url,
+    copy_to_clipboard, current_line, env_export_lines, redact, show_overwrite_diffs, show_result, sorted_keys,
+    wait_for_back, ApiMenuChoice, SELECT_PAGE_SIZE,
+};
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Input, Password, Select};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Password, Select};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// `codex list --json` 的输出 (API Key 已脱敏)
+#[derive(Serialize)]
+struct CodexListView<'a> {
+    active: Option<CodexActiveReference>,
+    sites: Vec<CodexSiteView<'a>>,
+}
+
+/// 单个站点的 JSON 视图
+#[derive(Serialize)]
+struct CodexSiteView<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    metadata: &'a SiteMetadata,
+    config: &'a CodexSiteConfig,
+    /// API Key 名称 -> 脱敏后的值
+    api_keys: BTreeMap<&'a str, String>,
+}
+
+impl<'a> CodexSiteView<'a> {
+    fn new(name: &'a str, site: &'a CodexSite) -> Self {
+        Self {
+            name,
+            metadata: &site.metadata,
+            config: &site.config,
+            api_keys: site.api_keys.iter().map(|(key_name, key)| (key_name.as_str(), redact(key))).collect(),
+        }
+    }
+}
 
 /// Codex API 管理命令
 pub struct CodexCommand {
     config_manager: ConfigManager,
+    dry_run: bool,
+    /// 切换前是否先验证 Token
+    verify_before_switch: bool,
+    /// 切换时只更新激活记录，不写入工具配置文件
+    record_only: bool,
 }
 
 impl CodexCommand {
     /// 创建新的 Codex 命令实例
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> Result<Self> {
         Ok(Self {
             config_manager: ConfigManager::new()?,
+            dry_run: false,
+            verify_before_switch: false,
+            record_only: false,
         })
     }
 
+    /// 设置 dry-run 模式（切换时只预览，不写入文件）
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     /// 执行 Codex API 管理命令
-    pub fn execute(&mut self) -> Result<(), String> {
+    pub fn execute(&mut self) -> Result<()> {
         loop {
-            let choice = crate::ui::show_api_menu("💻 Codex配置管理").map_err(|e| e.to_string())?;
+            let choice = crate::ui::show_api_menu("💻 Codex配置管理")?;
 
             match choice {
                 ApiMenuChoice::Switch => {
                     if let Err(e) = self.handle_switch() {
-                        show_error(&format!("切换配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("切换配置失败", &e);
                     }
                 }
                 ApiMenuChoice::List => {
                     if let Err(e) = self.handle_list() {
-                        show_error(&format!("查看配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("查看配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Add => {
                     if let Err(e) = self.handle_add() {
-                        show_error(&format!("添加配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("添加配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Edit => {
                     if let Err(e) = self.handle_edit() {
-                        show_error(&format!("编辑配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("编辑配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Delete => {
                     if let Err(e) = self.handle_delete() {
-                        show_error(&format!("删除配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("删除配置失败", &e);
+                    }
+                }
+                ApiMenuChoice::CopyKey => {
+                    if let Err(e) = self.handle_copy_key() {
+                        show_action_error("复制密钥失败", &e);
+                    }
+                }
+                ApiMenuChoice::ToggleVerify => {
+                    self.verify_before_switch = !self.verify_before_switch;
+                    if self.verify_before_switch {
+                        show_info("已开启切换前验证");
+                    } else {
+                        show_info("已关闭切换前验证");
+                    }
+                }
+                ApiMenuChoice::ToggleRecordOnly => {
+                    self.record_only = !self.record_only;
+                    if self.record_only {
+                        show_info("已开启仅记录模式: 切换时不写入 ~/.codex/");
+                    } else {
+                        show_info("已关闭仅记录模式");
                     }
                 }
                 ApiMenuChoice::Back => break,
@@ -66,7 +137,14 @@ impl CodexCommand {
     // 切换配置
     // ========================================================================
 
-    fn handle_switch(&mut self) -> Result<(), String> {
+    fn handle_switch(&mut self) -> Result<()> {
+        self.switch_site(None)?;
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 选择站点与密钥并切换（命令行 `codex switch [--tag <标签>]`），指定标签时只在带该标签的站点中选择
+    pub fn switch_site(&mut self, tag: Option<&str>) -> Result<()> {
         println!("\n{}", style("🔄 切换 Codex API 配置").cyan().bold());
         println!();
 
@@ -79,23 +157,7 @@ impl CodexCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names
-            .iter()
-            .map(|name| {
-                let site = sites.get(name).unwrap();
-                format!("🌐 {} ({})", name, site.metadata.url)
-            })
-            .collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site_with_tag("选择站点", &sites, tag)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         // 检查是否有 API Keys
@@ -105,30 +167,15 @@ impl CodexCommand {
         }
 
         // 选择 API Key
-        let key_names: Vec<String> = selected_site.api_keys.keys().cloned().collect();
-        let key_items: Vec<String> = key_names
-            .iter()
-            .map(|name| {
-                let key = selected_site.api_keys.get(name).unwrap();
-                let preview = if key.len() > 20 {
-                    format!("{}...", &key[..20])
-                } else {
-                    key.clone()
-                };
-                format!("🔑 {} ({})", name, preview)
-            })
-            .collect();
-
-        let key_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择 API Key")
-            .items(&key_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_key_name = &key_names[key_idx];
+        let selected_key_name = &select_key("选择 API Key", &selected_site.api_keys, &selected_site.usage)?;
         let selected_key = selected_site.api_keys.get(selected_key_name).unwrap();
 
+        // 按磁盘上的最新配置校验，站点或 API Key 可能已在别处被删除
+        if let Err(e) = self.config_manager.resolve_codex_switch(selected_site_name, selected_key_name) {
+            show_error(&format!("无法切换到 {} - {}: {}", selected_site_name, selected_key_name, e));
+            return Ok(());
+        }
+
         // 显示将要切换到的配置
         println!("\n{}", style("📋 即将切换到以下配置：").white());
         println!();
@@ -146,28 +193,143 @@ impl CodexCommand {
         println!(
             "  {} {}",
             style("API Key:").white(),
-            style(format!("{}...", &selected_key[..20.min(selected_key.len())])).cyan()
+            style(redact(selected_key)).cyan()
         );
         println!();
 
+        // dry-run: 只显示将要写入的变更
+        if self.dry_run && self.record_only {
+            show_info("dry-run 模式: 仅记录模式下只会更新 config.json 中的激活记录，未修改任何文件");
+            return Ok(());
+        }
+        if self.dry_run {
+            let files = self
+                .config_manager
+                .preview_codex_switch(selected_site_name, selected_key_name)?;
+
+            show_info("dry-run 模式: 以下为将要写入的变更，未修改任何文件");
+            show_file_diffs(&files);
+            return Ok(());
+        }
+
+        // 可选: 切换前验证 Token
+        if self.verify_before_switch {
+            let base_url = selected_site
+                .config
+                .base_url
+                .as_deref()
+                .unwrap_or(&selected_site.metadata.url);
+            if !verify_before_switch(&self.config_manager, base_url, selected_key, selected_site.auth_header_style())? {
+                show_info("用户取消切换");
+                return Ok(());
+            }
+        }
+
+        // 可选: 覆盖已有文件前先显示 diff（config.json 中的 settings.diff_before_overwrite）
+        let overwrites = !self.record_only
+            && self.config_manager.read_global_config()?.settings.diff_before_overwrite
+            && show_overwrite_diffs(
+                &self
+                    .config_manager
+                    .preview_codex_switch(selected_site_name, selected_key_name)?,
+            );
+
         // 确认切换
-        let confirmed = confirm("确认切换配置", true).map_err(|e| e.to_string())?;
+        let confirmed = if overwrites {
+            confirm_destructive("确认覆盖以上变更并切换")?
+        } else {
+            confirm("确认切换配置", true)?
+        };
 
         if !confirmed {
             show_info("用户取消切换");
             return Ok(());
         }
 
-        // 执行切换
-        self.config_manager
-            .switch_codex_config(selected_site_name, selected_key_name)?;
+        // 执行切换（仅记录模式下只更新 config.json 中的激活引用）
+        let written_paths = if self.record_only {
+            self.config_manager.set_active_codex(selected_site_name, selected_key_name)?;
+            Vec::new()
+        } else {
+            self.config_manager.switch_codex_config(selected_site_name, selected_key_name)?
+        };
 
         show_success(&format!(
             "✨ 成功切换到配置: {} - {}",
             selected_site_name, selected_key_name
         ));
-        self.wait_for_back();
+        if self.record_only {
+            show_info("仅记录模式: 未写入 ~/.codex/");
+        }
+        show_result(&format!("{}/{}", selected_site_name, selected_key_name));
+        show_written_paths_with_copy(&written_paths);
+        if !self.record_only {
+            auto_backup_after_switch(&self.config_manager, "codex");
+        }
+        Ok(())
+    }
 
+    /// 将当前激活的 API Key 复制到剪贴板
+    fn handle_copy_key(&self) -> Result<()> {
+        let Some(active_config) = self.config_manager.get_active_codex_config()? else {
+            show_error("当前没有激活的 Codex 配置，请先切换");
+            wait_for_back();
+            return Ok(());
+        };
+
+        show_warning("剪贴板将保存完整的 API Key，其他程序也能读取剪贴板内容");
+        let prompt = format!(
+            "复制 {} - {} 的 API Key 到剪贴板",
+            active_config.site, active_config.api_key_name
+        );
+        if !confirm(&prompt, false)? {
+            show_info("操作已取消");
+            wait_for_back();
+            return Ok(());
+        }
+
+        copy_to_clipboard(&active_config.api_key)?;
+        show_success("API Key 已复制到剪贴板");
+        show_info("使用后请及时清空剪贴板 (例如复制其他内容覆盖)");
+        wait_for_back();
+
+        Ok(())
+    }
+
+    /// 重新写入当前激活的配置（命令行 `codex apply [--project]`）
+    pub fn apply_active(&self, project: bool) -> Result<()> {
+        if self.dry_run {
+            let files = self.config_manager.preview_codex_apply(project)?;
+            show_info("dry-run 模式: 以下为将要写入的变更，未修改任何文件");
+            show_file_diffs(&files);
+            return Ok(());
+        }
+
+        let written_paths = self.config_manager.apply_codex(project)?;
+
+        if project {
+            let dir = project_dir(".codex")?;
+            show_success(&format!("✨ 已应用到当前项目: {}", dir.display()));
+            show_result(&dir.display().to_string());
+        } else {
+            show_success("✨ 已重新写入当前激活的 Codex 配置");
+        }
+        show_written_paths(&written_paths);
+
+        Ok(())
+    }
+
+    /// 输出当前激活配置的环境变量（命令行 `codex env [--fish]`，不写入任何文件）
+    pub fn print_env(&self, fish: bool) -> Result<()> {
+        let vars = self.config_manager.codex_env_vars()?;
+        print!("{}", env_export_lines(&vars, fish));
+        Ok(())
+    }
+
+    /// 单行输出当前激活的配置（命令行 `codex current`），没有激活的配置时返回错误
+    pub fn print_current(&self) -> Result<()> {
+        let active = self.config_manager.require_active_codex()?;
+        println!("{}", current_line("codex", &active.site, &active.api_key_name, active.model.as_deref()));
         Ok(())
     }
 
@@ -175,7 +337,43 @@ impl CodexCommand {
     // 查看配置
     // ========================================================================
 
-    fn handle_list(&self) -> Result<(), String> {
+    /// 列表中显示的站点: 按名称排序，指定标签时先按标签过滤，再取 offset/limit 指定的一页
+    fn listed_sites(&self, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<Vec<(String, CodexSite)>> {
+        let codex = self.config_manager.codex();
+        let Some(tag) = tag else {
+            return codex.get_sites_page(offset, limit);
+        };
+
+        let tagged = codex.get_sites_sorted()?.into_iter().filter(|(_, site)| site.has_tag(tag)).collect();
+        Ok(page_sites(tagged, offset, limit))
+    }
+
+    fn handle_list(&self) -> Result<()> {
+        self.print_list(None, 0, None)?;
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 列出所有站点（命令行 `codex list [--json]`）
+    pub fn list(&self, json: bool, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<()> {
+        if !json {
+            return self.print_list(tag, offset, limit);
+        }
+
+        let sites = self.listed_sites(tag, offset, limit)?;
+        let view = CodexListView {
+            active: self.config_manager.read_global_config()?.active.codex,
+            sites: sites.iter().map(|(name, site)| CodexSiteView::new(name, site)).collect(),
+        };
+
+        let content = serde_json::to_string_pretty(&view)
+            .map_err(|e| format!("序列化站点列表失败: {}", e))?;
+        println!("{}", content);
+        Ok(())
+    }
+
+    /// 以文本形式输出所有站点与当前激活的配置，指定标签时只列出带该标签的站点
+    fn print_list(&self, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<()> {
         println!("\n{}", style("📋 Codex API 配置列表").cyan().bold());
         println!();
 
@@ -206,9 +404,11 @@ impl CodexCommand {
                     style(reasoning_effort).yellow()
                 );
             }
-            if let Some(ref provider) = active_config.model_provider {
-                println!("  {} {}", style("Model Provider:").white(), style(provider).green());
-            }
+            println!(
+                "  {} {}",
+                style("Model Provider:").white(),
+                style(&active_config.model_provider).green()
+            );
             if let Some(ref network_access) = active_config.network_access {
                 println!("  {} {}", style("Network Access:").white(), style(network_access).cyan());
             }
@@ -219,11 +419,10 @@ impl CodexCommand {
         }
 
         // 显示所有站点
-        let sites = self.config_manager.codex().get_all_sites()?;
+        let sites = self.listed_sites(tag, offset, limit)?;
 
         if sites.is_empty() {
             show_info("没有可用的站点配置");
-            self.wait_for_back();
             return Ok(());
         }
 
@@ -238,6 +437,10 @@ impl CodexCommand {
                 println!("  {} {}", style("描述:").white(), style(desc).dim());
             }
 
+            if !site.metadata.tags.is_empty() {
+                println!("  {} {}", style("标签:").white(), style(site.metadata.tags.join(", ")).magenta());
+            }
+
             if let Some(ref base_url) = site.config.base_url {
                 println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
             }
@@ -262,20 +465,23 @@ impl CodexCommand {
                 println!("  {} {}", style("Network Access:").white(), style(network_access).cyan());
             }
 
+            if let Some(ref sandbox_mode) = site.config.sandbox_mode {
+                println!("  {} {}", style("Sandbox Mode:").white(), style(sandbox_mode).cyan());
+            }
+
+            if let Some(ref approval_policy) = site.config.approval_policy {
+                println!("  {} {}", style("Approval Policy:").white(), style(approval_policy).cyan());
+            }
+
             println!("  {} {}", style("API Keys:").white(), style(site.api_keys.len()).yellow());
-            for (key_name, key) in &site.api_keys {
-                let preview = if key.len() > 20 {
-                    format!("{}...", &key[..20])
-                } else {
-                    key.clone()
-                };
-                println!("    - {} ({})", style(key_name).cyan(), style(preview).dim());
+            for key_name in sorted_keys(&site.api_keys) {
+                let key = &site.api_keys[key_name];
+                println!("    - {} ({})", style(key_name).cyan(), style(redact(key)).dim());
             }
 
             println!();
         }
 
-        self.wait_for_back();
         Ok(())
     }
 
@@ -283,30 +489,35 @@ impl CodexCommand {
     // 添加配置
     // ========================================================================
 
-    fn handle_add(&mut self) -> Result<(), String> {
+    fn handle_add(&mut self) -> Result<()> {
         println!("\n{}", style("➕ 添加 Codex API 配置").cyan().bold());
         println!();
 
-        // 选择操作类型
-        let choices = vec!["添加新站点", "在已有站点中添加 API Key", "返回"];
+        // 选择操作类型（存在 ~/.codex/config.toml 时可从中导入）
+        let mut choices = vec!["添加新站点", "在已有站点中添加 API Key", "批量导入 Key"];
+        if self.config_manager.codex().has_existing_config() {
+            choices.push("从现有 ~/.codex/config.toml 导入");
+        }
+        choices.push("返回");
 
         let choice = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("选择操作")
             .items(&choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        match choice {
-            0 => self.add_new_site(),
-            1 => self.add_key_to_existing_site(),
-            2 => Ok(()),
-            _ => Ok(()),
+            .map_err(|_| CliError::UserCancelled)?;
+
+        match choices[choice] {
+            "添加新站点" => self.add_new_site(),
+            "在已有站点中添加 API Key" => self.add_key_to_existing_site(),
+            "批量导入 Key" => self.import_keys_to_existing_site(),
+            "返回" => Ok(()),
+            _ => self.import_existing(),
         }
     }
 
     /// 添加新站点
-    fn add_new_site(&mut self) -> Result<(), String> {
+    fn add_new_site(&mut self) -> Result<()> {
         println!("\n{}", style("创建新站点").cyan().bold());
         println!();
 
@@ -314,7 +525,7 @@ impl CodexCommand {
         let site_name: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("站点名称")
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // 检查站点是否已存在
         if self
@@ -323,22 +534,19 @@ impl CodexCommand {
             .get_site(&site_name)?
             .is_some()
         {
-            return Err(format!("站点 '{}' 已存在", site_name));
+            return Err(format!("站点 '{}' 已存在", site_name).into());
         }
 
-        // 输入 Base URL
-        let base_url: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("API Base URL")
-            .default("https://api.openai.com/v1".to_string())
-            .interact_text()
-            .map_err(|_| "用户取消操作")?;
+        // 选择或输入 Base URL
+        let base_url = select_base_url(CodexSite::BASE_URL_PRESETS)?;
+        warn_duplicate_base_url(&base_url, &self.config_manager.codex().find_sites_with_base_url(&base_url, None)?);
 
         // 输入描述（可选）
         let description: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("站点描述（可选）")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let description = if description.is_empty() {
             None
@@ -346,12 +554,15 @@ impl CodexCommand {
             Some(description)
         };
 
+        // 输入标签（可选）
+        let tags = input_tags(&[])?;
+
         // 输入 Model（可选）
         let model: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("默认模型（可选）")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let model = if model.is_empty() {
             None
@@ -364,7 +575,7 @@ impl CodexCommand {
             .with_prompt("Reasoning Effort (low/medium/high, 可选)")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let reasoning_effort = if reasoning_effort.is_empty() {
             None
@@ -377,7 +588,7 @@ impl CodexCommand {
             .with_prompt("Provider（可选，默认使用站点名）")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let provider = if provider.is_empty() {
             None
@@ -392,7 +603,7 @@ impl CodexCommand {
             .items(&wire_api_options)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let wire_api = match wire_api_idx {
             1 => Some("chat".to_string()),
@@ -400,10 +611,16 @@ impl CodexCommand {
             _ => None,
         };
 
+        // 选择认证头格式（预选该 provider 的默认值）
+        let auth_header_style = select_auth_header_style(CodexSite::DEFAULT_AUTH_HEADER_STYLE)?;
+
         // 创建站点（使用 base_url 作为 url）
         self.config_manager
             .codex_mut()
             .add_site(site_name.clone(), base_url.clone(), description)?;
+        if !tags.is_empty() {
+            self.config_manager.codex_mut().set_site_tags(&site_name, tags)?;
+        }
 
         // 更新站点配置（设置 base_url 和其他可选参数）
         self.config_manager.codex_mut().update_site_config(
@@ -417,22 +634,26 @@ impl CodexCommand {
             wire_api,
         )?;
 
+        self.config_manager
+            .codex_mut()
+            .set_auth_header_style(&site_name, auth_header_style)?;
+
         show_success(&format!("成功创建站点: {}", site_name));
 
         // 询问是否立即添加 API Key
         println!();
-        let add_key = confirm("是否立即添加 API Key", true).map_err(|e| e.to_string())?;
+        let add_key = confirm("是否立即添加 API Key", true)?;
 
         if add_key {
             self.add_key_to_site(&site_name)?;
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 在已有站点中添加 API Key
-    fn add_key_to_existing_site(&mut self) -> Result<(), String> {
+    fn add_key_to_existing_site(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.codex().get_all_sites()?;
 
@@ -442,25 +663,51 @@ impl CodexCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
+        let selected_site = &select_site("选择站点", &sites)?;
 
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        self.add_key_to_site(selected_site)?;
+        wait_for_back();
+        Ok(())
+    }
 
-        let selected_site = &site_names[site_idx];
+    /// 批量导入 API Key 到已有站点（粘贴列表或从文件读取）
+    fn import_keys_to_existing_site(&mut self) -> Result<()> {
+        let sites = self.config_manager.codex().get_all_sites()?;
 
-        self.add_key_to_site(selected_site)?;
-        self.wait_for_back();
+        if sites.is_empty() {
+            show_error("没有可用的站点，请先添加站点");
+            return Ok(());
+        }
+
+        let site_name = select_site("选择站点", &sites)?;
+        let existing: Vec<String> = sites[&site_name].api_keys.keys().cloned().collect();
+
+        let text = read_key_batch("API Key")?;
+        let mut batch = parse_key_batch(&text, &existing, "Key");
+
+        let mut added = Vec::new();
+        for (key_name, value) in batch.keys {
+            match self.config_manager.codex_mut().add_api_key(&site_name, key_name.clone(), value) {
+                Ok(()) => added.push(key_name),
+                Err(e) => batch.skipped.push(format!("API Key '{}' 添加失败: {}", key_name, e)),
+            }
+        }
+
+        report_key_batch("API Key", added.len(), &batch.skipped);
+
+        // 站点原本没有 API Key: 询问是否立即激活导入的第一个
+        if existing.is_empty() {
+            if let Some(first) = added.first() {
+                self.activate_new_key(&site_name, first)?;
+            }
+        }
+
+        wait_for_back();
         Ok(())
     }
 
     /// 添加 API Key 到指定站点
-    fn add_key_to_site(&mut self, site_name: &str) -> Result<(), String> {
+    fn add_key_to_site(&mut self, site_name: &str) -> Result<()> {
         println!("\n{}", style(format!("为站点 '{}' 添加 API Key", site_name)).cyan());
         println!();
 
@@ -469,16 +716,16 @@ impl CodexCommand {
             .with_prompt("API Key 名称")
             .default("主账号".to_string())
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // 输入 API Key 值
         let api_key: String = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("API Key 值（输入不可见）")
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         if api_key.is_empty() {
-            return Err("API Key 值不能为空".to_string());
+            return Err("API Key 值不能为空".into());
         }
 
         // 添加 API Key
@@ -488,6 +735,38 @@ impl CodexCommand {
 
         show_success(&format!("成功添加 API Key: {}", key_name));
 
+        // 站点的第一个 API Key: 询问是否立即激活
+        let key_count = self.config_manager.codex().get_site(site_name)?.map_or(0, |site| site.api_keys.len());
+        if key_count == 1 {
+            self.activate_new_key(site_name, &key_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// 新添加的 API Key 询问后立即激活（dry-run 下不询问）
+    fn activate_new_key(&mut self, site_name: &str, key_name: &str) -> Result<()> {
+        if self.dry_run || !confirm("是否立即激活此配置?", true)? {
+            return Ok(());
+        }
+
+        let written_paths = if self.record_only {
+            self.config_manager.set_active_codex(site_name, key_name)?;
+            Vec::new()
+        } else {
+            self.config_manager.switch_codex_config(site_name, key_name)?
+        };
+
+        show_success(&format!("✨ 成功切换到配置: {} - {}", site_name, key_name));
+        if self.record_only {
+            show_info("仅记录模式: 未写入 ~/.codex/");
+        }
+        show_result(&format!("{}/{}", site_name, key_name));
+        show_written_paths_with_copy(&written_paths);
+        if !self.record_only {
+            auto_backup_after_switch(&self.config_manager, "codex");
+        }
+
         Ok(())
     }
 
@@ -495,7 +774,7 @@ impl CodexCommand {
     // 编辑配置
     // ========================================================================
 
-    fn handle_edit(&mut self) -> Result<(), String> {
+    fn handle_edit(&mut self) -> Result<()> {
         println!("\n{}", style("✏️  编辑 Codex API 配置").cyan().bold());
         println!();
 
@@ -508,22 +787,12 @@ impl CodexCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要编辑的站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site("选择要编辑的站点", &sites)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         // 选择编辑类型
         let edit_choices = vec![
-            "编辑站点元数据（URL、描述）",
+            "编辑站点元数据（URL、描述、标签）",
             "编辑站点配置（Base URL、Model等）",
             "编辑 API Key",
             "返回",
@@ -534,7 +803,7 @@ impl CodexCommand {
             .items(&edit_choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match edit_choice {
             0 => self.edit_site_metadata(selected_site_name, selected_site)?,
@@ -544,12 +813,12 @@ impl CodexCommand {
             _ => return Ok(()),
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 编辑站点元数据
-    fn edit_site_metadata(&mut self, site_name: &str, site: &CodexSite) -> Result<(), String> {
+    fn edit_site_metadata(&mut self, site_name: &str, site: &CodexSite) -> Result<()> {
         println!("\n{}", style("编辑站点元数据").cyan());
         println!();
 
@@ -558,7 +827,10 @@ impl CodexCommand {
             .with_prompt("站点 URL")
             .default(site.metadata.url.clone())
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
+        if site.config.base_url.is_none() {
+            warn_duplicate_base_url(&new_url, &self.config_manager.codex().find_sites_with_base_url(&new_url, Some(site_name))?);
+        }
 
         // 编辑描述
         let current_desc = site.metadata.description.clone().unwrap_or_default();
@@ -567,7 +839,7 @@ impl CodexCommand {
             .default(current_desc)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_description = if new_description.is_empty() {
             None
@@ -575,10 +847,14 @@ impl CodexCommand {
             Some(new_description)
         };
 
+        // 编辑标签
+        let new_tags = input_tags(&site.metadata.tags)?;
+
         // 更新站点元数据
         self.config_manager
             .codex_mut()
             .update_site_metadata(site_name, Some(new_url), new_description)?;
+        self.config_manager.codex_mut().set_site_tags(site_name, new_tags)?;
 
         show_success("成功更新站点元数据");
 
@@ -586,7 +862,7 @@ impl CodexCommand {
     }
 
     /// 编辑站点配置
-    fn edit_site_config(&mut self, site_name: &str, site: &CodexSite) -> Result<(), String> {
+    fn edit_site_config(&mut self, site_name: &str, site: &CodexSite) -> Result<()> {
         println!("\n{}", style("编辑站点配置").cyan());
         println!();
 
@@ -597,13 +873,16 @@ impl CodexCommand {
             .default(current_base_url)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_base_url = if new_base_url.is_empty() {
             None
         } else {
             Some(new_base_url)
         };
+        if let Some(ref base_url) = new_base_url {
+            warn_duplicate_base_url(base_url, &self.config_manager.codex().find_sites_with_base_url(base_url, Some(site_name))?);
+        }
 
         // 编辑 Model
         let current_model = site.config.model.clone().unwrap_or_default();
@@ -612,7 +891,7 @@ impl CodexCommand {
             .default(current_model)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_model = if new_model.is_empty() {
             None
@@ -627,7 +906,7 @@ impl CodexCommand {
             .default(current_reasoning_effort)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_reasoning_effort = if new_reasoning_effort.is_empty() {
             None
@@ -642,7 +921,7 @@ impl CodexCommand {
             .default(current_provider)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_provider = if new_provider.is_empty() {
             None
@@ -651,19 +930,19 @@ impl CodexCommand {
         };
 
         // 编辑 Network Access
-        let current_network_access = site.config.network_access.clone().unwrap_or_default();
-        let new_network_access: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Network Access (enabled/disabled, 可选)")
-            .default(current_network_access)
-            .allow_empty(true)
-            .interact_text()
-            .map_err(|_| "用户取消操作")?;
+        let new_network_access = self.select_optional_value(
+            "Network Access",
+            CODEX_NETWORK_ACCESS_OPTIONS,
+            site.config.network_access.as_deref(),
+        )?;
 
-        let new_network_access = if new_network_access.is_empty() {
-            None
-        } else {
-            Some(new_network_access)
-        };
+        // 编辑 Disable Response Storage（未开启时清除该字段）
+        let new_disable_response_storage = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("禁用响应存储 (disable_response_storage)?")
+            .default(site.config.disable_response_storage.unwrap_or(false))
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?
+            .then_some(true);
 
         // 编辑 Wire API (使用选择器)
         let wire_api_options = vec!["不设置", "chat", "responses"];
@@ -684,7 +963,7 @@ impl CodexCommand {
             .items(&wire_api_options)
             .default(default_idx)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_wire_api = match wire_api_idx {
             1 => Some("chat".to_string()),
@@ -700,17 +979,64 @@ impl CodexCommand {
             new_reasoning_effort,
             new_provider,
             new_network_access,
-            None, // disable_response_storage (暂不在 UI 中编辑)
+            new_disable_response_storage,
             new_wire_api,
         )?;
 
+        // 编辑 Sandbox Mode 和 Approval Policy
+        let new_sandbox_mode = self.select_optional_value(
+            "Sandbox Mode",
+            CODEX_SANDBOX_MODES,
+            site.config.sandbox_mode.as_deref(),
+        )?;
+        let new_approval_policy = self.select_optional_value(
+            "Approval Policy",
+            CODEX_APPROVAL_POLICIES,
+            site.config.approval_policy.as_deref(),
+        )?;
+
+        self.config_manager.codex_mut().update_site_policies(
+            site_name,
+            new_sandbox_mode,
+            new_approval_policy,
+        )?;
+
         show_success("成功更新站点配置");
 
         Ok(())
     }
 
+    /// 从可选值列表中选择（第一项为"不设置"）
+    fn select_optional_value(
+        &self,
+        prompt: &str,
+        options: &[&str],
+        current: Option<&str>,
+    ) -> Result<Option<String>> {
+        let mut items = vec!["不设置"];
+        items.extend_from_slice(options);
+
+        let default_idx = current
+            .and_then(|value| options.iter().position(|o| *o == value))
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+
+        let idx = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(&items)
+            .default(default_idx)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        Ok(if idx == 0 {
+            None
+        } else {
+            Some(options[idx - 1].to_string())
+        })
+    }
+
     /// 编辑 API Key
-    fn edit_api_key(&mut self, site_name: &str, site: &CodexSite) -> Result<(), String> {
+    fn edit_api_key(&mut self, site_name: &str, site: &CodexSite) -> Result<()> {
         if site.api_keys.is_empty() {
             show_error("该站点没有 API Key");
             return Ok(());
@@ -720,29 +1046,16 @@ impl CodexCommand {
         println!();
 
         // 选择 API Key
-        let key_names: Vec<String> = site.api_keys.keys().cloned().collect();
-        let key_items: Vec<String> = key_names
-            .iter()
-            .map(|name| format!("🔑 {}", name))
-            .collect();
-
-        let key_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要编辑的 API Key")
-            .items(&key_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let key_name = &key_names[key_idx];
+        let key_name = &select_named("选择要编辑的 API Key", &site.api_keys, |name, _| format!("🔑 {}", name))?;
 
         // 输入新的 API Key 值
         let new_key: String = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("新的 API Key 值（输入不可见）")
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         if new_key.is_empty() {
-            return Err("API Key 值不能为空".to_string());
+            return Err("API Key 值不能为空".into());
         }
 
         // 更新 API Key
@@ -752,6 +1065,17 @@ impl CodexCommand {
 
         show_success(&format!("成功更新 API Key: {}", key_name));
 
+        // 编辑的是当前激活的 API Key 时，重新写入工具配置文件使新密钥生效
+        if self.config_manager.is_active_codex_key(site_name, key_name)? {
+            if self.record_only {
+                show_info("仅记录模式: 未重新写入工具配置文件，可稍后使用 ca-switch codex apply 写入");
+            } else if confirm("该 API Key 正在使用中，是否重新写入配置文件使其生效", true)? {
+                let written_paths = self.config_manager.apply_codex(false)?;
+                show_success("✨ 已重新写入当前激活的 Codex 配置");
+                show_written_paths_with_copy(&written_paths);
+            }
+        }
+
         Ok(())
     }
 
@@ -759,7 +1083,7 @@ impl CodexCommand {
     // 删除配置
     // ========================================================================
 
-    fn handle_delete(&mut self) -> Result<(), String> {
+    fn handle_delete(&mut self) -> Result<()> {
         println!("\n{}", style("🗑️  删除 Codex API 配置").cyan().bold());
         println!();
 
@@ -779,7 +1103,7 @@ impl CodexCommand {
             .items(&delete_choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match delete_choice {
             0 => self.delete_site()?,
@@ -788,62 +1112,109 @@ impl CodexCommand {
             _ => return Ok(()),
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 删除站点
-    fn delete_site(&mut self) -> Result<(), String> {
+    fn delete_site(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.codex().get_all_sites()?;
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
+        let selected_site = select_site("选择要删除的站点", &sites)?;
 
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        self.delete_site_by_name(&selected_site)
+    }
 
-        let selected_site = &site_names[site_idx];
+    /// 从现有的 ~/.codex/config.toml 与 auth.json 导入站点（同名站点覆盖前确认）
+    pub fn import_existing(&mut self) -> Result<()> {
+        let sites = self.config_manager.codex().read_existing_sites()?;
+        if sites.is_empty() {
+            show_warning("未在 ~/.codex/config.toml 中找到可导入的 model_providers 配置");
+            return Ok(());
+        }
 
-        // 确认删除
-        show_warning(&format!("⚠️  警告：即将删除站点 '{}'", selected_site));
-        let confirmed = confirm("确认删除", false).map_err(|e| e.to_string())?;
+        println!("\n{}", style("📥 从 ~/.codex/config.toml 导入").cyan().bold());
+        println!();
+        for (name, site) in &sites {
+            println!("  {} {}", style("站点:").white(), style(name).cyan());
+            println!("    {} {}", style("Base URL:").white(), style(&site.metadata.url).dim());
+            if let Some(ref model) = site.config.model {
+                println!("    {} {}", style("Model:").white(), style(model).yellow());
+            }
+            match site.api_keys.get(IMPORTED_KEY_NAME) {
+                Some(key) => println!("    {} {}", style("API Key:").white(), style(redact(key)).cyan()),
+                None => println!("    {} {}", style("API Key:").white(), style("未找到，导入后可手动添加").dim()),
+            }
+        }
+        println!();
 
-        if !confirmed {
+        if self.dry_run {
+            show_info("dry-run 模式: 仅显示将要导入的站点，未修改任何文件");
+            return Ok(());
+        }
+
+        if !confirm(&format!("确认导入 {} 个站点", sites.len()), true)? {
+            show_info("用户取消导入");
+            return Ok(());
+        }
+
+        let mut imported = Vec::new();
+        for (name, site) in sites {
+            if self.config_manager.codex().get_site(&name)?.is_some()
+                && !confirm(&format!("站点 '{}' 已存在，是否覆盖?", name), false)?
+            {
+                show_info(&format!("已跳过站点: {}", name));
+                continue;
+            }
+            self.config_manager.codex().upsert_site(&name, site)?;
+            imported.push(name);
+        }
+
+        if imported.is_empty() {
+            show_info("没有导入任何站点");
+            return Ok(());
+        }
+
+        show_success(&format!("成功导入 {} 个站点: {}", imported.len(), imported.join(", ")));
+        show_result(&imported.join("\n"));
+
+        Ok(())
+    }
+
+    /// 按名称删除站点（命令行 `codex delete <站点>` 也使用此方法）
+    pub fn delete_site_by_name(&mut self, site_name: &str) -> Result<()> {
+        if self.config_manager.codex().get_site(site_name)?.is_none() {
+            return Err(CliError::NotFound(format!("站点 '{}' 不存在", site_name)));
+        }
+
+        // 确认删除
+        show_warning(&format!("⚠️  警告：即将删除站点 '{}'", site_name));
+        if !confirm_destructive(&format!("确认删除站点 '{}'", site_name))? {
             show_info("用户取消删除");
             return Ok(());
         }
 
         // 执行删除
-        self.config_manager.codex_mut().delete_site(selected_site)?;
+        let cleared = self.config_manager.delete_codex_site(site_name)?;
 
-        show_success(&format!("成功删除站点: {}", selected_site));
+        show_success(&format!("成功删除站点: {}", site_name));
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
+        show_result(site_name);
 
         Ok(())
     }
 
-    /// 删除 API Key
-    fn delete_api_key(&mut self) -> Result<(), String> {
+    /// 删除 API Key（可多选批量删除）
+    fn delete_api_key(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.codex().get_all_sites()?;
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site("选择站点", &sites)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         if selected_site.api_keys.is_empty() {
@@ -851,28 +1222,58 @@ impl CodexCommand {
             return Ok(());
         }
 
-        // 选择 API Key
-        let key_names: Vec<String> = selected_site.api_keys.keys().cloned().collect();
-        let key_items: Vec<String> = key_names
+        // 当前激活的 API Key（仅当属于该站点时）
+        let active_name = self
+            .config_manager
+            .read_global_config()?
+            .active
+            .codex
+            .filter(|r| &r.site == selected_site_name)
+            .map(|r| r.api_key_name);
+
+        // 多选 API Key
+        let names: Vec<String> = sorted_keys(&selected_site.api_keys).into_iter().cloned().collect();
+        let items: Vec<String> = names
             .iter()
-            .map(|name| format!("🔑 {}", name))
+            .map(|name| {
+                if active_name.as_ref() == Some(name) {
+                    format!("🔑 {} (当前激活)", name)
+                } else {
+                    format!("🔑 {}", name)
+                }
+            })
             .collect();
 
-        let key_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的 API Key")
-            .items(&key_items)
-            .default(0)
+        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择要删除的 API Key (空格选择,回车确认)")
+            .items(&items)
+            .max_length(SELECT_PAGE_SIZE)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
+
+        if selections.is_empty() {
+            show_info("未选择任何 API Key");
+            return Ok(());
+        }
 
-        let selected_key = &key_names[key_idx];
+        let selected_names: Vec<&String> = selections.iter().map(|&i| &names[i]).collect();
+        let deletes_active = selected_names
+            .iter()
+            .any(|name| active_name.as_ref() == Some(*name));
 
         // 确认删除
         show_warning(&format!(
-            "⚠️  警告：即将删除站点 '{}' 的 API Key '{}'",
-            selected_site_name, selected_key
+            "⚠️  警告：即将删除站点 '{}' 的 {} 个 API Key:",
+            selected_site_name,
+            selected_names.len()
         ));
-        let confirmed = confirm("确认删除", false).map_err(|e| e.to_string())?;
+        for name in &selected_names {
+            println!("  - {}", name);
+        }
+        if deletes_active {
+            show_warning("其中包含当前激活的 API Key，删除后将清除当前激活配置");
+        }
+        let confirmed = confirm_destructive("确认删除")?;
 
         if !confirmed {
             show_info("用户取消删除");
@@ -880,11 +1281,18 @@ impl CodexCommand {
         }
 
         // 执行删除
-        self.config_manager
-            .codex_mut()
-            .delete_api_key(selected_site_name, selected_key)?;
+        let mut cleared = false;
+        for name in &selected_names {
+            cleared |= self
+                .config_manager
+                .delete_codex_api_key(selected_site_name, name)?;
+        }
+
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
 
-        show_success(&format!("成功删除 API Key: {}", selected_key));
+        show_success(&format!("成功删除 {} 个 API Key", selected_names.len()));
 
         Ok(())
     }
@@ -892,10 +1300,4 @@ impl CodexCommand {
     // ========================================================================
     // 辅助方法
     // ========================================================================
-
-    fn wait_for_back(&self) {
-        println!();
-        println!("{}", style("按回车键返回...").dim());
-        let _ = std::io::stdin().read_line(&mut String::new());
-    }
 }
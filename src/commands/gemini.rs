@@ -1,58 +1,131 @@
 // Gemini 命令模块 - 基于新架构重构
 // 支持新的配置文件结构：gemini.json + config.json
 
-use crate::config::{GeminiSite, ConfigManager};
-use crate::ui::{confirm, show_error, show_info, show_success, show_warning, ApiMenuChoice};
+use crate::commands::backup::auto_backup_after_switch;
+use crate::commands::key_import::{parse_key_batch, read_key_batch, report_key_batch};
+use crate::commands::verify::{select_auth_header_style, select_base_url, verify_before_switch};
+use crate::error::{CliError, Result};
+use crate::config::{
+    page_sites, project_dir, GeminiSite, GeminiSiteConfig, GeminiActiveReference, GeminiGenerationConfig, ConfigManager, SiteMetadata, SiteSummary,
+    GEMINI_FALLBACK_MODEL,
+};
+use crate::ui::{
+    confirm, confirm_destructive, input_tags, select_key, select_named, select_site, select_site_with_tag, show_action_error, show_error, show_file_diffs, show_info, show_success, show_warning, show_written_paths, show_written_paths_with_copy, warn_duplicate_base_url,
+    copy_to_clipboard, current_line, env_export_lines, redact, show_overwrite_diffs, show_result, sorted_keys,
+    wait_for_back, ApiMenuChoice, SELECT_PAGE_SIZE,
+};
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Input, Password, Select};
+use dialoguer::{theme::ColorfulTheme, Input, MultiSelect, Password, Select};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// `gemini list --json` 的输出 (API Key 已脱敏)
+#[derive(Serialize)]
+struct GeminiListView<'a> {
+    active: Option<GeminiActiveReference>,
+    sites: Vec<GeminiSiteView<'a>>,
+}
+
+/// 单个站点的 JSON 视图
+#[derive(Serialize)]
+struct GeminiSiteView<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    metadata: &'a SiteMetadata,
+    config: &'a GeminiSiteConfig,
+    /// API Key 名称 -> 脱敏后的值
+    api_keys: BTreeMap<&'a str, String>,
+}
+
+impl<'a> GeminiSiteView<'a> {
+    fn new(name: &'a str, site: &'a GeminiSite) -> Self {
+        Self {
+            name,
+            metadata: &site.metadata,
+            config: &site.config,
+            api_keys: site.api_keys.iter().map(|(key_name, key)| (key_name.as_str(), redact(key))).collect(),
+        }
+    }
+}
 
 /// Gemini API 管理命令
 pub struct GeminiCommand {
     config_manager: ConfigManager,
+    dry_run: bool,
+    /// 切换前是否先验证 Token
+    verify_before_switch: bool,
+    /// 切换时只更新激活记录，不写入工具配置文件
+    record_only: bool,
 }
 
 impl GeminiCommand {
     /// 创建新的 Gemini 命令实例
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> Result<Self> {
         Ok(Self {
             config_manager: ConfigManager::new()?,
+            dry_run: false,
+            verify_before_switch: false,
+            record_only: false,
         })
     }
 
+    /// 设置 dry-run 模式（切换时只预览，不写入文件）
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     /// 执行 Gemini API 管理命令
-    pub fn execute(&mut self) -> Result<(), String> {
+    pub fn execute(&mut self) -> Result<()> {
         loop {
-            let choice = crate::ui::show_api_menu("🌟 Gemini配置管理").map_err(|e| e.to_string())?;
+            let choice = crate::ui::show_api_menu("🌟 Gemini配置管理")?;
 
             match choice {
                 ApiMenuChoice::Switch => {
                     if let Err(e) = self.handle_switch() {
-                        show_error(&format!("切换配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("切换配置失败", &e);
                     }
                 }
                 ApiMenuChoice::List => {
                     if let Err(e) = self.handle_list() {
-                        show_error(&format!("查看配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("查看配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Add => {
                     if let Err(e) = self.handle_add() {
-                        show_error(&format!("添加配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("添加配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Edit => {
                     if let Err(e) = self.handle_edit() {
-                        show_error(&format!("编辑配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("编辑配置失败", &e);
                     }
                 }
                 ApiMenuChoice::Delete => {
                     if let Err(e) = self.handle_delete() {
-                        show_error(&format!("删除配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("删除配置失败", &e);
+                    }
+                }
+                ApiMenuChoice::CopyKey => {
+                    if let Err(e) = self.handle_copy_key() {
+                        show_action_error("复制密钥失败", &e);
+                    }
+                }
+                ApiMenuChoice::ToggleVerify => {
+                    self.verify_before_switch = !self.verify_before_switch;
+                    if self.verify_before_switch {
+                        show_info("已开启切换前验证");
+                    } else {
+                        show_info("已关闭切换前验证");
+                    }
+                }
+                ApiMenuChoice::ToggleRecordOnly => {
+                    self.record_only = !self.record_only;
+                    if self.record_only {
+                        show_info("已开启仅记录模式: 切换时不写入 ~/.gemini/");
+                    } else {
+                        show_info("已关闭仅记录模式");
                     }
                 }
                 ApiMenuChoice::Back => break,
@@ -66,7 +139,14 @@ impl GeminiCommand {
     // 切换配置
     // ========================================================================
 
-    fn handle_switch(&mut self) -> Result<(), String> {
+    fn handle_switch(&mut self) -> Result<()> {
+        self.switch_site(None)?;
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 选择站点与密钥并切换（命令行 `gemini switch [--tag <标签>]`），指定标签时只在带该标签的站点中选择
+    pub fn switch_site(&mut self, tag: Option<&str>) -> Result<()> {
         println!("\n{}", style("🔄 切换 Gemini API 配置").cyan().bold());
         println!();
 
@@ -79,23 +159,7 @@ impl GeminiCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names
-            .iter()
-            .map(|name| {
-                let site = sites.get(name).unwrap();
-                format!("🌐 {} ({})", name, site.metadata.url)
-            })
-            .collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site_with_tag("选择站点", &sites, tag)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         // 检查是否有 API Keys
@@ -105,30 +169,15 @@ impl GeminiCommand {
         }
 
         // 选择 API Key
-        let key_names: Vec<String> = selected_site.api_keys.keys().cloned().collect();
-        let key_items: Vec<String> = key_names
-            .iter()
-            .map(|name| {
-                let key = selected_site.api_keys.get(name).unwrap();
-                let preview = if key.len() > 20 {
-                    format!("{}...", &key[..20])
-                } else {
-                    key.clone()
-                };
-                format!("🔑 {} ({})", name, preview)
-            })
-            .collect();
-
-        let key_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择 API Key")
-            .items(&key_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_key_name = &key_names[key_idx];
+        let selected_key_name = &select_key("选择 API Key", &selected_site.api_keys, &selected_site.usage)?;
         let selected_key = selected_site.api_keys.get(selected_key_name).unwrap();
 
+        // 按磁盘上的最新配置校验，站点或 API Key 可能已在别处被删除
+        if let Err(e) = self.config_manager.resolve_gemini_switch(selected_site_name, selected_key_name) {
+            show_error(&format!("无法切换到 {} - {}: {}", selected_site_name, selected_key_name, e));
+            return Ok(());
+        }
+
         // 显示将要切换到的配置
         println!("\n{}", style("📋 即将切换到以下配置：").white());
         println!();
@@ -143,36 +192,187 @@ impl GeminiCommand {
         println!(
             "  {} {}",
             style("API Key:").white(),
-            style(format!("{}...", &selected_key[..20.min(selected_key.len())])).cyan()
+            style(redact(selected_key)).cyan()
         );
         println!();
 
+        // dry-run: 只显示将要写入的变更
+        if self.dry_run && self.record_only {
+            show_info("dry-run 模式: 仅记录模式下只会更新 config.json 中的激活记录，未修改任何文件");
+            return Ok(());
+        }
+        if self.dry_run {
+            let files = self
+                .config_manager
+                .preview_gemini_switch(selected_site_name, selected_key_name)?;
+
+            show_info("dry-run 模式: 以下为将要写入的变更，未修改任何文件");
+            show_file_diffs(&files);
+            return Ok(());
+        }
+
+        // 可选: 切换前验证 Token
+        if self.verify_before_switch {
+            let base_url = selected_site
+                .config
+                .base_url
+                .as_deref()
+                .unwrap_or(&selected_site.metadata.url);
+            if !verify_before_switch(&self.config_manager, base_url, selected_key, selected_site.auth_header_style())? {
+                show_info("用户取消切换");
+                return Ok(());
+            }
+        }
+
+        // 可选: 覆盖已有文件前先显示 diff（config.json 中的 settings.diff_before_overwrite）
+        let overwrites = !self.record_only
+            && self.config_manager.read_global_config()?.settings.diff_before_overwrite
+            && show_overwrite_diffs(
+                &self
+                    .config_manager
+                    .preview_gemini_switch(selected_site_name, selected_key_name)?,
+            );
+
         // 确认切换
-        let confirmed = confirm("确认切换配置", true).map_err(|e| e.to_string())?;
+        let confirmed = if overwrites {
+            confirm_destructive("确认覆盖以上变更并切换")?
+        } else {
+            confirm("确认切换配置", true)?
+        };
 
         if !confirmed {
             show_info("用户取消切换");
             return Ok(());
         }
 
-        // 执行切换
-        self.config_manager
-            .switch_gemini_config(selected_site_name, selected_key_name)?;
+        // 执行切换（仅记录模式下只更新 config.json 中的激活引用）
+        let written_paths = if self.record_only {
+            self.config_manager.set_active_gemini(selected_site_name, selected_key_name)?;
+            Vec::new()
+        } else {
+            self.config_manager.switch_gemini_config(selected_site_name, selected_key_name)?
+        };
 
         show_success(&format!(
             "✨ 成功切换到配置: {} - {}",
             selected_site_name, selected_key_name
         ));
-        self.wait_for_back();
+        if self.record_only {
+            show_info("仅记录模式: 未写入 ~/.gemini/");
+        }
+        show_result(&format!("{}/{}", selected_site_name, selected_key_name));
+        show_written_paths_with_copy(&written_paths);
+        if !self.record_only {
+            auto_backup_after_switch(&self.config_manager, "gemini");
+        }
+        Ok(())
+    }
+
+    /// 将当前激活的 API Key 复制到剪贴板
+    fn handle_copy_key(&self) -> Result<()> {
+        let Some(active_config) = self.config_manager.get_active_gemini_config()? else {
+            show_error("当前没有激活的 Gemini 配置，请先切换");
+            wait_for_back();
+            return Ok(());
+        };
+
+        show_warning("剪贴板将保存完整的 API Key，其他程序也能读取剪贴板内容");
+        let prompt = format!(
+            "复制 {} - {} 的 API Key 到剪贴板",
+            active_config.site, active_config.api_key_name
+        );
+        if !confirm(&prompt, false)? {
+            show_info("操作已取消");
+            wait_for_back();
+            return Ok(());
+        }
+
+        copy_to_clipboard(&active_config.api_key)?;
+        show_success("API Key 已复制到剪贴板");
+        show_info("使用后请及时清空剪贴板 (例如复制其他内容覆盖)");
+        wait_for_back();
+
+        Ok(())
+    }
+
+    /// 重新写入当前激活的配置（命令行 `gemini apply [--project]`）
+    pub fn apply_active(&self, project: bool) -> Result<()> {
+        if self.dry_run {
+            let files = self.config_manager.preview_gemini_apply(project)?;
+            show_info("dry-run 模式: 以下为将要写入的变更，未修改任何文件");
+            show_file_diffs(&files);
+            return Ok(());
+        }
+
+        let written_paths = self.config_manager.apply_gemini(project)?;
+
+        if project {
+            let dir = project_dir(".gemini")?;
+            show_success(&format!("✨ 已应用到当前项目: {}", dir.display()));
+            show_result(&dir.display().to_string());
+        } else {
+            show_success("✨ 已重新写入当前激活的 Gemini 配置");
+        }
+        show_written_paths(&written_paths);
 
         Ok(())
     }
 
+    /// 输出当前激活配置的环境变量（命令行 `gemini env [--fish]`，不写入任何文件）
+    pub fn print_env(&self, fish: bool) -> Result<()> {
+        let vars = self.config_manager.gemini_env_vars()?;
+        print!("{}", env_export_lines(&vars, fish));
+        Ok(())
+    }
+
+    /// 单行输出当前激活的配置（命令行 `gemini current`），没有激活的配置时返回错误
+    pub fn print_current(&self) -> Result<()> {
+        let active = self.config_manager.require_active_gemini()?;
+        println!("{}", current_line("gemini", &active.site, &active.api_key_name, active.model.as_deref()));
+        Ok(())
+    }
+
     // ========================================================================
     // 查看配置
     // ========================================================================
 
-    fn handle_list(&self) -> Result<(), String> {
+    /// 列表中显示的站点: 按名称排序，指定标签时先按标签过滤，再取 offset/limit 指定的一页
+    fn listed_sites(&self, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<Vec<(String, GeminiSite)>> {
+        let gemini = self.config_manager.gemini();
+        let Some(tag) = tag else {
+            return gemini.get_sites_page(offset, limit);
+        };
+
+        let tagged = gemini.get_sites_sorted()?.into_iter().filter(|(_, site)| site.has_tag(tag)).collect();
+        Ok(page_sites(tagged, offset, limit))
+    }
+
+    fn handle_list(&self) -> Result<()> {
+        self.print_list(None, 0, None)?;
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 列出所有站点（命令行 `gemini list [--json]`）
+    pub fn list(&self, json: bool, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<()> {
+        if !json {
+            return self.print_list(tag, offset, limit);
+        }
+
+        let sites = self.listed_sites(tag, offset, limit)?;
+        let view = GeminiListView {
+            active: self.config_manager.read_global_config()?.active.gemini,
+            sites: sites.iter().map(|(name, site)| GeminiSiteView::new(name, site)).collect(),
+        };
+
+        let content = serde_json::to_string_pretty(&view)
+            .map_err(|e| format!("序列化站点列表失败: {}", e))?;
+        println!("{}", content);
+        Ok(())
+    }
+
+    /// 以文本形式输出所有站点与当前激活的配置，指定标签时只列出带该标签的站点
+    fn print_list(&self, tag: Option<&str>, offset: usize, limit: Option<usize>) -> Result<()> {
         println!("\n{}", style("📋 Gemini API 配置列表").cyan().bold());
         println!();
 
@@ -194,7 +394,16 @@ impl GeminiCommand {
                 println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
             }
             if let Some(ref model) = active_config.model {
-                println!("  {} {}", style("Model:").white(), style(model).yellow());
+                if active_config.model_is_default {
+                    println!(
+                        "  {} {} {}",
+                        style("Model:").white(),
+                        style(model).yellow(),
+                        style("(默认)").dim()
+                    );
+                } else {
+                    println!("  {} {}", style("Model:").white(), style(model).yellow());
+                }
             }
             println!();
         } else {
@@ -203,14 +412,21 @@ impl GeminiCommand {
         }
 
         // 显示所有站点
-        let sites = self.config_manager.gemini().get_all_sites()?;
+        let sites = self.listed_sites(tag, offset, limit)?;
 
         if sites.is_empty() {
             show_info("没有可用的站点配置");
-            self.wait_for_back();
             return Ok(());
         }
 
+        let default_model = self.config_manager.gemini().get_default_model()?;
+        println!(
+            "{} {}",
+            style("默认模型:").white(),
+            style(&default_model).yellow()
+        );
+        println!();
+
         println!("{}", style("🌐 所有可用站点:").white().bold());
         println!();
 
@@ -222,6 +438,10 @@ impl GeminiCommand {
                 println!("  {} {}", style("描述:").white(), style(desc).dim());
             }
 
+            if !site.metadata.tags.is_empty() {
+                println!("  {} {}", style("标签:").white(), style(site.metadata.tags.join(", ")).magenta());
+            }
+
             if let Some(ref base_url) = site.config.base_url {
                 println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
             }
@@ -231,19 +451,14 @@ impl GeminiCommand {
             }
 
             println!("  {} {}", style("API Keys:").white(), style(site.api_keys.len()).yellow());
-            for (key_name, key) in &site.api_keys {
-                let preview = if key.len() > 20 {
-                    format!("{}...", &key[..20])
-                } else {
-                    key.clone()
-                };
-                println!("    - {} ({})", style(key_name).cyan(), style(preview).dim());
+            for key_name in sorted_keys(&site.api_keys) {
+                let key = &site.api_keys[key_name];
+                println!("    - {} ({})", style(key_name).cyan(), style(redact(key)).dim());
             }
 
             println!();
         }
 
-        self.wait_for_back();
         Ok(())
     }
 
@@ -251,30 +466,31 @@ impl GeminiCommand {
     // 添加配置
     // ========================================================================
 
-    fn handle_add(&mut self) -> Result<(), String> {
+    fn handle_add(&mut self) -> Result<()> {
         println!("\n{}", style("➕ 添加 Gemini API 配置").cyan().bold());
         println!();
 
         // 选择操作类型
-        let choices = vec!["添加新站点", "在已有站点中添加 API Key", "返回"];
+        let choices = vec!["添加新站点", "在已有站点中添加 API Key", "批量导入 Key", "返回"];
 
         let choice = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("选择操作")
             .items(&choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match choice {
             0 => self.add_new_site(),
             1 => self.add_key_to_existing_site(),
-            2 => Ok(()),
+            2 => self.import_keys_to_existing_site(),
+            3 => Ok(()),
             _ => Ok(()),
         }
     }
 
     /// 添加新站点
-    fn add_new_site(&mut self) -> Result<(), String> {
+    fn add_new_site(&mut self) -> Result<()> {
         println!("\n{}", style("创建新站点").cyan().bold());
         println!();
 
@@ -282,7 +498,7 @@ impl GeminiCommand {
         let site_name: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("站点名称")
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // 检查站点是否已存在
         if self
@@ -291,22 +507,19 @@ impl GeminiCommand {
             .get_site(&site_name)?
             .is_some()
         {
-            return Err(format!("站点 '{}' 已存在", site_name));
+            return Err(format!("站点 '{}' 已存在", site_name).into());
         }
 
-        // 输入 Base URL
-        let base_url: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("API Base URL")
-            .default("https://generativelanguage.googleapis.com".to_string())
-            .interact_text()
-            .map_err(|_| "用户取消操作")?;
+        // 选择或输入 Base URL
+        let base_url = select_base_url(GeminiSite::BASE_URL_PRESETS)?;
+        warn_duplicate_base_url(&base_url, &self.config_manager.gemini().find_sites_with_base_url(&base_url, None)?);
 
         // 输入描述（可选）
         let description: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("站点描述（可选）")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let description = if description.is_empty() {
             None
@@ -314,13 +527,16 @@ impl GeminiCommand {
             Some(description)
         };
 
-        // 输入 Model（可选）
+        // 输入标签（可选）
+        let tags = input_tags(&[])?;
+
+        // 输入 Model（可选，留空则继承默认模型）
+        let default_model = self.config_manager.gemini().get_default_model()?;
         let model: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("默认模型（可选）")
-            .default("gemini-2.0-flash-exp".to_string())
+            .with_prompt(format!("模型（留空使用默认模型 {}）", default_model))
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let model = if model.is_empty() {
             None
@@ -328,10 +544,16 @@ impl GeminiCommand {
             Some(model)
         };
 
+        // 选择认证头格式（预选该 provider 的默认值）
+        let auth_header_style = select_auth_header_style(GeminiSite::DEFAULT_AUTH_HEADER_STYLE)?;
+
         // 创建站点（使用 base_url 作为 url）
         self.config_manager
             .gemini_mut()
             .add_site(site_name.clone(), base_url.clone(), description)?;
+        if !tags.is_empty() {
+            self.config_manager.gemini_mut().set_site_tags(&site_name, tags)?;
+        }
 
         // 更新站点配置（设置 base_url 和 model）
         self.config_manager.gemini_mut().update_site_config(
@@ -340,22 +562,26 @@ impl GeminiCommand {
             model,
         )?;
 
+        self.config_manager
+            .gemini_mut()
+            .set_auth_header_style(&site_name, auth_header_style)?;
+
         show_success(&format!("成功创建站点: {}", site_name));
 
         // 询问是否立即添加 API Key
         println!();
-        let add_key = confirm("是否立即添加 API Key", true).map_err(|e| e.to_string())?;
+        let add_key = confirm("是否立即添加 API Key", true)?;
 
         if add_key {
             self.add_key_to_site(&site_name)?;
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 在已有站点中添加 API Key
-    fn add_key_to_existing_site(&mut self) -> Result<(), String> {
+    fn add_key_to_existing_site(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.gemini().get_all_sites()?;
 
@@ -365,25 +591,51 @@ impl GeminiCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
+        let selected_site = &select_site("选择站点", &sites)?;
 
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        self.add_key_to_site(selected_site)?;
+        wait_for_back();
+        Ok(())
+    }
 
-        let selected_site = &site_names[site_idx];
+    /// 批量导入 API Key 到已有站点（粘贴列表或从文件读取）
+    fn import_keys_to_existing_site(&mut self) -> Result<()> {
+        let sites = self.config_manager.gemini().get_all_sites()?;
 
-        self.add_key_to_site(selected_site)?;
-        self.wait_for_back();
+        if sites.is_empty() {
+            show_error("没有可用的站点，请先添加站点");
+            return Ok(());
+        }
+
+        let site_name = select_site("选择站点", &sites)?;
+        let existing: Vec<String> = sites[&site_name].api_keys.keys().cloned().collect();
+
+        let text = read_key_batch("API Key")?;
+        let mut batch = parse_key_batch(&text, &existing, "Key");
+
+        let mut added = Vec::new();
+        for (key_name, value) in batch.keys {
+            match self.config_manager.gemini_mut().add_api_key(&site_name, key_name.clone(), value) {
+                Ok(()) => added.push(key_name),
+                Err(e) => batch.skipped.push(format!("API Key '{}' 添加失败: {}", key_name, e)),
+            }
+        }
+
+        report_key_batch("API Key", added.len(), &batch.skipped);
+
+        // 站点原本没有 API Key: 询问是否立即激活导入的第一个
+        if existing.is_empty() {
+            if let Some(first) = added.first() {
+                self.activate_new_key(&site_name, first)?;
+            }
+        }
+
+        wait_for_back();
         Ok(())
     }
 
     /// 添加 API Key 到指定站点
-    fn add_key_to_site(&mut self, site_name: &str) -> Result<(), String> {
+    fn add_key_to_site(&mut self, site_name: &str) -> Result<()> {
         println!("\n{}", style(format!("为站点 '{}' 添加 API Key", site_name)).cyan());
         println!();
 
@@ -392,16 +644,16 @@ impl GeminiCommand {
             .with_prompt("API Key 名称")
             .default("主账号".to_string())
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // 输入 API Key 值
         let api_key: String = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("API Key 值（输入不可见）")
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         if api_key.is_empty() {
-            return Err("API Key 值不能为空".to_string());
+            return Err("API Key 值不能为空".into());
         }
 
         // 添加 API Key
@@ -411,6 +663,38 @@ impl GeminiCommand {
 
         show_success(&format!("成功添加 API Key: {}", key_name));
 
+        // 站点的第一个 API Key: 询问是否立即激活
+        let key_count = self.config_manager.gemini().get_site(site_name)?.map_or(0, |site| site.api_keys.len());
+        if key_count == 1 {
+            self.activate_new_key(site_name, &key_name)?;
+        }
+
+        Ok(())
+    }
+
+    /// 新添加的 API Key 询问后立即激活（dry-run 下不询问）
+    fn activate_new_key(&mut self, site_name: &str, key_name: &str) -> Result<()> {
+        if self.dry_run || !confirm("是否立即激活此配置?", true)? {
+            return Ok(());
+        }
+
+        let written_paths = if self.record_only {
+            self.config_manager.set_active_gemini(site_name, key_name)?;
+            Vec::new()
+        } else {
+            self.config_manager.switch_gemini_config(site_name, key_name)?
+        };
+
+        show_success(&format!("✨ 成功切换到配置: {} - {}", site_name, key_name));
+        if self.record_only {
+            show_info("仅记录模式: 未写入 ~/.gemini/");
+        }
+        show_result(&format!("{}/{}", site_name, key_name));
+        show_written_paths_with_copy(&written_paths);
+        if !self.record_only {
+            auto_backup_after_switch(&self.config_manager, "gemini");
+        }
+
         Ok(())
     }
 
@@ -418,10 +702,29 @@ impl GeminiCommand {
     // 编辑配置
     // ========================================================================
 
-    fn handle_edit(&mut self) -> Result<(), String> {
+    fn handle_edit(&mut self) -> Result<()> {
         println!("\n{}", style("✏️  编辑 Gemini API 配置").cyan().bold());
         println!();
 
+        let top_choices = vec!["编辑站点", "设置默认模型", "返回"];
+
+        let top_choice = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择操作")
+            .items(&top_choices)
+            .default(0)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        match top_choice {
+            0 => {}
+            1 => {
+                self.set_default_model()?;
+                wait_for_back();
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+
         // 获取所有站点
         let sites = self.config_manager.gemini().get_all_sites()?;
 
@@ -431,23 +734,14 @@ impl GeminiCommand {
         }
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要编辑的站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site("选择要编辑的站点", &sites)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         // 选择编辑类型
         let edit_choices = vec![
-            "编辑站点元数据（URL、描述）",
+            "编辑站点元数据（URL、描述、标签）",
             "编辑站点配置（Base URL、Model）",
+            "编辑生成参数（temperature、top_p、安全阈值）",
             "编辑 API Key",
             "返回",
         ];
@@ -457,22 +751,51 @@ impl GeminiCommand {
             .items(&edit_choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match edit_choice {
             0 => self.edit_site_metadata(selected_site_name, selected_site)?,
             1 => self.edit_site_config(selected_site_name, selected_site)?,
-            2 => self.edit_api_key(selected_site_name, selected_site)?,
-            3 => return Ok(()),
+            2 => self.edit_generation(selected_site_name, selected_site)?,
+            3 => self.edit_api_key(selected_site_name, selected_site)?,
+            4 => return Ok(()),
             _ => return Ok(()),
         }
 
-        self.wait_for_back();
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 设置默认模型（站点未指定模型时使用）
+    fn set_default_model(&mut self) -> Result<()> {
+        let current = self.config_manager.gemini().get_default_model()?;
+
+        let new_model: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("默认模型（留空恢复内置默认 {}）", GEMINI_FALLBACK_MODEL))
+            .default(current)
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let new_model = new_model.trim().to_string();
+        let new_model = if new_model.is_empty() {
+            None
+        } else {
+            Some(new_model)
+        };
+
+        self.config_manager
+            .gemini_mut()
+            .set_default_model(new_model)?;
+
+        let effective = self.config_manager.gemini().get_default_model()?;
+        show_success(&format!("默认模型已设置为: {}", effective));
+
         Ok(())
     }
 
     /// 编辑站点元数据
-    fn edit_site_metadata(&mut self, site_name: &str, site: &GeminiSite) -> Result<(), String> {
+    fn edit_site_metadata(&mut self, site_name: &str, site: &GeminiSite) -> Result<()> {
         println!("\n{}", style("编辑站点元数据").cyan());
         println!();
 
@@ -481,7 +804,10 @@ impl GeminiCommand {
             .with_prompt("站点 URL")
             .default(site.metadata.url.clone())
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
+        if site.config.base_url.is_none() {
+            warn_duplicate_base_url(&new_url, &self.config_manager.gemini().find_sites_with_base_url(&new_url, Some(site_name))?);
+        }
 
         // 编辑描述
         let current_desc = site.metadata.description.clone().unwrap_or_default();
@@ -490,7 +816,7 @@ impl GeminiCommand {
             .default(current_desc)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_description = if new_description.is_empty() {
             None
@@ -498,10 +824,14 @@ impl GeminiCommand {
             Some(new_description)
         };
 
+        // 编辑标签
+        let new_tags = input_tags(&site.metadata.tags)?;
+
         // 更新站点元数据
         self.config_manager
             .gemini_mut()
             .update_site_metadata(site_name, Some(new_url), new_description)?;
+        self.config_manager.gemini_mut().set_site_tags(site_name, new_tags)?;
 
         show_success("成功更新站点元数据");
 
@@ -509,7 +839,7 @@ impl GeminiCommand {
     }
 
     /// 编辑站点配置
-    fn edit_site_config(&mut self, site_name: &str, site: &GeminiSite) -> Result<(), String> {
+    fn edit_site_config(&mut self, site_name: &str, site: &GeminiSite) -> Result<()> {
         println!("\n{}", style("编辑站点配置").cyan());
         println!();
 
@@ -520,13 +850,16 @@ impl GeminiCommand {
             .default(current_base_url)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_base_url = if new_base_url.is_empty() {
             None
         } else {
             Some(new_base_url)
         };
+        if let Some(ref base_url) = new_base_url {
+            warn_duplicate_base_url(base_url, &self.config_manager.gemini().find_sites_with_base_url(base_url, Some(site_name))?);
+        }
 
         // 编辑 Model
         let current_model = site.config.model.clone().unwrap_or_default();
@@ -535,7 +868,7 @@ impl GeminiCommand {
             .default(current_model)
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_model = if new_model.is_empty() {
             None
@@ -555,8 +888,76 @@ impl GeminiCommand {
         Ok(())
     }
 
+    /// 编辑生成参数与安全阈值（切换时写入 ~/.gemini/settings.json）
+    fn edit_generation(&mut self, site_name: &str, site: &GeminiSite) -> Result<()> {
+        println!("\n{}", style("编辑生成参数").cyan());
+        println!();
+
+        let current = site.config.generation.clone().unwrap_or_default();
+        let generation = GeminiGenerationConfig {
+            temperature: input_optional_number("temperature（0-2，留空不设置）", current.temperature, |v| {
+                (0.0..=2.0).contains(v)
+            })?,
+            top_p: input_optional_number("top_p（0-1，留空不设置）", current.top_p, |v| (0.0..=1.0).contains(v))?,
+            max_output_tokens: input_optional_number(
+                "max_output_tokens（正整数，留空不设置）",
+                current.max_output_tokens,
+                |v| *v > 0,
+            )?,
+        };
+
+        // 选择需要设置阈值的安全类别，未选中的类别将被清除
+        let categories = GeminiSiteConfig::SAFETY_CATEGORIES;
+        let defaults: Vec<bool> = categories
+            .iter()
+            .map(|category| site.config.safety.contains_key(*category))
+            .collect();
+        let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择需要设置阈值的安全类别（空格选择，回车确认）")
+            .items(categories)
+            .defaults(&defaults)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        // 保留手动添加的其他类别
+        let mut safety: HashMap<String, String> = site
+            .config
+            .safety
+            .iter()
+            .filter(|(category, _)| !categories.contains(&category.as_str()))
+            .map(|(category, threshold)| (category.clone(), threshold.clone()))
+            .collect();
+
+        let thresholds = GeminiSiteConfig::SAFETY_THRESHOLDS;
+        for idx in selected {
+            let category = categories[idx];
+            let default = site
+                .config
+                .safety
+                .get(category)
+                .and_then(|threshold| thresholds.iter().position(|t| t == threshold))
+                .unwrap_or(0);
+            let threshold = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("{} 的阈值", category))
+                .items(thresholds)
+                .default(default)
+                .interact()
+                .map_err(|_| CliError::UserCancelled)?;
+            safety.insert(category.to_string(), thresholds[threshold].to_string());
+        }
+
+        self.config_manager
+            .gemini_mut()
+            .update_site_generation(site_name, Some(generation), safety)?;
+
+        show_success("成功更新生成参数");
+        show_info("切换到该站点后将写入 ~/.gemini/settings.json");
+
+        Ok(())
+    }
+
     /// 编辑 API Key
-    fn edit_api_key(&mut self, site_name: &str, site: &GeminiSite) -> Result<(), String> {
+    fn edit_api_key(&mut self, site_name: &str, site: &GeminiSite) -> Result<()> {
         if site.api_keys.is_empty() {
             show_error("该站点没有 API Key");
             return Ok(());
@@ -566,29 +967,16 @@ impl GeminiCommand {
         println!();
 
         // 选择 API Key
-        let key_names: Vec<String> = site.api_keys.keys().cloned().collect();
-        let key_items: Vec<String> = key_names
-            .iter()
-            .map(|name| format!("🔑 {}", name))
-            .collect();
-
-        let key_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要编辑的 API Key")
-            .items(&key_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let key_name = &key_names[key_idx];
+        let key_name = &select_named("选择要编辑的 API Key", &site.api_keys, |name, _| format!("🔑 {}", name))?;
 
         // 输入新的 API Key 值
         let new_key: String = Password::with_theme(&ColorfulTheme::default())
             .with_prompt("新的 API Key 值（输入不可见）")
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         if new_key.is_empty() {
-            return Err("API Key 值不能为空".to_string());
+            return Err("API Key 值不能为空".into());
         }
 
         // 更新 API Key
@@ -598,6 +986,17 @@ impl GeminiCommand {
 
         show_success(&format!("成功更新 API Key: {}", key_name));
 
+        // 编辑的是当前激活的 API Key 时，重新写入工具配置文件使新密钥生效
+        if self.config_manager.is_active_gemini_key(site_name, key_name)? {
+            if self.record_only {
+                show_info("仅记录模式: 未重新写入工具配置文件，可稍后使用 ca-switch gemini apply 写入");
+            } else if confirm("该 API Key 正在使用中，是否重新写入配置文件使其生效", true)? {
+                let written_paths = self.config_manager.apply_gemini(false)?;
+                show_success("✨ 已重新写入当前激活的 Gemini 配置");
+                show_written_paths_with_copy(&written_paths);
+            }
+        }
+
         Ok(())
     }
 
@@ -605,7 +1004,7 @@ impl GeminiCommand {
     // 删除配置
     // ========================================================================
 
-    fn handle_delete(&mut self) -> Result<(), String> {
+    fn handle_delete(&mut self) -> Result<()> {
         println!("\n{}", style("🗑️  删除 Gemini API 配置").cyan().bold());
         println!();
 
@@ -625,7 +1024,7 @@ impl GeminiCommand {
             .items(&delete_choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match delete_choice {
             0 => self.delete_site()?,
@@ -634,62 +1033,53 @@ impl GeminiCommand {
             _ => return Ok(()),
         }
 
-        self.wait_for_back();
+        wait_for_back();
         Ok(())
     }
 
     /// 删除站点
-    fn delete_site(&mut self) -> Result<(), String> {
+    fn delete_site(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.gemini().get_all_sites()?;
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
+        let selected_site = select_site("选择要删除的站点", &sites)?;
 
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        self.delete_site_by_name(&selected_site)
+    }
 
-        let selected_site = &site_names[site_idx];
+    /// 按名称删除站点（命令行 `gemini delete <站点>` 也使用此方法）
+    pub fn delete_site_by_name(&mut self, site_name: &str) -> Result<()> {
+        if self.config_manager.gemini().get_site(site_name)?.is_none() {
+            return Err(CliError::NotFound(format!("站点 '{}' 不存在", site_name)));
+        }
 
         // 确认删除
-        show_warning(&format!("⚠️  警告：即将删除站点 '{}'", selected_site));
-        let confirmed = confirm("确认删除", false).map_err(|e| e.to_string())?;
-
-        if !confirmed {
+        show_warning(&format!("⚠️  警告：即将删除站点 '{}'", site_name));
+        if !confirm_destructive(&format!("确认删除站点 '{}'", site_name))? {
             show_info("用户取消删除");
             return Ok(());
         }
 
         // 执行删除
-        self.config_manager.gemini_mut().delete_site(selected_site)?;
+        let cleared = self.config_manager.delete_gemini_site(site_name)?;
 
-        show_success(&format!("成功删除站点: {}", selected_site));
+        show_success(&format!("成功删除站点: {}", site_name));
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
+        show_result(site_name);
 
         Ok(())
     }
 
-    /// 删除 API Key
-    fn delete_api_key(&mut self) -> Result<(), String> {
+    /// 删除 API Key（可多选批量删除）
+    fn delete_api_key(&mut self) -> Result<()> {
         // 获取所有站点
         let sites = self.config_manager.gemini().get_all_sites()?;
 
         // 选择站点
-        let site_names: Vec<String> = sites.keys().cloned().collect();
-        let site_items: Vec<String> = site_names.iter().map(|name| format!("🌐 {}", name)).collect();
-
-        let site_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择站点")
-            .items(&site_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let selected_site_name = &site_names[site_idx];
+        let selected_site_name = &select_site("选择站点", &sites)?;
         let selected_site = sites.get(selected_site_name).unwrap();
 
         if selected_site.api_keys.is_empty() {
@@ -697,28 +1087,58 @@ impl GeminiCommand {
             return Ok(());
         }
 
-        // 选择 API Key
-        let key_names: Vec<String> = selected_site.api_keys.keys().cloned().collect();
-        let key_items: Vec<String> = key_names
+        // 当前激活的 API Key（仅当属于该站点时）
+        let active_name = self
+            .config_manager
+            .read_global_config()?
+            .active
+            .gemini
+            .filter(|r| &r.site == selected_site_name)
+            .map(|r| r.api_key_name);
+
+        // 多选 API Key
+        let names: Vec<String> = sorted_keys(&selected_site.api_keys).into_iter().cloned().collect();
+        let items: Vec<String> = names
             .iter()
-            .map(|name| format!("🔑 {}", name))
+            .map(|name| {
+                if active_name.as_ref() == Some(name) {
+                    format!("🔑 {} (当前激活)", name)
+                } else {
+                    format!("🔑 {}", name)
+                }
+            })
             .collect();
 
-        let key_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的 API Key")
-            .items(&key_items)
-            .default(0)
+        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择要删除的 API Key (空格选择,回车确认)")
+            .items(&items)
+            .max_length(SELECT_PAGE_SIZE)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
-        let selected_key = &key_names[key_idx];
+        if selections.is_empty() {
+            show_info("未选择任何 API Key");
+            return Ok(());
+        }
+
+        let selected_names: Vec<&String> = selections.iter().map(|&i| &names[i]).collect();
+        let deletes_active = selected_names
+            .iter()
+            .any(|name| active_name.as_ref() == Some(*name));
 
         // 确认删除
         show_warning(&format!(
-            "⚠️  警告：即将删除站点 '{}' 的 API Key '{}'",
-            selected_site_name, selected_key
+            "⚠️  警告：即将删除站点 '{}' 的 {} 个 API Key:",
+            selected_site_name,
+            selected_names.len()
         ));
-        let confirmed = confirm("确认删除", false).map_err(|e| e.to_string())?;
+        for name in &selected_names {
+            println!("  - {}", name);
+        }
+        if deletes_active {
+            show_warning("其中包含当前激活的 API Key，删除后将清除当前激活配置");
+        }
+        let confirmed = confirm_destructive("确认删除")?;
 
         if !confirmed {
             show_info("用户取消删除");
@@ -726,11 +1146,18 @@ impl GeminiCommand {
         }
 
         // 执行删除
-        self.config_manager
-            .gemini_mut()
-            .delete_api_key(selected_site_name, selected_key)?;
+        let mut cleared = false;
+        for name in &selected_names {
+            cleared |= self
+                .config_manager
+                .delete_gemini_api_key(selected_site_name, name)?;
+        }
+
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
 
-        show_success(&format!("成功删除 API Key: {}", selected_key));
+        show_success(&format!("成功删除 {} 个 API Key", selected_names.len()));
 
         Ok(())
     }
@@ -738,10 +1165,32 @@ impl GeminiCommand {
     // ========================================================================
     // 辅助方法
     // ========================================================================
+}
 
-    fn wait_for_back(&self) {
-        println!();
-        println!("{}", style("按回车键返回...").dim());
-        let _ = std::io::stdin().read_line(&mut String::new());
+/// 输入可选的数值，留空返回 None；`valid` 校验取值范围
+fn input_optional_number<T>(prompt: &str, current: Option<T>, valid: impl Fn(&T) -> bool) -> Result<Option<T>>
+where
+    T: FromStr + Display,
+{
+    let input: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(current.map(|v| v.to_string()).unwrap_or_default())
+        .allow_empty(true)
+        .validate_with(|input: &String| -> std::result::Result<(), &str> {
+            let input = input.trim();
+            if input.is_empty() || input.parse::<T>().is_ok_and(|v| valid(&v)) {
+                Ok(())
+            } else {
+                Err("请输入有效范围内的数值")
+            }
+        })
+        .interact_text()
+        .map_err(|_| CliError::UserCancelled)?;
+
+    let input = input.trim();
+    if input.is_empty() {
+        Ok(None)
+    } else {
+        Ok(input.parse().ok())
     }
 }
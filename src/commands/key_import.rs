@@ -0,0 +1,159 @@
+// 批量导入 Token / API Key
+// 从粘贴的列表或文件读取，每行 `名称=值`，或只有值（自动命名）
+
+use crate::commands::prompt::expand_home;
+use crate::error::{CliError, Result};
+use crate::ui::{prompt_theme, show_success, show_warning};
+use console::style;
+use dialoguer::{Input, Select};
+use std::io::BufRead;
+
+/// 解析后的批量导入列表
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct KeyBatch {
+    /// (名称, 值)，按输入顺序排列
+    pub keys: Vec<(String, String)>,
+    /// 被跳过的行及原因
+    pub skipped: Vec<String>,
+}
+
+/// 拆分 `名称=值` 行；`=` 右侧只有 `=` 时视为 base64 填充 (如 `sk-abc==`)，整行按值处理
+fn split_named(line: &str) -> Option<(&str, &str)> {
+    line.split_once('=').filter(|(_, value)| !value.chars().all(|c| c == '='))
+}
+
+/// 解析批量导入的文本
+///
+/// 空行和 `#` 开头的行会被忽略；名称与已有名称或前面的行重复、值为空或包含空白时跳过该行。
+/// 只有值的行按 `<前缀> 1`、`<前缀> 2` ... 自动命名，跳过已被使用的名称
+pub(crate) fn parse_key_batch(text: &str, existing: &[String], prefix: &str) -> KeyBatch {
+    let mut batch = KeyBatch::default();
+    let mut next_index = 1;
+    // 自动命名时同时避开后面的行显式指定的名称
+    let explicit: Vec<&str> = text
+        .lines()
+        .filter_map(|line| split_named(line.trim()).map(|(name, _)| name.trim()))
+        .collect();
+    let is_taken = |name: &str, batch: &KeyBatch| {
+        existing.iter().any(|n| n == name) || batch.keys.iter().any(|(n, _)| n == name)
+    };
+
+    for (line_no, line) in text.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = match split_named(line) {
+            Some((name, value)) => (Some(name.trim()), value.trim()),
+            None => (None, line),
+        };
+
+        if value.is_empty() || value.chars().any(char::is_whitespace) {
+            batch.skipped.push(format!("第 {} 行格式无效", line_no));
+            continue;
+        }
+
+        let name = match name {
+            Some("") => {
+                batch.skipped.push(format!("第 {} 行名称为空", line_no));
+                continue;
+            }
+            Some(name) if is_taken(name, &batch) => {
+                batch.skipped.push(format!("第 {} 行名称 '{}' 已存在", line_no, name));
+                continue;
+            }
+            Some(name) => name.to_string(),
+            None => loop {
+                let candidate = format!("{} {}", prefix, next_index);
+                next_index += 1;
+                if !is_taken(&candidate, &batch) && !explicit.contains(&candidate.as_str()) {
+                    break candidate;
+                }
+            },
+        };
+
+        batch.keys.push((name, value.to_string()));
+    }
+
+    batch
+}
+
+/// 选择来源并读取批量导入的文本（粘贴时以空行结束）
+pub(crate) fn read_key_batch(label: &str) -> Result<String> {
+    let sources = ["粘贴列表", "从文件读取"];
+    let source = Select::with_theme(&*prompt_theme())
+        .with_prompt("导入来源")
+        .items(&sources)
+        .default(0)
+        .interact()
+        .map_err(|_| CliError::UserCancelled)?;
+
+    if source == 1 {
+        let path: String = Input::with_theme(&*prompt_theme())
+            .with_prompt("文件路径")
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+        let path = expand_home(path.trim());
+        return std::fs::read_to_string(&path)
+            .map_err(|e| CliError::Config(format!("读取文件 {} 失败: {e}", path.display())));
+    }
+
+    println!(
+        "{}",
+        style(format!("请粘贴 {label} 列表，每行 名称=值 或只有值，输入空行结束")).dim()
+    );
+    let mut lines = Vec::new();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|_| CliError::UserCancelled)?;
+        if line.trim().is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// 输出批量导入结果: 成功数量与跳过的行
+pub(crate) fn report_key_batch(label: &str, added: usize, skipped: &[String]) {
+    for reason in skipped {
+        show_warning(&format!("已跳过: {}", reason));
+    }
+    show_success(&format!(
+        "成功导入 {} 个 {}，跳过 {} 行",
+        added,
+        label,
+        skipped.len()
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_batch() {
+        let text = "# 注释\nwork=sk-1\n\nsk-2\nKey 2=sk-3\nsk-4\nwork=sk-5\n=sk-6\nbad=a b\nsk-7\nsk-abc==\nb64=sk-def=";
+        let batch = parse_key_batch(text, &["Key 1".to_string()], "Key");
+
+        assert_eq!(
+            batch.keys,
+            vec![
+                ("work".to_string(), "sk-1".to_string()),
+                ("Key 3".to_string(), "sk-2".to_string()),
+                ("Key 2".to_string(), "sk-3".to_string()),
+                ("Key 4".to_string(), "sk-4".to_string()),
+                ("Key 5".to_string(), "sk-7".to_string()),
+                ("Key 6".to_string(), "sk-abc==".to_string()),
+                ("b64".to_string(), "sk-def=".to_string()),
+            ]
+        );
+        assert_eq!(
+            batch.skipped,
+            vec![
+                "第 7 行名称 'work' 已存在".to_string(),
+                "第 8 行名称为空".to_string(),
+                "第 9 行格式无效".to_string(),
+            ]
+        );
+    }
+}
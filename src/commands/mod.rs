@@ -2,10 +2,17 @@ pub mod backup;
 pub mod claude;
 pub mod codex;
 pub mod gemini;
+mod key_import;
 pub mod opencode;
+pub mod profile;
+pub mod prompt;
+mod report;
+mod verify;
 
 pub use backup::*;
 pub use claude::*;
 pub use codex::*;
 pub use gemini::*;
 pub use opencode::*;
+pub use profile::*;
+pub use prompt::*;
@@ -1,11 +1,20 @@
 // OpenCode 配置管理命令
 // 采用新架构:Provider与模型分离,支持跨Provider选择
 
-use crate::config::{ConfigManager, OpenCodeModelInfo, OpenCodeModelLimit, OpenCodeProvider};
-use crate::ui::style::{show_error, show_info, show_opencode_menu, show_success};
+use crate::commands::report::DetectionReport;
+use crate::error::{CliError, Result};
+use crate::config::{
+    known_models, ConfigManager, ModelDetectionResult, OpenCodeModelInfo, OpenCodeModelLimit, OpenCodeProvider,
+    SiteDetectionResult,
+};
+use crate::ui::style::{
+    confirm, confirm_destructive, reorder_names, select_item, select_named, select_site, show_action_error, show_error, show_info, show_opencode_menu, show_success,
+    redact, show_result, show_warning, show_written_paths_with_copy, sparkline, truncate_chars, wait_for_back, SELECT_PAGE_SIZE,
+};
+use crate::ui::spinner;
 use console::style;
 use dialoguer::{theme::ColorfulTheme, Input, MultiSelect, Select};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 /// OpenCode 管理命令
 pub struct OpenCodeCommand {
@@ -14,54 +23,72 @@ pub struct OpenCodeCommand {
 
 impl OpenCodeCommand {
     /// 创建新的命令实例
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> Result<Self> {
         Ok(Self {
             config_manager: ConfigManager::new()?,
         })
     }
 
+    /// 单行输出当前激活的 Provider（命令行 `opencode current`），没有激活的配置时返回错误
+    ///
+    /// OpenCode 只记录激活的 Provider，没有密钥名称与单一模型
+    pub fn print_current(&self) -> Result<()> {
+        let active = self.config_manager.require_active_opencode()?;
+        println!("opencode: {}", active.provider);
+        Ok(())
+    }
+
     /// 执行命令
-    pub fn execute(&mut self) -> Result<(), String> {
+    pub fn execute(&mut self) -> Result<()> {
         loop {
             let choice =
-                show_opencode_menu("🚀 OpenCode配置管理").map_err(|e| e.to_string())?;
+                show_opencode_menu("🚀 OpenCode配置管理")?;
 
             use crate::ui::style::OpenCodeMenuChoice;
             match choice {
                 OpenCodeMenuChoice::Apply => {
                     if let Err(e) = self.handle_apply() {
-                        show_error(&format!("应用配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("应用配置失败", &e);
+                    }
+                }
+                OpenCodeMenuChoice::Preview => {
+                    if let Err(e) = self.handle_preview() {
+                        show_action_error("预览配置失败", &e);
                     }
                 }
                 OpenCodeMenuChoice::Add => {
                     if let Err(e) = self.handle_add() {
-                        show_error(&format!("添加配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("添加配置失败", &e);
                     }
                 }
                 OpenCodeMenuChoice::Edit => {
                     if let Err(e) = self.handle_edit() {
-                        show_error(&format!("编辑配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("编辑配置失败", &e);
                     }
                 }
                 OpenCodeMenuChoice::Delete => {
                     if let Err(e) = self.handle_delete() {
-                        show_error(&format!("删除配置失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("删除配置失败", &e);
+                    }
+                }
+                OpenCodeMenuChoice::Reorder => {
+                    if let Err(e) = self.handle_reorder() {
+                        show_action_error("调整顺序失败", &e);
                     }
                 }
                 OpenCodeMenuChoice::DetectSite => {
                     if let Err(e) = self.handle_detect_site() {
-                        show_error(&format!("站点检测失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("站点检测失败", &e);
                     }
                 }
                 OpenCodeMenuChoice::DetectModel => {
                     if let Err(e) = self.handle_detect_model() {
-                        show_error(&format!("模型检测失败: {}", e));
-                        self.wait_for_back();
+                        show_action_error("模型检测失败", &e);
+                    }
+                }
+                OpenCodeMenuChoice::DetectionHistory => {
+                    if let Err(e) = self.handle_detection_history() {
+                        show_action_error("查看检测历史失败", &e);
                     }
                 }
                 OpenCodeMenuChoice::Back => break,
@@ -76,7 +103,7 @@ impl OpenCodeCommand {
     // ========================================================================
 
     /// 处理应用配置(支持多选Provider和多选应用范围)
-    fn handle_apply(&mut self) -> Result<(), String> {
+    fn handle_apply(&mut self) -> Result<()> {
         println!("\n{}", style("🚀 应用 OpenCode 配置").cyan().bold());
         println!("{}", style("选择要应用的 Provider 配置 (可多选)").dim());
         println!();
@@ -91,35 +118,14 @@ impl OpenCodeCommand {
         }
 
         // 多选 Provider
-        let provider_names: Vec<String> = all_providers.keys().cloned().collect();
-        let provider_items: Vec<String> = provider_names
-            .iter()
-            .map(|name| {
-                let provider = all_providers.get(name).unwrap();
-                format!(
-                    "🔌 {} ({})",
-                    name,
-                    provider.metadata.description.as_deref().unwrap_or("")
-                )
-            })
-            .collect();
+        let selected_providers =
+            select_providers(&all_providers, "选择要应用的 Provider (空格选择,回车确认)")?;
 
-        let provider_selections = MultiSelect::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要应用的 Provider (空格选择,回车确认)")
-            .items(&provider_items)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        if provider_selections.is_empty() {
+        if selected_providers.is_empty() {
             show_info("未选择任何 Provider");
             return Ok(());
         }
 
-        let selected_providers: Vec<String> = provider_selections
-            .iter()
-            .map(|&idx| provider_names[idx].clone())
-            .collect();
-
         // 显示配置预览
         println!("\n{}", style("📋 配置预览：").white().bold());
         println!();
@@ -155,7 +161,7 @@ impl OpenCodeCommand {
                 .with_prompt("选择应用范围 (空格选择,回车确认) - ⚠️ 必须至少选择一项")
                 .items(&scope_choices)
                 .interact()
-                .map_err(|_| "用户取消操作")?;
+                .map_err(|_| CliError::UserCancelled)?;
 
             if selections.is_empty() {
                 show_error("❌ 必须至少选择一个应用范围！");
@@ -189,12 +195,18 @@ impl OpenCodeCommand {
         }
         println!();
 
+        if self.confirm("是否先预览将写入的 opencode.json?", false)? {
+            self.print_preview(&selected_providers)?;
+        }
+
         if !self.confirm("确认应用此配置", true)? {
             show_info("用户取消应用");
             return Ok(());
         }
 
         // 执行应用
+        let mut written_paths = Vec::new();
+
         if apply_to_global {
             println!();
             println!(
@@ -204,13 +216,11 @@ impl OpenCodeCommand {
                     .bold()
             );
             
-            self.config_manager
+            let paths = self
+                .config_manager
                 .apply_multiple_opencode_to_global(&selected_providers)?;
             show_success("✨ 已应用到全局配置！");
-            println!(
-                "{}",
-                style("  配置文件: ~/.opencode/opencode.json").dim()
-            );
+            written_paths.extend(paths);
         }
 
         if apply_to_project {
@@ -222,21 +232,11 @@ impl OpenCodeCommand {
                     .bold()
             );
             
-            self.config_manager
+            let paths = self
+                .config_manager
                 .apply_multiple_opencode_to_project(&selected_providers)?;
             show_success("✨ 已应用到当前项目！");
-
-            // 获取当前目录并显示配置路径
-            if let Ok(current_dir) = std::env::current_dir() {
-                println!(
-                    "{}",
-                    style(format!(
-                        "  配置文件: {}/.opencode/opencode.json",
-                        current_dir.display()
-                    ))
-                    .dim()
-                );
-            }
+            written_paths.extend(paths);
         }
 
         println!();
@@ -244,14 +244,73 @@ impl OpenCodeCommand {
             "🎉 成功应用 {} 个 Provider 配置！",
             selected_providers.len()
         ));
+        show_result(&selected_providers.join(","));
+        println!();
+        show_written_paths_with_copy(&written_paths);
 
-        self.wait_for_back();
+        wait_for_back();
+
+        Ok(())
+    }
+
+    /// 预览将写入 opencode.json 的内容 (只读，不写入任何文件)
+    fn handle_preview(&mut self) -> Result<()> {
+        println!("\n{}", style("👀 预览 OpenCode 配置").cyan().bold());
+        println!("{}", style("选择 Provider 后显示应用时将写入的完整 opencode.json").dim());
+        println!();
+
+        let all_providers = self.config_manager.opencode().get_all_providers()?;
+
+        if all_providers.is_empty() {
+            show_error("没有可用的 Provider 配置");
+            show_info("请先使用「添加配置」功能添加 Provider");
+            return Ok(());
+        }
+
+        let selected_providers =
+            select_providers(&all_providers, "选择要预览的 Provider (空格选择,回车确认)")?;
+
+        if selected_providers.is_empty() {
+            show_info("未选择任何 Provider");
+            return Ok(());
+        }
+
+        self.print_preview(&selected_providers)?;
+        wait_for_back();
+
+        Ok(())
+    }
+
+    /// 显示应用这些 Provider 后 opencode.json 的内容 (apiKey 脱敏)
+    fn print_preview(&self, provider_names: &[String]) -> Result<()> {
+        let mut preview = self.config_manager.preview_opencode(provider_names)?;
+        redact_api_keys(&mut preview);
+
+        let opencode = self.config_manager.opencode();
+        println!("\n{}", style("📄 应用后将写入以下内容 (apiKey 已脱敏):").white().bold());
+        println!(
+            "  {} {}",
+            style("全局:").white(),
+            style(opencode.global_opencode_json().display()).dim()
+        );
+        println!(
+            "  {} {}",
+            style("项目:").white(),
+            style(opencode.project_opencode_json()?.display()).dim()
+        );
+        println!("{}", style("注意: 应用时会整体覆盖目标文件，而不是与现有内容合并").yellow());
+        println!();
+
+        let content = serde_json::to_string_pretty(&preview)
+            .map_err(|e| format!("序列化预览内容失败: {}", e))?;
+        println!("{}", content);
+        println!();
 
         Ok(())
     }
 
     /// 处理添加配置
-    fn handle_add(&mut self) -> Result<(), String> {
+    fn handle_add(&mut self) -> Result<()> {
         let choices = vec![
             "➕ 添加新 Provider",
             "🤖 向已有 Provider 添加模型",
@@ -263,7 +322,7 @@ impl OpenCodeCommand {
             .items(&choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match selection {
             0 => self.add_new_provider()?,
@@ -275,14 +334,14 @@ impl OpenCodeCommand {
     }
 
     /// 添加新 Provider
-    fn add_new_provider(&mut self) -> Result<(), String> {
+    fn add_new_provider(&mut self) -> Result<()> {
         println!("\n{}", style("➕ 添加新 Provider").cyan().bold());
         println!();
 
         // Provider 名称
         let provider_name: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("Provider 名称 (如: MyProvider, CustomAI)")
-            .validate_with(|input: &String| -> Result<(), &str> {
+            .validate_with(|input: &String| -> std::result::Result<(), &str> {
                 if input.trim().is_empty() {
                     Err("Provider 名称不能为空")
                 } else {
@@ -290,12 +349,12 @@ impl OpenCodeCommand {
                 }
             })
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // Base URL
         let base_url: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("Base URL")
-            .validate_with(|input: &String| -> Result<(), &str> {
+            .validate_with(|input: &String| -> std::result::Result<(), &str> {
                 if input.trim().is_empty() {
                     Err("Base URL 不能为空")
                 } else {
@@ -303,12 +362,12 @@ impl OpenCodeCommand {
                 }
             })
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // API Key
         let api_key: String = dialoguer::Password::with_theme(&ColorfulTheme::default())
             .with_prompt("API Key")
-            .validate_with(|input: &String| -> Result<(), &str> {
+            .validate_with(|input: &String| -> std::result::Result<(), &str> {
                 if input.trim().is_empty() {
                     Err("API Key 不能为空")
                 } else if input.len() < 10 {
@@ -318,14 +377,14 @@ impl OpenCodeCommand {
                 }
             })
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         // NPM 包
         let npm: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("NPM 包 (如: @ai-sdk/openai-compatible, 可选)")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let npm = if npm.trim().is_empty() {
             None
@@ -338,7 +397,7 @@ impl OpenCodeCommand {
             .with_prompt("描述 (可选)")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let description = if description.trim().is_empty() {
             None
@@ -358,13 +417,13 @@ impl OpenCodeCommand {
         show_success(&format!("✅ Provider '{}' 添加成功！", provider_name));
         show_info("接下来请前往编辑配置中添加模型");
 
-        self.wait_for_back();
+        wait_for_back();
 
         Ok(())
     }
 
     /// 向已有 Provider 添加模型(交互式)
-    fn add_model_to_provider_interactive(&mut self) -> Result<(), String> {
+    fn add_model_to_provider_interactive(&mut self) -> Result<()> {
         // 选择 Provider
         let all_providers = self.config_manager.opencode().get_all_providers()?;
 
@@ -388,7 +447,7 @@ impl OpenCodeCommand {
                 .items(&choices)
                 .default(0)
                 .interact()
-                .map_err(|_| "用户取消操作")?;
+                .map_err(|_| CliError::UserCancelled)?;
 
             match selection {
                 0 => self.add_model_to_provider(&provider_name)?,
@@ -400,11 +459,11 @@ impl OpenCodeCommand {
     }
 
     /// 添加模型到指定 Provider
-    fn add_model_to_provider(&mut self, provider_name: &str) -> Result<(), String> {
+    fn add_model_to_provider(&mut self, provider_name: &str) -> Result<()> {
         // 模型 ID
         let model_id: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("模型 ID (如: gpt-4, model-name)")
-            .validate_with(|input: &String| -> Result<(), &str> {
+            .validate_with(|input: &String| -> std::result::Result<(), &str> {
                 if input.trim().is_empty() {
                     Err("模型 ID 不能为空")
                 } else {
@@ -412,40 +471,53 @@ impl OpenCodeCommand {
                 }
             })
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
-        // 模型名称
-        let model_name: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("模型显示名称")
-            .default(model_id.clone())
-            .interact_text()
-            .map_err(|_| "用户取消操作")?;
-
-        // Context Limit
-        let context_limit_str: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Context Limit (留空则不设置)")
-            .allow_empty(true)
-            .interact_text()
-            .map_err(|_| "用户取消操作")?;
+        // 常见模型按内置表预填限制，用户可修改或清空
+        let known_limit = known_models::lookup(&model_id).map(|known| known.limit());
+        if known_limit.is_some() {
+            show_info("已按内置模型表预填 Context/Output Limit，可直接修改");
+        }
+        let default_name = known_models::display_name(&model_id).map_or_else(|| model_id.clone(), str::to_string);
+        let (model_name, limit) = self.prompt_model_details(default_name, known_limit.as_ref())?;
 
-        let context_limit = if context_limit_str.is_empty() {
-            None
-        } else {
-            Some(context_limit_str.parse::<u64>().map_err(|_| "无效的数字")?)
+        let model_info = OpenCodeModelInfo {
+            name: model_name,
+            limit,
+            model_detection: None,
         };
 
-        // Output Limit
-        let output_limit_str: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Output Limit (留空则不设置)")
-            .allow_empty(true)
+        self.config_manager.opencode_mut().add_model(
+            provider_name,
+            model_id.clone(),
+            model_info,
+        )?;
+
+        show_success(&format!(
+            "✅ 模型 '{}' 已添加到 Provider '{}'",
+            model_id, provider_name
+        ));
+
+        Ok(())
+    }
+
+    /// 输入模型显示名称和限制（默认值为当前值）
+    fn prompt_model_details(
+        &self,
+        default_name: String,
+        current_limit: Option<&OpenCodeModelLimit>,
+    ) -> Result<(String, Option<OpenCodeModelLimit>)> {
+        // 模型名称
+        let model_name: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("模型显示名称")
+            .default(default_name)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
-        let output_limit = if output_limit_str.is_empty() {
-            None
-        } else {
-            Some(output_limit_str.parse::<u64>().map_err(|_| "无效的数字")?)
-        };
+        let context_limit =
+            self.prompt_limit("Context Limit", current_limit.and_then(|l| l.context))?;
+        let output_limit =
+            self.prompt_limit("Output Limit", current_limit.and_then(|l| l.output))?;
 
         // 构建嵌套的 limit 结构
         let limit = if context_limit.is_some() || output_limit.is_some() {
@@ -457,28 +529,60 @@ impl OpenCodeCommand {
             None
         };
 
-        let model_info = OpenCodeModelInfo {
-            name: model_name,
-            limit,
-            model_detection: None,
-        };
+        Ok((model_name, limit))
+    }
 
-        self.config_manager.opencode_mut().add_model(
-            provider_name,
-            model_id.clone(),
-            model_info,
-        )?;
+    /// 输入单个限制值，留空则不设置
+    fn prompt_limit(&self, label: &str, current: Option<u64>) -> Result<Option<u64>> {
+        let input: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("{} (留空则不设置)", label))
+            .with_initial_text(current.map(|v| v.to_string()).unwrap_or_default())
+            .allow_empty(true)
+            .validate_with(|input: &String| -> std::result::Result<(), &str> {
+                if input.trim().is_empty() || input.trim().parse::<u64>().is_ok() {
+                    Ok(())
+                } else {
+                    Err("无效的数字")
+                }
+            })
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
 
-        show_success(&format!(
-            "✅ 模型 '{}' 已添加到 Provider '{}'",
-            model_id, provider_name
-        ));
+        let input = input.trim();
+        if input.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(input.parse::<u64>().map_err(|_| "无效的数字")?))
+        }
+    }
+
+    /// 编辑已有模型的显示名称和限制
+    fn edit_model_in_provider(&mut self, provider_name: &str) -> Result<()> {
+        let models = self.config_manager.opencode().get_models(provider_name)?;
+
+        if models.is_empty() {
+            show_error("该 Provider 没有模型");
+            return Ok(());
+        }
+
+        let model_id = &select_named("选择要编辑的模型", &models, |id, model_info| {
+            format!("🤖 {} ({})", id, model_info.name)
+        })?;
+        let model_info = &models[model_id];
+
+        let (name, limit) = self.prompt_model_details(model_info.name.clone(), model_info.limit.as_ref())?;
+
+        self.config_manager
+            .opencode_mut()
+            .update_model(provider_name, model_id, name, limit)?;
+
+        show_success(&format!("✅ 模型 '{}' 已更新", model_id));
 
         Ok(())
     }
 
     /// 处理编辑配置
-    fn handle_edit(&mut self) -> Result<(), String> {
+    fn handle_edit(&mut self) -> Result<()> {
         println!("\n{}", style("📝 编辑配置").cyan().bold());
         println!();
 
@@ -504,7 +608,7 @@ impl OpenCodeCommand {
             .items(&choices)
             .default(0)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         match selection {
             0 => self.edit_provider_metadata(&provider_name)?,
@@ -516,7 +620,7 @@ impl OpenCodeCommand {
     }
 
     /// 编辑 Provider 元数据
-    fn edit_provider_metadata(&mut self, provider_name: &str) -> Result<(), String> {
+    fn edit_provider_metadata(&mut self, provider_name: &str) -> Result<()> {
         println!("\n{}", style("📝 编辑 Provider 元数据").cyan().bold());
         println!();
 
@@ -524,14 +628,14 @@ impl OpenCodeCommand {
             .config_manager
             .opencode()
             .get_provider(provider_name)?
-            .ok_or_else(|| format!("Provider '{}' 不存在", provider_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
 
         // Base URL (留空保持不变)
         let base_url_input: String = Input::with_theme(&ColorfulTheme::default())
             .with_prompt("Base URL (留空保持不变)")
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_base_url = if base_url_input.trim().is_empty() {
             None
@@ -544,7 +648,7 @@ impl OpenCodeCommand {
             .with_prompt("API Key (留空保持不变)")
             .allow_empty_password(true)
             .interact()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_api_key = if new_api_key.trim().is_empty() {
             None
@@ -558,7 +662,7 @@ impl OpenCodeCommand {
             .default(provider.npm.clone().unwrap_or_default())
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_npm = if new_npm.trim().is_empty() {
             None
@@ -572,7 +676,7 @@ impl OpenCodeCommand {
             .default(provider.metadata.description.clone().unwrap_or_default())
             .allow_empty(true)
             .interact_text()
-            .map_err(|_| "用户取消操作")?;
+            .map_err(|_| CliError::UserCancelled)?;
 
         let new_description = if new_description.trim().is_empty() {
             None
@@ -592,29 +696,37 @@ impl OpenCodeCommand {
 
         show_success(&format!("✅ Provider '{}' 元数据已更新", provider_name));
 
-        self.wait_for_back();
+        wait_for_back();
 
         Ok(())
     }
 
     /// 管理模型
-    fn edit_models(&mut self, provider_name: &str) -> Result<(), String> {
+    fn edit_models(&mut self, provider_name: &str) -> Result<()> {
         loop {
             println!("\n{}", style("🤖 管理模型").cyan().bold());
             println!();
 
-            let choices = vec!["➕ 添加新模型", "🗑️  删除模型", "⬅️  返回上一级菜单"];
+            let choices = vec![
+                "➕ 添加新模型",
+                "✏️  编辑模型",
+                "🗑️  删除模型",
+                "🔃 调整模型顺序",
+                "⬅️  返回上一级菜单",
+            ];
 
             let selection = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt("请选择操作")
                 .items(&choices)
                 .default(0)
                 .interact()
-                .map_err(|_| "用户取消操作")?;
+                .map_err(|_| CliError::UserCancelled)?;
 
             match selection {
                 0 => self.add_model_to_provider(provider_name)?,
-                1 => self.delete_model_from_provider(provider_name)?,
+                1 => self.edit_model_in_provider(provider_name)?,
+                2 => self.delete_model_from_provider(provider_name)?,
+                3 => self.reorder_models(provider_name)?,
                 _ => break,
             }
         }
@@ -623,7 +735,7 @@ impl OpenCodeCommand {
     }
 
     /// 删除模型
-    fn delete_model_from_provider(&mut self, provider_name: &str) -> Result<(), String> {
+    fn delete_model_from_provider(&mut self, provider_name: &str) -> Result<()> {
         let models = self.config_manager.opencode().get_models(provider_name)?;
 
         if models.is_empty() {
@@ -631,25 +743,11 @@ impl OpenCodeCommand {
             return Ok(());
         }
 
-        let model_ids: Vec<String> = models.keys().cloned().collect();
-        let model_items: Vec<String> = model_ids
-            .iter()
-            .map(|id| {
-                let model_info = models.get(id).unwrap();
-                format!("🤖 {} ({})", id, model_info.name)
-            })
-            .collect();
-
-        let model_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择要删除的模型")
-            .items(&model_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        let model_id = &model_ids[model_idx];
+        let model_id = &select_named("选择要删除的模型", &models, |id, model_info| {
+            format!("🤖 {} ({})", id, model_info.name)
+        })?;
 
-        if !self.confirm(&format!("确认删除模型 '{}'?", model_id), false)? {
+        if !confirm_destructive(&format!("确认删除模型 '{}'?", model_id))? {
             show_info("取消删除");
             return Ok(());
         }
@@ -663,8 +761,50 @@ impl OpenCodeCommand {
         Ok(())
     }
 
+    /// 调整 Provider 下模型的显示顺序
+    fn reorder_models(&mut self, provider_name: &str) -> Result<()> {
+        let models = self.config_manager.opencode().get_models(provider_name)?;
+
+        if models.len() < 2 {
+            show_info("模型少于两个，无需调整顺序");
+            return Ok(());
+        }
+
+        match reorder_names("🔃 调整模型顺序", models.keys().cloned().collect())? {
+            Some(order) => {
+                self.config_manager
+                    .opencode_mut()
+                    .reorder_models(provider_name, &order)?;
+                show_success("✅ 模型顺序已更新");
+            }
+            None => show_info("顺序未变化"),
+        }
+
+        Ok(())
+    }
+
+    /// 调整 Provider 的显示顺序
+    fn handle_reorder(&mut self) -> Result<()> {
+        let all_providers = self.config_manager.opencode().get_all_providers()?;
+
+        if all_providers.len() < 2 {
+            show_info("Provider 少于两个，无需调整顺序");
+            return Ok(());
+        }
+
+        match reorder_names("🔃 调整 Provider 顺序", all_providers.keys().cloned().collect())? {
+            Some(order) => {
+                self.config_manager.opencode_mut().reorder_providers(&order)?;
+                show_success("✅ Provider 顺序已更新");
+            }
+            None => show_info("顺序未变化"),
+        }
+
+        Ok(())
+    }
+
     /// 处理删除配置
-    fn handle_delete(&mut self) -> Result<(), String> {
+    fn handle_delete(&mut self) -> Result<()> {
         println!("\n{}", style("🗑️  删除配置").red().bold());
         println!();
 
@@ -684,18 +824,21 @@ impl OpenCodeCommand {
         );
         println!();
 
-        if !self.confirm(&format!("确认删除 Provider '{}'?", provider_name), false)? {
+        if !confirm_destructive(&format!("确认删除 Provider '{}'?", provider_name))? {
             show_info("取消删除");
             return Ok(());
         }
 
-        self.config_manager
-            .opencode_mut()
-            .delete_provider(&provider_name)?;
+        let cleared = self
+            .config_manager
+            .delete_opencode_provider(&provider_name)?;
 
         show_success(&format!("✅ Provider '{}' 已删除", provider_name));
+        if cleared {
+            show_info("已清除当前激活配置");
+        }
 
-        self.wait_for_back();
+        wait_for_back();
 
         Ok(())
     }
@@ -707,38 +850,14 @@ impl OpenCodeCommand {
     /// 选择 Provider
     fn select_provider(
         &self,
-        all_providers: &HashMap<String, OpenCodeProvider>,
-    ) -> Result<String, String> {
-        let provider_names: Vec<String> = all_providers.keys().cloned().collect();
-        let provider_items: Vec<String> = provider_names
-            .iter()
-            .map(|name| {
-                let provider = all_providers.get(name).unwrap();
-                format!(
-                    "🔌 {} ({})",
-                    name,
-                    provider.metadata.description.as_deref().unwrap_or("")
-                )
-            })
-            .collect();
-
-        let provider_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择 Provider")
-            .items(&provider_items)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
-
-        Ok(provider_names[provider_idx].clone())
+        all_providers: &IndexMap<String, OpenCodeProvider>,
+    ) -> Result<String> {
+        select_site("选择 Provider", all_providers)
     }
 
     /// 确认对话框
-    fn confirm(&self, prompt: &str, default: bool) -> Result<bool, String> {
-        dialoguer::Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(prompt)
-            .default(default)
-            .interact()
-            .map_err(|_| "用户取消操作".to_string())
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool> {
+        confirm(prompt, default)
     }
 
     // ========================================================================
@@ -746,7 +865,7 @@ impl OpenCodeCommand {
     // ========================================================================
 
     /// 处理站点检测
-    fn handle_detect_site(&mut self) -> Result<(), String> {
+    fn handle_detect_site(&mut self) -> Result<()> {
         println!("\n{}", style("🌐 站点检测").cyan().bold());
 
         // 1. 获取所有Providers
@@ -771,28 +890,37 @@ impl OpenCodeCommand {
         );
 
         // 3. 执行检测
-        show_info("正在检测站点...");
+        use crate::config::{Detector, DetectorTimeouts};
 
-        use crate::config::Detector;
-
-        let detector = Detector::new();
+        let settings = self.config_manager.read_global_config()?.settings;
+        let detector = Detector::with_timeouts(DetectorTimeouts::from_settings(&settings));
         let base_url = provider.options.base_url.clone();
         let api_key = provider.options.api_key.clone();
 
+        let loading = spinner("正在检测站点...");
         let result = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 detector.detect_site(&base_url, &api_key).await
             })
         });
+        loading.finish();
+
+        // 每次检测都记入历史，与是否保存到配置无关
+        if let Err(e) = self.config_manager.opencode().record_site_detection(&provider_name, &result) {
+            show_warning(&format!("记录检测历史失败: {}", e));
+        }
 
         // 4. 显示结果
-        self.show_site_detection_report(&result);
+        let report = DetectionReport::site(&provider_name, &result);
+        report.print();
+        self.offer_report_export(&report)?;
 
         // 5. 批量导入模型(如果检测成功)
-        if result.is_available && !result.available_models.is_empty() {
-            if self.confirm("是否批量导入检测到的模型?", true)? {
-                self.batch_import_models(&provider_name, &result.available_models)?;
-            }
+        if result.is_available
+            && !result.available_models.is_empty()
+            && self.confirm("是否批量导入检测到的模型?", true)?
+        {
+            self.batch_import_models(&provider_name, &result)?;
         }
 
         // 6. 保存检测结果
@@ -804,63 +932,11 @@ impl OpenCodeCommand {
         Ok(())
     }
 
-    /// 显示站点检测报告
-    fn show_site_detection_report(&self, result: &crate::config::models::SiteDetectionResult) {
-        println!("\n{}", style("═".repeat(60)).dim());
-        println!("{}", style("📊 站点检测报告").cyan().bold());
-        println!("{}", style("═".repeat(60)).dim());
-
-        // 站点状态
-        if result.is_available {
-            println!("\n{} {}", "✅", style("站点状态: 可用").green().bold());
-            println!("{} {}", "🔑", style("API Key: 有效").green());
-
-            if let Some(time) = result.response_time_ms {
-                println!(
-                    "{} {} ms",
-                    "⚡",
-                    style(format!("响应时间: {:.0}", time)).yellow()
-                );
-            }
-
-            println!(
-                "\n{} {} 个",
-                "🤖",
-                style(format!(
-                    "检测到模型: {}",
-                    result.available_models.len()
-                ))
-                .cyan()
-                .bold()
-            );
-
-            for (i, model) in result.available_models.iter().enumerate() {
-                println!("  {}. {}", i + 1, style(model).white());
-            }
-        } else {
-            println!(
-                "\n{} {}",
-                "❌",
-                style("站点状态: 不可用").red().bold()
-            );
-
-            if let Some(err) = &result.error_message {
-                println!("{} {}", "⚠️ ", style(format!("错误: {}", err)).yellow());
-            }
-        }
-
-        println!(
-            "\n{}",
-            style(format!("检测时间: {}", result.detected_at)).dim()
-        );
-        println!("{}", style("═".repeat(60)).dim());
-    }
-
     /// 批量导入模型
-    fn batch_import_models(&mut self, provider_name: &str, models: &[String]) -> Result<(), String> {
+    fn batch_import_models(&mut self, provider_name: &str, result: &SiteDetectionResult) -> Result<()> {
         let mut imported = 0;
 
-        for model_id in models {
+        for model_id in &result.available_models {
             // 检查模型是否已存在
             if self
                 .config_manager
@@ -871,12 +947,8 @@ impl OpenCodeCommand {
                 continue; // 跳过已存在的
             }
 
-            // 添加模型
-            let new_model_info = OpenCodeModelInfo {
-                name: model_id.clone(),
-                limit: None,
-                model_detection: None,
-            };
+            // 添加模型 (名称与限制尽量按内置模型表填写，站点返回的上下文长度优先)
+            let new_model_info = known_models::imported_model_info(model_id, result.context_length(model_id));
 
             self.config_manager.opencode_mut().add_model(
                 provider_name,
@@ -896,13 +968,13 @@ impl OpenCodeCommand {
         &mut self,
         provider_name: &str,
         result: crate::config::models::SiteDetectionResult,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.config_manager.opencode().read_config()?;
 
         if let Some(provider) = config.providers.get_mut(provider_name) {
             provider.site_detection = Some(result);
         } else {
-            return Err("Provider不存在".to_string());
+            return Err("Provider不存在".into());
         }
 
         self.config_manager.opencode().write_config(&config)?;
@@ -910,7 +982,7 @@ impl OpenCodeCommand {
     }
 
     /// 处理模型检测
-    fn handle_detect_model(&mut self) -> Result<(), String> {
+    fn handle_detect_model(&mut self) -> Result<()> {
         println!("\n{}", style("🤖 模型检测").cyan().bold());
 
         // 1. 选择Provider
@@ -936,6 +1008,18 @@ impl OpenCodeCommand {
             return Ok(());
         }
 
+        // 多个模型时可选择并发检测全部模型
+        if models.len() > 1 {
+            let scopes = [
+                "检测单个模型".to_string(),
+                format!("检测全部模型 ({} 个，并发检测)", models.len()),
+            ];
+            let scope = select_item("检测范围", &scopes, 0).map_err(|_| CliError::UserCancelled)?;
+            if scope == 1 {
+                return self.detect_all_models(&provider_name, provider, &models);
+            }
+        }
+
         let model_id = self.select_model_from_list(&models)?;
 
         println!(
@@ -948,14 +1032,14 @@ impl OpenCodeCommand {
         let test_stream = self.confirm("是否测试流式输出功能?", false)?;
 
         // 4. 执行检测
-        show_info("正在检测模型...");
-
-        use crate::config::Detector;
+        use crate::config::{Detector, DetectorTimeouts};
 
-        let detector = Detector::new();
+        let settings = self.config_manager.read_global_config()?.settings;
+        let detector = Detector::with_timeouts(DetectorTimeouts::from_settings(&settings));
         let base_url = provider.options.base_url.clone();
         let api_key = provider.options.api_key.clone();
 
+        let loading = spinner("正在检测模型...");
         let result = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 detector
@@ -963,149 +1047,436 @@ impl OpenCodeCommand {
                     .await
             })
         });
+        loading.finish();
+
+        // 每次检测都记入历史，与是否保存到配置无关
+        if let Err(e) = self.config_manager.opencode().record_model_detection(&provider_name, &result) {
+            show_warning(&format!("记录检测历史失败: {}", e));
+        }
 
         // 5. 显示结果
-        self.show_model_detection_report(&result);
+        let report = DetectionReport::model(&provider_name, &result);
+        report.print();
+        self.offer_report_export(&report)?;
 
         // 6. 保存检测结果
-        if result.is_available {
-            if self.confirm("是否保存检测结果到配置?", true)? {
-                self.save_model_detection(&provider_name, &model_id, result)?;
-                show_success("检测结果已保存");
+        if result.is_available && self.confirm("是否保存检测结果到配置?", true)? {
+            self.save_model_detection(&provider_name, &model_id, result)?;
+            show_success("检测结果已保存");
+        }
+
+        Ok(())
+    }
+
+    /// 保存模型检测结果
+    fn save_model_detection(
+        &mut self,
+        provider_name: &str,
+        model_id: &str,
+        result: crate::config::models::ModelDetectionResult,
+    ) -> Result<()> {
+        let mut config = self.config_manager.opencode().read_config()?;
+
+        if let Some(provider) = config.providers.get_mut(provider_name) {
+            if let Some(model_info) = provider.models.get_mut(model_id) {
+                model_info.model_detection = Some(result);
+            } else {
+                return Err("模型不存在".into());
             }
+        } else {
+            return Err("Provider不存在".into());
         }
 
+        self.config_manager.opencode().write_config(&config)?;
         Ok(())
     }
 
-    /// 显示模型检测报告
-    fn show_model_detection_report(&self, result: &crate::config::models::ModelDetectionResult) {
-        println!("\n{}", style("═".repeat(60)).dim());
-        println!(
-            "{}",
-            style(format!("📊 模型检测报告: {}", result.model_id))
-                .cyan()
-                .bold()
-        );
-        println!("{}", style("═".repeat(60)).dim());
+    /// 并发检测 Provider 下的全部模型，之后可选择移除检测失败的模型
+    fn detect_all_models(
+        &mut self,
+        provider_name: &str,
+        provider: &OpenCodeProvider,
+        models: &IndexMap<String, OpenCodeModelInfo>,
+    ) -> Result<()> {
+        use crate::config::{Detector, DetectorTimeouts};
 
-        if result.is_available {
-            println!("\n{} {}", "✅", style("模型状态: 可用").green().bold());
+        let settings = self.config_manager.read_global_config()?.settings;
+        let detector = Detector::with_timeouts(DetectorTimeouts::from_settings(&settings));
+        let base_url = provider.options.base_url.clone();
+        let api_key = provider.options.api_key.clone();
+        let model_ids: Vec<String> = models.keys().cloned().collect();
 
-            if let Some(time) = result.first_token_time_ms {
-                println!(
-                    "{} {} ms",
-                    "⚡",
-                    style(format!("首次响应时间: {:.0}", time)).yellow()
-                );
-            }
+        let loading = spinner(&format!("正在检测 {} 个模型...", model_ids.len()));
+        let results = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                detector.detect_models(&base_url, &api_key, &model_ids).await
+            })
+        });
+        loading.finish();
 
-            if let Some(time) = result.total_response_time_ms {
-                println!(
-                    "{} {} ms",
-                    "⏱️ ",
-                    style(format!("总响应时间: {:.0}", time)).yellow()
-                );
+        // 每次检测都记入历史，与是否保存到配置无关
+        for result in &results {
+            if let Err(e) = self.config_manager.opencode().record_model_detection(provider_name, result) {
+                show_warning(&format!("记录检测历史失败: {}", e));
+                break;
             }
+        }
 
-            if let Some(tps) = result.tokens_per_second {
-                println!(
-                    "{} {} tokens/s",
-                    "🚀",
-                    style(format!("Token速度: {:.2}", tps)).cyan().bold()
-                );
-            }
+        show_model_summary(&results);
 
-            if let Some(stream) = result.stream_available {
-                if stream {
-                    println!(
-                        "{} {}",
-                        "✅",
-                        style("流式输出: 支持").green()
-                    );
-                } else {
-                    println!(
-                        "{} {}",
-                        "❌",
-                        style("流式输出: 不支持").red()
-                    );
-                }
+        if results.iter().any(|r| r.is_available) && self.confirm("是否保存检测结果到配置?", true)? {
+            for result in results.iter().filter(|r| r.is_available) {
+                self.save_model_detection(provider_name, &result.model_id, result.clone())?;
             }
-        } else {
-            println!(
-                "\n{} {}",
-                "❌",
-                style("模型状态: 不可用").red().bold()
-            );
+            show_success("检测结果已保存");
+        }
 
-            if let Some(err) = &result.error_message {
-                println!("{} {}", "⚠️ ", style(format!("错误: {}", err)).yellow());
-            }
+        self.prune_dead_models(provider_name, &results)
+    }
+
+    /// 列出检测失败的模型，确认后从 Provider 中移除 (默认不移除)
+    fn prune_dead_models(&mut self, provider_name: &str, results: &[ModelDetectionResult]) -> Result<()> {
+        let candidates = dead_model_candidates(results);
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        show_warning(&format!("{} 个模型检测失败", candidates.len()));
+        if !self.confirm("是否从 Provider 中移除检测失败的模型?", false)? {
+            return Ok(());
+        }
+
+        let items: Vec<String> = candidates
+            .iter()
+            .map(|c| format!("{} - {}", c.model_id, c.reason))
+            .collect();
+        let defaults: Vec<bool> = candidates.iter().map(|c| c.prechecked).collect();
+
+        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择要移除的模型 (超时的模型默认不勾选，空格选择,回车确认)")
+            .items(&items)
+            .defaults(&defaults)
+            .max_length(SELECT_PAGE_SIZE)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        if selections.is_empty() {
+            show_info("未选择任何模型");
+            return Ok(());
+        }
+
+        println!("\n{}", style("将移除以下模型:").yellow().bold());
+        for &idx in &selections {
+            println!("  - {}", style(&candidates[idx].model_id).white());
+        }
+        if !self.confirm(&format!("确认移除以上 {} 个模型?", selections.len()), false)? {
+            show_info("已取消移除");
+            return Ok(());
         }
 
+        for &idx in &selections {
+            self.config_manager
+                .opencode_mut()
+                .delete_model(provider_name, &candidates[idx].model_id)?;
+        }
+        show_success(&format!("已移除 {} 个模型", selections.len()));
+
+        Ok(())
+    }
+
+    /// 询问是否将检测报告导出为当前目录下的 Markdown 文件
+    fn offer_report_export(&self, report: &DetectionReport) -> Result<()> {
+        if self.confirm("是否导出检测报告为 Markdown?", false)? {
+            let path = report.export_markdown()?;
+            show_success(&format!("检测报告已导出: {}", path.display()));
+        }
+        Ok(())
+    }
+
+    /// 查看检测历史: 选择 Provider 后显示站点与各模型的趋势
+    fn handle_detection_history(&mut self) -> Result<()> {
+        println!("\n{}", style("📈 检测历史").cyan().bold());
+
+        let history = self.config_manager.opencode().read_detection_history()?;
+        let providers: Vec<String> = history.providers().into_iter().cloned().collect();
+
+        if providers.is_empty() {
+            show_info("还没有检测记录，请先使用「站点检测」或「模型检测」");
+            return Ok(());
+        }
+
+        let idx = select_item("选择 Provider", &providers, 0)
+            .map_err(|_| CliError::UserCancelled)?;
+        let provider_name = &providers[idx];
+
+        println!("\n{}", style("═".repeat(60)).dim());
         println!(
-            "\n{}",
-            style(format!("检测时间: {}", result.detected_at)).dim()
+            "{}",
+            style(format!("📈 检测历史: {}", provider_name)).cyan().bold()
         );
         println!("{}", style("═".repeat(60)).dim());
-    }
 
-    /// 保存模型检测结果
-    fn save_model_detection(
-        &mut self,
-        provider_name: &str,
-        model_id: &str,
-        result: crate::config::models::ModelDetectionResult,
-    ) -> Result<(), String> {
-        let mut config = self.config_manager.opencode().read_config()?;
-
-        if let Some(provider) = config.providers.get_mut(provider_name) {
-            if let Some(model_info) = provider.models.get_mut(model_id) {
-                model_info.model_detection = Some(result);
-            } else {
-                return Err("模型不存在".to_string());
+        if let Some(entries) = history.sites.get(provider_name) {
+            show_site_history(entries);
+        }
+        if let Some(models) = history.models.get(provider_name) {
+            for (model_id, entries) in models {
+                show_model_history(model_id, entries);
             }
-        } else {
-            return Err("Provider不存在".to_string());
         }
 
-        self.config_manager.opencode().write_config(&config)?;
+        println!("\n{}", style("═".repeat(60)).dim());
         Ok(())
     }
 
     /// 从模型列表中选择模型
     fn select_model_from_list(
         &self,
-        models: &HashMap<String, OpenCodeModelInfo>,
-    ) -> Result<String, String> {
-        let mut model_list: Vec<_> = models.iter().collect();
-        model_list.sort_by(|a, b| a.0.cmp(b.0));
-
-        let model_names: Vec<String> = model_list.iter().map(|(id, _)| (*id).clone()).collect();
+        models: &IndexMap<String, OpenCodeModelInfo>,
+    ) -> Result<String> {
+        let model_names: Vec<String> = models.keys().cloned().collect();
 
-        let selection_idx = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("选择模型")
-            .items(&model_names)
-            .default(0)
-            .interact()
-            .map_err(|_| "用户取消操作")?;
+        let selection_idx = select_item("选择模型", &model_names, 0)
+            .map_err(|_| CliError::UserCancelled)?;
 
         Ok(model_names[selection_idx].clone())
     }
+}
 
-    /// 等待用户返回
-    fn wait_for_back(&self) {
-        let items = vec!["⬅️  返回上一级菜单"];
-        let _ = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("操作完成")
-            .items(&items)
-            .default(0)
-            .interact();
+/// 显示站点检测历史: 响应时间趋势与逐次记录 (最新在前)
+fn show_site_history(entries: &[SiteDetectionResult]) {
+    let times: Vec<Option<f64>> = entries.iter().map(|r| r.response_time_ms).collect();
+    let available = entries.iter().filter(|r| r.is_available).count();
+
+    println!(
+        "\n🌐 {}",
+        style(format!("站点检测 (最近 {} 次)", entries.len())).cyan().bold()
+    );
+    println!(
+        "  响应时间  {}  {}",
+        sparkline(&times),
+        style(trend_summary(&times, "ms", 0)).dim()
+    );
+    println!("  可用次数  {}/{}", available, entries.len());
+
+    for result in entries.iter().rev() {
+        println!(
+            "  {}  {}  {:>10}  {} 个模型",
+            style(format_detected_at(&result.detected_at)).dim(),
+            status_label(result.is_available, result.timed_out),
+            format_metric(result.response_time_ms, "ms", 0),
+            result.available_models.len()
+        );
+    }
+}
+
+/// 显示模型检测历史: Token 速度趋势与逐次记录 (最新在前)
+fn show_model_history(model_id: &str, entries: &[ModelDetectionResult]) {
+    let speeds: Vec<Option<f64>> = entries.iter().map(|r| r.tokens_per_second).collect();
+    let totals: Vec<Option<f64>> = entries.iter().map(|r| r.total_response_time_ms).collect();
+    let available = entries.iter().filter(|r| r.is_available).count();
+
+    println!(
+        "\n🤖 {}",
+        style(format!("{} (最近 {} 次)", model_id, entries.len())).cyan().bold()
+    );
+    println!(
+        "  Token速度 {}  {}",
+        sparkline(&speeds),
+        style(trend_summary(&speeds, "tokens/s", 2)).dim()
+    );
+    println!(
+        "  总响应    {}  {}",
+        sparkline(&totals),
+        style(trend_summary(&totals, "ms", 0)).dim()
+    );
+    println!("  可用次数  {}/{}", available, entries.len());
+
+    for result in entries.iter().rev() {
+        println!(
+            "  {}  {}  {:>10}  {:>16}",
+            style(format_detected_at(&result.detected_at)).dim(),
+            status_label(result.is_available, result.timed_out),
+            format_metric(result.total_response_time_ms, "ms", 0),
+            format_metric(result.tokens_per_second, "tokens/s", 2)
+        );
     }
 }
 
-impl Default for OpenCodeCommand {
-    fn default() -> Self {
-        Self::new().expect("Failed to create OpenCodeCommand")
+/// 多选 Provider，返回按列表顺序排列的名称
+fn select_providers(
+    all_providers: &IndexMap<String, OpenCodeProvider>,
+    prompt: &str,
+) -> Result<Vec<String>> {
+    let provider_names: Vec<String> = all_providers.keys().cloned().collect();
+    let provider_items: Vec<String> = all_providers
+        .iter()
+        .map(|(name, provider)| {
+            format!(
+                "🔌 {} ({})",
+                name,
+                provider.metadata.description.as_deref().unwrap_or("")
+            )
+        })
+        .collect();
+
+    let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&provider_items)
+        .max_length(SELECT_PAGE_SIZE)
+        .interact()
+        .map_err(|_| CliError::UserCancelled)?;
+
+    Ok(selections
+        .into_iter()
+        .map(|idx| provider_names[idx].clone())
+        .collect())
+}
+
+/// 将 opencode.json 内容中各 Provider 的 apiKey 脱敏
+fn redact_api_keys(sync_data: &mut serde_json::Value) {
+    if let Some(providers) = sync_data.get_mut("provider").and_then(|p| p.as_object_mut()) {
+        for provider in providers.values_mut() {
+            if let Some(api_key) = provider.pointer_mut("/options/apiKey") {
+                if let Some(key) = api_key.as_str() {
+                    *api_key = serde_json::Value::String(redact(key));
+                }
+            }
+        }
+    }
+}
+
+/// 检测失败、可移除的模型
+#[derive(Debug, Clone, PartialEq)]
+struct DeadModel {
+    model_id: String,
+    reason: String,
+    /// 是否默认勾选：超时可能只是暂时变慢，默认不勾选
+    prechecked: bool,
+}
+
+/// 从批量检测结果中选出检测失败的模型
+fn dead_model_candidates(results: &[ModelDetectionResult]) -> Vec<DeadModel> {
+    results
+        .iter()
+        .filter(|r| !r.is_available)
+        .map(|r| DeadModel {
+            model_id: r.model_id.clone(),
+            reason: r.error_message.clone().unwrap_or_else(|| "未知错误".to_string()),
+            prechecked: !r.timed_out,
+        })
+        .collect()
+}
+
+/// 显示批量模型检测汇总
+fn show_model_summary(results: &[ModelDetectionResult]) {
+    let available = results.iter().filter(|r| r.is_available).count();
+
+    println!("\n{}", style("═".repeat(60)).dim());
+    println!(
+        "{}",
+        style(format!("📊 模型检测汇总: 可用 {}/{}", available, results.len()))
+            .cyan()
+            .bold()
+    );
+    println!("{}", style("═".repeat(60)).dim());
+
+    for result in results {
+        println!(
+            "  {}  {:<32}  {:>10}  {:>16}",
+            status_label(result.is_available, result.timed_out),
+            truncate_chars(&result.model_id, 29),
+            format_metric(result.total_response_time_ms, "ms", 0),
+            format_metric(result.tokens_per_second, "tokens/s", 2)
+        );
+    }
+    println!("{}", style("═".repeat(60)).dim());
+}
+
+/// 最新值与平均值摘要，没有有效值时显示 "-"
+fn trend_summary(values: &[Option<f64>], unit: &str, precision: usize) -> String {
+    let present: Vec<f64> = values.iter().flatten().copied().collect();
+    if present.is_empty() {
+        return "-".to_string();
+    }
+
+    let average = present.iter().sum::<f64>() / present.len() as f64;
+    format!(
+        "最新 {} · 平均 {:.*} {}",
+        format_metric(*values.last().unwrap_or(&None), unit, precision),
+        precision,
+        average,
+        unit
+    )
+}
+
+/// 格式化可选指标，缺失时显示 "-"
+fn format_metric(value: Option<f64>, unit: &str, precision: usize) -> String {
+    value
+        .map(|v| format!("{:.*} {}", precision, v, unit))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// 检测状态标签
+fn status_label(is_available: bool, timed_out: bool) -> console::StyledObject<&'static str> {
+    if is_available {
+        style("可用").green()
+    } else if timed_out {
+        style("超时").red()
+    } else {
+        style("失败").red()
+    }
+}
+
+/// 将 RFC3339 检测时间转换为本地时间显示
+fn format_detected_at(detected_at: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(detected_at)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| detected_at.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_api_keys() {
+        let mut data = serde_json::json!({
+            "provider": {
+                "relay": {"options": {"baseURL": "https://relay.example.com", "apiKey": "sk-abcdefghijklmnop"}},
+                "empty": {"options": {"baseURL": "https://x.example.com"}}
+            }
+        });
+        redact_api_keys(&mut data);
+        assert_eq!(data["provider"]["relay"]["options"]["apiKey"], "sk-abc****mnop");
+        assert_eq!(data["provider"]["relay"]["options"]["baseURL"], "https://relay.example.com");
+        assert!(data["provider"]["empty"]["options"].get("apiKey").is_none());
+    }
+
+    #[test]
+    fn test_dead_model_candidates_skip_timeouts_by_default() {
+        let result = |model_id: &str, is_available: bool, timed_out: bool| ModelDetectionResult {
+            detected_at: String::new(),
+            model_id: model_id.to_string(),
+            is_available,
+            first_token_time_ms: None,
+            tokens_per_second: None,
+            total_response_time_ms: None,
+            stream_available: None,
+            error_message: (!is_available).then(|| "HTTP 404 Not Found: 模型返回错误".to_string()),
+            timed_out,
+        };
+
+        let candidates = dead_model_candidates(&[
+            result("ok", true, false),
+            result("gone", false, false),
+            result("slow", false, true),
+        ]);
+        let summary: Vec<(&str, bool)> = candidates
+            .iter()
+            .map(|c| (c.model_id.as_str(), c.prechecked))
+            .collect();
+        assert_eq!(summary, vec![("gone", true), ("slow", false)]);
+        assert!(candidates[0].reason.contains("404"));
     }
 }
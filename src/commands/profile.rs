@@ -0,0 +1,192 @@
+// Profile 命令模块
+// 将各工具的激活引用保存为命名快照，并可一次切换所有工具
+
+use crate::commands::backup::auto_backup_after_switch;
+use crate::config::claude_manager::vertex_warnings;
+use crate::config::{ActiveConfigs, ConfigManager};
+use crate::error::{CliError, Result};
+use crate::ui::{confirm_destructive, is_quiet_mode, show_error, show_info, show_result, show_success, show_warning, show_written_paths};
+use console::style;
+
+/// Profile 管理命令
+pub struct ProfileCommand {
+    config_manager: ConfigManager,
+}
+
+impl ProfileCommand {
+    /// 创建新的命令实例
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            config_manager: ConfigManager::new()?,
+        })
+    }
+
+    /// 将当前的激活配置保存为 profile（命令行 `profile save <name>`）
+    pub fn save(&mut self, name: &str) -> Result<()> {
+        let replaced = self.config_manager.save_profile(name)?;
+
+        if replaced {
+            show_success(&format!("已更新 profile: {}", name));
+        } else {
+            show_success(&format!("已保存 profile: {}", name));
+        }
+        print_entries(&self.config_manager.read_global_config()?.active);
+        show_result(name);
+
+        Ok(())
+    }
+
+    /// 切换到 profile（命令行 `profile use <name>`）
+    pub fn use_profile(&mut self, name: &str) -> Result<()> {
+        let profile = self
+            .config_manager
+            .profiles()?
+            .shift_remove(name)
+            .ok_or_else(|| CliError::NotFound(format!("Profile '{}' 不存在", name)))?;
+        let written_paths = self.config_manager.use_profile(name)?;
+
+        show_success(&format!("✨ 已切换到 profile: {}", name));
+        print_entries(&self.config_manager.read_global_config()?.active);
+        if let Some(ref claude) = profile.claude {
+            warn_vertex(&self.config_manager, &claude.site);
+        }
+        show_result(name);
+        show_written_paths(&written_paths);
+
+        // 与单独切换时一致，按设置自动备份切换过的类别
+        for (switched, category) in [
+            (profile.claude.is_some(), "claudeCode"),
+            (profile.codex.is_some(), "codex"),
+            (profile.gemini.is_some(), "gemini"),
+        ] {
+            if switched {
+                auto_backup_after_switch(&self.config_manager, category);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 将所有工具切换到同名的 Token/API Key（命令行 `switch <密钥名称>`），没有同名密钥时按同名标签匹配站点
+    ///
+    /// 都没有匹配的工具跳过；单个工具切换失败时继续切换其余工具，全部未切换时返回错误
+    pub fn switch_key(&mut self, key_name: &str) -> Result<()> {
+        let matches = self.config_manager.find_key_matches(key_name)?;
+
+        let mut switched = Vec::new();
+        let mut written_paths = Vec::new();
+        for tool in ["claude", "codex", "gemini"] {
+            let Some(key_match) = matches.iter().find(|m| m.tool == tool) else {
+                show_info(&format!("{}: 没有名为 '{}' 的密钥或标签，已跳过", tool, key_name));
+                continue;
+            };
+
+            match self.config_manager.switch_key_match(key_match) {
+                Ok(paths) => {
+                    let reference = format!("{}/{}", key_match.site, key_match.key_name);
+                    if key_match.by_tag {
+                        show_success(&format!("{}: {} (按标签 '{}' 匹配)", tool, reference, key_name));
+                    } else if key_match.candidates > 1 {
+                        show_success(&format!(
+                            "{}: {} (共 {} 个站点有该密钥，已选择当前或最近使用的站点)",
+                            tool, reference, key_match.candidates
+                        ));
+                    } else {
+                        show_success(&format!("{}: {}", tool, reference));
+                    }
+                    if tool == "claude" {
+                        warn_vertex(&self.config_manager, &key_match.site);
+                    }
+                    show_result(&format!("{}\t{}", tool, reference));
+                    written_paths.extend(paths);
+                    switched.push(tool);
+                }
+                Err(e) => show_error(&format!("{}: 切换失败: {}", tool, e)),
+            }
+        }
+
+        if switched.is_empty() {
+            return Err(CliError::NotFound(format!("没有工具切换到密钥 '{}'", key_name)));
+        }
+        show_written_paths(&written_paths);
+
+        // 与单独切换时一致，按设置自动备份切换过的类别
+        for (tool, category) in [("claude", "claudeCode"), ("codex", "codex"), ("gemini", "gemini")] {
+            if switched.contains(&tool) {
+                auto_backup_after_switch(&self.config_manager, category);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 列出已保存的 profile（命令行 `profile list [--json]`）
+    pub fn list(&self, json: bool) -> Result<()> {
+        let global_config = self.config_manager.read_global_config()?;
+
+        if json {
+            let output = serde_json::to_string_pretty(&global_config.profiles)
+                .map_err(|e| format!("序列化 profile 列表失败: {}", e))?;
+            println!("{}", output);
+            return Ok(());
+        }
+
+        if global_config.profiles.is_empty() {
+            show_info("还没有保存任何 profile，可使用 ca-switch profile save <名称> 保存当前配置");
+            return Ok(());
+        }
+
+        let current = global_config.active.entries();
+        for (name, profile) in &global_config.profiles {
+            let entries = profile.entries();
+            if entries == current {
+                println!("{} {}", style(name).cyan().bold(), style("(当前)").green());
+            } else {
+                println!("{}", style(name).cyan().bold());
+            }
+            for (tool, reference) in entries {
+                println!("  {} {}", style(format!("{}:", tool)).white(), style(reference).dim());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 删除 profile（命令行 `profile delete <name>`）
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        if !self.config_manager.profiles()?.contains_key(name) {
+            return Err(CliError::NotFound(format!("Profile '{}' 不存在", name)));
+        }
+
+        show_warning(&format!("⚠️  警告：即将删除 profile '{}'", name));
+        if !confirm_destructive(&format!("确认删除 profile '{}'", name))? {
+            show_info("用户取消删除");
+            return Ok(());
+        }
+
+        self.config_manager.delete_profile(name)?;
+        show_success(&format!("成功删除 profile: {}", name));
+        show_result(name);
+
+        Ok(())
+    }
+}
+
+/// 显示各工具的激活引用（安静模式下不输出）
+fn print_entries(active: &ActiveConfigs) {
+    if is_quiet_mode() {
+        return;
+    }
+    for (tool, reference) in active.entries() {
+        println!("  {} {}", style(format!("{}:", tool)).white(), style(reference).cyan());
+    }
+}
+
+/// 切换到的 Claude 站点启用 Vertex AI 但缺少项目 ID 或凭据时提示
+fn warn_vertex(config_manager: &ConfigManager, site_name: &str) {
+    if let Ok(Some(site)) = config_manager.claude().get_site(site_name) {
+        for issue in vertex_warnings(&site.config.vertex) {
+            show_warning(&issue);
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use crate::error::{CliError, Result};
-use crate::ui::{confirm, show_error, show_info, show_success};
+use crate::config::PROMPTS_DIR;
+use crate::ui::{confirm, confirm_destructive, copy_to_clipboard, show_action_error, show_error, show_info, show_success, show_warning, sorted_keys, truncate_chars, wait_for_back};
 use console::style;
-use dialoguer::{theme::ColorfulTheme, Editor, Input, Select};
+use dialoguer::{theme::ColorfulTheme, Editor, Input, MultiSelect, Select};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 
 /// Prompt 元数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,8 +16,21 @@ pub struct PromptMetadata {
     pub description: Option<String>,
     pub category: Option<String>,
     pub file: String,  // 对应的 txt 文件名
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// 内容中 `{{变量}}` 占位符的声明（含可选默认值）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<PromptVariable>,
+}
+
+/// Prompt 模板变量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptVariable {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
 }
 
 /// Prompt 索引文件结构
@@ -25,6 +40,233 @@ pub struct PromptIndex {
     pub prompts: HashMap<String, PromptMetadata>,
 }
 
+/// 提取内容中的 `{{变量}}` 占位符，按首次出现的顺序去重
+pub fn extract_placeholders(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+
+        let name = after[..end].trim();
+        if is_placeholder_name(name) && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+
+    names
+}
+
+/// 用变量值替换 `{{变量}}` 占位符，没有提供值的占位符保持原样
+pub fn render_template(content: &str, values: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+
+        rendered.push_str(&rest[..start]);
+        match values.get(after[..end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// 按名称查找 prompt: 优先精确匹配，否则使用不区分大小写的唯一前缀匹配
+fn resolve_prompt<'a>(index: &'a PromptIndex, name: &str) -> Result<&'a PromptMetadata> {
+    if let Some(metadata) = index.prompts.get(name) {
+        return Ok(metadata);
+    }
+
+    let prefix = name.to_lowercase();
+    let mut matches: Vec<&PromptMetadata> = index
+        .prompts
+        .values()
+        .filter(|m| m.name.to_lowercase().starts_with(&prefix))
+        .collect();
+
+    match matches.len() {
+        0 => Err(CliError::NotFound(format!("Prompt '{name}' 不存在"))),
+        1 => Ok(matches[0]),
+        _ => {
+            matches.sort_by(|a, b| a.name.cmp(&b.name));
+            let names: Vec<&str> = matches.iter().map(|m| m.name.as_str()).collect();
+            Err(format!("Prompt 名称 '{name}' 不唯一，可能是: {}", names.join(", ")).into())
+        }
+    }
+}
+
+/// 展开路径开头的 `~`
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    if let (Some(rest), Some(home)) = (path.strip_prefix('~'), crate::config::home_dir().ok()) {
+        if rest.is_empty() {
+            return home;
+        }
+        if let Some(rest) = rest.strip_prefix(['/', '\\']) {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+/// 读取外部文本文件作为 prompt 内容
+fn read_content_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)
+        .map_err(|e| CliError::Config(format!("读取文件 {} 失败: {e}", path.display())))?;
+    String::from_utf8(bytes)
+        .map_err(|_| CliError::Parse(format!("文件 {} 不是 UTF-8 文本", path.display())))
+}
+
+/// 未设置 $VISUAL / $EDITOR 时使用的编辑器
+const FALLBACK_EDITOR: &str = if cfg!(windows) { "notepad" } else { "nano" };
+
+/// 终端输入内容时表示结束的行
+const INPUT_TERMINATOR: &str = ".";
+
+/// 用户配置的编辑器，按 $VISUAL、$EDITOR 的顺序查找（忽略空值）
+fn configured_editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// 在编辑器中编辑内容，编辑器无法启动时改为在终端中逐行输入
+/// 返回 None 表示用户未保存就退出了编辑器
+fn edit_content(initial: &str) -> Result<Option<String>> {
+    let editor = configured_editor().unwrap_or_else(|| {
+        show_warning(&format!(
+            "未设置 $EDITOR 环境变量 (或 $VISUAL)，使用默认编辑器 {FALLBACK_EDITOR}"
+        ));
+        FALLBACK_EDITOR.to_string()
+    });
+
+    match Editor::new().executable(&editor).edit(initial) {
+        Ok(content) => Ok(content),
+        Err(e) => {
+            show_warning(&format!(
+                "无法启动编辑器 {editor}: {e}，可设置 $EDITOR 环境变量指定其他编辑器"
+            ));
+            if initial.is_empty() {
+                println!("{}", style(format!("请直接输入内容，单独一行输入 {INPUT_TERMINATOR} 结束")).dim());
+            } else {
+                println!("{}", style(format!("请输入新的完整内容，单独一行输入 {INPUT_TERMINATOR} 结束 (直接结束则保留原内容)")).dim());
+            }
+            let content = read_until_terminator(std::io::stdin().lock())
+                .map_err(|_| CliError::UserCancelled)?;
+            Ok(Some(content))
+        }
+    }
+}
+
+/// 逐行读取内容，直到遇到结束行或输入结束
+fn read_until_terminator(reader: impl BufRead) -> std::io::Result<String> {
+    let mut lines = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim_end() == INPUT_TERMINATOR {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// 按名称、描述、分类匹配关键字（不区分大小写）
+fn prompt_matches(metadata: &PromptMetadata, keyword: &str) -> bool {
+    let keyword = keyword.trim().to_lowercase();
+    [
+        Some(metadata.name.as_str()),
+        metadata.description.as_deref(),
+        metadata.category.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|field| field.to_lowercase().contains(&keyword))
+}
+
+/// 解析逗号分隔的标签（支持中文逗号），去除空白与重复项
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split([',', '，']).map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// 是否包含所有指定标签（未指定标签时视为匹配）
+fn has_all_tags(metadata: &PromptMetadata, tags: &[&str]) -> bool {
+    tags.iter().all(|tag| metadata.tags.iter().any(|t| t == tag))
+}
+
+/// 占位符名称: 非空，只包含字母、数字、`_`、`-`、`.`
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Prompt 库导出文件结构: 索引 + 所有 txt 文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArchive {
+    pub exported_at: String,
+    pub index: PromptIndex,
+    /// 文件名 -> 内容
+    pub contents: HashMap<String, String>,
+}
+
+impl PromptArchive {
+    /// 检查索引中引用的每个文件都有对应内容
+    pub fn validate(&self) -> Result<()> {
+        let mut missing: Vec<&str> = self
+            .index
+            .prompts
+            .values()
+            .filter(|m| !self.contents.contains_key(&m.file))
+            .map(|m| m.file.as_str())
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        missing.sort();
+        Err(CliError::Parse(format!(
+            "导入文件缺少以下 prompt 的内容: {}",
+            missing.join(", ")
+        )))
+    }
+}
+
+/// 导入时名称冲突的处理方式
+enum ImportConflict {
+    Skip,
+    Overwrite,
+    Rename(String),
+}
+
+/// 搜索内容时单个 prompt 文件的大小上限，避免扫描过大的文件
+const MAX_CONTENT_SEARCH_BYTES: u64 = 256 * 1024;
+
+/// 列表中描述最多显示的字符数，完整描述可在查看详情时看到
+const PROMPT_LIST_DESCRIPTION_CHARS: usize = 60;
+
 /// Prompt 管理命令
 pub struct PromptCommand {
     prompts_dir: PathBuf,
@@ -33,8 +275,12 @@ pub struct PromptCommand {
 
 impl PromptCommand {
     pub fn new() -> Result<Self> {
-        let home = dirs::home_dir().ok_or_else(|| CliError::Config("无法获取用户主目录".into()))?;
-        let prompts_dir = home.join(".ca-switch").join("prompts");
+        let config_dir = crate::config::config_dir(&crate::config::home_dir()?)?;
+        Self::with_dir(config_dir.join(PROMPTS_DIR))
+    }
+
+    /// 使用指定的 prompts 目录
+    fn with_dir(prompts_dir: PathBuf) -> Result<Self> {
         let index_file = prompts_dir.join("index.json");
 
         // 确保 prompts 目录存在
@@ -66,38 +312,42 @@ impl PromptCommand {
             match choice {
                 PromptMenuChoice::List => {
                     if let Err(e) = self.handle_list().await {
-                        show_error(&format!("查看 prompts 失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("查看 prompts 失败", &e);
+                    }
+                }
+                PromptMenuChoice::Search => {
+                    if let Err(e) = self.handle_search().await {
+                        show_action_error("搜索 prompts 失败", &e);
                     }
                 }
                 PromptMenuChoice::View => {
                     if let Err(e) = self.handle_view().await {
-                        show_error(&format!("查看 prompt 失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("查看 prompt 失败", &e);
                     }
                 }
                 PromptMenuChoice::Add => {
                     if let Err(e) = self.handle_add().await {
-                        show_error(&format!("添加 prompt 失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("添加 prompt 失败", &e);
                     }
                 }
                 PromptMenuChoice::Edit => {
                     if let Err(e) = self.handle_edit().await {
-                        show_error(&format!("编辑 prompt 失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("编辑 prompt 失败", &e);
                     }
                 }
                 PromptMenuChoice::Delete => {
                     if let Err(e) = self.handle_delete().await {
-                        show_error(&format!("删除 prompt 失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("删除 prompt 失败", &e);
                     }
                 }
                 PromptMenuChoice::Copy => {
                     if let Err(e) = self.handle_copy().await {
-                        show_error(&format!("复制 prompt 失败: {e}"));
-                        self.wait_for_back()?;
+                        show_action_error("复制 prompt 失败", &e);
+                    }
+                }
+                PromptMenuChoice::Render => {
+                    if let Err(e) = self.handle_render().await {
+                        show_action_error("渲染 prompt 失败", &e);
                     }
                 }
                 PromptMenuChoice::Back => break,
@@ -114,11 +364,13 @@ impl PromptCommand {
 
         let choices = [
             PromptMenuChoice::List,
+            PromptMenuChoice::Search,
             PromptMenuChoice::View,
             PromptMenuChoice::Add,
             PromptMenuChoice::Edit,
             PromptMenuChoice::Delete,
             PromptMenuChoice::Copy,
+            PromptMenuChoice::Render,
             PromptMenuChoice::Back,
         ];
 
@@ -198,26 +450,125 @@ impl PromptCommand {
         filename
     }
 
-    /// 处理列表展示
-    async fn handle_list(&self) -> Result<()> {
-        println!("\n{}", style("📋 Prompt 列表").cyan().bold());
-        println!("{}", style("═".repeat(60)).dim());
+    /// 重命名 prompt，同时按新名称重命名内容文件
+    fn rename_prompt(&self, index: &mut PromptIndex, old_name: &str, new_name: &str) -> Result<()> {
+        if index.prompts.contains_key(new_name) {
+            return Err(CliError::Config(format!("Prompt '{new_name}' 已存在")));
+        }
+        let mut metadata = index
+            .prompts
+            .remove(old_name)
+            .ok_or_else(|| CliError::NotFound(format!("Prompt '{old_name}' 不存在")))?;
+
+        let filename = self.generate_filename(new_name);
+        let old_path = self.prompts_dir.join(&metadata.file);
+        if old_path.exists() {
+            fs::rename(&old_path, self.prompts_dir.join(&filename))
+                .map_err(|e| CliError::Config(format!("重命名 prompt 文件失败: {e}")))?;
+        }
 
-        let index = self.read_index()?;
+        metadata.name = new_name.to_string();
+        metadata.file = filename;
+        index.prompts.insert(new_name.to_string(), metadata);
+        Ok(())
+    }
 
-        if index.prompts.is_empty() {
-            show_info("暂无保存的 prompts");
-            println!("\n提示: 使用 '添加 Prompt' 功能创建新的 prompt");
-            return Ok(());
+    /// 删除未被索引引用的 .txt 文件，返回被删除的文件名
+    fn remove_orphan_files(&self, index: &PromptIndex) -> Result<Vec<String>> {
+        let entries = fs::read_dir(&self.prompts_dir)
+            .map_err(|e| CliError::Config(format!("读取 prompts 目录失败: {e}")))?;
+
+        let mut removed = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if index.prompts.values().any(|metadata| metadata.file == file_name) {
+                continue;
+            }
+            self.delete_prompt_file(&file_name)?;
+            removed.push(file_name);
         }
 
-        // 按名称排序
-        let mut sorted_prompts: Vec<_> = index.prompts.iter().collect();
-        sorted_prompts.sort_by(|a, b| a.0.cmp(b.0));
+        removed.sort();
+        Ok(removed)
+    }
+
+    /// 清理孤立的内容文件并提示数量
+    fn cleanup_orphan_files(&self, index: &PromptIndex) -> Result<()> {
+        let removed = self.remove_orphan_files(index)?;
+        if !removed.is_empty() {
+            show_info(&format!("已清理 {} 个未被引用的 prompt 文件", removed.len()));
+        }
+        Ok(())
+    }
+
+    /// 为内容中的占位符生成变量声明，已声明的变量保留原有默认值，新变量询问默认值
+    fn declare_variables(&self, content: &str, existing: &[PromptVariable]) -> Result<Vec<PromptVariable>> {
+        let mut variables = Vec::new();
+
+        for name in extract_placeholders(content) {
+            if let Some(variable) = existing.iter().find(|v| v.name == name) {
+                variables.push(variable.clone());
+                continue;
+            }
+
+            let default: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("变量 '{name}' 的默认值 (可选)"))
+                .allow_empty(true)
+                .interact_text()
+                .map_err(|_| CliError::UserCancelled)?;
+
+            variables.push(PromptVariable {
+                name,
+                default: Some(default.trim().to_string()).filter(|d| !d.is_empty()),
+            });
+        }
+
+        Ok(variables)
+    }
+
+    /// 依次询问占位符的值并渲染内容，没有占位符时原样返回
+    fn fill_variables(&self, content: &str, metadata: &PromptMetadata) -> Result<String> {
+        let placeholders = extract_placeholders(content);
+        if placeholders.is_empty() {
+            return Ok(content.to_string());
+        }
+
+        println!("\n{}", style("请填写模板变量:").white().bold());
+
+        let theme = ColorfulTheme::default();
+        let mut values = HashMap::new();
+        for name in placeholders {
+            let default = metadata
+                .variables
+                .iter()
+                .find(|v| v.name == name)
+                .and_then(|v| v.default.clone());
+
+            let mut input = Input::<String>::with_theme(&theme)
+                .with_prompt(&name)
+                .allow_empty(true);
+            if let Some(default) = default {
+                input = input.default(default);
+            }
+
+            let value = input.interact_text().map_err(|_| CliError::UserCancelled)?;
+            values.insert(name, value);
+        }
+
+        Ok(render_template(content, &values))
+    }
+
+    /// 按名称排序后显示 prompt 列表
+    fn render_prompt_list(&self, mut prompts: Vec<&PromptMetadata>) {
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
 
-        println!("\n共找到 {} 个 prompts:\n", style(index.prompts.len()).cyan().bold());
+        println!("\n共找到 {} 个 prompts:\n", style(prompts.len()).cyan().bold());
 
-        for (name, metadata) in sorted_prompts {
+        for metadata in prompts {
             let category = metadata.category
                 .as_ref()
                 .map(|c| format!("[{}]", style(c).yellow()))
@@ -225,15 +576,19 @@ impl PromptCommand {
 
             let description = metadata.description
                 .as_ref()
-                .map(|d| style(d).dim().to_string())
+                .map(|d| style(truncate_chars(d, PROMPT_LIST_DESCRIPTION_CHARS)).dim().to_string())
                 .unwrap_or_else(|| style("无描述").dim().to_string());
 
             println!("  {} {} {}",
                 style("▪").cyan(),
-                style(name).white().bold(),
+                style(&metadata.name).white().bold(),
                 category
             );
             println!("    {}", description);
+            if !metadata.tags.is_empty() {
+                let tags: Vec<String> = metadata.tags.iter().map(|t| format!("#{t}")).collect();
+                println!("    {}", style(tags.join(" ")).magenta());
+            }
             println!("    {} {} | {} {}",
                 style("文件:").dim(),
                 style(&metadata.file).dim(),
@@ -242,11 +597,146 @@ impl PromptCommand {
             );
             println!();
         }
+    }
+
+    /// 处理列表展示
+    async fn handle_list(&self) -> Result<()> {
+        println!("\n{}", style("📋 Prompt 列表").cyan().bold());
+        println!("{}", style("═".repeat(60)).dim());
 
-        self.wait_for_back()?;
+        let index = self.read_index()?;
+
+        if index.prompts.is_empty() {
+            show_info("暂无保存的 prompts");
+            println!("\n提示: 使用 '添加 Prompt' 功能创建新的 prompt");
+            return Ok(());
+        }
+
+        let prompts: Vec<&PromptMetadata> = index.prompts.values().collect();
+        self.render_prompt_list(prompts);
+
+        wait_for_back();
         Ok(())
     }
 
+    /// 处理搜索 / 按分类筛选
+    async fn handle_search(&self) -> Result<()> {
+        println!("\n{}", style("🔍 搜索 Prompt").cyan().bold());
+        println!("{}", style("═".repeat(60)).dim());
+
+        let index = self.read_index()?;
+
+        if index.prompts.is_empty() {
+            show_info("暂无保存的 prompts");
+            return Ok(());
+        }
+
+        let modes = ["按关键字搜索", "按分类筛选", "按标签筛选"];
+        let mode = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("筛选方式")
+            .items(&modes)
+            .default(0)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let matched: Vec<&PromptMetadata> = if mode == 0 {
+            let keyword: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("关键字 (匹配名称/描述/分类)")
+                .interact_text()
+                .map_err(|_| CliError::UserCancelled)?;
+
+            // 内容搜索需要逐个读取文件，默认关闭
+            let search_content = confirm("是否同时搜索 prompt 内容? (较慢)", false)?;
+
+            index
+                .prompts
+                .values()
+                .filter(|m| {
+                    prompt_matches(m, &keyword)
+                        || (search_content && self.content_matches(&m.file, &keyword))
+                })
+                .collect()
+        } else if mode == 2 {
+            let mut tags: Vec<&str> = index
+                .prompts
+                .values()
+                .flat_map(|m| m.tags.iter().map(String::as_str))
+                .collect();
+            tags.sort();
+            tags.dedup();
+
+            if tags.is_empty() {
+                show_info("所有 prompts 均未设置标签");
+                return Ok(());
+            }
+
+            let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("选择标签 (空格选择,回车确认，需同时包含所有选中的标签)")
+                .items(&tags)
+                .interact()
+                .map_err(|_| CliError::UserCancelled)?;
+
+            let selected: Vec<&str> = selections.iter().map(|&i| tags[i]).collect();
+            index
+                .prompts
+                .values()
+                .filter(|m| has_all_tags(m, &selected))
+                .collect()
+        } else {
+            let mut categories: Vec<&str> = index
+                .prompts
+                .values()
+                .filter_map(|m| m.category.as_deref())
+                .collect();
+            categories.sort();
+            categories.dedup();
+
+            if categories.is_empty() {
+                show_info("所有 prompts 均未设置分类");
+                return Ok(());
+            }
+
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("选择分类")
+                .items(&categories)
+                .default(0)
+                .interact()
+                .map_err(|_| CliError::UserCancelled)?;
+
+            let category = categories[selection];
+            index
+                .prompts
+                .values()
+                .filter(|m| m.category.as_deref() == Some(category))
+                .collect()
+        };
+
+        if matched.is_empty() {
+            show_info("没有匹配的 prompts");
+        } else {
+            self.render_prompt_list(matched);
+        }
+
+        wait_for_back();
+        Ok(())
+    }
+
+    /// prompt 文件内容是否包含关键字（超过大小上限的文件不搜索）
+    fn content_matches(&self, file_name: &str, keyword: &str) -> bool {
+        let file_path = self.prompts_dir.join(file_name);
+        let too_large = fs::metadata(&file_path)
+            .map(|m| m.len() > MAX_CONTENT_SEARCH_BYTES)
+            .unwrap_or(true);
+        if too_large {
+            return false;
+        }
+
+        let keyword = keyword.trim().to_lowercase();
+        fs::read_to_string(&file_path)
+            .map(|content| content.to_lowercase().contains(&keyword))
+            .unwrap_or(false)
+    }
+
     /// 处理查看内容
     async fn handle_view(&self) -> Result<()> {
         let index = self.read_index()?;
@@ -257,8 +747,7 @@ impl PromptCommand {
         }
 
         let prompt_names: Vec<String> = {
-            let mut names: Vec<_> = index.prompts.keys().cloned().collect();
-            names.sort();
+            let names: Vec<String> = sorted_keys(&index.prompts).into_iter().cloned().collect();
             names
         };
 
@@ -298,6 +787,10 @@ impl PromptCommand {
             println!("{} {}", style("描述:").white(), description);
         }
 
+        if !metadata.tags.is_empty() {
+            println!("{} {}", style("标签:").white(), style(metadata.tags.join(", ")).magenta());
+        }
+
         println!("{} {}", style("文件:").dim(), metadata.file);
         println!("{} {}", style("创建于:").dim(), metadata.created_at);
         println!("{} {}", style("更新于:").dim(), metadata.updated_at);
@@ -307,7 +800,7 @@ impl PromptCommand {
         println!("{}", content);
         println!("{}", style("─".repeat(60)).dim());
 
-        self.wait_for_back()?;
+        wait_for_back();
         Ok(())
     }
 
@@ -335,11 +828,11 @@ impl PromptCommand {
         let mut index = self.read_index()?;
 
         // 检查是否已存在
-        if index.prompts.contains_key(&name) {
-            if !confirm(&format!("Prompt '{name}' 已存在，是否覆盖?"), false)? {
-                show_info("操作已取消");
-                return Ok(());
-            }
+        if index.prompts.contains_key(&name)
+            && !confirm(&format!("Prompt '{name}' 已存在，是否覆盖?"), false)?
+        {
+            show_info("操作已取消");
+            return Ok(());
         }
 
         // 输入描述
@@ -368,12 +861,43 @@ impl PromptCommand {
             Some(category.trim().to_string())
         };
 
-        // 使用编辑器输入内容
-        println!("\n{}", style("请在编辑器中输入 prompt 内容...").dim());
-        let content = Editor::new()
-            .edit("")
-            .map_err(|e| CliError::Config(format!("打开编辑器失败: {e}")))?
-            .ok_or_else(|| CliError::UserCancelled)?;
+        // 输入标签
+        let tags: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("标签 (可选，逗号分隔)")
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let tags = parse_tags(&tags);
+
+        // 选择内容来源（默认使用编辑器）
+        let sources = ["在编辑器中输入", "从文件导入"];
+        let source = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("内容来源")
+            .items(&sources)
+            .default(0)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let content = if source == 1 {
+            let path: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("文件路径")
+                .validate_with(|input: &String| -> std::result::Result<(), String> {
+                    let path = expand_home(input.trim());
+                    if path.is_file() {
+                        Ok(())
+                    } else {
+                        Err(format!("文件不存在: {}", path.display()))
+                    }
+                })
+                .interact_text()
+                .map_err(|_| CliError::UserCancelled)?;
+
+            read_content_file(&expand_home(path.trim()))?
+        } else {
+            println!("\n{}", style("请在编辑器中输入 prompt 内容...").dim());
+            edit_content("")?.ok_or(CliError::UserCancelled)?
+        };
 
         let content = content.trim().to_string();
 
@@ -382,6 +906,9 @@ impl PromptCommand {
             return Ok(());
         }
 
+        // 声明模板变量
+        let variables = self.declare_variables(&content, &[])?;
+
         // 生成文件名
         let filename = self.generate_filename(&name);
 
@@ -395,16 +922,22 @@ impl PromptCommand {
             description,
             category,
             file: filename,
+            tags,
             created_at: now.clone(),
             updated_at: now,
+            variables,
         };
 
-        index.prompts.insert(name.clone(), metadata);
+        // 覆盖同名 prompt 时删除其原有内容文件
+        if let Some(previous) = index.prompts.insert(name.clone(), metadata) {
+            self.delete_prompt_file(&previous.file)?;
+        }
         self.save_index(&index)?;
+        self.cleanup_orphan_files(&index)?;
 
         show_success(&format!("Prompt '{name}' 已保存"));
 
-        self.wait_for_back()?;
+        wait_for_back();
         Ok(())
     }
 
@@ -418,8 +951,7 @@ impl PromptCommand {
         }
 
         let prompt_names: Vec<String> = {
-            let mut names: Vec<_> = index.prompts.keys().cloned().collect();
-            names.sort();
+            let names: Vec<String> = sorted_keys(&index.prompts).into_iter().cloned().collect();
             names
         };
 
@@ -435,13 +967,37 @@ impl PromptCommand {
             .interact()
             .map_err(|_| CliError::UserCancelled)?;
 
-        let selected_name = &prompt_names[selection];
-        let metadata = index.prompts.get_mut(selected_name)
-            .ok_or_else(|| CliError::Config(format!("Prompt '{selected_name}' 不存在")))?;
+        let mut selected_name = prompt_names[selection].clone();
 
         println!("\n{}", style("📝 编辑 Prompt").cyan().bold());
         println!("{}", style("═".repeat(40)).dim());
 
+        // 编辑名称，重命名时内容文件随之重命名
+        let new_name: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("名称")
+            .default(selected_name.clone())
+            .validate_with(|input: &String| -> std::result::Result<(), String> {
+                let input = input.trim();
+                if input.is_empty() {
+                    Err("名称不能为空".to_string())
+                } else if input != selected_name && index.prompts.contains_key(input) {
+                    Err(format!("Prompt '{input}' 已存在"))
+                } else {
+                    Ok(())
+                }
+            })
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let new_name = new_name.trim().to_string();
+        if new_name != selected_name {
+            self.rename_prompt(&mut index, &selected_name, &new_name)?;
+            selected_name = new_name;
+        }
+
+        let metadata = index.prompts.get_mut(&selected_name)
+            .ok_or_else(|| CliError::Config(format!("Prompt '{selected_name}' 不存在")))?;
+
         // 编辑描述
         let default_desc = metadata.description.clone().unwrap_or_default();
         let new_description: String = Input::with_theme(&ColorfulTheme::default())
@@ -472,15 +1028,22 @@ impl PromptCommand {
             Some(new_category.trim().to_string())
         };
 
+        // 编辑标签
+        let new_tags: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("标签 (可选，逗号分隔)")
+            .allow_empty(true)
+            .default(metadata.tags.join(", "))
+            .interact_text()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        metadata.tags = parse_tags(&new_tags);
+
         // 编辑内容
         if confirm("是否编辑内容?", true)? {
             let current_content = self.read_prompt_content(&metadata.file)?;
 
             println!("\n{}", style("请在编辑器中修改 prompt 内容...").dim());
-            let new_content = Editor::new()
-                .edit(&current_content)
-                .map_err(|e| CliError::Config(format!("打开编辑器失败: {e}")))?
-                .ok_or_else(|| CliError::UserCancelled)?;
+            let new_content = edit_content(&current_content)?.ok_or(CliError::UserCancelled)?;
 
             let new_content = new_content.trim().to_string();
 
@@ -489,14 +1052,19 @@ impl PromptCommand {
             }
         }
 
+        // 同步模板变量声明
+        let content = self.read_prompt_content(&metadata.file)?;
+        metadata.variables = self.declare_variables(&content, &metadata.variables)?;
+
         // 更新时间
         metadata.updated_at = chrono::Local::now().to_rfc3339();
 
         // 保存索引
         self.save_index(&index)?;
+        self.cleanup_orphan_files(&index)?;
         show_success(&format!("Prompt '{selected_name}' 已更新"));
 
-        self.wait_for_back()?;
+        wait_for_back();
         Ok(())
     }
 
@@ -510,8 +1078,7 @@ impl PromptCommand {
         }
 
         let prompt_names: Vec<String> = {
-            let mut names: Vec<_> = index.prompts.keys().cloned().collect();
-            names.sort();
+            let names: Vec<String> = sorted_keys(&index.prompts).into_iter().cloned().collect();
             names
         };
 
@@ -529,7 +1096,7 @@ impl PromptCommand {
 
         let selected_name = &prompt_names[selection];
 
-        if confirm(&format!("确定要删除 prompt '{selected_name}'?"), false)? {
+        if confirm_destructive(&format!("确定要删除 prompt '{selected_name}'?"))? {
             // 获取文件名并删除文件
             if let Some(metadata) = index.prompts.get(selected_name) {
                 self.delete_prompt_file(&metadata.file)?;
@@ -538,13 +1105,14 @@ impl PromptCommand {
             // 从索引中移除
             index.prompts.remove(selected_name);
             self.save_index(&index)?;
+            self.cleanup_orphan_files(&index)?;
 
             show_success(&format!("Prompt '{selected_name}' 已删除"));
         } else {
             show_info("操作已取消");
         }
 
-        self.wait_for_back()?;
+        wait_for_back();
         Ok(())
     }
 
@@ -558,8 +1126,7 @@ impl PromptCommand {
         }
 
         let prompt_names: Vec<String> = {
-            let mut names: Vec<_> = index.prompts.keys().cloned().collect();
-            names.sort();
+            let names: Vec<String> = sorted_keys(&index.prompts).into_iter().cloned().collect();
             names
         };
 
@@ -578,28 +1145,194 @@ impl PromptCommand {
         let selected_name = &prompt_names[selection];
         let metadata = &index.prompts[selected_name];
 
-        // 读取内容
+        // 读取内容并填写模板变量
         let content = self.read_prompt_content(&metadata.file)?;
+        let content = self.fill_variables(&content, metadata)?;
 
-        // 使用 cli-clipboard 复制到剪贴板
-        use cli_clipboard::{ClipboardContext, ClipboardProvider};
+        // 复制到剪贴板
+        copy_to_clipboard(&content)?;
 
-        let mut ctx = ClipboardContext::new()
-            .map_err(|e| CliError::Config(format!("初始化剪贴板失败: {e}")))?;
+        show_success(&format!("Prompt '{selected_name}' 已复制到剪贴板"));
 
-        ctx.set_contents(content)
-            .map_err(|e| CliError::Config(format!("复制到剪贴板失败: {e}")))?;
+        wait_for_back();
+        Ok(())
+    }
 
-        show_success(&format!("Prompt '{selected_name}' 已复制到剪贴板"));
+    /// 处理渲染模板
+    async fn handle_render(&self) -> Result<()> {
+        let index = self.read_index()?;
 
-        self.wait_for_back()?;
+        let mut templates: Vec<&PromptMetadata> = index
+            .prompts
+            .values()
+            .filter(|m| !m.variables.is_empty())
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if templates.is_empty() {
+            show_info("暂无包含 {{变量}} 的 prompt 模板");
+            return Ok(());
+        }
+
+        let items: Vec<String> = templates
+            .iter()
+            .map(|m| {
+                let names: Vec<&str> = m.variables.iter().map(|v| v.name.as_str()).collect();
+                format!("🧩 {} ({})", m.name, names.join(", "))
+            })
+            .collect();
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("选择要渲染的 prompt")
+            .items(&items)
+            .default(0)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        let metadata = templates[selection];
+        let content = self.read_prompt_content(&metadata.file)?;
+        let rendered = self.fill_variables(&content, metadata)?;
+
+        println!("\n{}", style("渲染结果:").white().bold());
+        println!("{}", style("─".repeat(60)).dim());
+        println!("{}", rendered);
+        println!("{}", style("─".repeat(60)).dim());
+
+        if confirm("是否复制到剪贴板?", true)? {
+            copy_to_clipboard(&rendered)?;
+            show_success(&format!("Prompt '{}' 已复制到剪贴板", metadata.name));
+        }
+
+        wait_for_back();
+        Ok(())
+    }
+
+    /// 按名称获取 prompt 原始内容（非交互，供 `prompt get` 使用）
+    pub fn get_content(&self, name: &str) -> Result<String> {
+        let index = self.read_index()?;
+        let metadata = resolve_prompt(&index, name)?;
+        self.read_prompt_content(&metadata.file)
+    }
+
+    /// 将整个 prompt 库导出为单个 JSON 文件
+    pub fn export_library(&self, path: &Path) -> Result<()> {
+        let index = self.read_index()?;
+
+        let mut contents = HashMap::new();
+        for metadata in index.prompts.values() {
+            contents.insert(metadata.file.clone(), self.read_prompt_content(&metadata.file)?);
+        }
+
+        let archive = PromptArchive {
+            exported_at: chrono::Local::now().to_rfc3339(),
+            index,
+            contents,
+        };
+
+        let content = serde_json::to_string_pretty(&archive)
+            .map_err(|e| CliError::Config(format!("序列化 prompt 库失败: {e}")))?;
+        fs::write(path, content)?;
+
+        show_success(&format!(
+            "已导出 {} 个 prompts 到 {}",
+            archive.index.prompts.len(),
+            path.display()
+        ));
+        Ok(())
+    }
+
+    /// 从导出文件导入 prompt 库，名称冲突时询问处理方式
+    pub fn import_library(&self, path: &Path) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+        let archive: PromptArchive = serde_json::from_str(&content)
+            .map_err(|e| CliError::Parse(format!("解析 prompt 导入文件失败: {e}")))?;
+        archive.validate()?;
+
+        let (imported, skipped) = self.merge_archive(archive, |index, name| {
+            self.ask_import_conflict(index, name)
+        })?;
+
+        show_success(&format!("已导入 {imported} 个 prompts"));
+        if skipped > 0 {
+            show_info(&format!("跳过 {skipped} 个同名 prompts"));
+        }
         Ok(())
     }
 
-    /// 等待返回
-    fn wait_for_back(&self) -> Result<()> {
-        use crate::ui::wait_for_back_confirm;
-        wait_for_back_confirm("")
+    /// 将导入文件合并到当前索引，返回 (导入数量, 跳过数量)
+    fn merge_archive(
+        &self,
+        mut archive: PromptArchive,
+        mut on_conflict: impl FnMut(&PromptIndex, &str) -> Result<ImportConflict>,
+    ) -> Result<(usize, usize)> {
+        let mut index = self.read_index()?;
+
+        let names: Vec<String> = sorted_keys(&archive.index.prompts).into_iter().cloned().collect();
+
+        let (mut imported, mut skipped) = (0, 0);
+        for name in names {
+            let mut metadata = archive.index.prompts.remove(&name).unwrap();
+            let content = archive.contents.get(&metadata.file).cloned().unwrap_or_default();
+
+            let target_name = match index.prompts.get(&name) {
+                None => name,
+                Some(existing) => match on_conflict(&index, &name)? {
+                    ImportConflict::Skip => {
+                        skipped += 1;
+                        continue;
+                    }
+                    ImportConflict::Overwrite => {
+                        self.delete_prompt_file(&existing.file)?;
+                        name
+                    }
+                    ImportConflict::Rename(new_name) => new_name,
+                },
+            };
+
+            let filename = self.generate_filename(&target_name);
+            self.save_prompt_content(&filename, &content)?;
+
+            metadata.name = target_name.clone();
+            metadata.file = filename;
+            index.prompts.insert(target_name, metadata);
+            imported += 1;
+        }
+
+        self.save_index(&index)?;
+        Ok((imported, skipped))
+    }
+
+    /// 询问同名 prompt 的处理方式
+    fn ask_import_conflict(&self, index: &PromptIndex, name: &str) -> Result<ImportConflict> {
+        let choices = ["跳过", "覆盖", "重命名"];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Prompt '{name}' 已存在，如何处理?"))
+            .items(&choices)
+            .default(0)
+            .interact()
+            .map_err(|_| CliError::UserCancelled)?;
+
+        match selection {
+            1 => Ok(ImportConflict::Overwrite),
+            2 => {
+                let new_name: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("新的名称")
+                    .validate_with(|input: &String| -> std::result::Result<(), &str> {
+                        let input = input.trim();
+                        if input.is_empty() {
+                            Err("名称不能为空")
+                        } else if index.prompts.contains_key(input) {
+                            Err("该名称已存在")
+                        } else {
+                            Ok(())
+                        }
+                    })
+                    .interact_text()
+                    .map_err(|_| CliError::UserCancelled)?;
+                Ok(ImportConflict::Rename(new_name.trim().to_string()))
+            }
+            _ => Ok(ImportConflict::Skip),
+        }
     }
 }
 
@@ -607,11 +1340,13 @@ impl PromptCommand {
 #[derive(Debug, Clone, Copy)]
 enum PromptMenuChoice {
     List,
+    Search,
     View,
     Add,
     Edit,
     Delete,
     Copy,
+    Render,
     Back,
 }
 
@@ -619,12 +1354,220 @@ impl std::fmt::Display for PromptMenuChoice {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             PromptMenuChoice::List => write!(f, "📋 列表展示 - 查看所有 prompts"),
+            PromptMenuChoice::Search => write!(f, "🔍 搜索 Prompt - 按关键字或分类筛选"),
             PromptMenuChoice::View => write!(f, "👁️  查看内容 - 查看 prompt 详细内容"),
             PromptMenuChoice::Add => write!(f, "➕ 添加 Prompt - 创建新的 prompt"),
             PromptMenuChoice::Edit => write!(f, "📝 编辑 Prompt - 修改现有 prompt"),
             PromptMenuChoice::Delete => write!(f, "🗑️  删除 Prompt - 删除指定 prompt"),
             PromptMenuChoice::Copy => write!(f, "📋 复制到剪贴板 - 快速复用 prompt"),
+            PromptMenuChoice::Render => write!(f, "🧩 渲染模板 - 填写变量后预览并复制"),
             PromptMenuChoice::Back => write!(f, "⬅️  返回上一级菜单"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_and_render_placeholders() {
+        let content = "你好 {{ name }}，请审查 {{lang}} 代码。{{name}} {{}} {{not valid}} {{unclosed";
+        assert_eq!(extract_placeholders(content), vec!["name", "lang"]);
+
+        let values = HashMap::from([
+            ("name".to_string(), "Alice".to_string()),
+            ("lang".to_string(), "Rust".to_string()),
+        ]);
+        assert_eq!(
+            render_template(content, &values),
+            "你好 Alice，请审查 Rust 代码。Alice {{}} {{not valid}} {{unclosed"
+        );
+
+        // 未提供值的占位符保持原样
+        assert_eq!(render_template("{{a}}-{{b}}", &HashMap::new()), "{{a}}-{{b}}");
+    }
+
+    #[test]
+    fn test_read_until_terminator() {
+        let input = std::io::Cursor::new("第一行\n\n  缩进行\n.\n被忽略\n");
+        assert_eq!(read_until_terminator(input).unwrap(), "第一行\n\n  缩进行");
+
+        // 没有结束行时读到输入结束
+        let input = std::io::Cursor::new("only\n");
+        assert_eq!(read_until_terminator(input).unwrap(), "only");
+    }
+
+    #[test]
+    fn test_prompt_matches() {
+        let metadata = PromptMetadata {
+            name: "Code Review".to_string(),
+            description: Some("审查 PR".to_string()),
+            category: Some("dev".to_string()),
+            file: "code_review.txt".to_string(),
+            tags: vec!["review".to_string(), "rust".to_string()],
+            created_at: String::new(),
+            updated_at: String::new(),
+            variables: vec![],
+        };
+
+        assert!(prompt_matches(&metadata, "review"));
+        assert!(prompt_matches(&metadata, " PR "));
+        assert!(prompt_matches(&metadata, "DEV"));
+        assert!(!prompt_matches(&metadata, "翻译"));
+        assert!(!prompt_matches(&metadata, "code_review.txt"));
+
+        assert!(has_all_tags(&metadata, &["rust"]));
+        assert!(has_all_tags(&metadata, &["rust", "review"]));
+        assert!(!has_all_tags(&metadata, &["rust", "go"]));
+        assert!(has_all_tags(&metadata, &[]));
+    }
+
+    fn temp_prompts_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ca-switch-prompt-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn add_prompt(cmd: &PromptCommand, name: &str, content: &str) {
+        let mut index = cmd.read_index().unwrap();
+        let file = cmd.generate_filename(name);
+        cmd.save_prompt_content(&file, content).unwrap();
+        index.prompts.insert(
+            name.to_string(),
+            PromptMetadata {
+                name: name.to_string(),
+                description: None,
+                category: None,
+                file,
+                tags: vec![],
+                created_at: String::new(),
+                updated_at: String::new(),
+                variables: vec![],
+            },
+        );
+        cmd.save_index(&index).unwrap();
+    }
+
+    #[test]
+    fn test_rename_prompt_leaves_no_orphans() {
+        let cmd = PromptCommand::with_dir(temp_prompts_dir("rename")).unwrap();
+        add_prompt(&cmd, "review", "审查");
+        add_prompt(&cmd, "translate", "翻译");
+
+        let mut index = cmd.read_index().unwrap();
+        cmd.rename_prompt(&mut index, "review", "code-review").unwrap();
+        assert!(cmd.rename_prompt(&mut index, "code-review", "translate").is_err());
+        cmd.save_index(&index).unwrap();
+
+        let metadata = &index.prompts["code-review"];
+        assert_eq!(metadata.name, "code-review");
+        assert_eq!(metadata.file, "code-review.txt");
+        assert_eq!(cmd.read_prompt_content(&metadata.file).unwrap(), "审查");
+        assert!(!cmd.prompts_dir.join("review.txt").exists());
+
+        // 手动遗留的文件会被清理，索引引用的文件保留
+        fs::write(cmd.prompts_dir.join("stale.txt"), "旧").unwrap();
+        assert_eq!(cmd.remove_orphan_files(&index).unwrap(), vec!["stale.txt"]);
+        assert!(cmd.remove_orphan_files(&index).unwrap().is_empty());
+
+        let mut files: Vec<String> = fs::read_dir(&cmd.prompts_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".txt"))
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["code-review.txt", "translate.txt"]);
+    }
+
+    #[test]
+    fn test_export_import_library() {
+        let source = PromptCommand::with_dir(temp_prompts_dir("source")).unwrap();
+        add_prompt(&source, "review", "审查 {{lang}} 代码");
+        add_prompt(&source, "translate", "翻译");
+
+        let archive_path = source.prompts_dir.join("archive.json");
+        source.export_library(&archive_path).unwrap();
+
+        let archive: PromptArchive =
+            serde_json::from_str(&fs::read_to_string(&archive_path).unwrap()).unwrap();
+        archive.validate().unwrap();
+
+        // 目标库已有同名 prompt: 一个重命名、其余跳过
+        let target = PromptCommand::with_dir(temp_prompts_dir("target")).unwrap();
+        add_prompt(&target, "review", "旧内容");
+
+        let (imported, skipped) = target
+            .merge_archive(archive.clone(), |_, _| Ok(ImportConflict::Rename("review-2".to_string())))
+            .unwrap();
+        assert_eq!((imported, skipped), (2, 0));
+
+        let index = target.read_index().unwrap();
+        assert_eq!(target.read_prompt_content(&index.prompts["review"].file).unwrap(), "旧内容");
+        assert_eq!(
+            target.read_prompt_content(&index.prompts["review-2"].file).unwrap(),
+            "审查 {{lang}} 代码"
+        );
+
+        let (imported, skipped) = target.merge_archive(archive, |_, _| Ok(ImportConflict::Skip)).unwrap();
+        assert_eq!((imported, skipped), (0, 2));
+
+        // 缺少内容的导入文件无法通过校验
+        let mut broken = PromptArchive {
+            exported_at: String::new(),
+            index: target.read_index().unwrap(),
+            contents: HashMap::new(),
+        };
+        assert!(broken.validate().is_err());
+        broken.index.prompts.clear();
+        assert!(broken.validate().is_ok());
+    }
+
+    #[test]
+    fn test_get_content_resolves_names() {
+        let cmd = PromptCommand::with_dir(temp_prompts_dir("get")).unwrap();
+        add_prompt(&cmd, "review", "审查代码");
+        add_prompt(&cmd, "review-rust", "审查 Rust 代码");
+        add_prompt(&cmd, "translate", "翻译");
+
+        assert_eq!(cmd.get_content("review").unwrap(), "审查代码");
+        assert_eq!(cmd.get_content("TRANS").unwrap(), "翻译");
+        assert!(matches!(cmd.get_content("missing"), Err(CliError::NotFound(_))));
+        assert!(matches!(cmd.get_content("rev"), Err(CliError::Custom(_))));
+    }
+
+    #[test]
+    fn test_read_content_file() {
+        let dir = temp_prompts_dir("read-file");
+        fs::create_dir_all(&dir).unwrap();
+
+        let text = dir.join("prompt.md");
+        fs::write(&text, "# 审查\n请审查以下代码").unwrap();
+        assert_eq!(read_content_file(&text).unwrap(), "# 审查\n请审查以下代码");
+
+        let binary = dir.join("image.bin");
+        fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        assert!(matches!(read_content_file(&binary), Err(CliError::Parse(_))));
+
+        assert!(read_content_file(&dir.join("missing.txt")).is_err());
+
+        if let Ok(home) = crate::config::home_dir() {
+            assert_eq!(expand_home("~/a.txt"), home.join("a.txt"));
+        }
+        assert_eq!(expand_home("/tmp/a.txt"), PathBuf::from("/tmp/a.txt"));
+    }
+
+    #[test]
+    fn test_parse_tags_and_backward_compat() {
+        assert_eq!(parse_tags(" rust, review，rust ,, "), vec!["rust", "review"]);
+        assert!(parse_tags("").is_empty());
+
+        // 旧版 index.json 中没有 tags 字段
+        let metadata: PromptMetadata = serde_json::from_str(
+            r#"{"name": "a", "description": null, "category": null, "file": "a.txt",
+                "created_at": "", "updated_at": ""}"#,
+        )
+        .unwrap();
+        assert!(metadata.tags.is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+// 检测报告渲染
+// 站点/模型检测结果先整理为 DetectionReport，再输出到终端 (彩色) 或 Markdown 文本
+
+use crate::config::{DetectedModel, ModelDetectionResult, SiteDetectionResult};
+use crate::error::Result;
+use crate::logging::log_write;
+use console::style;
+use std::path::PathBuf;
+
+/// 指标在终端中的显示样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tone {
+    Good,
+    Bad,
+    Metric,
+    Highlight,
+}
+
+/// 报告类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportKind {
+    Site,
+    Model,
+}
+
+/// 报告中的一项指标
+#[derive(Debug, Clone)]
+struct ReportField {
+    icon: &'static str,
+    label: &'static str,
+    value: String,
+    tone: Tone,
+}
+
+impl ReportField {
+    fn new(icon: &'static str, label: &'static str, value: impl Into<String>, tone: Tone) -> Self {
+        Self {
+            icon,
+            label,
+            value: value.into(),
+            tone,
+        }
+    }
+}
+
+/// 站点或模型的检测报告
+#[derive(Debug, Clone)]
+pub struct DetectionReport {
+    kind: ReportKind,
+    title: String,
+    provider: String,
+    fields: Vec<ReportField>,
+    models: Vec<DetectedModel>,
+    error: Option<String>,
+    detected_at: String,
+}
+
+impl DetectionReport {
+    /// 整理站点检测结果
+    pub fn site(provider: &str, result: &SiteDetectionResult) -> Self {
+        let mut fields = Vec::new();
+        if result.is_available {
+            fields.push(ReportField::new("✅", "站点状态", "可用", Tone::Good));
+            fields.push(ReportField::new("🔑", "API Key", "有效", Tone::Good));
+            if let Some(time) = result.response_time_ms {
+                fields.push(ReportField::new("⚡", "响应时间", format!("{:.0} ms", time), Tone::Metric));
+            }
+            fields.push(ReportField::new(
+                "🤖",
+                "检测到模型",
+                format!("{} 个", result.available_models.len()),
+                Tone::Highlight,
+            ));
+        } else if result.timed_out {
+            fields.push(ReportField::new("⏱️ ", "站点状态", "超时", Tone::Bad));
+        } else {
+            fields.push(ReportField::new("❌", "站点状态", "不可用", Tone::Bad));
+        }
+
+        Self {
+            kind: ReportKind::Site,
+            title: "站点检测报告".to_string(),
+            provider: provider.to_string(),
+            fields,
+            models: site_models(result),
+            error: (!result.is_available)
+                .then(|| result.error_message.clone())
+                .flatten(),
+            detected_at: result.detected_at.clone(),
+        }
+    }
+
+    /// 整理模型检测结果
+    pub fn model(provider: &str, result: &ModelDetectionResult) -> Self {
+        let mut fields = Vec::new();
+        if result.is_available {
+            fields.push(ReportField::new("✅", "模型状态", "可用", Tone::Good));
+            if let Some(time) = result.first_token_time_ms {
+                fields.push(ReportField::new("⚡", "首次响应时间", format!("{:.0} ms", time), Tone::Metric));
+            }
+            if let Some(time) = result.total_response_time_ms {
+                fields.push(ReportField::new("⏱️ ", "总响应时间", format!("{:.0} ms", time), Tone::Metric));
+            }
+            if let Some(tps) = result.tokens_per_second {
+                fields.push(ReportField::new("🚀", "Token速度", format!("{:.2} tokens/s", tps), Tone::Highlight));
+            }
+            match result.stream_available {
+                Some(true) => fields.push(ReportField::new("✅", "流式输出", "支持", Tone::Good)),
+                Some(false) => fields.push(ReportField::new("❌", "流式输出", "不支持", Tone::Bad)),
+                None => {}
+            }
+        } else if result.timed_out {
+            fields.push(ReportField::new("⏱️ ", "模型状态", "超时", Tone::Bad));
+        } else {
+            fields.push(ReportField::new("❌", "模型状态", "不可用", Tone::Bad));
+        }
+
+        Self {
+            kind: ReportKind::Model,
+            title: format!("模型检测报告: {}", result.model_id),
+            provider: provider.to_string(),
+            fields,
+            models: vec![DetectedModel {
+                id: result.model_id.clone(),
+                context_length: None,
+                owned_by: None,
+            }],
+            error: (!result.is_available)
+                .then(|| result.error_message.clone())
+                .flatten(),
+            detected_at: result.detected_at.clone(),
+        }
+    }
+
+    /// 在终端中显示报告
+    pub fn print(&self) {
+        println!("\n{}", style("═".repeat(60)).dim());
+        println!("{}", style(format!("📊 {}", self.title)).cyan().bold());
+        println!("{}", style("═".repeat(60)).dim());
+        println!();
+
+        for field in &self.fields {
+            let text = format!("{}: {}", field.label, field.value);
+            let styled = match field.tone {
+                Tone::Good => style(text).green(),
+                Tone::Bad => style(text).red().bold(),
+                Tone::Metric => style(text).yellow(),
+                Tone::Highlight => style(text).cyan().bold(),
+            };
+            println!("{} {}", field.icon, styled);
+        }
+
+        // 站点报告列出检测到的模型
+        if self.kind == ReportKind::Site {
+            for (i, model) in self.models.iter().enumerate() {
+                match model_details(model) {
+                    Some(details) => println!("  {}. {} {}", i + 1, style(&model.id).white(), style(format!("({details})")).dim()),
+                    None => println!("  {}. {}", i + 1, style(&model.id).white()),
+                }
+            }
+        }
+
+        if let Some(err) = &self.error {
+            println!("⚠️  {}", style(format!("错误: {}", err)).yellow());
+        }
+
+        println!(
+            "\n{}",
+            style(format!("检测时间: {}", self.detected_at)).dim()
+        );
+        println!("{}", style("═".repeat(60)).dim());
+    }
+
+    /// 渲染为 Markdown 文本 (不含颜色与 emoji)
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# {}\n\n", self.title);
+        md.push_str(&format!("- Provider: {}\n", escape_cell(&self.provider)));
+        md.push_str(&format!("- 检测时间: {}\n", self.detected_at));
+        md.push_str(&format!(
+            "- 生成时间: {}\n\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        ));
+
+        md.push_str("| 指标 | 结果 |\n|------|------|\n");
+        for field in &self.fields {
+            md.push_str(&format!("| {} | {} |\n", field.label, escape_cell(&field.value)));
+        }
+        if let Some(err) = &self.error {
+            md.push_str(&format!("| 错误 | {} |\n", escape_cell(err)));
+        }
+
+        if self.kind == ReportKind::Site && !self.models.is_empty() {
+            // 站点返回了上下文长度或所有者时增加对应列
+            let has_details = self.models.iter().any(|m| m.context_length.is_some() || m.owned_by.is_some());
+            if has_details {
+                md.push_str("\n## 模型列表\n\n| # | 模型 | 上下文 | 所有者 |\n|---|------|--------|--------|\n");
+            } else {
+                md.push_str("\n## 模型列表\n\n| # | 模型 |\n|---|------|\n");
+            }
+            for (i, model) in self.models.iter().enumerate() {
+                if has_details {
+                    md.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        i + 1,
+                        escape_cell(&model.id),
+                        model.context_length.map_or("-".to_string(), format_tokens),
+                        model.owned_by.as_deref().map_or("-".to_string(), escape_cell)
+                    ));
+                } else {
+                    md.push_str(&format!("| {} | {} |\n", i + 1, escape_cell(&model.id)));
+                }
+            }
+        }
+
+        md
+    }
+
+    /// 将 Markdown 报告写入当前目录，返回文件路径
+    pub fn export_markdown(&self) -> Result<PathBuf> {
+        let kind = match self.kind {
+            ReportKind::Site => "site",
+            ReportKind::Model => "model",
+        };
+        let file_name = format!(
+            "detection-report-{}-{}-{}.md",
+            kind,
+            sanitize_file_name(&self.provider),
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = std::env::current_dir()
+            .map_err(|e| format!("获取当前目录失败: {}", e))?
+            .join(file_name);
+
+        log_write(&path);
+        std::fs::write(&path, self.to_markdown())
+            .map_err(|e| format!("写入检测报告失败: {}", e))?;
+
+        Ok(path)
+    }
+}
+
+/// 站点报告中的模型列表，旧版检测结果没有详情时按模型 ID 补齐
+fn site_models(result: &SiteDetectionResult) -> Vec<DetectedModel> {
+    if !result.models.is_empty() {
+        return result.models.clone();
+    }
+    result
+        .available_models
+        .iter()
+        .map(|id| DetectedModel {
+            id: id.clone(),
+            context_length: None,
+            owned_by: None,
+        })
+        .collect()
+}
+
+/// 模型详情 (如 `128K 上下文, openai`)，没有任何详情时返回 None
+fn model_details(model: &DetectedModel) -> Option<String> {
+    let details: Vec<String> = model
+        .context_length
+        .map(|tokens| format!("{} 上下文", format_tokens(tokens)))
+        .into_iter()
+        .chain(model.owned_by.clone())
+        .collect();
+    (!details.is_empty()).then(|| details.join(", "))
+}
+
+/// 格式化 token 数量: 1000 或 1024 的整数倍显示为 K/M (如 128K、1M)，否则原样显示
+fn format_tokens(tokens: u64) -> String {
+    for (unit, suffix) in [(1_000_000, "M"), (1024 * 1024, "M"), (1000, "K"), (1024, "K")] {
+        if tokens >= unit && tokens.is_multiple_of(unit) {
+            return format!("{}{}", tokens / unit, suffix);
+        }
+    }
+    tokens.to_string()
+}
+
+/// 转义 Markdown 表格单元格中的 `|` 与换行
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// 文件名中只保留字母数字、`-`、`_` 与 `.`，其余替换为 `_`
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_report_markdown() {
+        let result = SiteDetectionResult {
+            detected_at: "2026-01-01T00:00:00Z".to_string(),
+            is_available: true,
+            api_key_valid: true,
+            available_models: vec!["gpt-4o".to_string(), "a|b".to_string()],
+            models: vec![],
+            response_time_ms: Some(321.4),
+            error_message: None,
+            timed_out: false,
+        };
+        let md = DetectionReport::site("relay", &result).to_markdown();
+
+        assert!(md.starts_with("# 站点检测报告\n"));
+        assert!(md.contains("- Provider: relay\n"));
+        assert!(md.contains("- 检测时间: 2026-01-01T00:00:00Z\n"));
+        assert!(md.contains("| 响应时间 | 321 ms |\n"));
+        assert!(md.contains("| 检测到模型 | 2 个 |\n"));
+        assert!(md.contains("| 2 | a\\|b |\n"));
+    }
+
+    #[test]
+    fn test_model_report_markdown() {
+        let result = ModelDetectionResult {
+            detected_at: "2026-01-01T00:00:00Z".to_string(),
+            model_id: "gpt-4o".to_string(),
+            is_available: false,
+            first_token_time_ms: None,
+            tokens_per_second: None,
+            total_response_time_ms: None,
+            stream_available: None,
+            error_message: Some("请求超时: 60 秒内未完成".to_string()),
+            timed_out: true,
+        };
+        let md = DetectionReport::model("relay/cn", &result).to_markdown();
+
+        assert!(md.starts_with("# 模型检测报告: gpt-4o\n"));
+        assert!(md.contains("| 模型状态 | 超时 |\n"));
+        assert!(md.contains("| 错误 | 请求超时: 60 秒内未完成 |\n"));
+        assert!(!md.contains("## 模型列表"));
+        assert_eq!(sanitize_file_name("relay/cn"), "relay_cn");
+    }
+
+    #[test]
+    fn test_site_report_with_model_details() {
+        let models: Vec<DetectedModel> = serde_json::from_str(
+            r#"[{"id": "gpt-4o", "owned_by": "openai", "context_length": 128000, "created": 1},
+                {"id": "qwen", "max_model_len": 32768},
+                {"id": "plain"}]"#,
+        )
+        .unwrap();
+        let result = SiteDetectionResult {
+            detected_at: "2026-01-01T00:00:00Z".to_string(),
+            is_available: true,
+            api_key_valid: true,
+            available_models: models.iter().map(|m| m.id.clone()).collect(),
+            models,
+            response_time_ms: None,
+            error_message: None,
+            timed_out: false,
+        };
+        assert_eq!(result.context_length("qwen"), Some(32768));
+
+        let md = DetectionReport::site("relay", &result).to_markdown();
+        assert!(md.contains("| # | 模型 | 上下文 | 所有者 |\n"));
+        assert!(md.contains("| 1 | gpt-4o | 128K | openai |\n"));
+        assert!(md.contains("| 2 | qwen | 32K | - |\n"));
+        assert!(md.contains("| 3 | plain | - | - |\n"));
+    }
+}
@@ -0,0 +1,84 @@
+// 切换前的 Token 验证
+// Base URL 预设与认证头格式选择，以及切换前的 Token 验证: 复用 Detector 的 /v1/models 请求，检查 Key 是否有效并显示响应延迟
+
+use crate::config::{AuthHeaderStyle, BaseUrlPreset, ConfigManager, Detector, DetectorTimeouts};
+use crate::error::{CliError, Result};
+use crate::ui::{confirm, prompt_theme, show_error, show_success, spinner};
+use dialoguer::{Input, Select};
+
+/// 验证 API Key，并在失败时询问是否继续切换
+///
+/// 返回 `false` 表示用户选择放弃切换
+pub(crate) fn verify_before_switch(
+    config_manager: &ConfigManager,
+    base_url: &str,
+    api_key: &str,
+    auth_style: AuthHeaderStyle,
+) -> Result<bool> {
+    let settings = config_manager.read_global_config()?.settings;
+    let detector = Detector::with_timeouts(DetectorTimeouts::from_settings(&settings));
+
+    let loading = spinner("正在验证 Token...");
+    let result = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(async {
+            detector
+                .detect_site_with_auth(base_url, api_key, auth_style)
+                .await
+        })
+    });
+    loading.finish();
+
+    if result.api_key_valid {
+        let latency = result
+            .response_time_ms
+            .map(|ms| format!("{:.0}ms", ms))
+            .unwrap_or_else(|| "-".to_string());
+        show_success(&format!("Token 验证通过 (延迟 {})", latency));
+        return Ok(true);
+    }
+
+    show_error(&format!(
+        "Token 验证失败: {}",
+        result.error_message.as_deref().unwrap_or("未知错误")
+    ));
+    confirm("仍要继续切换吗?", false)
+}
+
+/// 选择认证头格式，默认选中 provider 的默认值
+pub(crate) fn select_auth_header_style(default: AuthHeaderStyle) -> Result<AuthHeaderStyle> {
+    let styles = AuthHeaderStyle::ALL;
+    let default_idx = styles.iter().position(|s| *s == default).unwrap_or(0);
+
+    let idx = Select::with_theme(&*prompt_theme())
+        .with_prompt("认证头格式")
+        .items(&styles)
+        .default(default_idx)
+        .interact()
+        .map_err(|_| CliError::UserCancelled)?;
+
+    Ok(styles[idx])
+}
+
+/// 从预设中选择 Base URL，选择“自定义”时手动输入 (默认填入第一项预设)
+pub(crate) fn select_base_url(presets: &[BaseUrlPreset]) -> Result<String> {
+    let mut items: Vec<String> = presets.iter().map(|p| p.to_string()).collect();
+    items.push("自定义".to_string());
+
+    let idx = Select::with_theme(&*prompt_theme())
+        .with_prompt("API Base URL")
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|_| CliError::UserCancelled)?;
+
+    if let Some(preset) = presets.get(idx) {
+        return Ok(preset.url.to_string());
+    }
+
+    let theme = prompt_theme();
+    let mut input = Input::<String>::with_theme(&*theme).with_prompt("API Base URL");
+    if let Some(first) = presets.first() {
+        input = input.default(first.url.to_string());
+    }
+    input.interact_text().map_err(|_| CliError::UserCancelled)
+}
@@ -1,29 +1,28 @@
 // Claude 配置管理器
 // 负责管理 ~/.ca-switch/claude.json 和同步到 ~/.claude/settings.json
 
+use crate::error::{CliError, Result};
+use crate::logging::log_write;
+use crate::config::migration::read_migrated;
 use crate::config::models::{
-    ClaudeActiveConfig, ClaudeConfig, ClaudeSite, VertexConfig,
+    page_sites, sites_with_base_url, sort_sites, AuthHeaderStyle, BedrockConfig, ClaudeActiveConfig, ClaudeConfig, ClaudeSite, VertexConfig,
 };
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Claude 配置管理器
 pub struct ClaudeConfigManager {
     #[allow(dead_code)]
     config_dir: PathBuf,       // ~/.ca-switch
     claude_config_file: PathBuf, // ~/.ca-switch/claude.json
-    #[allow(dead_code)]
     claude_dir: PathBuf,       // ~/.claude
     settings_file: PathBuf,    // ~/.claude/settings.json
 }
 
 impl ClaudeConfigManager {
-    /// 创建新的 Claude 配置管理器
-    pub fn new() -> Result<Self, String> {
-        let home_dir = dirs::home_dir().ok_or("无法获取用户主目录")?;
-
-        let config_dir = home_dir.join(".ca-switch");
+    /// 创建新的 Claude 配置管理器 (`config_dir` 为 ca-switch 数据目录)
+    pub fn new(config_dir: PathBuf, home_dir: &Path) -> Result<Self> {
         let claude_config_file = config_dir.join("claude.json");
         let claude_dir = home_dir.join(".claude");
         let settings_file = claude_dir.join("settings.json");
@@ -45,26 +44,22 @@ impl ClaudeConfigManager {
     // ========================================================================
 
     /// 读取 claude.json
-    pub fn read_config(&self) -> Result<ClaudeConfig, String> {
-        if !self.claude_config_file.exists() {
-            // 如果文件不存在，返回空配置
-            return Ok(ClaudeConfig::new());
-        }
-
-        let content = fs::read_to_string(&self.claude_config_file)
-            .map_err(|e| format!("读取配置文件失败: {}", e))?;
-
-        serde_json::from_str(&content)
-            .map_err(|e| format!("解析配置文件失败: {}", e))
+    pub fn read_config(&self) -> Result<ClaudeConfig> {
+        let config = read_migrated(&self.claude_config_file, "claude.json", |config| self.write_config(config))?;
+        // 文件不存在时返回空配置
+        Ok(config.unwrap_or_else(ClaudeConfig::new))
     }
 
     /// 写入 claude.json
-    pub fn write_config(&self, config: &ClaudeConfig) -> Result<(), String> {
+    pub fn write_config(&self, config: &ClaudeConfig) -> Result<()> {
         let content = serde_json::to_string_pretty(config)
             .map_err(|e| format!("序列化配置失败: {}", e))?;
 
+        log_write(&self.claude_config_file);
         fs::write(&self.claude_config_file, content)
-            .map_err(|e| format!("写入配置文件失败: {}", e))
+            .map_err(|e| format!("写入配置文件失败: {}", e))?;
+
+        Ok(())
     }
 
     // ========================================================================
@@ -72,13 +67,28 @@ impl ClaudeConfigManager {
     // ========================================================================
 
     /// 获取所有站点
-    pub fn get_all_sites(&self) -> Result<HashMap<String, ClaudeSite>, String> {
+    pub fn get_all_sites(&self) -> Result<HashMap<String, ClaudeSite>> {
         let config = self.read_config()?;
         Ok(config.sites)
     }
 
+    /// Base URL 与 `base_url` 相同的其他站点 (忽略末尾的 `/` 与 `/v1`)，`exclude` 为正在编辑的站点
+    pub fn find_sites_with_base_url(&self, base_url: &str, exclude: Option<&str>) -> Result<Vec<String>> {
+        Ok(sites_with_base_url(&self.get_all_sites()?, base_url, exclude))
+    }
+
+    /// 获取按名称排序的所有站点
+    pub fn get_sites_sorted(&self) -> Result<Vec<(String, ClaudeSite)>> {
+        Ok(sort_sites(self.get_all_sites()?))
+    }
+
+    /// 获取按名称排序后的一页站点，`limit` 为 None 时取 `offset` 之后的全部
+    pub fn get_sites_page(&self, offset: usize, limit: Option<usize>) -> Result<Vec<(String, ClaudeSite)>> {
+        Ok(page_sites(self.get_sites_sorted()?, offset, limit))
+    }
+
     /// 获取单个站点
-    pub fn get_site(&self, site_name: &str) -> Result<Option<ClaudeSite>, String> {
+    pub fn get_site(&self, site_name: &str) -> Result<Option<ClaudeSite>> {
         let config = self.read_config()?;
         Ok(config.get_site(site_name).cloned())
     }
@@ -89,12 +99,12 @@ impl ClaudeConfigManager {
         site_name: String,
         url: String,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         // 检查站点是否已存在
         if config.sites.contains_key(&site_name) {
-            return Err(format!("站点 '{}' 已存在", site_name));
+            return Err(format!("站点 '{}' 已存在", site_name).into());
         }
 
         let site = ClaudeSite::new(url, description);
@@ -108,12 +118,12 @@ impl ClaudeConfigManager {
         site_name: &str,
         url: Option<String>,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if let Some(new_url) = url {
             site.metadata.url = new_url;
@@ -127,6 +137,38 @@ impl ClaudeConfigManager {
         self.write_config(&config)
     }
 
+    /// 设置站点标签 (为空时清除)
+    pub fn set_site_tags(&self, site_name: &str, tags: Vec<String>) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.metadata.tags = tags;
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
+    /// 设置站点的认证头格式
+    pub fn set_auth_header_style(
+        &self,
+        site_name: &str,
+        auth_header_style: AuthHeaderStyle,
+    ) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.config.auth_header_style = Some(auth_header_style);
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
     /// 更新站点配置
     pub fn update_site_config(
         &self,
@@ -134,12 +176,13 @@ impl ClaudeConfigManager {
         base_url: Option<String>,
         model: Option<String>,
         vertex: Option<VertexConfig>,
-    ) -> Result<(), String> {
+        bedrock: Option<BedrockConfig>,
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if let Some(url) = base_url {
             site.config.base_url = Some(url);
@@ -151,6 +194,17 @@ impl ClaudeConfigManager {
 
         if let Some(v) = vertex {
             site.config.vertex = v;
+            // Vertex 与 Bedrock 互斥
+            if site.config.vertex.enabled {
+                site.config.bedrock.enabled = false;
+            }
+        }
+
+        if let Some(b) = bedrock {
+            site.config.bedrock = b;
+            if site.config.bedrock.enabled {
+                site.config.vertex.enabled = false;
+            }
         }
 
         site.update_timestamp();
@@ -158,12 +212,12 @@ impl ClaudeConfigManager {
     }
 
     /// 删除站点
-    pub fn remove_site(&self, site_name: &str) -> Result<(), String> {
+    pub fn remove_site(&self, site_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         config
             .remove_site(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         self.write_config(&config)
     }
@@ -178,16 +232,16 @@ impl ClaudeConfigManager {
         site_name: &str,
         token_name: String,
         token: String,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         // 检查 token 是否已存在
         if site.tokens.contains_key(&token_name) {
-            return Err(format!("Token '{}' 已存在于站点 '{}'", token_name, site_name));
+            return Err(format!("Token '{}' 已存在于站点 '{}'", token_name, site_name).into());
         }
 
         site.add_token(token_name, token);
@@ -200,15 +254,15 @@ impl ClaudeConfigManager {
         site_name: &str,
         token_name: &str,
         new_token: String,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if !site.tokens.contains_key(token_name) {
-            return Err(format!("Token '{}' 不存在于站点 '{}'", token_name, site_name));
+            return Err(CliError::NotFound(format!("Token '{}' 不存在于站点 '{}'", token_name, site_name)));
         }
 
         site.add_token(token_name.to_string(), new_token);
@@ -216,27 +270,39 @@ impl ClaudeConfigManager {
     }
 
     /// 删除 token
-    pub fn remove_token(&self, site_name: &str, token_name: &str) -> Result<(), String> {
+    pub fn remove_token(&self, site_name: &str, token_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         site.remove_token(token_name)
-            .ok_or_else(|| format!("Token '{}' 不存在于站点 '{}'", token_name, site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("Token '{}' 不存在于站点 '{}'", token_name, site_name)))?;
+
+        self.write_config(&config)
+    }
+
+    /// 记录一次 token 激活（使用次数与最近使用时间）
+    pub fn record_usage(&self, site_name: &str, token_name: &str) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
+        site.record_usage(token_name);
         self.write_config(&config)
     }
 
     /// 获取站点的所有 tokens
     #[allow(dead_code)]
-    pub fn get_tokens(&self, site_name: &str) -> Result<HashMap<String, String>, String> {
+    pub fn get_tokens(&self, site_name: &str) -> Result<HashMap<String, String>> {
         let config = self.read_config()?;
 
         let site = config
             .get_site(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         Ok(site.tokens.clone())
     }
@@ -246,10 +312,41 @@ impl ClaudeConfigManager {
     // ========================================================================
 
     /// 同步配置到 Claude Code 官方配置文件
-    pub fn sync_to_settings(&self, active_config: &ClaudeActiveConfig) -> Result<(), String> {
+    pub fn sync_to_settings(&self, active_config: &ClaudeActiveConfig) -> Result<Vec<PathBuf>> {
+        self.sync_to_dir(&self.claude_dir, active_config)
+    }
+
+    /// 同步配置到指定的 .claude 目录（如项目级 ./.claude/settings.json）
+    pub fn sync_to_dir(&self, claude_dir: &Path, active_config: &ClaudeActiveConfig) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(claude_dir).map_err(|e| format!("创建 .claude 目录失败: {}", e))?;
+
+        let settings_file = claude_dir.join("settings.json");
+        let content = self.render_settings(&settings_file, active_config)?;
+
+        log_write(&settings_file);
+        fs::write(&settings_file, content)
+            .map_err(|e| format!("写入 settings.json 失败: {}", e))?;
+
+        Ok(vec![settings_file])
+    }
+
+    /// 渲染将要写入的文件内容（不写入磁盘，用于 dry-run）
+    pub fn render_files(&self, active_config: &ClaudeActiveConfig) -> Result<Vec<(PathBuf, String)>> {
+        self.render_files_in(&self.claude_dir, active_config)
+    }
+
+    /// 渲染将要写入指定 .claude 目录的文件内容
+    pub fn render_files_in(&self, claude_dir: &Path, active_config: &ClaudeActiveConfig) -> Result<Vec<(PathBuf, String)>> {
+        let settings_file = claude_dir.join("settings.json");
+        let content = self.render_settings(&settings_file, active_config)?;
+        Ok(vec![(settings_file, content)])
+    }
+
+    /// 渲染与现有 settings.json 合并后的内容
+    fn render_settings(&self, settings_file: &Path, active_config: &ClaudeActiveConfig) -> Result<String> {
         // 读取现有 settings.json（如果存在）
-        let mut settings = if self.settings_file.exists() {
-            let content = fs::read_to_string(&self.settings_file)
+        let mut settings = if settings_file.exists() {
+            let content = fs::read_to_string(settings_file)
                 .map_err(|e| format!("读取 settings.json 失败: {}", e))?;
 
             serde_json::from_str::<serde_json::Value>(&content)
@@ -266,10 +363,11 @@ impl ClaudeConfigManager {
             obj.remove("ANTHROPIC_VERTEX_PROJECT_ID");
             obj.remove("CLAUDE_CODE_USE_VERTEX");
             obj.remove("CLAUDE_CODE_SKIP_VERTEX_AUTH");
+            obj.remove("CLAUDE_CODE_USE_BEDROCK");
         }
 
         // 确保 env 对象存在
-        if !settings.get("env").is_some() {
+        if settings.get("env").is_none() {
             settings["env"] = serde_json::json!({});
         }
 
@@ -282,50 +380,85 @@ impl ClaudeConfigManager {
             env_obj.remove("CLAUDE_CODE_USE_VERTEX");
             env_obj.remove("CLAUDE_CODE_SKIP_VERTEX_AUTH");
             env_obj.remove("CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC");
+            env_obj.remove("CLAUDE_CODE_USE_BEDROCK");
+            env_obj.remove("AWS_BEARER_TOKEN_BEDROCK");
+            env_obj.remove("AWS_REGION");
+            env_obj.remove("AWS_PROFILE");
         }
 
         // 构建新的 env 配置
-        let mut new_env = serde_json::json!({
-            "ANTHROPIC_AUTH_TOKEN": active_config.token,
-        });
+        let new_env: serde_json::Map<String, serde_json::Value> = self
+            .settings_env(active_config)
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), serde_json::json!(value)))
+            .collect();
+        let new_env = serde_json::Value::Object(new_env);
+
+        // 深度合并到 env 对象
+        if let Some(env_obj) = settings.get_mut("env") {
+            self.deep_merge(env_obj, &new_env);
+        }
 
-        // 根据是否启用 Vertex 决定使用哪个 Base URL
-        if active_config.vertex.enabled {
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("序列化 settings.json 失败: {}", e))?;
+
+        Ok(content)
+    }
+
+    /// 写入 settings.json env 的环境变量
+    fn settings_env(&self, active_config: &ClaudeActiveConfig) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+
+        if active_config.bedrock.enabled {
+            // Bedrock 模式：Token 作为 Bedrock API Key 使用，并使用 AWS 区域和 profile
+            env.push(("AWS_BEARER_TOKEN_BEDROCK", active_config.token.clone()));
+            env.push(("CLAUDE_CODE_USE_BEDROCK", "1".to_string()));
+
+            if let Some(ref region) = active_config.bedrock.region {
+                env.push(("AWS_REGION", region.clone()));
+            }
+
+            if let Some(ref profile) = active_config.bedrock.profile {
+                env.push(("AWS_PROFILE", profile.clone()));
+            }
+        } else if active_config.vertex.enabled {
             // Vertex 模式：只使用 ANTHROPIC_VERTEX_BASE_URL
-            new_env["CLAUDE_CODE_USE_VERTEX"] = serde_json::json!("1");
+            env.push(("ANTHROPIC_AUTH_TOKEN", active_config.token.clone()));
+            env.push(("CLAUDE_CODE_USE_VERTEX", "1".to_string()));
 
             if let Some(ref project_id) = active_config.vertex.project_id {
-                new_env["ANTHROPIC_VERTEX_PROJECT_ID"] = serde_json::json!(project_id);
+                env.push(("ANTHROPIC_VERTEX_PROJECT_ID", project_id.clone()));
             }
 
             if let Some(ref vertex_url) = active_config.vertex.base_url {
-                new_env["ANTHROPIC_VERTEX_BASE_URL"] = serde_json::json!(vertex_url);
+                env.push(("ANTHROPIC_VERTEX_BASE_URL", vertex_url.clone()));
             }
 
             if active_config.vertex.skip_auth {
-                new_env["CLAUDE_CODE_SKIP_VERTEX_AUTH"] = serde_json::json!("1");
+                env.push(("CLAUDE_CODE_SKIP_VERTEX_AUTH", "1".to_string()));
             }
 
             // 添加 CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC
-            new_env["CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC"] = serde_json::json!("1");
+            env.push(("CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC", "1".to_string()));
         } else {
             // 普通模式：使用 ANTHROPIC_BASE_URL
+            env.push(("ANTHROPIC_AUTH_TOKEN", active_config.token.clone()));
+
             if let Some(ref base_url) = active_config.base_url {
-                new_env["ANTHROPIC_BASE_URL"] = serde_json::json!(base_url);
+                env.push(("ANTHROPIC_BASE_URL", base_url.clone()));
             }
         }
 
-        // 深度合并到 env 对象
-        if let Some(env_obj) = settings.get_mut("env") {
-            self.deep_merge(env_obj, &new_env);
-        }
-
-        // 写入文件
-        let content = serde_json::to_string_pretty(&settings)
-            .map_err(|e| format!("序列化 settings.json 失败: {}", e))?;
+        env
+    }
 
-        fs::write(&self.settings_file, content)
-            .map_err(|e| format!("写入 settings.json 失败: {}", e))
+    /// 导出到 shell 的环境变量（settings.json 中的变量以及模型）
+    pub fn env_vars(&self, active_config: &ClaudeActiveConfig) -> Vec<(&'static str, String)> {
+        let mut env = self.settings_env(active_config);
+        if let Some(ref model) = active_config.model {
+            env.push(("ANTHROPIC_MODEL", model.clone()));
+        }
+        env
     }
 
     /// 深度合并 JSON 对象
@@ -350,25 +483,229 @@ impl ClaudeConfigManager {
     // ========================================================================
 
     /// 获取配置文件路径（用于备份等）
-    #[allow(dead_code)]
     pub fn get_config_file_path(&self) -> &PathBuf {
         &self.claude_config_file
     }
 
     /// 获取 settings.json 路径
-    #[allow(dead_code)]
     pub fn get_settings_file_path(&self) -> &PathBuf {
         &self.settings_file
     }
+
+    /// 切换时写入的 Claude Code 配置文件（用于导出）
+    pub fn live_config_files(&self) -> Vec<PathBuf> {
+        vec![self.settings_file.clone()]
+    }
+
+    // ========================================================================
+    // 从现有 ~/.claude/settings.json 导入
+    // ========================================================================
+
+    /// 读取现有的 ~/.claude/settings.json 并转换为待导入的站点（文件不存在或没有可导入的配置时返回 None）
+    pub fn read_existing_site(&self) -> Result<Option<ClaudeSite>> {
+        if !self.settings_file.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&self.settings_file)
+            .map_err(|e| format!("读取 settings.json 失败: {}", e))?;
+
+        parse_existing_settings(&content)
+    }
+
+    /// 写入站点（同名时覆盖）
+    pub fn upsert_site(&self, site_name: &str, site: ClaudeSite) -> Result<()> {
+        let mut config = self.read_config()?;
+        config.add_site(site_name.to_string(), site);
+        self.write_config(&config)
+    }
+}
+
+/// 检查 Vertex AI 模式的项目 ID 与 Google 凭据，返回切换前需要提示的问题（未启用 Vertex 时为空）
+///
+/// 未跳过认证时需要 `$GOOGLE_APPLICATION_CREDENTIALS` 指向的凭据文件或 gcloud 的 ADC
+/// (`gcloud auth application-default login` 生成)，否则 Claude Code 无法调用 Vertex AI
+pub fn vertex_warnings(vertex: &VertexConfig) -> Vec<String> {
+    let credentials = std::env::var_os("GOOGLE_APPLICATION_CREDENTIALS")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from);
+    check_vertex(vertex, credentials.as_deref(), gcloud_adc_file().as_deref())
+}
+
+/// gcloud ADC 文件位置: `$CLOUDSDK_CONFIG`，Windows 上为 `%APPDATA%\gcloud`，其他系统为 `~/.config/gcloud`
+fn gcloud_adc_file() -> Option<PathBuf> {
+    let config_dir = match std::env::var_os("CLOUDSDK_CONFIG").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None if cfg!(windows) => PathBuf::from(std::env::var_os("APPDATA")?).join("gcloud"),
+        None => crate::config::home_dir().ok()?.join(".config").join("gcloud"),
+    };
+    Some(config_dir.join("application_default_credentials.json"))
+}
+
+/// `credentials` 为 `$GOOGLE_APPLICATION_CREDENTIALS` 的值，`adc_file` 为 gcloud ADC 文件路径
+fn check_vertex(vertex: &VertexConfig, credentials: Option<&Path>, adc_file: Option<&Path>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !vertex.enabled {
+        return warnings;
+    }
+
+    if vertex.project_id.as_deref().is_none_or(|id| id.trim().is_empty()) {
+        warnings.push("Vertex AI 已启用但未设置 Project ID，Claude Code 将无法确定要使用的项目".to_string());
+    }
+
+    if !vertex.skip_auth {
+        match credentials {
+            Some(path) if !path.is_file() => warnings.push(format!(
+                "GOOGLE_APPLICATION_CREDENTIALS 指向的凭据文件不存在: {}",
+                path.display()
+            )),
+            Some(_) => {}
+            None if adc_file.is_some_and(Path::is_file) => {}
+            None => warnings.push(
+                "未找到 Google 凭据: 请设置 GOOGLE_APPLICATION_CREDENTIALS 或运行 gcloud auth application-default login，\
+                 或在站点配置中启用「跳过 Vertex 认证」"
+                    .to_string(),
+            ),
+        }
+    }
+
+    warnings
+}
+
+/// 导入的 Token 名称
+pub const IMPORTED_TOKEN_NAME: &str = "default";
+
+/// 将 Claude Code 的 settings.json 中 env 的认证、Base URL、模型及 Vertex / Bedrock 变量转换为站点
+///
+/// 没有 Token 也没有 Base URL 时返回 None
+pub fn parse_existing_settings(content: &str) -> Result<Option<ClaudeSite>> {
+    let settings: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| CliError::Parse(format!("解析 settings.json 失败: {}", e)))?;
+
+    let env = settings.get("env").and_then(|v| v.as_object());
+    let get_env = |key: &str| {
+        env.and_then(|env| env.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .filter(|v| !v.is_empty())
+    };
+    let is_enabled = |key: &str| get_env(key).is_some_and(|v| v != "0" && v != "false");
+
+    let vertex = VertexConfig {
+        enabled: is_enabled("CLAUDE_CODE_USE_VERTEX"),
+        project_id: get_env("ANTHROPIC_VERTEX_PROJECT_ID"),
+        base_url: get_env("ANTHROPIC_VERTEX_BASE_URL"),
+        skip_auth: is_enabled("CLAUDE_CODE_SKIP_VERTEX_AUTH"),
+    };
+    let bedrock = BedrockConfig {
+        enabled: is_enabled("CLAUDE_CODE_USE_BEDROCK"),
+        region: get_env("AWS_REGION"),
+        profile: get_env("AWS_PROFILE"),
+    };
+
+    let token = if bedrock.enabled {
+        get_env("AWS_BEARER_TOKEN_BEDROCK")
+    } else {
+        get_env("ANTHROPIC_AUTH_TOKEN").or_else(|| get_env("ANTHROPIC_API_KEY"))
+    };
+    let base_url = get_env("ANTHROPIC_BASE_URL");
+    if token.is_none() && base_url.is_none() {
+        return Ok(None);
+    }
+
+    let url = base_url
+        .clone()
+        .or_else(|| vertex.base_url.clone().filter(|_| vertex.enabled))
+        .unwrap_or_else(|| ClaudeSite::BASE_URL_PRESETS[0].url.to_string());
+    let mut site = ClaudeSite::new(url, Some("从 ~/.claude/settings.json 导入".to_string()));
+    site.config.base_url = base_url;
+    site.config.model = get_env("ANTHROPIC_MODEL")
+        .or_else(|| settings.get("model").and_then(|v| v.as_str()).map(str::to_string));
+    if vertex.enabled {
+        site.config.vertex = vertex;
+    } else if bedrock.enabled {
+        site.config.bedrock = bedrock;
+    }
+    if let Some(token) = token {
+        site.tokens.insert(IMPORTED_TOKEN_NAME.to_string(), token);
+    }
+
+    Ok(Some(site))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_check_vertex() {
+        let dir = std::env::temp_dir().join(format!("ca-switch-vertex-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let adc = dir.join("application_default_credentials.json");
+        fs::write(&adc, "{}").unwrap();
+        let missing = dir.join("missing.json");
+
+        let mut vertex = VertexConfig::default();
+        assert!(check_vertex(&vertex, None, None).is_empty());
+
+        vertex.enabled = true;
+        vertex.project_id = Some("my-project".to_string());
+        assert!(check_vertex(&vertex, None, Some(&adc)).is_empty());
+        assert!(check_vertex(&vertex, Some(&adc), None).is_empty());
+        // 凭据缺失或路径无效
+        assert_eq!(check_vertex(&vertex, None, Some(&missing)).len(), 1);
+        assert!(check_vertex(&vertex, Some(&missing), Some(&adc))[0].contains("missing.json"));
+
+        // 跳过认证时只检查项目 ID
+        vertex.skip_auth = true;
+        vertex.project_id = None;
+        assert_eq!(check_vertex(&vertex, None, None).len(), 1);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_parse_existing_settings() {
+        let site = parse_existing_settings(
+            r#"{
+                "model": "claude-sonnet-4",
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": "sk-relay",
+                    "ANTHROPIC_BASE_URL": "https://relay.example.com"
+                }
+            }"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(site.metadata.url, "https://relay.example.com");
+        assert_eq!(site.config.base_url.as_deref(), Some("https://relay.example.com"));
+        assert_eq!(site.config.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(site.tokens[IMPORTED_TOKEN_NAME], "sk-relay");
+        assert!(!site.config.vertex.enabled);
+
+        let site = parse_existing_settings(
+            r#"{"env": {
+                "ANTHROPIC_AUTH_TOKEN": "token",
+                "CLAUDE_CODE_USE_VERTEX": "1",
+                "ANTHROPIC_VERTEX_PROJECT_ID": "my-project",
+                "ANTHROPIC_VERTEX_BASE_URL": "https://vertex.example.com"
+            }}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(site.config.vertex.enabled);
+        assert_eq!(site.config.vertex.project_id.as_deref(), Some("my-project"));
+        assert_eq!(site.metadata.url, "https://vertex.example.com");
+        assert!(site.config.base_url.is_none());
+
+        // 没有认证信息时无可导入的内容
+        assert!(parse_existing_settings(r#"{"theme": "dark"}"#).unwrap().is_none());
+    }
+
     #[test]
     fn test_deep_merge() {
-        let manager = ClaudeConfigManager::new().unwrap();
+        let home_dir = std::env::temp_dir().join(format!("ca-switch-merge-{}", std::process::id()));
+        let manager = ClaudeConfigManager::new(home_dir.join(".ca-switch"), &home_dir).unwrap();
 
         let mut target = serde_json::json!({
             "a": 1,
@@ -393,5 +730,130 @@ mod tests {
         assert_eq!(target["b"]["d"], 3);
         assert_eq!(target["b"]["e"], 4);
         assert_eq!(target["f"], 5);
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_update_site_config_vertex_bedrock_exclusive() {
+        let home_dir = std::env::temp_dir().join(format!("ca-switch-exclusive-{}", std::process::id()));
+        let manager = ClaudeConfigManager::new(home_dir.join(".ca-switch"), &home_dir).unwrap();
+        manager.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+
+        let vertex = VertexConfig {
+            enabled: true,
+            project_id: Some("my-project".to_string()),
+            ..Default::default()
+        };
+        let bedrock = BedrockConfig {
+            enabled: true,
+            region: Some("us-east-1".to_string()),
+            profile: None,
+        };
+
+        // 启用 Bedrock 时关闭 Vertex，其余 Vertex 配置保留
+        manager.update_site_config("a", None, None, Some(vertex.clone()), None).unwrap();
+        manager.update_site_config("a", None, None, None, Some(bedrock)).unwrap();
+        let site = manager.get_site("a").unwrap().unwrap();
+        assert!(site.config.bedrock.enabled);
+        assert!(!site.config.vertex.enabled);
+        assert_eq!(site.config.vertex.project_id.as_deref(), Some("my-project"));
+
+        // 反之亦然
+        manager.update_site_config("a", None, None, Some(vertex), None).unwrap();
+        let site = manager.get_site("a").unwrap().unwrap();
+        assert!(site.config.vertex.enabled);
+        assert!(!site.config.bedrock.enabled);
+        assert_eq!(site.config.bedrock.region.as_deref(), Some("us-east-1"));
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_render_settings_bedrock_and_vertex() {
+        let home_dir = std::env::temp_dir().join(format!("ca-switch-render-{}", std::process::id()));
+        let manager = ClaudeConfigManager::new(home_dir.join(".ca-switch"), &home_dir).unwrap();
+        let settings_file = home_dir.join("missing").join("settings.json");
+
+        let mut site = crate::config::models::ClaudeSite::new("https://a.example.com".to_string(), None);
+        site.add_token("t".to_string(), "sk-test".to_string());
+        site.config.vertex = VertexConfig {
+            enabled: false,
+            project_id: Some("my-project".to_string()),
+            base_url: Some("https://vertex.example.com".to_string()),
+            skip_auth: true,
+        };
+        site.config.bedrock = BedrockConfig {
+            enabled: true,
+            region: Some("us-west-2".to_string()),
+            profile: Some("dev".to_string()),
+        };
+        let reference = crate::config::models::ClaudeActiveReference {
+            site: "a".to_string(),
+            token_name: "t".to_string(),
+        };
+        let render = |site: &crate::config::models::ClaudeSite| -> serde_json::Value {
+            let active_config = ClaudeActiveConfig::from_reference(&reference, site).unwrap();
+            let content = manager.render_settings(&settings_file, &active_config).unwrap();
+            serde_json::from_str(&content).unwrap()
+        };
+
+        let env = &render(&site)["env"];
+        assert_eq!(env["CLAUDE_CODE_USE_BEDROCK"], "1");
+        assert_eq!(env["AWS_BEARER_TOKEN_BEDROCK"], "sk-test");
+        assert_eq!(env["AWS_REGION"], "us-west-2");
+        assert_eq!(env["AWS_PROFILE"], "dev");
+        for key in [
+            "CLAUDE_CODE_USE_VERTEX",
+            "ANTHROPIC_VERTEX_PROJECT_ID",
+            "ANTHROPIC_VERTEX_BASE_URL",
+            "CLAUDE_CODE_SKIP_VERTEX_AUTH",
+            "ANTHROPIC_AUTH_TOKEN",
+        ] {
+            assert!(env.get(key).is_none(), "{key}");
+        }
+
+        site.config.vertex.enabled = true;
+        site.config.bedrock.enabled = false;
+        let env = &render(&site)["env"];
+        assert_eq!(env["CLAUDE_CODE_USE_VERTEX"], "1");
+        assert_eq!(env["ANTHROPIC_VERTEX_PROJECT_ID"], "my-project");
+        assert_eq!(env["ANTHROPIC_VERTEX_BASE_URL"], "https://vertex.example.com");
+        assert_eq!(env["ANTHROPIC_AUTH_TOKEN"], "sk-test");
+        for key in ["CLAUDE_CODE_USE_BEDROCK", "AWS_BEARER_TOKEN_BEDROCK", "AWS_REGION", "AWS_PROFILE"] {
+            assert!(env.get(key).is_none(), "{key}");
+        }
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_sync_to_dir_merges_existing_project_settings() {
+        let home_dir = std::env::temp_dir().join(format!("ca-switch-project-{}", std::process::id()));
+        let manager = ClaudeConfigManager::new(home_dir.join(".ca-switch"), &home_dir).unwrap();
+        manager.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        manager.add_token("a", "t".to_string(), "sk-test".to_string()).unwrap();
+        let site = manager.get_site("a").unwrap().unwrap();
+        let reference = crate::config::models::ClaudeActiveReference {
+            site: "a".to_string(),
+            token_name: "t".to_string(),
+        };
+        let active_config = ClaudeActiveConfig::from_reference(&reference, &site).unwrap();
+
+        let project_dir = home_dir.join("project").join(".claude");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join("settings.json"), r#"{"permissions": {"allow": ["Bash"]}}"#).unwrap();
+
+        let written = manager.sync_to_dir(&project_dir, &active_config).unwrap();
+        assert_eq!(written, vec![project_dir.join("settings.json")]);
+
+        let settings: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&written[0]).unwrap()).unwrap();
+        assert_eq!(settings["permissions"]["allow"][0], "Bash");
+        assert_eq!(settings["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-test");
+        // 主目录下的 settings.json 不受影响
+        assert!(!home_dir.join(".claude").join("settings.json").exists());
+
+        let _ = fs::remove_dir_all(&home_dir);
     }
 }
@@ -1,25 +1,26 @@
 // Codex 配置管理器
 // 负责管理 ~/.ca-switch/codex.json 和同步到 ~/.codex/
 
-use crate::config::models::{CodexActiveConfig, CodexConfig, CodexSite};
+use crate::error::{CliError, Result};
+use crate::logging::log_write;
+use crate::config::migration::read_migrated;
+use crate::config::models::{page_sites, sites_with_base_url, sort_sites, AuthHeaderStyle, CodexActiveConfig, CodexConfig, CodexSite};
 use serde_json;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Codex 配置管理器
 pub struct CodexConfigManager {
     #[allow(dead_code)]
     config_dir: PathBuf,         // ~/.ca-switch
     codex_config_file: PathBuf,  // ~/.ca-switch/codex.json
-    codex_dir: PathBuf,          // ~/.codex
-    codex_config_toml: PathBuf,  // ~/.codex/config.toml
-    codex_auth_json: PathBuf,    // ~/.codex/auth.json
+    codex_dir: PathBuf,          // ~/.codex (config.toml, auth.json)
 }
 
 impl CodexConfigManager {
     /// 创建新的 Codex 配置管理器
-    pub fn new(config_dir: PathBuf) -> Result<Self, String> {
+    pub fn new(config_dir: PathBuf) -> Result<Self> {
         // 确保 ~/.ca-switch 目录存在
         if !config_dir.exists() {
             fs::create_dir_all(&config_dir)
@@ -29,19 +30,12 @@ impl CodexConfigManager {
         let codex_config_file = config_dir.join("codex.json");
 
         // Codex 官方配置目录
-        let codex_dir = dirs::home_dir()
-            .ok_or("无法获取用户主目录")?
-            .join(".codex");
-
-        let codex_config_toml = codex_dir.join("config.toml");
-        let codex_auth_json = codex_dir.join("auth.json");
+        let codex_dir = super::home_dir()?.join(".codex");
 
         Ok(Self {
             config_dir,
             codex_config_file,
             codex_dir,
-            codex_config_toml,
-            codex_auth_json,
         })
     }
 
@@ -50,25 +44,22 @@ impl CodexConfigManager {
     // ========================================================================
 
     /// 读取 codex.json 配置
-    pub fn read_config(&self) -> Result<CodexConfig, String> {
-        if !self.codex_config_file.exists() {
-            return Ok(CodexConfig::new());
-        }
-
-        let content = fs::read_to_string(&self.codex_config_file)
-            .map_err(|e| format!("读取 codex.json 失败: {}", e))?;
-
-        serde_json::from_str(&content)
-            .map_err(|e| format!("解析 codex.json 失败: {}", e))
+    pub fn read_config(&self) -> Result<CodexConfig> {
+        let config = read_migrated(&self.codex_config_file, "codex.json", |config| self.write_config(config))?;
+        // 文件不存在时返回空配置
+        Ok(config.unwrap_or_else(CodexConfig::new))
     }
 
     /// 写入 codex.json 配置
-    pub fn write_config(&self, config: &CodexConfig) -> Result<(), String> {
+    pub fn write_config(&self, config: &CodexConfig) -> Result<()> {
         let content = serde_json::to_string_pretty(config)
             .map_err(|e| format!("序列化 codex.json 失败: {}", e))?;
 
+        log_write(&self.codex_config_file);
         fs::write(&self.codex_config_file, content)
-            .map_err(|e| format!("写入 codex.json 失败: {}", e))
+            .map_err(|e| format!("写入 codex.json 失败: {}", e))?;
+
+        Ok(())
     }
 
     // ========================================================================
@@ -76,29 +67,44 @@ impl CodexConfigManager {
     // ========================================================================
 
     /// 获取站点
-    pub fn get_site(&self, site_name: &str) -> Result<Option<CodexSite>, String> {
+    pub fn get_site(&self, site_name: &str) -> Result<Option<CodexSite>> {
         let config = self.read_config()?;
         Ok(config.get_site(site_name).cloned())
     }
 
     /// 获取所有站点
-    pub fn get_all_sites(&self) -> Result<HashMap<String, CodexSite>, String> {
+    pub fn get_all_sites(&self) -> Result<HashMap<String, CodexSite>> {
         let config = self.read_config()?;
         Ok(config.sites.clone())
     }
 
+    /// Base URL 与 `base_url` 相同的其他站点 (忽略末尾的 `/` 与 `/v1`)，`exclude` 为正在编辑的站点
+    pub fn find_sites_with_base_url(&self, base_url: &str, exclude: Option<&str>) -> Result<Vec<String>> {
+        Ok(sites_with_base_url(&self.get_all_sites()?, base_url, exclude))
+    }
+
+    /// 获取按名称排序的所有站点
+    pub fn get_sites_sorted(&self) -> Result<Vec<(String, CodexSite)>> {
+        Ok(sort_sites(self.get_all_sites()?))
+    }
+
+    /// 获取按名称排序后的一页站点，`limit` 为 None 时取 `offset` 之后的全部
+    pub fn get_sites_page(&self, offset: usize, limit: Option<usize>) -> Result<Vec<(String, CodexSite)>> {
+        Ok(page_sites(self.get_sites_sorted()?, offset, limit))
+    }
+
     /// 添加站点
     pub fn add_site(
         &mut self,
         site_name: String,
         url: String,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         // 检查站点是否已存在
         if config.get_site(&site_name).is_some() {
-            return Err(format!("站点 '{}' 已存在", site_name));
+            return Err(format!("站点 '{}' 已存在", site_name).into());
         }
 
         let site = CodexSite::new(url, description);
@@ -113,12 +119,12 @@ impl CodexConfigManager {
         site_name: &str,
         url: Option<String>,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if let Some(url) = url {
             site.metadata.url = url;
@@ -133,11 +139,11 @@ impl CodexConfigManager {
     }
 
     /// 删除站点
-    pub fn delete_site(&mut self, site_name: &str) -> Result<(), String> {
+    pub fn delete_site(&mut self, site_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         if config.remove_site(site_name).is_none() {
-            return Err(format!("站点 '{}' 不存在", site_name));
+            return Err(CliError::NotFound(format!("站点 '{}' 不存在", site_name)));
         }
 
         self.write_config(&config)
@@ -145,21 +151,57 @@ impl CodexConfigManager {
 
     /// 删除站点（兼容接口）
     #[allow(dead_code)]
-    pub fn remove_site(&self, site_name: &str) -> Result<(), String> {
+    pub fn remove_site(&self, site_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         if config.remove_site(site_name).is_none() {
-            return Err(format!("站点 '{}' 不存在", site_name));
+            return Err(CliError::NotFound(format!("站点 '{}' 不存在", site_name)));
         }
 
         let content = serde_json::to_string_pretty(&config)
             .map_err(|e| format!("序列化 codex.json 失败: {}", e))?;
 
+        log_write(&self.codex_config_file);
         fs::write(&self.codex_config_file, content)
-            .map_err(|e| format!("写入 codex.json 失败: {}", e))
+            .map_err(|e| format!("写入 codex.json 失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 设置站点标签 (为空时清除)
+    pub fn set_site_tags(&self, site_name: &str, tags: Vec<String>) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.metadata.tags = tags;
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
+    /// 设置站点的认证头格式
+    pub fn set_auth_header_style(
+        &mut self,
+        site_name: &str,
+        auth_header_style: AuthHeaderStyle,
+    ) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.config.auth_header_style = Some(auth_header_style);
+        site.update_timestamp();
+
+        self.write_config(&config)
     }
 
     /// 更新站点配置
+    #[allow(clippy::too_many_arguments)]
     pub fn update_site_config(
         &mut self,
         site_name: &str,
@@ -170,12 +212,12 @@ impl CodexConfigManager {
         network_access: Option<String>,
         disable_response_storage: Option<bool>,
         wire_api: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         // 更新配置
         if let Some(base_url) = base_url {
@@ -190,12 +232,9 @@ impl CodexConfigManager {
         if let Some(model_provider) = model_provider {
             site.config.model_provider = Some(model_provider);
         }
-        if let Some(network_access) = network_access {
-            site.config.network_access = Some(network_access);
-        }
-        if let Some(disable_response_storage) = disable_response_storage {
-            site.config.disable_response_storage = Some(disable_response_storage);
-        }
+        // network_access 与 disable_response_storage 直接覆盖（None 表示清除）
+        site.config.network_access = network_access;
+        site.config.disable_response_storage = disable_response_storage;
         if let Some(wire_api) = wire_api {
             site.config.wire_api = Some(wire_api);
         }
@@ -205,17 +244,37 @@ impl CodexConfigManager {
         self.write_config(&config)
     }
 
+    /// 更新站点的 sandbox_mode 和 approval_policy（None 表示清除）
+    pub fn update_site_policies(
+        &mut self,
+        site_name: &str,
+        sandbox_mode: Option<String>,
+        approval_policy: Option<String>,
+    ) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.config.sandbox_mode = sandbox_mode;
+        site.config.approval_policy = approval_policy;
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
     // ========================================================================
     // API Key 管理
     // ========================================================================
 
     /// 获取 API Keys
     #[allow(dead_code)]
-    pub fn get_api_keys(&self, site_name: &str) -> Result<HashMap<String, String>, String> {
+    pub fn get_api_keys(&self, site_name: &str) -> Result<HashMap<String, String>> {
         let config = self.read_config()?;
         let site = config
             .get_site(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         Ok(site.api_keys.clone())
     }
@@ -226,16 +285,16 @@ impl CodexConfigManager {
         site_name: &str,
         key_name: String,
         api_key: String,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         // 检查 key 是否已存在
         if site.get_api_key(&key_name).is_some() {
-            return Err(format!("API Key '{}' 已存在于站点 '{}'", key_name, site_name));
+            return Err(format!("API Key '{}' 已存在于站点 '{}'", key_name, site_name).into());
         }
 
         site.add_api_key(key_name, api_key);
@@ -249,15 +308,15 @@ impl CodexConfigManager {
         site_name: &str,
         key_name: &str,
         new_api_key: String,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if site.get_api_key(key_name).is_none() {
-            return Err(format!("API Key '{}' 不存在于站点 '{}'", key_name, site_name));
+            return Err(CliError::NotFound(format!("API Key '{}' 不存在于站点 '{}'", key_name, site_name)));
         }
 
         site.add_api_key(key_name.to_string(), new_api_key);
@@ -266,43 +325,86 @@ impl CodexConfigManager {
     }
 
     /// 删除 API Key
-    pub fn delete_api_key(&mut self, site_name: &str, key_name: &str) -> Result<(), String> {
+    pub fn delete_api_key(&mut self, site_name: &str, key_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if site.remove_api_key(key_name).is_none() {
-            return Err(format!("API Key '{}' 不存在于站点 '{}'", key_name, site_name));
+            return Err(CliError::NotFound(format!("API Key '{}' 不存在于站点 '{}'", key_name, site_name)));
         }
 
         self.write_config(&config)
     }
 
+    /// 记录一次 API Key 激活（使用次数与最近使用时间）
+    pub fn record_usage(&self, site_name: &str, key_name: &str) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.record_usage(key_name);
+        self.write_config(&config)
+    }
+
     // ========================================================================
     // 配置同步到 ~/.codex/
     // ========================================================================
 
     /// 同步配置到 Codex 官方配置文件
-    pub fn sync_to_codex(&self, active_config: &CodexActiveConfig) -> Result<(), String> {
-        // 确保 ~/.codex 目录存在
-        if !self.codex_dir.exists() {
-            fs::create_dir_all(&self.codex_dir)
+    pub fn sync_to_codex(&self, active_config: &CodexActiveConfig) -> Result<Vec<PathBuf>> {
+        self.sync_to_dir(&self.codex_dir, active_config)
+    }
+
+    /// 同步配置到指定的 .codex 目录（如项目级 ./.codex/）
+    pub fn sync_to_dir(&self, codex_dir: &Path, active_config: &CodexActiveConfig) -> Result<Vec<PathBuf>> {
+        // 确保 .codex 目录存在
+        if !codex_dir.exists() {
+            fs::create_dir_all(codex_dir)
                 .map_err(|e| format!("创建 .codex 目录失败: {}", e))?;
         }
 
+        let codex_config_toml = codex_dir.join("config.toml");
+        let codex_auth_json = codex_dir.join("auth.json");
+
         // 同步到 auth.json
-        self.sync_to_auth_json(active_config)?;
+        log_write(&codex_auth_json);
+        fs::write(&codex_auth_json, self.render_auth_json(active_config)?)
+            .map_err(|e| format!("写入 auth.json 失败: {}", e))?;
 
         // 同步到 config.toml
-        self.sync_to_config_toml(active_config)?;
+        log_write(&codex_config_toml);
+        fs::write(&codex_config_toml, self.render_config_toml(active_config))
+            .map_err(|e| format!("写入 config.toml 失败: {}", e))?;
 
-        Ok(())
+        Ok(vec![codex_config_toml, codex_auth_json])
+    }
+
+    /// 渲染将要写入的文件内容（不写入磁盘，用于 dry-run）
+    pub fn render_files(&self, active_config: &CodexActiveConfig) -> Result<Vec<(PathBuf, String)>> {
+        self.render_files_in(&self.codex_dir, active_config)
     }
 
-    /// 同步到 auth.json
-    fn sync_to_auth_json(&self, active_config: &CodexActiveConfig) -> Result<(), String> {
+    /// 渲染将要写入指定 .codex 目录的文件内容
+    pub fn render_files_in(&self, codex_dir: &Path, active_config: &CodexActiveConfig) -> Result<Vec<(PathBuf, String)>> {
+        Ok(vec![
+            (
+                codex_dir.join("config.toml"),
+                self.render_config_toml(active_config),
+            ),
+            (
+                codex_dir.join("auth.json"),
+                self.render_auth_json(active_config)?,
+            ),
+        ])
+    }
+
+    /// 渲染 auth.json 内容
+    fn render_auth_json(&self, active_config: &CodexActiveConfig) -> Result<String> {
         let auth_data = serde_json::json!({
             "OPENAI_API_KEY": active_config.api_key,
         });
@@ -310,19 +412,24 @@ impl CodexConfigManager {
         let content = serde_json::to_string_pretty(&auth_data)
             .map_err(|e| format!("序列化 auth.json 失败: {}", e))?;
 
-        fs::write(&self.codex_auth_json, content)
-            .map_err(|e| format!("写入 auth.json 失败: {}", e))
+        Ok(content)
     }
 
-    /// 同步到 config.toml
-    fn sync_to_config_toml(&self, active_config: &CodexActiveConfig) -> Result<(), String> {
+    /// 导出到 shell 的环境变量（OpenAI 兼容的 Key 与 Base URL）
+    pub fn env_vars(&self, active_config: &CodexActiveConfig) -> Vec<(&'static str, String)> {
+        let mut env = vec![("OPENAI_API_KEY", active_config.api_key.clone())];
+        if let Some(ref base_url) = active_config.base_url {
+            env.push(("OPENAI_BASE_URL", base_url.clone()));
+        }
+        env
+    }
+
+    /// 渲染 config.toml 内容
+    fn render_config_toml(&self, active_config: &CodexActiveConfig) -> String {
         let mut lines = Vec::new();
 
-        // Model Provider（如果不填则默认使用站点名）
-        let provider_name = active_config
-            .model_provider
-            .as_ref()
-            .unwrap_or(&active_config.site);
+        // Model Provider（未设置时 from_reference 已替换为站点名）
+        let provider_name = &active_config.model_provider;
         lines.push(format!("model_provider = \"{}\"", provider_name));
 
         // Model
@@ -345,6 +452,16 @@ impl CodexConfigManager {
             lines.push(format!("disable_response_storage = {}", disable_response_storage));
         }
 
+        // Sandbox Mode
+        if let Some(ref sandbox_mode) = active_config.sandbox_mode {
+            lines.push(format!("sandbox_mode = \"{}\"", sandbox_mode));
+        }
+
+        // Approval Policy
+        if let Some(ref approval_policy) = active_config.approval_policy {
+            lines.push(format!("approval_policy = \"{}\"", approval_policy));
+        }
+
         // 添加空行
         lines.push(String::new());
 
@@ -365,10 +482,230 @@ impl CodexConfigManager {
         // Requires OpenAI Auth（默认为 true）
         lines.push("requires_openai_auth = true".to_string());
 
-        let content = lines.join("\n") + "\n";
+        lines.join("\n") + "\n"
+    }
+
+    /// 获取配置文件路径
+    pub fn get_config_file_path(&self) -> &PathBuf {
+        &self.codex_config_file
+    }
 
-        fs::write(&self.codex_config_toml, content)
-            .map_err(|e| format!("写入 config.toml 失败: {}", e))
+    /// 切换时写入的 Codex 配置文件（用于导出）
+    pub fn live_config_files(&self) -> Vec<PathBuf> {
+        vec![self.codex_dir.join("config.toml"), self.codex_dir.join("auth.json")]
     }
 
+    // ========================================================================
+    // 从现有 ~/.codex/ 导入
+    // ========================================================================
+
+    /// ~/.codex/config.toml 是否存在
+    pub fn has_existing_config(&self) -> bool {
+        self.codex_dir.join("config.toml").exists()
+    }
+
+    /// 读取现有的 ~/.codex/config.toml 与 auth.json，转换为待导入的站点（文件不存在时返回空）
+    pub fn read_existing_sites(&self) -> Result<Vec<(String, CodexSite)>> {
+        let config_toml = self.codex_dir.join("config.toml");
+        if !config_toml.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&config_toml)
+            .map_err(|e| format!("读取 config.toml 失败: {}", e))?;
+        let auth_json = fs::read_to_string(self.codex_dir.join("auth.json")).ok();
+
+        parse_existing_config(&content, auth_json.as_deref())
+    }
+
+    /// 写入站点（同名时覆盖）
+    pub fn upsert_site(&self, site_name: &str, site: CodexSite) -> Result<()> {
+        let mut config = self.read_config()?;
+        config.add_site(site_name.to_string(), site);
+        self.write_config(&config)
+    }
+}
+
+/// 导入的 API Key 名称
+pub const IMPORTED_KEY_NAME: &str = "default";
+
+/// 将 Codex 的 config.toml（及 auth.json）中的 [model_providers.*] 转换为站点
+///
+/// 当前激活的 provider（顶层 model_provider）继承顶层的模型与策略配置，并使用 auth.json 中的
+/// OPENAI_API_KEY；其他 provider 在设置了 env_key 且对应环境变量存在时导入该值
+pub fn parse_existing_config(config_toml: &str, auth_json: Option<&str>) -> Result<Vec<(String, CodexSite)>> {
+    let table: toml::Table = config_toml
+        .parse()
+        .map_err(|e| CliError::Parse(format!("解析 config.toml 失败: {}", e)))?;
+
+    let get_str = |table: &toml::Table, key: &str| table.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let active_provider = get_str(&table, "model_provider");
+    let auth_key = auth_json
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(content).ok())
+        .and_then(|auth| auth.get("OPENAI_API_KEY").and_then(|v| v.as_str()).map(str::to_string))
+        .filter(|key| !key.is_empty());
+
+    let Some(providers) = table.get("model_providers").and_then(|v| v.as_table()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut sites = Vec::new();
+    for (name, provider) in providers {
+        let Some(provider) = provider.as_table() else {
+            continue;
+        };
+        let Some(base_url) = get_str(provider, "base_url") else {
+            continue;
+        };
+
+        let mut site = CodexSite::new(base_url.clone(), Some("从 ~/.codex/config.toml 导入".to_string()));
+        site.config.base_url = Some(base_url);
+        site.config.wire_api = get_str(provider, "wire_api");
+
+        let is_active = active_provider.as_deref() == Some(name.as_str());
+        let api_key = if is_active {
+            site.config.model = get_str(&table, "model");
+            site.config.model_reasoning_effort = get_str(&table, "model_reasoning_effort");
+            site.config.network_access = get_str(&table, "network_access");
+            site.config.disable_response_storage = table.get("disable_response_storage").and_then(|v| v.as_bool());
+            site.config.sandbox_mode = get_str(&table, "sandbox_mode");
+            site.config.approval_policy = get_str(&table, "approval_policy");
+            auth_key.clone()
+        } else {
+            get_str(provider, "env_key").and_then(|env_key| std::env::var(env_key).ok())
+        };
+        if let Some(api_key) = api_key {
+            site.api_keys.insert(IMPORTED_KEY_NAME.to_string(), api_key);
+        }
+
+        sites.push((name.clone(), site));
+    }
+
+    sites.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(sites)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_existing_config() {
+        let config_toml = r#"
+model_provider = "relay"
+model = "gpt-5"
+sandbox_mode = "workspace-write"
+
+[model_providers.relay]
+name = "relay"
+base_url = "https://relay.example.com/v1"
+wire_api = "responses"
+
+[model_providers.other]
+name = "other"
+base_url = "https://other.example.com/v1"
+env_key = "CA_SWITCH_TEST_UNSET_KEY"
+
+[model_providers.broken]
+name = "broken"
+"#;
+        let sites = parse_existing_config(config_toml, Some(r#"{"OPENAI_API_KEY": "sk-relay"}"#)).unwrap();
+
+        let names: Vec<&str> = sites.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["other", "relay"]);
+
+        let relay = &sites[1].1;
+        assert_eq!(relay.config.base_url.as_deref(), Some("https://relay.example.com/v1"));
+        assert_eq!(relay.config.wire_api.as_deref(), Some("responses"));
+        assert_eq!(relay.config.model.as_deref(), Some("gpt-5"));
+        assert_eq!(relay.config.sandbox_mode.as_deref(), Some("workspace-write"));
+        assert_eq!(relay.api_keys[IMPORTED_KEY_NAME], "sk-relay");
+
+        // 非激活 provider 不继承顶层配置，环境变量未设置时没有 API Key
+        let other = &sites[0].1;
+        assert!(other.config.model.is_none());
+        assert!(other.api_keys.is_empty());
+
+        assert!(parse_existing_config("not toml = ", None).is_err());
+    }
+
+    #[test]
+    fn test_model_provider_defaults_to_site_name() {
+        use crate::config::models::CodexActiveReference;
+
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-codex-provider-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let mut manager = CodexConfigManager::new(config_dir.clone()).unwrap();
+        manager
+            .add_site("relay".to_string(), "https://relay.example.com".to_string(), None)
+            .unwrap();
+        manager
+            .add_api_key("relay", "main".to_string(), "sk-relay".to_string())
+            .unwrap();
+
+        let render = |manager: &CodexConfigManager| {
+            let reference = CodexActiveReference {
+                site: "relay".to_string(),
+                api_key_name: "main".to_string(),
+            };
+            let site = manager.get_site("relay").unwrap().unwrap();
+            let active_config = CodexActiveConfig::from_reference(&reference, &site).unwrap();
+            manager.render_config_toml(&active_config).parse::<toml::Table>().unwrap()
+        };
+
+        // 未设置 model_provider 时，顶层 model_provider 与 [model_providers.*] 均使用站点名
+        let table = render(&manager);
+        assert_eq!(table["model_provider"].as_str(), Some("relay"));
+        assert_eq!(table["model_providers"]["relay"]["name"].as_str(), Some("relay"));
+
+        let mut config = manager.read_config().unwrap();
+        config.get_site_mut("relay").unwrap().config.model_provider = Some("custom".to_string());
+        manager.write_config(&config).unwrap();
+
+        let table = render(&manager);
+        assert_eq!(table["model_provider"].as_str(), Some("custom"));
+        assert!(table["model_providers"].get("relay").is_none());
+        assert_eq!(table["model_providers"]["custom"]["name"].as_str(), Some("custom"));
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
+
+    #[test]
+    fn test_update_site_config_clears_network_settings() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-codex-update-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let mut manager = CodexConfigManager::new(config_dir.clone()).unwrap();
+        manager
+            .add_site("site".to_string(), "https://example.com".to_string(), None)
+            .unwrap();
+
+        let update = |manager: &mut CodexConfigManager, network_access: Option<&str>, disable: Option<bool>| {
+            manager
+                .update_site_config(
+                    "site",
+                    None,
+                    Some("gpt-5".to_string()),
+                    None,
+                    None,
+                    network_access.map(str::to_string),
+                    disable,
+                    None,
+                )
+                .unwrap();
+            manager.get_site("site").unwrap().unwrap().config
+        };
+
+        let config = update(&mut manager, Some("enabled"), Some(true));
+        assert_eq!(config.network_access.as_deref(), Some("enabled"));
+        assert_eq!(config.disable_response_storage, Some(true));
+
+        // 取消设置时清除字段，而不是写入空字符串
+        let config = update(&mut manager, None, None);
+        assert_eq!(config.network_access, None);
+        assert_eq!(config.disable_response_storage, None);
+        assert_eq!(config.model.as_deref(), Some("gpt-5"));
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
 }
@@ -1,8 +1,10 @@
 // OpenCode 站点和模型检测器
 // 用于检测站点可用性、获取模型列表、测试模型性能
 
-use crate::config::models::{ModelDetectionResult, SiteDetectionResult};
-use reqwest::Client;
+use crate::config::models::{AuthHeaderStyle, DetectedModel, GlobalSettings, ModelDetectionResult, SiteDetectionResult};
+use crate::error::strip_url_credentials;
+use crate::logging::SendLogged;
+use reqwest::{Client, RequestBuilder};
 use serde::Deserialize;
 use std::time::{Duration, Instant};
 
@@ -19,19 +21,102 @@ fn build_api_url(base_url: &str, path: &str) -> String {
     }
 }
 
+impl AuthHeaderStyle {
+    /// 为请求添加认证头
+    pub fn apply(self, request: RequestBuilder, api_key: &str) -> RequestBuilder {
+        match self {
+            AuthHeaderStyle::Bearer => request.header("Authorization", format!("Bearer {}", api_key)),
+            AuthHeaderStyle::XApiKey => request
+                .header("x-api-key", api_key)
+                .header("anthropic-version", "2023-06-01"),
+        }
+    }
+}
+
+/// 检测请求的超时时间
+///
+/// 站点探测应尽快失败，模型生成则可能需要较长时间，因此分开设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectorTimeouts {
+    /// 站点检测 (/v1/models)
+    pub site: Duration,
+    /// 模型检测 (每个 chat/completions 请求)
+    pub model: Duration,
+}
+
+impl DetectorTimeouts {
+    /// 默认站点检测超时 (秒)
+    pub const DEFAULT_SITE_SECS: u64 = 10;
+    /// 默认模型检测超时 (秒)
+    pub const DEFAULT_MODEL_SECS: u64 = 60;
+
+    /// 根据全局设置中的覆盖值创建，未设置或为 0 时使用默认值
+    pub fn from_settings(settings: &GlobalSettings) -> Self {
+        let secs = |value: Option<u64>, default: u64| {
+            Duration::from_secs(value.filter(|secs| *secs > 0).unwrap_or(default))
+        };
+        Self {
+            site: secs(settings.detect_site_timeout_secs, Self::DEFAULT_SITE_SECS),
+            model: secs(settings.detect_model_timeout_secs, Self::DEFAULT_MODEL_SECS),
+        }
+    }
+}
+
+impl Default for DetectorTimeouts {
+    fn default() -> Self {
+        Self::from_settings(&GlobalSettings::default())
+    }
+}
+
+/// 检测请求失败的原因
+#[derive(Debug)]
+enum ProbeError {
+    /// 超过设定的超时时间
+    Timeout(Duration),
+    /// 其他错误 (网络、HTTP 状态、解析等)
+    Failed(String),
+}
+
+impl ProbeError {
+    /// 将请求错误转换为检测错误，超时单独归类
+    fn request(e: reqwest::Error, context: &str, timeout: Duration) -> Self {
+        if e.is_timeout() {
+            ProbeError::Timeout(timeout)
+        } else {
+            ProbeError::Failed(format!("{}: {}", context, strip_url_credentials(e)))
+        }
+    }
+
+    fn is_timeout(&self) -> bool {
+        matches!(self, ProbeError::Timeout(_))
+    }
+
+    fn message(self) -> String {
+        match self {
+            ProbeError::Timeout(timeout) => {
+                format!("请求超时: {} 秒内未完成", timeout.as_secs())
+            }
+            ProbeError::Failed(message) => message,
+        }
+    }
+}
+
+/// 批量检测模型时的最大并发数
+const MAX_CONCURRENT_MODEL_DETECTIONS: usize = 4;
+
 /// 站点和模型检测器
+#[derive(Clone)]
 pub struct Detector {
     client: Client,
+    timeouts: DetectorTimeouts,
 }
 
 impl Detector {
-    /// 创建新的检测器
-    pub fn new() -> Self {
+    /// 使用指定超时创建检测器 (超时按请求设置，见 [`DetectorTimeouts`])
+    pub fn with_timeouts(timeouts: DetectorTimeouts) -> Self {
         Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(30))
-                .build()
-                .unwrap(),
+            client: Client::new(),
+            timeouts,
         }
     }
 
@@ -42,6 +127,17 @@ impl Detector {
         &self,
         base_url: &str,
         api_key: &str,
+    ) -> SiteDetectionResult {
+        self.detect_site_with_auth(base_url, api_key, AuthHeaderStyle::Bearer)
+            .await
+    }
+
+    /// 使用指定认证头格式的站点检测
+    pub async fn detect_site_with_auth(
+        &self,
+        base_url: &str,
+        api_key: &str,
+        auth_style: AuthHeaderStyle,
     ) -> SiteDetectionResult {
         let start = Instant::now();
         let mut result = SiteDetectionResult {
@@ -49,21 +145,25 @@ impl Detector {
             is_available: false,
             api_key_valid: false,
             available_models: vec![],
+            models: vec![],
             response_time_ms: None,
             error_message: None,
+            timed_out: false,
         };
 
         // 尝试获取模型列表
-        match self.fetch_models_list(base_url, api_key).await {
+        match self.fetch_models_list(base_url, api_key, auth_style).await {
             Ok(models) => {
                 result.is_available = true;
                 result.api_key_valid = true;
-                result.available_models = models;
+                result.available_models = models.iter().map(|m| m.id.clone()).collect();
+                result.models = models;
                 result.response_time_ms = Some(start.elapsed().as_millis() as f64);
             }
             Err(e) => {
                 result.is_available = false;
-                result.error_message = Some(e);
+                result.timed_out = e.is_timeout();
+                result.error_message = Some(e.message());
             }
         }
 
@@ -75,37 +175,33 @@ impl Detector {
         &self,
         base_url: &str,
         api_key: &str,
-    ) -> Result<Vec<String>, String> {
+        auth_style: AuthHeaderStyle,
+    ) -> Result<Vec<DetectedModel>, ProbeError> {
         let url = build_api_url(base_url, "/models");
+        let timeout = self.timeouts.site;
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .send()
+        let response = auth_style
+            .apply(self.client.get(&url), api_key)
+            .timeout(timeout)
+            .send_logged()
             .await
-            .map_err(|e| format!("请求失败: {}", e))?;
+            .map_err(|e| ProbeError::request(e, "请求失败", timeout))?;
 
         if !response.status().is_success() {
-            return Err(format!("HTTP {}: API返回错误", response.status()));
+            return Err(ProbeError::Failed(format!("HTTP {}: API返回错误", response.status())));
         }
 
         #[derive(Deserialize)]
         struct ModelsResponse {
-            data: Vec<ModelInfo>,
-        }
-
-        #[derive(Deserialize)]
-        struct ModelInfo {
-            id: String,
+            data: Vec<DetectedModel>,
         }
 
         let models_resp: ModelsResponse = response
             .json()
             .await
-            .map_err(|e| format!("解析响应失败: {}", e))?;
+            .map_err(|e| ProbeError::request(e, "解析响应失败", timeout))?;
 
-        Ok(models_resp.data.into_iter().map(|m| m.id).collect())
+        Ok(models_resp.data)
     }
 
     // ========== 模型检测 ==========
@@ -127,6 +223,7 @@ impl Detector {
             total_response_time_ms: None,
             stream_available: None,
             error_message: None,
+            timed_out: false,
         };
 
         // 1. 测试非流式请求
@@ -138,7 +235,8 @@ impl Detector {
                 result.tokens_per_second = perf.tokens_per_sec;
             }
             Err(e) => {
-                result.error_message = Some(e);
+                result.timed_out = e.is_timeout();
+                result.error_message = Some(e.message());
                 return result; // 非流式失败就不测试流式了
             }
         }
@@ -154,14 +252,48 @@ impl Detector {
         result
     }
 
+    /// 并发检测多个模型 (不测试流式)，结果顺序与 `model_ids` 一致
+    pub async fn detect_models(
+        &self,
+        base_url: &str,
+        api_key: &str,
+        model_ids: &[String],
+    ) -> Vec<ModelDetectionResult> {
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_MODEL_DETECTIONS));
+        let mut tasks = tokio::task::JoinSet::new();
+
+        for (index, model_id) in model_ids.iter().enumerate() {
+            let detector = self.clone();
+            let semaphore = semaphore.clone();
+            let base_url = base_url.to_string();
+            let api_key = api_key.to_string();
+            let model_id = model_id.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let result = detector.detect_model(&base_url, &api_key, &model_id, false).await;
+                (index, result)
+            });
+        }
+
+        let mut results: Vec<(usize, ModelDetectionResult)> = Vec::with_capacity(model_ids.len());
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok(entry) = joined {
+                results.push(entry);
+            }
+        }
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
     /// 测试模型的非流式完成请求
     async fn test_model_completion(
         &self,
         base_url: &str,
         api_key: &str,
         model_id: &str,
-    ) -> Result<ModelPerformance, String> {
+    ) -> Result<ModelPerformance, ProbeError> {
         let url = build_api_url(base_url, "/chat/completions");
+        let timeout = self.timeouts.model;
 
         let body = serde_json::json!({
             "model": model_id,
@@ -183,14 +315,15 @@ impl Detector {
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .timeout(timeout)
+            .send_logged()
             .await
-            .map_err(|e| format!("请求失败: {}", e))?;
+            .map_err(|e| ProbeError::request(e, "请求失败", timeout))?;
 
         let first_token_ms = start.elapsed().as_millis() as f64;
 
         if !response.status().is_success() {
-            return Err(format!("HTTP {}: 模型返回错误", response.status()));
+            return Err(ProbeError::Failed(format!("HTTP {}: 模型返回错误", response.status())));
         }
 
         // 解析响应
@@ -213,7 +346,7 @@ impl Detector {
         let completion_resp: CompletionResponse = response
             .json()
             .await
-            .map_err(|e| format!("解析响应失败: {}", e))?;
+            .map_err(|e| ProbeError::request(e, "解析响应失败", timeout))?;
 
         let total_ms = start.elapsed().as_millis() as f64;
 
@@ -248,7 +381,7 @@ impl Detector {
         base_url: &str,
         api_key: &str,
         model_id: &str,
-    ) -> Result<(), String> {
+    ) -> Result<(), ProbeError> {
         let url = build_api_url(base_url, "/chat/completions");
 
         let body = serde_json::json!({
@@ -269,12 +402,13 @@ impl Detector {
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .timeout(self.timeouts.model)
+            .send_logged()
             .await
-            .map_err(|e| format!("请求失败: {}", e))?;
+            .map_err(|e| ProbeError::request(e, "请求失败", self.timeouts.model))?;
 
         if !response.status().is_success() {
-            return Err(format!("HTTP {}: 流式请求失败", response.status()));
+            return Err(ProbeError::Failed(format!("HTTP {}: 流式请求失败", response.status())));
         }
 
         // 简单验证: 只要能收到响应就认为流式可用
@@ -289,3 +423,80 @@ struct ModelPerformance {
     total_ms: f64,
     tokens_per_sec: Option<f64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeouts_from_settings() {
+        let defaults = DetectorTimeouts::default();
+        assert_eq!(defaults.site, Duration::from_secs(10));
+        assert_eq!(defaults.model, Duration::from_secs(60));
+
+        let settings = GlobalSettings {
+            detect_site_timeout_secs: Some(3),
+            detect_model_timeout_secs: Some(0),
+            ..Default::default()
+        };
+        let timeouts = DetectorTimeouts::from_settings(&settings);
+        assert_eq!(timeouts.site, Duration::from_secs(3));
+        assert_eq!(timeouts.model, defaults.model);
+    }
+
+    #[tokio::test]
+    async fn test_detect_site_reports_timeout() {
+        // 只接受连接、从不响应的服务器
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        let detector = Detector::with_timeouts(DetectorTimeouts {
+            site: Duration::from_millis(200),
+            model: Duration::from_millis(200),
+        });
+        let base_url = format!("http://{}", addr);
+
+        let result = detector.detect_site(&base_url, "sk-test").await;
+        assert!(!result.is_available);
+        assert!(result.timed_out);
+        assert!(result.error_message.unwrap().contains("超时"));
+
+        let result = detector.detect_model(&base_url, "sk-test", "m", false).await;
+        assert!(!result.is_available);
+        assert!(result.timed_out);
+
+        // 批量检测保持输入顺序
+        let model_ids: Vec<String> = (0..6).map(|i| format!("m{}", i)).collect();
+        let results = detector.detect_models(&base_url, "sk-test", &model_ids).await;
+        let ids: Vec<&String> = results.iter().map(|r| &r.model_id).collect();
+        assert_eq!(ids, model_ids.iter().collect::<Vec<_>>());
+        assert!(results.iter().all(|r| r.timed_out));
+
+        server.abort();
+    }
+
+    #[test]
+    fn test_auth_header_style_apply() {
+        let client = Client::new();
+
+        let request = AuthHeaderStyle::Bearer
+            .apply(client.get("https://example.com/v1/models"), "sk-test")
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()["Authorization"], "Bearer sk-test");
+        assert!(request.headers().get("x-api-key").is_none());
+
+        let request = AuthHeaderStyle::XApiKey
+            .apply(client.get("https://example.com/v1/models"), "sk-test")
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()["x-api-key"], "sk-test");
+        assert!(request.headers().get("Authorization").is_none());
+    }
+}
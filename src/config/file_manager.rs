@@ -1,7 +1,11 @@
-use crate::error::Result;
+use crate::error::{CliError, Result};
+use crate::logging::log_write;
+use crate::ui::show_warning;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 /// 配置类别路径
@@ -44,8 +48,89 @@ pub struct DirInfo {
 pub struct BackupData {
     pub category: String,
     pub timestamp: String,
-    pub files: HashMap<String, String>,  // 文件名 -> 内容
+    pub files: HashMap<String, BackupFileContent>,  // 文件名 -> 内容
     pub metadata: BackupMetadata,
+    /// 分块上传时各分块相对备份目录的路径，下载时合并回 `files`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<String>,
+}
+
+/// 备份中的单个文件内容
+///
+/// 文本文件直接保存原文 (与旧版备份格式兼容)，非 UTF-8 文件以 base64 编码并标记编码方式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BackupFileContent {
+    Text(String),
+    Encoded {
+        encoding: FileEncoding,
+        content: String,
+    },
+}
+
+/// 文件内容编码方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileEncoding {
+    Base64,
+}
+
+impl BackupFileContent {
+    /// 根据文件字节创建，非 UTF-8 内容使用 base64 编码
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => BackupFileContent::Text(text),
+            Err(e) => BackupFileContent::Encoded {
+                encoding: FileEncoding::Base64,
+                content: BASE64.encode(e.into_bytes()),
+            },
+        }
+    }
+
+    /// 解码为原始文件字节
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            BackupFileContent::Text(text) => Ok(text.as_bytes().to_vec()),
+            BackupFileContent::Encoded {
+                encoding: FileEncoding::Base64,
+                content,
+            } => BASE64
+                .decode(content)
+                .map_err(|e| CliError::Parse(format!("备份文件内容 base64 解码失败: {e}"))),
+        }
+    }
+
+    /// 备份中保存的内容 (文本原文或编码后的字符串)
+    pub fn stored(&self) -> &str {
+        match self {
+            BackupFileContent::Text(text) => text,
+            BackupFileContent::Encoded { content, .. } => content,
+        }
+    }
+
+    /// 原始文件大小 (字节)
+    pub fn len(&self) -> usize {
+        match self {
+            BackupFileContent::Text(text) => text.len(),
+            BackupFileContent::Encoded { content, .. } => {
+                let padding = content.bytes().rev().take_while(|b| *b == b'=').count();
+                content.len() / 4 * 3 - padding
+            }
+        }
+    }
+}
+
+/// 当前主机名，获取失败时为 `unknown`
+pub fn current_hostname() -> String {
+    hostname::get()
+        .ok()
+        .and_then(|h| h.into_string().ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 当前操作系统与架构
+pub fn current_os() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
 }
 
 /// 备份元数据
@@ -54,25 +139,392 @@ pub struct BackupMetadata {
     pub version: String,
     pub created_at: String,
     pub hostname: String,
+    /// 备份来源的操作系统与架构 (如 `linux-x86_64`)，旧备份中没有该字段
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub os: String,
     pub total_files: usize,
     pub total_size: u64,
+    /// 因排除规则或大小限制未包含的文件 (类别内的相对路径)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_files: Vec<String>,
+}
+
+/// 备份过滤规则文件名 (位于 ~/.ca-switch/)
+pub const BACKUP_FILTER_FILE: &str = "backup-filter.json";
+
+/// 目录备份的过滤规则 (~/.ca-switch/backup-filter.json)
+///
+/// `exclude` 支持 `*` / `?` 通配符：以 `/` 结尾的规则匹配目录名 (如 `node_modules/`)，
+/// 包含 `/` 的规则匹配相对路径，其余规则匹配文件名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupFilter {
+    pub exclude: Vec<String>,
+    /// 单个文件大小上限 (字节)，0 表示不限制
+    pub max_file_size: u64,
+    /// 单次备份总大小超过该值 (字节) 时需要确认后才上传，0 表示不提示
+    pub warn_total_size: u64,
+}
+
+impl Default for BackupFilter {
+    fn default() -> Self {
+        Self {
+            exclude: vec![
+                "*.log".to_string(),
+                ".DS_Store".to_string(),
+                "Thumbs.db".to_string(),
+                "node_modules/".to_string(),
+                ".git/".to_string(),
+                "__pycache__/".to_string(),
+            ],
+            max_file_size: 5 * 1024 * 1024,
+            warn_total_size: 50 * 1024 * 1024,
+        }
+    }
+}
+
+impl BackupFilter {
+    /// 判断目录内的相对路径是否被排除
+    pub fn is_excluded(&self, relative_path: &str) -> bool {
+        let relative_path = relative_path.replace('\\', "/");
+        let components: Vec<&str> = relative_path.split('/').collect();
+        let (file_name, dirs) = components.split_last().unwrap_or((&"", &[]));
+
+        self.exclude.iter().any(|pattern| {
+            if let Some(dir_pattern) = pattern.strip_suffix('/') {
+                dirs.iter().any(|dir| glob_match(dir_pattern, dir))
+            } else if pattern.contains('/') {
+                glob_match(pattern, &relative_path)
+            } else {
+                glob_match(pattern, file_name)
+            }
+        })
+    }
+
+    /// 判断目录内的子目录 (相对路径) 是否被排除
+    pub fn is_dir_excluded(&self, relative_dir: &str) -> bool {
+        let relative_dir = relative_dir.replace('\\', "/");
+        self.exclude
+            .iter()
+            .filter_map(|pattern| pattern.strip_suffix('/'))
+            .any(|dir_pattern| relative_dir.split('/').any(|dir| glob_match(dir_pattern, dir)))
+    }
+
+    /// 判断文件是否超过大小上限
+    pub fn exceeds_size(&self, size: u64) -> bool {
+        self.max_file_size > 0 && size > self.max_file_size
+    }
+
+    /// 判断单次备份的总大小是否需要确认
+    pub fn exceeds_total_warning(&self, total_size: u64) -> bool {
+        self.warn_total_size > 0 && total_size > self.warn_total_size
+    }
+}
+
+/// 简单通配符匹配 (`*` 匹配任意字符序列，`?` 匹配单个字符)
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // 回溯：让上一个 * 多匹配一个字符
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 内置备份类别 (键, 显示名称)，按菜单顺序排列
+pub const BUILTIN_CATEGORIES: [(&str, &str); 5] = [
+    ("ccCli", "CA-Switch配置"),
+    ("claudeCode", "Claude Code配置"),
+    ("codex", "Codex配置"),
+    ("gemini", "Gemini配置"),
+    ("opencode", "OpenCode配置"),
+];
+
+/// 自定义备份类别文件名 (位于 ~/.ca-switch/)
+pub const BACKUP_CATEGORIES_FILE: &str = "backup-categories.json";
+
+/// 自定义备份类别 (~/.ca-switch/backup-categories.json)
+///
+/// ```json
+/// { "categories": { "zed": { "name": "Zed配置", "paths": ["~/.config/zed"] } } }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomCategories {
+    /// 类别键 -> 定义，键用于备份文件名 (只允许字母、数字、`-` 与 `_`)
+    #[serde(default)]
+    pub categories: IndexMap<String, CustomCategory>,
+}
+
+/// 单个自定义备份类别
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomCategory {
+    /// 显示名称，省略时使用类别键
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 要备份的文件或目录: `~/` 开头或相对路径均相对于主目录，也可以是主目录下的绝对路径
+    pub paths: Vec<String>,
+}
+
+impl CustomCategories {
+    /// 校验并解析为类别路径，返回 (有效类别, 被忽略类别的说明)
+    ///
+    /// 类别键不能与内置类别重名 (忽略大小写)；路径必须位于主目录下且不能包含 `..`，
+    /// 同一类别中条目名 (路径的最后一段) 不能重复。已存在的目录或以 `/` 结尾的路径按目录备份
+    pub fn resolve(&self, home_dir: &Path) -> (IndexMap<String, CategoryPaths>, Vec<String>) {
+        let mut resolved = IndexMap::new();
+        let mut warnings = Vec::new();
+
+        for (key, category) in &self.categories {
+            match Self::resolve_category(key, category, home_dir) {
+                Ok(paths) => {
+                    resolved.insert(key.clone(), paths);
+                }
+                Err(e) => warnings.push(format!("已忽略自定义备份类别 '{key}': {e}")),
+            }
+        }
+
+        (resolved, warnings)
+    }
+
+    fn resolve_category(key: &str, category: &CustomCategory, home_dir: &Path) -> Result<CategoryPaths> {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')) {
+            return Err(CliError::Config("类别键只能包含字母、数字、'-' 与 '_'".to_string()));
+        }
+        if BUILTIN_CATEGORIES.iter().any(|(builtin, _)| builtin.eq_ignore_ascii_case(key)) {
+            return Err(CliError::Config("与内置类别重名".to_string()));
+        }
+        if category.paths.is_empty() {
+            return Err(CliError::Config("未配置任何路径".to_string()));
+        }
+
+        let mut files = HashMap::new();
+        let mut directories = HashMap::new();
+        for raw in &category.paths {
+            let trimmed = raw.trim();
+            let relative = trimmed.strip_prefix("~/").unwrap_or(trimmed);
+            let path = home_dir.join(relative);
+
+            if Path::new(relative).components().any(|c| c == std::path::Component::ParentDir) {
+                return Err(CliError::Config(format!("路径 '{raw}' 不能包含 '..'")));
+            }
+            if !path.starts_with(home_dir) || path == home_dir {
+                return Err(CliError::Config(format!("路径 '{raw}' 必须位于主目录下")));
+            }
+
+            let entry_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .ok_or_else(|| CliError::Config(format!("路径 '{raw}' 无效")))?;
+            if files.contains_key(&entry_name) || directories.contains_key(&entry_name) {
+                return Err(CliError::Config(format!("条目名 '{entry_name}' 重复")));
+            }
+
+            if path.is_dir() || trimmed.ends_with('/') {
+                directories.insert(entry_name, path);
+            } else {
+                files.insert(entry_name, path);
+            }
+        }
+
+        Ok(CategoryPaths {
+            name: category.name.clone().unwrap_or_else(|| key.to_string()),
+            files,
+            directories,
+        })
+    }
+}
+
+/// 增量备份状态文件名 (位于 ~/.ca-switch/，本机专用，不参与备份)
+pub const BACKUP_STATE_FILE: &str = "backup-state.json";
+
+/// 增量备份状态 (~/.ca-switch/backup-state.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupState {
+    #[serde(default)]
+    pub categories: HashMap<String, CategoryBackupState>,
+}
+
+/// 单个类别最近一次成功备份的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryBackupState {
+    pub hash: String,
+    pub file_name: String,
+    pub backed_up_at: String,
+}
+
+/// 分块上传断点记录文件名 (位于 ~/.ca-switch/，本机专用，不参与备份)
+pub const UPLOAD_MANIFEST_FILE: &str = "upload-manifest.json";
+
+/// 单个分块的目标大小，超过该大小的备份改为分块上传
+pub const UPLOAD_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// 分块上传断点记录 (~/.ca-switch/upload-manifest.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UploadManifest {
+    /// 类别 -> 未完成的上传
+    #[serde(default)]
+    pub uploads: HashMap<String, PendingUpload>,
+}
+
+/// 一次未完成的分块上传
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingUpload {
+    /// 备份内容哈希，内容变化后断点作废
+    pub hash: String,
+    /// 首次上传时确定的备份文件名，续传时沿用
+    pub file_name: String,
+    /// 分块总数
+    pub total_parts: usize,
+    /// 已上传成功的分块序号
+    #[serde(default)]
+    pub uploaded: Vec<usize>,
+}
+
+impl UploadManifest {
+    /// 取出可续传的记录：哈希与分块数都一致时沿用，否则返回 None
+    pub fn resumable(&self, category: &str, hash: &str, total_parts: usize) -> Option<PendingUpload> {
+        self.uploads
+            .get(category)
+            .filter(|pending| pending.hash == hash && pending.total_parts == total_parts)
+            .cloned()
+    }
+}
+
+/// 恢复时本地文件已存在且内容不同的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// 逐个询问
+    Ask,
+    /// 全部覆盖
+    Overwrite,
+    /// 全部跳过
+    Skip,
+}
+
+/// 逐个询问时对单个冲突文件的决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictDecision {
+    Skip,
+    Overwrite,
+    /// 覆盖当前文件及后续所有冲突文件
+    OverwriteAll,
+}
+
+/// 单个文件的恢复结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreOutcome {
+    /// 本地不存在，已新建
+    Created,
+    /// 与本地内容相同，无需写入
+    Unchanged,
+    /// 已覆盖，原文件备份到 `backup`
+    Overwritten { backup: PathBuf },
+    /// 存在冲突，已跳过
+    Skipped,
 }
 
 /// 文件管理器
 pub struct FileManager {
     home_dir: PathBuf,
+    /// ca-switch 数据目录 (默认 ~/.ca-switch)
+    config_dir: PathBuf,
+    filter: BackupFilter,
+    custom_categories: IndexMap<String, CategoryPaths>,
+    category_warnings: Vec<String>,
 }
 
 impl FileManager {
     /// 创建新的文件管理器
     pub fn new() -> Result<Self> {
-        let home_dir = dirs::home_dir()
-            .ok_or_else(|| crate::error::CliError::Config("无法获取用户主目录".to_string()))?;
+        let home_dir = super::home_dir()?;
+        let config_dir = super::config_dir(&home_dir)?;
 
-        Ok(Self { home_dir })
+        Ok(Self::with_dirs(home_dir, config_dir))
     }
 
-    /// 初始化配置路径
+    /// 使用指定的主目录创建文件管理器 (数据目录为默认的 ~/.ca-switch)
+    #[cfg(test)]
+    pub fn with_home_dir(home_dir: PathBuf) -> Self {
+        let config_dir = home_dir.join(super::paths::DEFAULT_CONFIG_DIR);
+        Self::with_dirs(home_dir, config_dir)
+    }
+
+    /// 使用指定的主目录与数据目录创建文件管理器
+    pub fn with_dirs(home_dir: PathBuf, config_dir: PathBuf) -> Self {
+        let filter = Self::load_filter(&config_dir);
+        let (custom_categories, category_warnings) = Self::load_custom_categories(&home_dir, &config_dir);
+        Self {
+            home_dir,
+            config_dir,
+            filter,
+            custom_categories,
+            category_warnings,
+        }
+    }
+
+    /// 当前使用的备份过滤规则
+    pub fn filter(&self) -> &BackupFilter {
+        &self.filter
+    }
+
+    /// 读取备份过滤规则，文件不存在或无法解析时使用默认规则
+    fn load_filter(config_dir: &Path) -> BackupFilter {
+        std::fs::read_to_string(config_dir.join(BACKUP_FILTER_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 读取自定义备份类别，文件不存在时为空；无法解析或校验失败的条目记录到警告中
+    fn load_custom_categories(home_dir: &Path, config_dir: &Path) -> (IndexMap<String, CategoryPaths>, Vec<String>) {
+        let Ok(content) = std::fs::read_to_string(config_dir.join(BACKUP_CATEGORIES_FILE)) else {
+            return (IndexMap::new(), Vec::new());
+        };
+
+        match serde_json::from_str::<CustomCategories>(&content) {
+            Ok(custom) => custom.resolve(home_dir),
+            Err(e) => (IndexMap::new(), vec![format!("解析 {BACKUP_CATEGORIES_FILE} 失败: {e}")]),
+        }
+    }
+
+    /// 加载自定义备份类别时被忽略的条目说明
+    pub fn category_warnings(&self) -> &[String] {
+        &self.category_warnings
+    }
+
+    /// 有效的自定义备份类别 (键 -> 路径)，按配置文件中的顺序排列
+    pub fn custom_categories(&self) -> &IndexMap<String, CategoryPaths> {
+        &self.custom_categories
+    }
+
+    /// 类别的显示名称，未知类别返回类别键本身
+    pub fn category_name(&self, category: &str) -> String {
+        BUILTIN_CATEGORIES
+            .iter()
+            .find(|(key, _)| *key == category)
+            .map(|(_, name)| name.to_string())
+            .or_else(|| self.custom_categories.get(category).map(|paths| paths.name.clone()))
+            .unwrap_or_else(|| category.to_string())
+    }
+
+    /// 初始化配置路径 (内置类别与自定义类别合并)
     pub fn init_config_paths(&self) -> HashMap<String, CategoryPaths> {
         let mut paths = HashMap::new();
 
@@ -81,7 +533,7 @@ impl FileManager {
         let mut cc_cli_dirs = HashMap::new();
         cc_cli_dirs.insert(
             ".ca-switch".to_string(),
-            self.home_dir.join(".ca-switch"),
+            self.config_dir.clone(),
         );
         paths.insert(
             "ccCli".to_string(),
@@ -186,6 +638,11 @@ impl FileManager {
             },
         );
 
+        // 自定义类别 (已在加载时排除与内置类别重名的条目)
+        for (key, category) in &self.custom_categories {
+            paths.insert(key.clone(), category.clone());
+        }
+
         paths
     }
 
@@ -212,7 +669,7 @@ impl FileManager {
         let config_paths = self.init_config_paths();
         let paths = config_paths
             .get(category)
-            .ok_or_else(|| crate::error::CliError::Config(format!("未知的配置类别: {category}")))?;
+            .ok_or_else(|| crate::error::CliError::NotFound(format!("未知的配置类别: {category}")))?;
 
         let mut result = FileCheckResult {
             category: category.to_string(),
@@ -251,7 +708,7 @@ impl FileManager {
         for (name, path) in &paths.directories {
             let exists = path.exists();
             let file_count = if exists {
-                self.count_files_in_dir(path).await.unwrap_or(0)
+                self.count_files_in_dir(path, path).await.unwrap_or(0)
             } else {
                 0
             };
@@ -274,16 +731,40 @@ impl FileManager {
         Ok(result)
     }
 
-    /// 统计目录中的文件数量
-    async fn count_files_in_dir(&self, dir: &PathBuf) -> Result<usize> {
-        let mut count = 0;
-        let mut entries = fs::read_dir(dir).await?;
+    /// 递归统计目录中会被备份的文件数量（过滤规则与 collect_dir_recursive 一致，子目录本身不计数）
+    fn count_files_in_dir<'a>(
+        &'a self,
+        base_dir: &'a PathBuf,
+        current_dir: &'a PathBuf,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<usize>> + 'a>> {
+        Box::pin(async move {
+            let mut count = 0;
+            let mut entries = fs::read_dir(current_dir).await?;
 
-        while let Some(_entry) = entries.next_entry().await? {
-            count += 1;
-        }
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                let relative_path = path
+                    .strip_prefix(base_dir)
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string();
+
+                if path.is_file() {
+                    if path == self.backup_state_file() || path == self.upload_manifest_file() {
+                        continue;
+                    }
+
+                    let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+                    if !self.filter.is_excluded(&relative_path) && !self.filter.exceeds_size(size) {
+                        count += 1;
+                    }
+                } else if path.is_dir() && path != self.profiles_dir() && !self.filter.is_dir_excluded(&relative_path) {
+                    count += self.count_files_in_dir(base_dir, &path).await?;
+                }
+            }
 
-        Ok(count)
+            Ok(count)
+        })
     }
 
     /// 收集备份数据
@@ -291,18 +772,19 @@ impl FileManager {
         let config_paths = self.init_config_paths();
         let paths = config_paths
             .get(category)
-            .ok_or_else(|| crate::error::CliError::Config(format!("未知的配置类别: {category}")))?;
+            .ok_or_else(|| crate::error::CliError::NotFound(format!("未知的配置类别: {category}")))?;
 
         let mut files_content = HashMap::new();
+        let mut skipped_files = Vec::new();
         let mut total_size = 0u64;
 
         // 收集文件内容
         for (name, path) in &paths.files {
             if path.exists() {
-                match fs::read_to_string(path).await {
-                    Ok(content) => {
-                        total_size += content.len() as u64;
-                        files_content.insert(name.clone(), content);
+                match fs::read(path).await {
+                    Ok(bytes) => {
+                        total_size += bytes.len() as u64;
+                        files_content.insert(name.clone(), BackupFileContent::from_bytes(bytes));
                     }
                     Err(e) => {
                         eprintln!("读取文件 {} 失败: {}", path.display(), e);
@@ -314,7 +796,8 @@ impl FileManager {
         // 收集目录内容
         for (dir_name, dir_path) in &paths.directories {
             if dir_path.exists() {
-                let dir_files = self.collect_directory_files(dir_path).await?;
+                let (dir_files, skipped) = self.collect_directory_files(dir_path).await?;
+                skipped_files.extend(skipped.into_iter().map(|f| format!("{dir_name}/{f}")));
                 for (file_name, content) in dir_files {
                     total_size += content.len() as u64;
                     files_content.insert(format!("{dir_name}/{file_name}"), content);
@@ -324,11 +807,6 @@ impl FileManager {
 
         // 创建备份数据
         let timestamp = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S").to_string();
-        let hostname = hostname::get()
-            .ok()
-            .and_then(|h| h.into_string().ok())
-            .unwrap_or_else(|| "unknown".to_string());
-
         Ok(BackupData {
             category: category.to_string(),
             timestamp: timestamp.clone(),
@@ -336,29 +814,39 @@ impl FileManager {
             metadata: BackupMetadata {
                 version: env!("CARGO_PKG_VERSION").to_string(),
                 created_at: chrono::Utc::now().to_rfc3339(),
-                hostname,
+                hostname: current_hostname(),
+                os: current_os(),
                 total_files: files_content.len(),
                 total_size,
+                skipped_files: {
+                    skipped_files.sort();
+                    skipped_files
+                },
             },
+            parts: Vec::new(),
         })
     }
 
-    /// 递归收集目录中的所有文件
-    async fn collect_directory_files(&self, dir: &PathBuf) -> Result<HashMap<String, String>> {
+    /// 递归收集目录中的所有文件，返回 (收集到的文件, 被过滤规则跳过的文件)
+    async fn collect_directory_files(
+        &self,
+        dir: &PathBuf,
+    ) -> Result<(HashMap<String, BackupFileContent>, Vec<String>)> {
         let mut files = HashMap::new();
+        let mut skipped = Vec::new();
 
-        self.collect_dir_recursive(dir, dir, &mut files).await?;
+        self.collect_dir_recursive(dir, dir, &mut files, &mut skipped).await?;
 
-        Ok(files)
+        Ok((files, skipped))
     }
 
     /// 递归收集目录文件的辅助函数
-    #[allow(clippy::only_used_in_recursion)]
     fn collect_dir_recursive<'a>(
         &'a self,
         base_dir: &'a PathBuf,
         current_dir: &'a PathBuf,
-        files: &'a mut HashMap<String, String>,
+        files: &'a mut HashMap<String, BackupFileContent>,
+        skipped: &'a mut Vec<String>,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + 'a>> {
         Box::pin(async move {
             let mut entries = fs::read_dir(current_dir).await?;
@@ -367,6 +855,10 @@ impl FileManager {
                 let path = entry.path();
 
                 if path.is_file() {
+                    if path == self.backup_state_file() || path == self.upload_manifest_file() {
+                        continue;
+                    }
+
                     // 计算相对路径
                     let relative_path = path
                         .strip_prefix(base_dir)
@@ -374,12 +866,34 @@ impl FileManager {
                         .display()
                         .to_string();
 
-                    if let Ok(content) = fs::read_to_string(&path).await {
-                        files.insert(relative_path, content);
+                    let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+                    if self.filter.is_excluded(&relative_path) || self.filter.exceeds_size(size) {
+                        skipped.push(relative_path);
+                        continue;
+                    }
+
+                    if let Ok(bytes) = fs::read(&path).await {
+                        files.insert(relative_path, BackupFileContent::from_bytes(bytes));
                     }
                 } else if path.is_dir() {
+                    // 其他 profile 的数据目录各自备份，不混入当前数据目录的备份
+                    if path == self.profiles_dir() {
+                        continue;
+                    }
+
+                    // 被排除的目录整体跳过，不再深入 (如 node_modules/)
+                    let relative_dir = path
+                        .strip_prefix(base_dir)
+                        .unwrap_or(&path)
+                        .display()
+                        .to_string();
+                    if self.filter.is_dir_excluded(&relative_dir) {
+                        skipped.push(format!("{relative_dir}/"));
+                        continue;
+                    }
+
                     // 递归处理子目录
-                    self.collect_dir_recursive(base_dir, &path, files).await?;
+                    self.collect_dir_recursive(base_dir, &path, files, skipped).await?;
                 }
             }
 
@@ -387,55 +901,253 @@ impl FileManager {
         })
     }
 
+    /// 计算备份内容的哈希 (FNV-1a，按文件名排序，与时间戳和主机名无关)
+    pub fn content_hash(backup_data: &BackupData) -> String {
+        const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let mut names: Vec<&String> = backup_data.files.keys().collect();
+        names.sort();
+
+        let mut hash = FNV_OFFSET;
+        for name in names {
+            // 以 0 字节分隔文件名与内容，避免拼接歧义
+            let content = backup_data.files[name].stored();
+            for byte in name.bytes().chain([0]).chain(content.bytes()).chain([0]) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+
+        format!("{hash:016x}")
+    }
+
+    /// 按文件名排序后将文件切分为若干分块，每块累计大小不超过 `chunk_size` (单个大文件独占一块)
+    pub fn split_into_parts(
+        backup_data: &BackupData,
+        chunk_size: usize,
+    ) -> Vec<HashMap<String, BackupFileContent>> {
+        let mut names: Vec<&String> = backup_data.files.keys().collect();
+        names.sort();
+
+        let mut parts = Vec::new();
+        let mut current: HashMap<String, BackupFileContent> = HashMap::new();
+        let mut current_size = 0;
+        for name in names {
+            let content = &backup_data.files[name];
+            let size = content.stored().len();
+            if !current.is_empty() && current_size + size > chunk_size {
+                parts.push(std::mem::take(&mut current));
+                current_size = 0;
+            }
+            current.insert(name.clone(), content.clone());
+            current_size += size;
+        }
+        if !current.is_empty() {
+            parts.push(current);
+        }
+
+        parts
+    }
+
+    /// 默认数据目录下存放各 profile 的目录，不参与备份与恢复
+    fn profiles_dir(&self) -> PathBuf {
+        self.config_dir.join(super::paths::PROFILES_DIR)
+    }
+
+    /// 分块上传断点记录文件路径
+    fn upload_manifest_file(&self) -> PathBuf {
+        self.config_dir.join(UPLOAD_MANIFEST_FILE)
+    }
+
+    /// 读取分块上传断点记录，不存在或损坏时返回空记录
+    pub async fn load_upload_manifest(&self) -> UploadManifest {
+        fs::read_to_string(self.upload_manifest_file())
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 保存分块上传断点记录
+    pub async fn save_upload_manifest(&self, manifest: &UploadManifest) -> Result<()> {
+        let path = self.upload_manifest_file();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        log_write(&path);
+        fs::write(&path, serde_json::to_string_pretty(manifest)?).await?;
+        Ok(())
+    }
+
+    /// 增量备份状态文件路径
+    fn backup_state_file(&self) -> PathBuf {
+        self.config_dir.join(BACKUP_STATE_FILE)
+    }
+
+    /// 读取增量备份状态，文件不存在或损坏时返回空状态
+    pub async fn load_backup_state(&self) -> BackupState {
+        fs::read_to_string(self.backup_state_file())
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 保存增量备份状态
+    pub async fn save_backup_state(&self, state: &BackupState) -> Result<()> {
+        let path = self.backup_state_file();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        log_write(&path);
+        fs::write(&path, serde_json::to_string_pretty(state)?).await?;
+        Ok(())
+    }
+
     /// 恢复备份数据
-    #[allow(dead_code)]
-    pub async fn restore_backup_data(&self, category: &str, backup_data: &BackupData) -> Result<()> {
+    ///
+    /// 本地文件已存在且内容不同时按 `policy` 处理，`ask` 仅在 [`ConflictPolicy::Ask`] 下调用，
+    /// 参数为 (本地路径, 本地内容, 备份内容)。被覆盖的文件会先复制为同目录下的 `.bak`。
+    /// base64 编码的二进制文件会先解码再写入
+    pub async fn restore_backup_data<F>(
+        &self,
+        category: &str,
+        backup_data: &BackupData,
+        policy: ConflictPolicy,
+        ask: F,
+    ) -> Result<Vec<(PathBuf, RestoreOutcome)>>
+    where
+        F: FnMut(&Path, &[u8], &[u8]) -> Result<ConflictDecision>,
+    {
+        let file_names: Vec<&str> = backup_data.files.keys().map(String::as_str).collect();
+        self.restore_selected(category, backup_data, &file_names, policy, ask).await
+    }
+
+    /// 只恢复备份中被选中的文件，规则同 [`Self::restore_backup_data`]
+    ///
+    /// `selected` 为备份中的文件名 (即 `BackupData::files` 的键)，不在备份中的名称会被忽略
+    pub async fn restore_selected<F>(
+        &self,
+        category: &str,
+        backup_data: &BackupData,
+        selected: &[&str],
+        policy: ConflictPolicy,
+        mut ask: F,
+    ) -> Result<Vec<(PathBuf, RestoreOutcome)>>
+    where
+        F: FnMut(&Path, &[u8], &[u8]) -> Result<ConflictDecision>,
+    {
         let config_paths = self.init_config_paths();
         let paths = config_paths
             .get(category)
-            .ok_or_else(|| crate::error::CliError::Config(format!("未知的配置类别: {category}")))?;
-
-        // 恢复文件
-        for (file_name, content) in &backup_data.files {
-            // 判断是普通文件还是目录中的文件
-            if file_name.contains('/') {
-                // 目录中的文件
-                let parts: Vec<&str> = file_name.splitn(2, '/').collect();
-                if parts.len() == 2 {
-                    let dir_name = parts[0];
-                    let relative_path = parts[1];
-
-                    if let Some(base_dir) = paths.directories.get(dir_name) {
-                        let file_path = base_dir.join(relative_path);
-
-                        // 确保父目录存在
-                        if let Some(parent) = file_path.parent() {
-                            fs::create_dir_all(parent).await?;
-                        }
-
-                        fs::write(&file_path, content).await?;
+            .ok_or_else(|| crate::error::CliError::NotFound(format!("未知的配置类别: {category}")))?;
+
+        let mut file_names: Vec<&str> = selected
+            .iter()
+            .copied()
+            .filter(|name| backup_data.files.contains_key(*name))
+            .collect();
+        file_names.sort();
+        file_names.dedup();
+
+        let mut policy = policy;
+        let mut outcomes = Vec::new();
+
+        for file_name in file_names {
+            let content = backup_data.files[file_name].to_bytes()?;
+            let Some(file_path) = Self::resolve_restore_path(paths, file_name) else {
+                continue;
+            };
+            if file_path.starts_with(self.profiles_dir()) {
+                show_warning(&format!("已跳过其他 profile 的文件: {file_name}"));
+                continue;
+            }
+
+            let outcome = match fs::read(&file_path).await {
+                Ok(existing) if existing == content => RestoreOutcome::Unchanged,
+                Ok(existing) => {
+                    let overwrite = match policy {
+                        ConflictPolicy::Overwrite => true,
+                        ConflictPolicy::Skip => false,
+                        ConflictPolicy::Ask => match ask(&file_path, &existing, &content)? {
+                            ConflictDecision::Skip => false,
+                            ConflictDecision::Overwrite => true,
+                            ConflictDecision::OverwriteAll => {
+                                policy = ConflictPolicy::Overwrite;
+                                true
+                            }
+                        },
+                    };
+
+                    if overwrite {
+                        let backup = Self::bak_path(&file_path);
+                        fs::copy(&file_path, &backup).await?;
+                        log_write(&file_path);
+                        fs::write(&file_path, &content).await?;
+                        RestoreOutcome::Overwritten { backup }
+                    } else {
+                        RestoreOutcome::Skipped
                     }
                 }
-            } else {
-                // 普通文件
-                if let Some(file_path) = paths.files.get(file_name) {
+                Err(_) => {
                     // 确保父目录存在
                     if let Some(parent) = file_path.parent() {
                         fs::create_dir_all(parent).await?;
                     }
-
-                    fs::write(file_path, content).await?;
+                    log_write(&file_path);
+                    fs::write(&file_path, &content).await?;
+                    RestoreOutcome::Created
                 }
-            }
+            };
+
+            outcomes.push((file_path, outcome));
         }
 
-        Ok(())
+        Ok(outcomes)
+    }
+
+    /// 根据备份中的文件名确定本地恢复路径
+    ///
+    /// 普通文件直接使用类别中的文件名，目录中的文件格式为 `目录名/相对路径`。
+    /// 相对路径含 `..`、绝对路径等会离开类别目录的成分时跳过并给出警告
+    fn resolve_restore_path(paths: &CategoryPaths, file_name: &str) -> Option<PathBuf> {
+        if let Some(file_path) = paths.files.get(file_name) {
+            return Some(file_path.clone());
+        }
+
+        let (dir_name, relative_path) = file_name.split_once('/')?;
+        let base_dir = paths.directories.get(dir_name)?;
+        if !Self::is_safe_relative_path(relative_path) {
+            show_warning(&format!("已跳过不安全的备份文件路径: {file_name}"));
+            return None;
+        }
+        Some(base_dir.join(relative_path))
+    }
+
+    /// 相对路径非空且只由普通路径成分组成
+    fn is_safe_relative_path(relative_path: &str) -> bool {
+        let path = Path::new(relative_path);
+        path.components().next().is_some()
+            && path.components().all(|c| matches!(c, std::path::Component::Normal(_)))
     }
 
-    /// 获取所有配置类别
-    #[allow(dead_code)]
+    /// 覆盖前的备份文件路径 (原文件名追加 .bak)
+    fn bak_path(path: &Path) -> PathBuf {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        PathBuf::from(backup)
+    }
+
+    /// 获取所有配置类别，内置类别在前，自定义类别按配置顺序在后
     pub fn get_categories(&self) -> Vec<String> {
-        self.init_config_paths().keys().cloned().collect()
+        BUILTIN_CATEGORIES
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .chain(self.custom_categories.keys().cloned())
+            .collect()
     }
 
     /// 格式化文件大小
@@ -453,8 +1165,400 @@ impl FileManager {
     }
 }
 
-impl Default for FileManager {
-    fn default() -> Self {
-        Self::new().expect("Failed to create FileManager")
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_home(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ca-switch-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn backup_of(files: &[(&str, &str)]) -> BackupData {
+        BackupData {
+            category: "gemini".to_string(),
+            timestamp: "2025-01-01-00-00-00".to_string(),
+            files: files
+                .iter()
+                .map(|(name, content)| {
+                    (name.to_string(), BackupFileContent::Text(content.to_string()))
+                })
+                .collect(),
+            metadata: BackupMetadata {
+                version: "test".to_string(),
+                created_at: String::new(),
+                hostname: "test".to_string(),
+                os: String::new(),
+                total_files: files.len(),
+                total_size: 0,
+                skipped_files: Vec::new(),
+            },
+            parts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_metadata_os_optional() {
+        let json = r#"{"version":"1","created_at":"","hostname":"h","total_files":0,"total_size":0}"#;
+        let metadata: BackupMetadata = serde_json::from_str(json).unwrap();
+        assert!(metadata.os.is_empty());
+        assert!(!serde_json::to_string(&metadata).unwrap().contains("\"os\""));
+        assert!(current_os().starts_with(std::env::consts::OS));
+    }
+
+    #[tokio::test]
+    async fn test_content_hash_and_state_file_excluded() {
+        let a = backup_of(&[("a", "1"), ("b", "2")]);
+        let mut b = backup_of(&[("b", "2"), ("a", "1")]);
+        b.timestamp = "other".to_string();
+        assert_eq!(FileManager::content_hash(&a), FileManager::content_hash(&b));
+        assert_ne!(
+            FileManager::content_hash(&a),
+            FileManager::content_hash(&backup_of(&[("a", "1"), ("b", "3")]))
+        );
+
+        // 状态文件本身不参与 ~/.ca-switch 的备份
+        let home = temp_home("state");
+        let manager = FileManager::with_home_dir(home.clone());
+        std::fs::create_dir_all(home.join(".ca-switch")).unwrap();
+        std::fs::write(home.join(".ca-switch").join("config.json"), "{}").unwrap();
+        manager.save_backup_state(&BackupState::default()).await.unwrap();
+
+        let data = manager.collect_backup_data("ccCli").await.unwrap();
+        assert_eq!(data.files.keys().collect::<Vec<_>>(), vec![".ca-switch/config.json"]);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_profiles_excluded_from_default_data_dir() {
+        let home = temp_home("profiles");
+        let manager = FileManager::with_home_dir(home.clone());
+        let data_dir = home.join(".ca-switch");
+        let profile_file = data_dir.join("profiles").join("client-a").join("claude.json");
+        std::fs::create_dir_all(profile_file.parent().unwrap()).unwrap();
+        std::fs::write(data_dir.join("claude.json"), "{}").unwrap();
+        std::fs::write(&profile_file, "{\"profile\": true}").unwrap();
+
+        let check = manager.check_category_files("ccCli").await.unwrap();
+        assert_eq!(check.directories[".ca-switch"].file_count, 1);
+        let data = manager.collect_backup_data("ccCli").await.unwrap();
+        assert_eq!(data.files.keys().collect::<Vec<_>>(), vec![".ca-switch/claude.json"]);
+
+        // 旧备份中的 profile 文件不会覆盖本地 profile
+        let mut backup = backup_of(&[]);
+        backup.category = "ccCli".to_string();
+        backup.files.insert(
+            ".ca-switch/profiles/client-a/claude.json".to_string(),
+            BackupFileContent::Text("{}".to_string()),
+        );
+        let outcomes = manager
+            .restore_backup_data("ccCli", &backup, ConflictPolicy::Overwrite, |_, _, _| unreachable!())
+            .await
+            .unwrap();
+        assert!(outcomes.is_empty());
+        assert_eq!(std::fs::read_to_string(&profile_file).unwrap(), "{\"profile\": true}");
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_split_into_parts_and_resume_manifest() {
+        let backup = backup_of(&[("c", "333"), ("a", "1"), ("b", "22"), ("big", "4444444")]);
+        let parts = FileManager::split_into_parts(&backup, 4);
+        let names: Vec<Vec<&String>> = parts
+            .iter()
+            .map(|part| {
+                let mut names: Vec<&String> = part.keys().collect();
+                names.sort();
+                names
+            })
+            .collect();
+        // 按文件名排序切分，超过分块大小的文件独占一块
+        assert_eq!(names, vec![vec!["a", "b"], vec!["big"], vec!["c"]]);
+        assert_eq!(FileManager::split_into_parts(&backup, usize::MAX).len(), 1);
+
+        let home = temp_home("manifest");
+        let manager = FileManager::with_home_dir(home.clone());
+        let pending = PendingUpload {
+            hash: "h1".to_string(),
+            file_name: "gemini-x.json".to_string(),
+            total_parts: 3,
+            uploaded: vec![0, 1],
+        };
+        let mut manifest = UploadManifest::default();
+        manifest.uploads.insert("gemini".to_string(), pending.clone());
+        manager.save_upload_manifest(&manifest).await.unwrap();
+
+        // 只有内容哈希和分块数都一致时才续传
+        let loaded = manager.load_upload_manifest().await;
+        assert_eq!(loaded.resumable("gemini", "h1", 3), Some(pending));
+        assert_eq!(loaded.resumable("gemini", "h2", 3), None);
+        assert_eq!(loaded.resumable("gemini", "h1", 4), None);
+        assert_eq!(loaded.resumable("codex", "h1", 3), None);
+
+        // 断点记录不参与 ~/.ca-switch 的备份
+        let data = manager.collect_backup_data("ccCli").await.unwrap();
+        assert!(data.files.is_empty());
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_restore_conflict_policies() {
+        let home = temp_home("restore");
+        let manager = FileManager::with_home_dir(home.clone());
+        let env_file = home.join(".gemini").join(".env");
+        let settings_file = home.join(".gemini").join("settings.json");
+        std::fs::create_dir_all(env_file.parent().unwrap()).unwrap();
+        std::fs::write(&env_file, "OLD=1\n").unwrap();
+
+        let backup = backup_of(&[(".env", "NEW=1\n"), ("settings.json", "{}")]);
+
+        // 跳过策略：保留本地文件，不存在的文件照常创建
+        let outcomes = manager
+            .restore_backup_data("gemini", &backup, ConflictPolicy::Skip, |_, _, _| unreachable!())
+            .await
+            .unwrap();
+        assert_eq!(outcomes[0], (env_file.clone(), RestoreOutcome::Skipped));
+        assert_eq!(outcomes[1], (settings_file.clone(), RestoreOutcome::Created));
+        assert_eq!(std::fs::read_to_string(&env_file).unwrap(), "OLD=1\n");
+
+        // 逐个询问并覆盖：原文件保存为 .bak
+        let outcomes = manager
+            .restore_backup_data("gemini", &backup, ConflictPolicy::Ask, |_, old, new| {
+                assert_eq!((old, new), (&b"OLD=1\n"[..], &b"NEW=1\n"[..]));
+                Ok(ConflictDecision::Overwrite)
+            })
+            .await
+            .unwrap();
+        let bak = home.join(".gemini").join(".env.bak");
+        assert_eq!(outcomes[0], (env_file.clone(), RestoreOutcome::Overwritten { backup: bak.clone() }));
+        assert_eq!(outcomes[1], (settings_file, RestoreOutcome::Unchanged));
+        assert_eq!(std::fs::read_to_string(&env_file).unwrap(), "NEW=1\n");
+        assert_eq!(std::fs::read_to_string(&bak).unwrap(), "OLD=1\n");
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    fn skills_paths(base_dir: &Path) -> CategoryPaths {
+        CategoryPaths {
+            name: "skills".to_string(),
+            files: HashMap::new(),
+            directories: HashMap::from([("skills".to_string(), base_dir.to_path_buf())]),
+        }
+    }
+
+    #[test]
+    fn test_resolve_restore_path_rejects_parent_dir() {
+        let base = PathBuf::from("/home/u/.claude/skills");
+        let paths = skills_paths(&base);
+
+        assert_eq!(
+            FileManager::resolve_restore_path(&paths, "skills/a/SKILL.md"),
+            Some(base.join("a/SKILL.md"))
+        );
+        assert_eq!(FileManager::resolve_restore_path(&paths, "skills/../../.ssh/authorized_keys"), None);
+        assert_eq!(FileManager::resolve_restore_path(&paths, "skills/a/../../b"), None);
+        assert_eq!(FileManager::resolve_restore_path(&paths, "skills/"), None);
+    }
+
+    #[test]
+    fn test_resolve_restore_path_rejects_absolute_path() {
+        let paths = skills_paths(Path::new("/home/u/.claude/skills"));
+
+        assert_eq!(FileManager::resolve_restore_path(&paths, "skills//etc/x"), None);
+        assert_eq!(FileManager::resolve_restore_path(&paths, "skills/./x"), None);
+    }
+
+    #[tokio::test]
+    async fn test_restore_skips_path_outside_category() {
+        let home = temp_home("restore-traversal");
+        let manager = FileManager::with_home_dir(home.clone());
+        let mut backup = backup_of(&[("settings.json", "{}")]);
+        backup
+            .files
+            .insert("skills/../../escaped.txt".to_string(), BackupFileContent::Text("x".to_string()));
+        backup.category = "claudeCode".to_string();
+
+        let outcomes = manager
+            .restore_backup_data("claudeCode", &backup, ConflictPolicy::Overwrite, |_, _, _| unreachable!())
+            .await
+            .unwrap();
+        assert!(outcomes.iter().all(|(path, _)| path.starts_with(&home)));
+        assert!(!home.join("escaped.txt").exists());
+        assert!(!home.parent().unwrap().join("escaped.txt").exists());
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_restore_selected_files_only() {
+        let home = temp_home("restore-selected");
+        let manager = FileManager::with_home_dir(home.clone());
+        let backup = backup_of(&[(".env", "NEW=1\n"), ("settings.json", "{}")]);
+
+        let outcomes = manager
+            .restore_selected("gemini", &backup, &["settings.json", "missing.txt"], ConflictPolicy::Skip, |_, _, _| {
+                unreachable!()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            vec![(home.join(".gemini").join("settings.json"), RestoreOutcome::Created)]
+        );
+        assert!(!home.join(".gemini").join(".env").exists());
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore_binary_file() {
+        let home = temp_home("binary");
+        let manager = FileManager::with_home_dir(home.clone());
+        let asset = home.join(".claude").join("skills").join("demo").join("icon.bin");
+        let bytes: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe, 0x80];
+        std::fs::create_dir_all(asset.parent().unwrap()).unwrap();
+        std::fs::write(&asset, &bytes).unwrap();
+
+        let data = manager.collect_backup_data("claudeCode").await.unwrap();
+        let entry = &data.files["skills/demo/icon.bin"];
+        assert!(matches!(
+            entry,
+            BackupFileContent::Encoded { encoding: FileEncoding::Base64, .. }
+        ));
+        assert_eq!(data.metadata.total_size, bytes.len() as u64);
+
+        // 经过 JSON 序列化 (上传/下载) 后恢复
+        let json = serde_json::to_string(&data).unwrap();
+        let restored: BackupData = serde_json::from_str(&json).unwrap();
+        std::fs::remove_file(&asset).unwrap();
+
+        manager
+            .restore_backup_data("claudeCode", &restored, ConflictPolicy::Skip, |_, _, _| unreachable!())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&asset).unwrap(), bytes);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_backup_filter_patterns() {
+        let filter = BackupFilter::default();
+        assert!(filter.is_excluded("debug.log"));
+        assert!(filter.is_excluded("demo/.DS_Store"));
+        assert!(filter.is_excluded("demo/node_modules/pkg/index.js"));
+        assert!(!filter.is_excluded("demo/SKILL.md"));
+        assert!(!filter.is_excluded("logs.md"));
+
+        let filter = BackupFilter {
+            exclude: vec!["cache/*.json".to_string(), "tmp-??".to_string()],
+            max_file_size: 10,
+            warn_total_size: 0,
+        };
+        assert!(filter.is_excluded("cache/a.json"));
+        assert!(!filter.is_excluded("other/cache/a.json"));
+        assert!(filter.is_excluded("x/tmp-01"));
+        assert!(!filter.is_excluded("x/tmp-001"));
+        assert!(filter.exceeds_size(11));
+        assert!(!filter.exceeds_size(10));
+        assert!(!filter.exceeds_total_warning(u64::MAX));
+
+        // 旧的规则文件没有 warn_total_size 时使用默认阈值
+        let filter: BackupFilter = serde_json::from_str(r#"{"max_file_size": 10}"#).unwrap();
+        assert!(filter.exceeds_total_warning(BackupFilter::default().warn_total_size + 1));
+    }
+
+    #[tokio::test]
+    async fn test_count_files_in_dir_counts_nested_leaf_files() {
+        let home = temp_home("count");
+        let dir = home.join("skills");
+        std::fs::create_dir_all(dir.join("a").join("b")).unwrap();
+        std::fs::create_dir_all(dir.join("empty")).unwrap();
+        std::fs::write(dir.join("top.md"), "1").unwrap();
+        std::fs::write(dir.join("a").join("one.md"), "2").unwrap();
+        std::fs::write(dir.join("a").join("b").join("two.md"), "3").unwrap();
+
+        let manager = FileManager::with_home_dir(home.clone());
+        assert_eq!(manager.count_files_in_dir(&dir, &dir).await.unwrap(), 3);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_collect_skips_excluded_and_large_files() {
+        let home = temp_home("filter");
+        let skills = home.join(".claude").join("skills").join("demo");
+        std::fs::create_dir_all(skills.join("node_modules").join("pkg")).unwrap();
+        std::fs::write(skills.join("SKILL.md"), "# demo").unwrap();
+        std::fs::write(skills.join("run.log"), "log").unwrap();
+        std::fs::write(skills.join("node_modules").join("pkg").join("index.js"), "x").unwrap();
+        std::fs::write(skills.join("big.bin"), vec![0u8; 64]).unwrap();
+
+        let ca_switch_dir = home.join(".ca-switch");
+        std::fs::create_dir_all(&ca_switch_dir).unwrap();
+        let filter = BackupFilter {
+            max_file_size: 32,
+            ..BackupFilter::default()
+        };
+        std::fs::write(
+            ca_switch_dir.join(BACKUP_FILTER_FILE),
+            serde_json::to_string(&filter).unwrap(),
+        )
+        .unwrap();
+
+        let manager = FileManager::with_home_dir(home.clone());
+        let data = manager.collect_backup_data("claudeCode").await.unwrap();
+        assert_eq!(data.files.keys().collect::<Vec<_>>(), vec!["skills/demo/SKILL.md"]);
+        let skills_dir = home.join(".claude").join("skills");
+        assert_eq!(manager.count_files_in_dir(&skills_dir, &skills_dir).await.unwrap(), 1);
+        assert_eq!(
+            data.metadata.skipped_files,
+            vec![
+                "skills/demo/big.bin",
+                "skills/demo/node_modules/",
+                "skills/demo/run.log"
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[tokio::test]
+    async fn test_custom_categories_merged_and_validated() {
+        let home = temp_home("custom-categories");
+        std::fs::create_dir_all(home.join(".config/zed/themes")).unwrap();
+        std::fs::write(home.join(".config/zed/settings.json"), "{}").unwrap();
+        std::fs::write(home.join(".config/zed/themes/dark.json"), "{}").unwrap();
+        std::fs::write(home.join(".vimrc"), "set nu").unwrap();
+        std::fs::create_dir_all(home.join(".ca-switch")).unwrap();
+        std::fs::write(
+            home.join(".ca-switch").join(BACKUP_CATEGORIES_FILE),
+            r#"{"categories": {
+                "editors": {"name": "编辑器配置", "paths": ["~/.config/zed", ".vimrc"]},
+                "Codex": {"paths": ["~/.codexrc"]},
+                "escape": {"paths": ["../etc/passwd"]},
+                "bad name": {"paths": [".bashrc"]}
+            }}"#,
+        )
+        .unwrap();
+
+        let manager = FileManager::with_home_dir(home.clone());
+        assert_eq!(manager.get_categories().last().map(String::as_str), Some("editors"));
+        assert_eq!(manager.category_name("editors"), "编辑器配置");
+        assert_eq!(manager.category_warnings().len(), 3);
+        assert!(manager.category_warnings()[0].contains("内置类别"));
+
+        let data = manager.collect_backup_data("editors").await.unwrap();
+        let mut names: Vec<&str> = data.files.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, vec![".vimrc", "zed/settings.json", "zed/themes/dark.json"]);
+
+        let _ = std::fs::remove_dir_all(&home);
     }
 }
@@ -1,22 +1,24 @@
 // Gemini 配置管理器
 // 负责管理 ~/.ca-switch/gemini.json 和同步到 ~/.gemini/
 
-use crate::config::models::{GeminiActiveConfig, GeminiConfig, GeminiSite};
+use crate::error::{CliError, Result};
+use crate::logging::log_write;
+use crate::config::migration::read_migrated;
+use crate::config::models::{page_sites, sites_with_base_url, sort_sites, AuthHeaderStyle, GeminiActiveConfig, GeminiConfig, GeminiGenerationConfig, GeminiSite};
 use serde_json;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Gemini 配置管理器
 pub struct GeminiConfigManager {
     gemini_config_file: PathBuf,  // ~/.ca-switch/gemini.json
-    gemini_dir: PathBuf,           // ~/.gemini
-    gemini_env_file: PathBuf,      // ~/.gemini/.env
+    gemini_dir: PathBuf,           // ~/.gemini (.env)
 }
 
 impl GeminiConfigManager {
     /// 创建新的 Gemini 配置管理器
-    pub fn new(config_dir: PathBuf) -> Result<Self, String> {
+    pub fn new(config_dir: PathBuf) -> Result<Self> {
         // 确保 ~/.ca-switch 目录存在
         if !config_dir.exists() {
             fs::create_dir_all(&config_dir)
@@ -26,16 +28,11 @@ impl GeminiConfigManager {
         let gemini_config_file = config_dir.join("gemini.json");
 
         // Gemini 官方配置目录
-        let gemini_dir = dirs::home_dir()
-            .ok_or("无法获取用户主目录")?
-            .join(".gemini");
-
-        let gemini_env_file = gemini_dir.join(".env");
+        let gemini_dir = super::home_dir()?.join(".gemini");
 
         Ok(Self {
             gemini_config_file,
             gemini_dir,
-            gemini_env_file,
         })
     }
 
@@ -44,25 +41,22 @@ impl GeminiConfigManager {
     // ========================================================================
 
     /// 读取 gemini.json 配置
-    pub fn read_config(&self) -> Result<GeminiConfig, String> {
-        if !self.gemini_config_file.exists() {
-            return Ok(GeminiConfig::new());
-        }
-
-        let content = fs::read_to_string(&self.gemini_config_file)
-            .map_err(|e| format!("读取 gemini.json 失败: {}", e))?;
-
-        serde_json::from_str(&content)
-            .map_err(|e| format!("解析 gemini.json 失败: {}", e))
+    pub fn read_config(&self) -> Result<GeminiConfig> {
+        let config = read_migrated(&self.gemini_config_file, "gemini.json", |config| self.write_config(config))?;
+        // 文件不存在时返回空配置
+        Ok(config.unwrap_or_else(GeminiConfig::new))
     }
 
     /// 写入 gemini.json 配置
-    pub fn write_config(&self, config: &GeminiConfig) -> Result<(), String> {
+    pub fn write_config(&self, config: &GeminiConfig) -> Result<()> {
         let content = serde_json::to_string_pretty(config)
             .map_err(|e| format!("序列化 gemini.json 失败: {}", e))?;
 
+        log_write(&self.gemini_config_file);
         fs::write(&self.gemini_config_file, content)
-            .map_err(|e| format!("写入 gemini.json 失败: {}", e))
+            .map_err(|e| format!("写入 gemini.json 失败: {}", e))?;
+
+        Ok(())
     }
 
     // ========================================================================
@@ -70,29 +64,44 @@ impl GeminiConfigManager {
     // ========================================================================
 
     /// 获取站点
-    pub fn get_site(&self, site_name: &str) -> Result<Option<GeminiSite>, String> {
+    pub fn get_site(&self, site_name: &str) -> Result<Option<GeminiSite>> {
         let config = self.read_config()?;
         Ok(config.get_site(site_name).cloned())
     }
 
     /// 获取所有站点
-    pub fn get_all_sites(&self) -> Result<HashMap<String, GeminiSite>, String> {
+    pub fn get_all_sites(&self) -> Result<HashMap<String, GeminiSite>> {
         let config = self.read_config()?;
         Ok(config.sites.clone())
     }
 
+    /// Base URL 与 `base_url` 相同的其他站点 (忽略末尾的 `/` 与 `/v1`)，`exclude` 为正在编辑的站点
+    pub fn find_sites_with_base_url(&self, base_url: &str, exclude: Option<&str>) -> Result<Vec<String>> {
+        Ok(sites_with_base_url(&self.get_all_sites()?, base_url, exclude))
+    }
+
+    /// 获取按名称排序的所有站点
+    pub fn get_sites_sorted(&self) -> Result<Vec<(String, GeminiSite)>> {
+        Ok(sort_sites(self.get_all_sites()?))
+    }
+
+    /// 获取按名称排序后的一页站点，`limit` 为 None 时取 `offset` 之后的全部
+    pub fn get_sites_page(&self, offset: usize, limit: Option<usize>) -> Result<Vec<(String, GeminiSite)>> {
+        Ok(page_sites(self.get_sites_sorted()?, offset, limit))
+    }
+
     /// 添加站点
     pub fn add_site(
         &mut self,
         site_name: String,
         url: String,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         // 检查站点是否已存在
         if config.get_site(&site_name).is_some() {
-            return Err(format!("站点 '{}' 已存在", site_name));
+            return Err(format!("站点 '{}' 已存在", site_name).into());
         }
 
         let site = GeminiSite::new(url, description);
@@ -107,12 +116,12 @@ impl GeminiConfigManager {
         site_name: &str,
         url: Option<String>,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if let Some(url) = url {
             site.metadata.url = url;
@@ -127,28 +136,73 @@ impl GeminiConfigManager {
     }
 
     /// 删除站点
-    pub fn delete_site(&mut self, site_name: &str) -> Result<(), String> {
+    pub fn delete_site(&mut self, site_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         if config.remove_site(site_name).is_none() {
-            return Err(format!("站点 '{}' 不存在", site_name));
+            return Err(CliError::NotFound(format!("站点 '{}' 不存在", site_name)));
         }
 
         self.write_config(&config)
     }
 
+    /// 获取默认模型（未设置时返回内置默认值）
+    pub fn get_default_model(&self) -> Result<String> {
+        let config = self.read_config()?;
+        Ok(config.effective_default_model().to_string())
+    }
+
+    /// 设置默认模型（None 表示恢复内置默认值）
+    pub fn set_default_model(&mut self, model: Option<String>) -> Result<()> {
+        let mut config = self.read_config()?;
+        config.default_model = model;
+        self.write_config(&config)
+    }
+
+    /// 设置站点标签 (为空时清除)
+    pub fn set_site_tags(&self, site_name: &str, tags: Vec<String>) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.metadata.tags = tags;
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
+    /// 设置站点的认证头格式
+    pub fn set_auth_header_style(
+        &mut self,
+        site_name: &str,
+        auth_header_style: AuthHeaderStyle,
+    ) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.config.auth_header_style = Some(auth_header_style);
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
     /// 更新站点配置
     pub fn update_site_config(
         &mut self,
         site_name: &str,
         base_url: Option<String>,
         model: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         // 更新配置
         if let Some(base_url) = base_url {
@@ -163,6 +217,26 @@ impl GeminiConfigManager {
         self.write_config(&config)
     }
 
+    /// 更新站点的生成参数与安全阈值（`generation` 为 None 或空时清除）
+    pub fn update_site_generation(
+        &mut self,
+        site_name: &str,
+        generation: Option<GeminiGenerationConfig>,
+        safety: HashMap<String, String>,
+    ) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.config.generation = generation.filter(|g| !g.is_empty());
+        site.config.safety = safety;
+        site.update_timestamp();
+
+        self.write_config(&config)
+    }
+
     // ========================================================================
     // API Key 管理
     // ========================================================================
@@ -173,19 +247,19 @@ impl GeminiConfigManager {
         site_name: &str,
         key_name: String,
         api_key: String,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         // 检查 key 是否已存在
         if site.get_api_key(&key_name).is_some() {
             return Err(format!(
                 "API Key '{}' 已存在于站点 '{}'",
                 key_name, site_name
-            ));
+            ).into());
         }
 
         site.add_api_key(key_name, api_key);
@@ -199,18 +273,18 @@ impl GeminiConfigManager {
         site_name: &str,
         key_name: &str,
         new_api_key: String,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if site.get_api_key(key_name).is_none() {
-            return Err(format!(
+            return Err(CliError::NotFound(format!(
                 "API Key '{}' 不存在于站点 '{}'",
                 key_name, site_name
-            ));
+            )));
         }
 
         site.add_api_key(key_name.to_string(), new_api_key);
@@ -219,63 +293,268 @@ impl GeminiConfigManager {
     }
 
     /// 删除 API Key
-    pub fn delete_api_key(&mut self, site_name: &str, key_name: &str) -> Result<(), String> {
+    pub fn delete_api_key(&mut self, site_name: &str, key_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         let site = config
             .get_site_mut(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
 
         if site.remove_api_key(key_name).is_none() {
-            return Err(format!(
+            return Err(CliError::NotFound(format!(
                 "API Key '{}' 不存在于站点 '{}'",
                 key_name, site_name
-            ));
+            )));
         }
 
         self.write_config(&config)
     }
 
+    /// 记录一次 API Key 激活（使用次数与最近使用时间）
+    pub fn record_usage(&self, site_name: &str, key_name: &str) -> Result<()> {
+        let mut config = self.read_config()?;
+
+        let site = config
+            .get_site_mut(site_name)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        site.record_usage(key_name);
+        self.write_config(&config)
+    }
+
     // ========================================================================
     // 配置同步到 ~/.gemini/
     // ========================================================================
 
     /// 同步配置到 Gemini 官方配置文件
-    pub fn sync_to_gemini(&self, active_config: &GeminiActiveConfig) -> Result<(), String> {
-        // 确保 ~/.gemini 目录存在
-        if !self.gemini_dir.exists() {
-            fs::create_dir_all(&self.gemini_dir)
+    pub fn sync_to_gemini(&self, active_config: &GeminiActiveConfig) -> Result<Vec<PathBuf>> {
+        self.sync_to_dir(&self.gemini_dir, active_config)
+    }
+
+    /// 同步配置到指定的 .gemini 目录（如项目级 ./.gemini/.env）
+    pub fn sync_to_dir(&self, gemini_dir: &Path, active_config: &GeminiActiveConfig) -> Result<Vec<PathBuf>> {
+        // 确保 .gemini 目录存在
+        if !gemini_dir.exists() {
+            fs::create_dir_all(gemini_dir)
                 .map_err(|e| format!("创建 .gemini 目录失败: {}", e))?;
         }
 
         // 同步到 .env
-        self.sync_to_env(active_config)?;
+        let gemini_env_file = gemini_dir.join(".env");
+        log_write(&gemini_env_file);
+        fs::write(&gemini_env_file, self.render_env(active_config))
+            .map_err(|e| format!("写入 .env 失败: {}", e))?;
+        let mut written = vec![gemini_env_file];
+
+        // settings.json 只更新生成参数与安全阈值，保留用户其他配置
+        let settings_file = gemini_dir.join("settings.json");
+        if let Some(content) = self.render_settings(&settings_file, active_config)? {
+            log_write(&settings_file);
+            fs::write(&settings_file, content)
+                .map_err(|e| format!("写入 settings.json 失败: {}", e))?;
+            written.push(settings_file);
+        }
+
+        Ok(written)
+    }
 
-        // settings.json 保留用户原有配置，这里不做修改
+    /// 渲染将要写入的文件内容（不写入磁盘，用于 dry-run）
+    pub fn render_files(&self, active_config: &GeminiActiveConfig) -> Result<Vec<(PathBuf, String)>> {
+        self.render_files_in(&self.gemini_dir, active_config)
+    }
 
-        Ok(())
+    /// 渲染将要写入指定 .gemini 目录的文件内容
+    pub fn render_files_in(&self, gemini_dir: &Path, active_config: &GeminiActiveConfig) -> Result<Vec<(PathBuf, String)>> {
+        let mut files = vec![(gemini_dir.join(".env"), self.render_env(active_config))];
+
+        let settings_file = gemini_dir.join("settings.json");
+        if let Some(content) = self.render_settings(&settings_file, active_config)? {
+            files.push((settings_file, content));
+        }
+
+        Ok(files)
     }
 
-    /// 同步到 .env 文件
-    fn sync_to_env(&self, active_config: &GeminiActiveConfig) -> Result<(), String> {
-        let mut lines = Vec::new();
+    /// 渲染与现有 settings.json 合并后的内容，无需改动时返回 None
+    ///
+    /// generationConfig / safetySettings 由站点配置管理，切换到未设置的站点时会被移除，避免残留
+    fn render_settings(&self, settings_file: &Path, active_config: &GeminiActiveConfig) -> Result<Option<String>> {
+        // 读取现有 settings.json（如果存在）
+        let original = if settings_file.exists() {
+            let content = fs::read_to_string(settings_file)
+                .map_err(|e| format!("读取 settings.json 失败: {}", e))?;
+
+            Some(serde_json::from_str::<serde_json::Value>(&content).unwrap_or_else(|_| serde_json::json!({})))
+        } else {
+            None
+        };
+
+        let mut settings = original.clone().unwrap_or_else(|| serde_json::json!({}));
+        let Some(obj) = settings.as_object_mut() else {
+            return Ok(None);
+        };
+
+        obj.remove("generationConfig");
+        obj.remove("safetySettings");
+
+        if let Some(generation) = active_config.generation.as_ref().filter(|g| !g.is_empty()) {
+            let mut config = serde_json::Map::new();
+            if let Some(temperature) = generation.temperature {
+                config.insert("temperature".to_string(), serde_json::json!(temperature));
+            }
+            if let Some(top_p) = generation.top_p {
+                config.insert("topP".to_string(), serde_json::json!(top_p));
+            }
+            if let Some(max_output_tokens) = generation.max_output_tokens {
+                config.insert("maxOutputTokens".to_string(), serde_json::json!(max_output_tokens));
+            }
+            obj.insert("generationConfig".to_string(), serde_json::Value::Object(config));
+        }
+
+        if !active_config.safety.is_empty() {
+            let mut categories: Vec<&String> = active_config.safety.keys().collect();
+            categories.sort();
+            let safety: Vec<serde_json::Value> = categories
+                .into_iter()
+                .map(|category| {
+                    serde_json::json!({
+                        "category": category,
+                        "threshold": active_config.safety[category],
+                    })
+                })
+                .collect();
+            obj.insert("safetySettings".to_string(), serde_json::Value::Array(safety));
+        }
+
+        // 内容未变化（或文件不存在且无需写入）时不修改 settings.json
+        let unchanged = match &original {
+            Some(original) => *original == settings,
+            None => obj.is_empty(),
+        };
+        if unchanged {
+            return Ok(None);
+        }
+
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("序列化 settings.json 失败: {}", e))?;
+
+        Ok(Some(content))
+    }
+
+    /// 渲染 .env 文件内容
+    fn render_env(&self, active_config: &GeminiActiveConfig) -> String {
+        let lines: Vec<String> = self
+            .env_vars(active_config)
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Gemini CLI 读取的环境变量
+    pub fn env_vars(&self, active_config: &GeminiActiveConfig) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
 
         // Base URL
         if let Some(ref base_url) = active_config.base_url {
-            lines.push(format!("GOOGLE_GEMINI_BASE_URL={}", base_url));
+            env.push(("GOOGLE_GEMINI_BASE_URL", base_url.clone()));
         }
 
         // API Key
-        lines.push(format!("GEMINI_API_KEY={}", active_config.api_key));
+        env.push(("GEMINI_API_KEY", active_config.api_key.clone()));
 
         // Model
         if let Some(ref model) = active_config.model {
-            lines.push(format!("GEMINI_MODEL={}", model));
+            env.push(("GEMINI_MODEL", model.clone()));
         }
 
-        let content = lines.join("\n") + "\n";
+        env
+    }
+
+    /// 获取配置文件路径
+    pub fn get_config_file_path(&self) -> &PathBuf {
+        &self.gemini_config_file
+    }
+
+    /// 切换时写入的 Gemini CLI 配置文件（用于导出，不含保存 API Key 的 .env）
+    pub fn live_config_files(&self) -> Vec<PathBuf> {
+        vec![self.gemini_dir.join("settings.json")]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::migration::CONFIG_VERSION;
+
+    #[test]
+    fn test_read_config_upgrades_old_version() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-migrate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let manager = GeminiConfigManager::new(config_dir.clone()).unwrap();
+
+        let config_file = config_dir.join("gemini.json");
+        fs::write(&config_file, r#"{"version": "2.0.0", "sites": {}}"#).unwrap();
+
+        let config = manager.read_config().unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+
+        // 升级后的版本号已写回文件
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config_file).unwrap()).unwrap();
+        assert_eq!(saved["version"], CONFIG_VERSION);
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
 
-        fs::write(&self.gemini_env_file, content)
-            .map_err(|e| format!("写入 .env 失败: {}", e))
+    #[test]
+    fn test_sync_merges_generation_into_settings() {
+        use crate::config::models::GeminiActiveReference;
+
+        let home_dir = std::env::temp_dir().join(format!("ca-switch-gemini-settings-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home_dir);
+        let mut manager = GeminiConfigManager::new(home_dir.join(".ca-switch")).unwrap();
+        manager.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        manager.add_api_key("a", "k1".to_string(), "key-1".to_string()).unwrap();
+
+        let reference = GeminiActiveReference {
+            site: "a".to_string(),
+            api_key_name: "k1".to_string(),
+        };
+        let active = |manager: &GeminiConfigManager| {
+            GeminiActiveConfig::from_reference(&reference, &manager.read_config().unwrap()).unwrap()
+        };
+
+        // 未设置生成参数时不创建 settings.json
+        let gemini_dir = home_dir.join(".gemini");
+        assert_eq!(manager.sync_to_dir(&gemini_dir, &active(&manager)).unwrap().len(), 1);
+        assert!(!gemini_dir.join("settings.json").exists());
+
+        let settings_file = gemini_dir.join("settings.json");
+        fs::write(&settings_file, r#"{"theme": "Dracula"}"#).unwrap();
+        let generation = GeminiGenerationConfig {
+            temperature: Some(0.5),
+            top_p: None,
+            max_output_tokens: Some(2048),
+        };
+        let safety = HashMap::from([("HARM_CATEGORY_HARASSMENT".to_string(), "BLOCK_NONE".to_string())]);
+        manager.update_site_generation("a", Some(generation), safety).unwrap();
+        manager.sync_to_dir(&gemini_dir, &active(&manager)).unwrap();
+
+        let settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&settings_file).unwrap()).unwrap();
+        assert_eq!(settings["theme"], "Dracula");
+        assert_eq!(settings["generationConfig"]["temperature"], 0.5);
+        assert_eq!(settings["generationConfig"]["maxOutputTokens"], 2048);
+        assert!(settings["generationConfig"].get("topP").is_none());
+        assert_eq!(settings["safetySettings"][0]["threshold"], "BLOCK_NONE");
+
+        // 清除后切换，移除残留的生成参数并保留其他配置
+        manager.update_site_generation("a", None, HashMap::new()).unwrap();
+        manager.sync_to_dir(&gemini_dir, &active(&manager)).unwrap();
+        let settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&settings_file).unwrap()).unwrap();
+        assert_eq!(settings, serde_json::json!({"theme": "Dracula"}));
+
+        let _ = fs::remove_dir_all(&home_dir);
     }
 }
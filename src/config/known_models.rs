@@ -0,0 +1,143 @@
+// 常见模型的默认限制
+// 添加模型时按模型 ID 匹配下表，预填 context/output 限制；仅作为默认值，用户可以修改
+//
+// 扩展时在 KNOWN_MODELS 中追加一行即可：`id` 为模型 ID 的前缀 (小写)，`name` 为显示名称，
+// 匹配时取最长的前缀，因此 `gpt-4o-mini` 应与 `gpt-4o` 分别列出
+
+use super::models::{OpenCodeModelInfo, OpenCodeModelLimit};
+
+/// 内置模型表中的一项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownModel {
+    /// 模型 ID 前缀 (小写)
+    pub id: &'static str,
+    /// 显示名称
+    pub name: &'static str,
+    /// 上下文窗口 (tokens)
+    pub context: u64,
+    /// 最大输出 (tokens)
+    pub output: u64,
+}
+
+impl KnownModel {
+    const fn new(id: &'static str, name: &'static str, context: u64, output: u64) -> Self {
+        Self {
+            id,
+            name,
+            context,
+            output,
+        }
+    }
+
+    /// 转换为 OpenCode 的 limit 配置
+    pub fn limit(&self) -> OpenCodeModelLimit {
+        OpenCodeModelLimit {
+            context: Some(self.context),
+            output: Some(self.output),
+        }
+    }
+}
+
+/// 常见模型的默认限制
+pub const KNOWN_MODELS: &[KnownModel] = &[
+    // OpenAI
+    KnownModel::new("gpt-4o", "GPT-4o", 128_000, 16_384),
+    KnownModel::new("gpt-4o-mini", "GPT-4o mini", 128_000, 16_384),
+    KnownModel::new("gpt-4.1", "GPT-4.1", 1_047_576, 32_768),
+    KnownModel::new("gpt-5", "GPT-5", 400_000, 128_000),
+    KnownModel::new("o3", "o3", 200_000, 100_000),
+    KnownModel::new("o4-mini", "o4-mini", 200_000, 100_000),
+    // Anthropic
+    KnownModel::new("claude-opus-4", "Claude Opus 4", 200_000, 32_000),
+    KnownModel::new("claude-sonnet-4", "Claude Sonnet 4", 200_000, 64_000),
+    KnownModel::new("claude-haiku-4", "Claude Haiku 4", 200_000, 64_000),
+    KnownModel::new("claude-3-7-sonnet", "Claude 3.7 Sonnet", 200_000, 64_000),
+    KnownModel::new("claude-3-5-sonnet", "Claude 3.5 Sonnet", 200_000, 8_192),
+    KnownModel::new("claude-3-5-haiku", "Claude 3.5 Haiku", 200_000, 8_192),
+    // Google
+    KnownModel::new("gemini-2.5-pro", "Gemini 2.5 Pro", 1_048_576, 65_536),
+    KnownModel::new("gemini-2.5-flash", "Gemini 2.5 Flash", 1_048_576, 65_536),
+    KnownModel::new("gemini-2.0-flash", "Gemini 2.0 Flash", 1_048_576, 8_192),
+    // DeepSeek
+    KnownModel::new("deepseek-chat", "DeepSeek V3", 128_000, 8_192),
+    KnownModel::new("deepseek-reasoner", "DeepSeek R1", 128_000, 64_000),
+    // Qwen
+    KnownModel::new("qwen3-coder-plus", "Qwen3 Coder Plus", 1_000_000, 65_536),
+];
+
+/// 按模型 ID 查找默认限制
+///
+/// 忽略大小写与 `provider/` 前缀 (如 `openai/gpt-4o`)；表中的 ID 需与模型 ID 完全相同，
+/// 或后面紧跟 `-`、`.`、`:`、`@` (如日期版本 `claude-sonnet-4-20250514`)，多项匹配时取最长的
+pub fn lookup(model_id: &str) -> Option<&'static KnownModel> {
+    let id = normalize(model_id);
+
+    KNOWN_MODELS
+        .iter()
+        .filter(|known| {
+            id.strip_prefix(known.id)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '.', ':', '@']))
+        })
+        .max_by_key(|known| known.id.len())
+}
+
+/// 归一化模型 ID: 小写并去掉 `provider/` 前缀
+fn normalize(model_id: &str) -> String {
+    let id = model_id.trim().to_lowercase();
+    id.rsplit('/').next().unwrap_or(&id).to_string()
+}
+
+/// 模型 ID 与表中某项完全相同时返回其显示名称 (带日期等后缀的 ID 不改名，以免丢失版本信息)
+pub fn display_name(model_id: &str) -> Option<&'static str> {
+    let id = normalize(model_id);
+    KNOWN_MODELS.iter().find(|known| known.id == id).map(|known| known.name)
+}
+
+/// 批量导入模型时使用的名称与限制
+///
+/// 名称见 [`display_name`]，无法确定时沿用模型 ID；站点 `/models` 返回的上下文长度优先于内置表，
+/// 输出限制只来自内置表。没有任何元数据时 limit 为 None
+pub fn imported_model_info(model_id: &str, detected_context: Option<u64>) -> OpenCodeModelInfo {
+    let known = lookup(model_id);
+    let context = detected_context.or(known.map(|k| k.context));
+    let output = known.map(|k| k.output);
+
+    OpenCodeModelInfo {
+        name: display_name(model_id).map_or_else(|| model_id.to_string(), str::to_string),
+        limit: (context.is_some() || output.is_some()).then_some(OpenCodeModelLimit { context, output }),
+        model_detection: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_prefers_longest_match() {
+        assert_eq!(lookup("gpt-4o").unwrap().id, "gpt-4o");
+        assert_eq!(lookup("GPT-4o-mini-2024-07-18").unwrap().id, "gpt-4o-mini");
+        assert_eq!(lookup("anthropic/claude-sonnet-4-20250514").unwrap().output, 64_000);
+        assert_eq!(lookup("gpt-4.1-mini").unwrap().id, "gpt-4.1");
+        assert!(lookup("o3").is_some());
+        assert!(lookup("o30").is_none());
+        assert!(lookup("my-custom-model").is_none());
+    }
+
+    #[test]
+    fn test_imported_model_info() {
+        let info = imported_model_info("openai/gpt-4o", None);
+        assert_eq!(info.name, "GPT-4o");
+        assert_eq!(info.limit.as_ref().and_then(|l| l.context), Some(128_000));
+
+        // 站点返回的上下文长度优先，带后缀的 ID 保留原名
+        let info = imported_model_info("claude-sonnet-4-20250514", Some(1_000_000));
+        assert_eq!(info.name, "claude-sonnet-4-20250514");
+        let limit = info.limit.unwrap();
+        assert_eq!((limit.context, limit.output), (Some(1_000_000), Some(64_000)));
+
+        let info = imported_model_info("my-custom-model", None);
+        assert_eq!(info.name, "my-custom-model");
+        assert!(info.limit.is_none());
+    }
+}
@@ -1,17 +1,26 @@
 // 核心配置管理器
 // 负责管理全局 config.json 和协调各供应商配置管理器
 
+use crate::error::{CliError, Result};
+use crate::logging::log_write;
 use crate::config::claude_manager::ClaudeConfigManager;
 use crate::config::codex_manager::CodexConfigManager;
 use crate::config::gemini_manager::GeminiConfigManager;
 use crate::config::opencode_manager::OpenCodeConfigManager;
+use crate::config::migration::read_migrated;
 use crate::config::models::{
-    ClaudeActiveConfig, ClaudeActiveReference, CodexActiveConfig, CodexActiveReference,
+    ActiveConfigs, ClaudeActiveConfig, ClaudeActiveReference, CodexActiveConfig, CodexActiveReference,
     GeminiActiveConfig, GeminiActiveReference, OpenCodeActiveConfig, OpenCodeActiveReference,
-    GlobalConfig,
+    GlobalConfig, KeyUsage, SiteExport, SiteExportPayload, SiteSummary,
 };
+use indexmap::IndexMap;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use crate::config::webdav::WEBDAV_CONFIG_FILE;
+use std::path::{Path, PathBuf};
+
+/// ~/.ca-switch 下存放 prompt 库的目录名
+pub const PROMPTS_DIR: &str = "prompts";
 
 /// 核心配置管理器
 pub struct ConfigManager {
@@ -24,16 +33,21 @@ pub struct ConfigManager {
 
 impl ConfigManager {
     /// 创建新的配置管理器
-    pub fn new() -> Result<Self, String> {
-        let home_dir = dirs::home_dir().ok_or("无法获取用户主目录")?;
-        let config_dir = home_dir.join(".ca-switch");
+    pub fn new() -> Result<Self> {
+        let home_dir = super::home_dir()?;
+        let config_dir = super::config_dir(&home_dir)?;
+        Self::with_dirs(home_dir, config_dir)
+    }
+
+    /// 以指定的主目录与数据目录创建配置管理器
+    pub fn with_dirs(home_dir: PathBuf, config_dir: PathBuf) -> Result<Self> {
         let global_config_file = config_dir.join("config.json");
 
         // 确保配置目录存在
         fs::create_dir_all(&config_dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
 
         // 初始化供应商配置管理器
-        let claude_manager = ClaudeConfigManager::new()?;
+        let claude_manager = ClaudeConfigManager::new(config_dir.clone(), &home_dir)?;
         let codex_manager = CodexConfigManager::new(config_dir.clone())?;
         let gemini_manager = GeminiConfigManager::new(config_dir.clone())?;
         let opencode_manager = OpenCodeConfigManager::new(config_dir.clone())?;
@@ -52,26 +66,41 @@ impl ConfigManager {
     // ========================================================================
 
     /// 读取全局配置
-    pub fn read_global_config(&self) -> Result<GlobalConfig, String> {
-        if !self.global_config_file.exists() {
-            // 如果文件不存在，返回新配置
-            return Ok(GlobalConfig::new());
-        }
-
-        let content = fs::read_to_string(&self.global_config_file)
-            .map_err(|e| format!("读取全局配置失败: {}", e))?;
-
-        serde_json::from_str(&content)
-            .map_err(|e| format!("解析全局配置失败: {}", e))
+    pub fn read_global_config(&self) -> Result<GlobalConfig> {
+        let config = read_migrated(&self.global_config_file, "config.json", |config| self.write_global_config(config))?;
+        // 文件不存在时返回空配置
+        Ok(config.unwrap_or_else(GlobalConfig::new))
     }
 
     /// 写入全局配置
-    pub fn write_global_config(&self, config: &GlobalConfig) -> Result<(), String> {
+    pub fn write_global_config(&self, config: &GlobalConfig) -> Result<()> {
         let content = serde_json::to_string_pretty(config)
             .map_err(|e| format!("序列化全局配置失败: {}", e))?;
 
+        log_write(&self.global_config_file);
         fs::write(&self.global_config_file, content)
-            .map_err(|e| format!("写入全局配置失败: {}", e))
+            .map_err(|e| format!("写入全局配置失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// ca-switch 管理的各个文件与目录的路径，按显示顺序排列
+    pub fn config_paths(&self) -> Vec<(&'static str, PathBuf)> {
+        let config_dir = self
+            .global_config_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        vec![
+            ("config.json", self.global_config_file.clone()),
+            ("claude.json", self.claude_manager.get_config_file_path().clone()),
+            ("codex.json", self.codex_manager.get_config_file_path().clone()),
+            ("gemini.json", self.gemini_manager.get_config_file_path().clone()),
+            ("opencode.json", self.opencode_manager.get_config_file_path().clone()),
+            ("prompts", config_dir.join(PROMPTS_DIR)),
+            ("webdav", config_dir.join(WEBDAV_CONFIG_FILE)),
+        ]
     }
 
     // ========================================================================
@@ -89,7 +118,7 @@ impl ConfigManager {
     }
 
     /// 获取当前激活的 Claude 配置（完整配置）
-    pub fn get_active_claude_config(&self) -> Result<Option<ClaudeActiveConfig>, String> {
+    pub fn get_active_claude_config(&self) -> Result<Option<ClaudeActiveConfig>> {
         // 1. 读取全局配置获取引用
         let global_config = self.read_global_config()?;
 
@@ -101,7 +130,7 @@ impl ConfigManager {
         // 2. 从 claude.json 获取完整站点配置
         let site = match self.claude_manager.get_site(&reference.site)? {
             Some(s) => s,
-            None => return Err(format!("站点 '{}' 不存在", reference.site)),
+            None => return Err(CliError::NotFound(format!("站点 '{}' 不存在", reference.site))),
         };
 
         // 3. 构建完整的激活配置
@@ -115,39 +144,98 @@ impl ConfigManager {
         &mut self,
         site_name: &str,
         token_name: &str,
-    ) -> Result<(), String> {
-        // 1. 验证站点和 token 是否存在
-        let site = self
-            .claude_manager
-            .get_site(site_name)?
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+    ) -> Result<Vec<PathBuf>> {
+        // 1. 更新全局配置中的引用
+        let active_config = self.set_active_claude(site_name, token_name)?;
 
-        if !site.tokens.contains_key(token_name) {
-            return Err(format!(
-                "Token '{}' 不存在于站点 '{}'",
-                token_name, site_name
-            ));
-        }
+        // 2. 记录使用次数与最近使用时间
+        self.claude_manager.record_usage(site_name, token_name)?;
 
-        // 2. 更新全局配置中的引用
-        let mut global_config = self.read_global_config()?;
+        // 3. 同步到 ~/.claude/settings.json
+        self.sync_claude(&active_config)
+    }
+
+    /// 当前激活的 Claude 是否为该站点的该 Token（只比较 config.json 中的引用）
+    pub fn is_active_claude_token(&self, site_name: &str, token_name: &str) -> Result<bool> {
+        let active = self.read_global_config()?.active.claude;
+        Ok(active.is_some_and(|r| r.site == site_name && r.token_name == token_name))
+    }
+
+    /// 仅更新 config.json 中的 Claude 激活引用，不写入 settings.json
+    pub fn set_active_claude(&mut self, site_name: &str, token_name: &str) -> Result<ClaudeActiveConfig> {
+        // 验证站点和 token 能否组成有效的激活配置
+        let active_config = self.resolve_claude_switch(site_name, token_name)?;
 
+        let mut global_config = self.read_global_config()?;
         global_config.active.claude = Some(ClaudeActiveReference {
             site: site_name.to_string(),
             token_name: token_name.to_string(),
         });
-
         global_config.update_timestamp();
         self.write_global_config(&global_config)?;
 
-        // 3. 同步到 ~/.claude/settings.json
-        let active_config = self
-            .get_active_claude_config()?
-            .ok_or("无法获取激活的 Claude 配置")?;
+        Ok(active_config)
+    }
 
-        self.claude_manager.sync_to_settings(&active_config)?;
+    /// 将激活配置写入 ~/.claude/settings.json
+    pub fn sync_claude(&self, active_config: &ClaudeActiveConfig) -> Result<Vec<PathBuf>> {
+        self.claude_manager.sync_to_settings(active_config)
+    }
 
-        Ok(())
+    /// 重新写入当前激活的 Claude 配置，`project` 为 true 时写入当前目录下的 .claude/
+    pub fn apply_claude(&self, project: bool) -> Result<Vec<PathBuf>> {
+        let active_config = self.require_active_claude()?;
+        if project {
+            self.claude_manager.sync_to_dir(&project_dir(".claude")?, &active_config)
+        } else {
+            self.sync_claude(&active_config)
+        }
+    }
+
+    /// 预览 [`Self::apply_claude`] 将写入的文件内容（不修改任何文件）
+    pub fn preview_claude_apply(&self, project: bool) -> Result<Vec<(PathBuf, String)>> {
+        let active_config = self.require_active_claude()?;
+        if project {
+            self.claude_manager.render_files_in(&project_dir(".claude")?, &active_config)
+        } else {
+            self.claude_manager.render_files(&active_config)
+        }
+    }
+
+    /// 当前激活配置对应的环境变量（用于 `claude env` 导出到 shell）
+    pub fn claude_env_vars(&self) -> Result<Vec<(&'static str, String)>> {
+        let active_config = self.require_active_claude()?;
+        Ok(self.claude_manager.env_vars(&active_config))
+    }
+
+    /// 当前激活的 Claude 配置，没有激活时返回 NotFound
+    pub fn require_active_claude(&self) -> Result<ClaudeActiveConfig> {
+        self.get_active_claude_config()?
+            .ok_or_else(|| CliError::NotFound("当前没有激活的 Claude 配置，请先切换".to_string()))
+    }
+
+    /// 按磁盘上的最新配置解析切换目标，站点或 Token 不存在时返回 NotFound
+    pub fn resolve_claude_switch(&self, site_name: &str, token_name: &str) -> Result<ClaudeActiveConfig> {
+        let site = self
+            .claude_manager
+            .get_site(site_name)?
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        let reference = ClaudeActiveReference {
+            site: site_name.to_string(),
+            token_name: token_name.to_string(),
+        };
+        ClaudeActiveConfig::from_reference(&reference, &site)
+    }
+
+    /// 预览切换 Claude 配置将写入的文件内容（不修改任何文件）
+    pub fn preview_claude_switch(
+        &self,
+        site_name: &str,
+        token_name: &str,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let active_config = self.resolve_claude_switch(site_name, token_name)?;
+        self.claude_manager.render_files(&active_config)
     }
 
     // ========================================================================
@@ -165,7 +253,7 @@ impl ConfigManager {
     }
 
     /// 获取当前激活的 Codex 配置
-    pub fn get_active_codex_config(&self) -> Result<Option<CodexActiveConfig>, String> {
+    pub fn get_active_codex_config(&self) -> Result<Option<CodexActiveConfig>> {
         let global_config = self.read_global_config()?;
 
         // 获取 Codex 引用
@@ -179,10 +267,10 @@ impl ConfigManager {
         let site = match site {
             Some(s) => s,
             None => {
-                return Err(format!(
+                return Err(CliError::NotFound(format!(
                     "引用的 Codex 站点 '{}' 不存在于 codex.json 中",
                     reference.site
-                ))
+                )))
             }
         };
 
@@ -195,39 +283,98 @@ impl ConfigManager {
         &mut self,
         site_name: &str,
         api_key_name: &str,
-    ) -> Result<(), String> {
-        // 1. 验证站点和 API Key 是否存在
-        let site = self
-            .codex_manager
-            .get_site(site_name)?
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+    ) -> Result<Vec<PathBuf>> {
+        // 1. 更新全局配置中的引用
+        let active_config = self.set_active_codex(site_name, api_key_name)?;
 
-        if site.get_api_key(api_key_name).is_none() {
-            return Err(format!(
-                "API Key '{}' 不存在于站点 '{}'",
-                api_key_name, site_name
-            ));
-        }
+        // 2. 记录使用次数与最近使用时间
+        self.codex_manager.record_usage(site_name, api_key_name)?;
 
-        // 2. 更新全局配置中的引用
-        let mut global_config = self.read_global_config()?;
+        // 3. 同步到 ~/.codex/
+        self.sync_codex(&active_config)
+    }
+
+    /// 当前激活的 Codex 是否为该站点的该 API Key（只比较 config.json 中的引用）
+    pub fn is_active_codex_key(&self, site_name: &str, api_key_name: &str) -> Result<bool> {
+        let active = self.read_global_config()?.active.codex;
+        Ok(active.is_some_and(|r| r.site == site_name && r.api_key_name == api_key_name))
+    }
+
+    /// 仅更新 config.json 中的 Codex 激活引用，不写入 ~/.codex/
+    pub fn set_active_codex(&mut self, site_name: &str, api_key_name: &str) -> Result<CodexActiveConfig> {
+        // 验证站点和 API Key 能否组成有效的激活配置
+        let active_config = self.resolve_codex_switch(site_name, api_key_name)?;
 
+        let mut global_config = self.read_global_config()?;
         global_config.active.codex = Some(CodexActiveReference {
             site: site_name.to_string(),
             api_key_name: api_key_name.to_string(),
         });
-
         global_config.update_timestamp();
         self.write_global_config(&global_config)?;
 
-        // 3. 同步到 ~/.codex/
-        let active_config = self
-            .get_active_codex_config()?
-            .ok_or("无法获取激活的 Codex 配置")?;
+        Ok(active_config)
+    }
 
-        self.codex_manager.sync_to_codex(&active_config)?;
+    /// 将激活配置写入 ~/.codex/
+    pub fn sync_codex(&self, active_config: &CodexActiveConfig) -> Result<Vec<PathBuf>> {
+        self.codex_manager.sync_to_codex(active_config)
+    }
 
-        Ok(())
+    /// 重新写入当前激活的 Codex 配置，`project` 为 true 时写入当前目录下的 .codex/
+    pub fn apply_codex(&self, project: bool) -> Result<Vec<PathBuf>> {
+        let active_config = self.require_active_codex()?;
+        if project {
+            self.codex_manager.sync_to_dir(&project_dir(".codex")?, &active_config)
+        } else {
+            self.sync_codex(&active_config)
+        }
+    }
+
+    /// 预览 [`Self::apply_codex`] 将写入的文件内容（不修改任何文件）
+    pub fn preview_codex_apply(&self, project: bool) -> Result<Vec<(PathBuf, String)>> {
+        let active_config = self.require_active_codex()?;
+        if project {
+            self.codex_manager.render_files_in(&project_dir(".codex")?, &active_config)
+        } else {
+            self.codex_manager.render_files(&active_config)
+        }
+    }
+
+    /// 当前激活配置对应的环境变量（用于 `codex env` 导出到 shell）
+    pub fn codex_env_vars(&self) -> Result<Vec<(&'static str, String)>> {
+        let active_config = self.require_active_codex()?;
+        Ok(self.codex_manager.env_vars(&active_config))
+    }
+
+    /// 当前激活的 Codex 配置，没有激活时返回 NotFound
+    pub fn require_active_codex(&self) -> Result<CodexActiveConfig> {
+        self.get_active_codex_config()?
+            .ok_or_else(|| CliError::NotFound("当前没有激活的 Codex 配置，请先切换".to_string()))
+    }
+
+    /// 按磁盘上的最新配置解析切换目标，站点或 API Key 不存在时返回 NotFound
+    pub fn resolve_codex_switch(&self, site_name: &str, api_key_name: &str) -> Result<CodexActiveConfig> {
+        let site = self
+            .codex_manager
+            .get_site(site_name)?
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", site_name)))?;
+
+        let reference = CodexActiveReference {
+            site: site_name.to_string(),
+            api_key_name: api_key_name.to_string(),
+        };
+        CodexActiveConfig::from_reference(&reference, &site)
+    }
+
+    /// 预览切换 Codex 配置将写入的文件内容（不修改任何文件）
+    pub fn preview_codex_switch(
+        &self,
+        site_name: &str,
+        api_key_name: &str,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let active_config = self.resolve_codex_switch(site_name, api_key_name)?;
+        self.codex_manager.render_files(&active_config)
     }
 
     // ========================================================================
@@ -245,18 +392,18 @@ impl ConfigManager {
     }
 
     /// 获取当前激活的 Gemini 配置
-    pub fn get_active_gemini_config(&self) -> Result<Option<GeminiActiveConfig>, String> {
+    pub fn get_active_gemini_config(&self) -> Result<Option<GeminiActiveConfig>> {
         let global_config = self.read_global_config()?;
 
         if let Some(ref reference) = global_config.active.gemini {
             // 从 gemini.json 读取站点配置
             let gemini_config = self.gemini_manager.read_config()?;
-            let site = gemini_config
-                .get_site(&reference.site)
-                .ok_or_else(|| format!("站点 '{}' 不存在于 gemini.json", reference.site))?;
+            if gemini_config.get_site(&reference.site).is_none() {
+                return Err(CliError::NotFound(format!("站点 '{}' 不存在于 gemini.json", reference.site)));
+            }
 
             // 构建完整配置
-            let active_config = GeminiActiveConfig::from_reference(reference, site)?;
+            let active_config = GeminiActiveConfig::from_reference(reference, &gemini_config)?;
             Ok(Some(active_config))
         } else {
             Ok(None)
@@ -268,37 +415,94 @@ impl ConfigManager {
         &mut self,
         site_name: &str,
         api_key_name: &str,
-    ) -> Result<(), String> {
-        // 验证站点和 API Key 是否存在
-        let gemini_config = self.gemini_manager.read_config()?;
-        let site = gemini_config
-            .get_site(site_name)
-            .ok_or_else(|| format!("站点 '{}' 不存在", site_name))?;
+    ) -> Result<Vec<PathBuf>> {
+        // 更新全局配置中的引用
+        let active_config = self.set_active_gemini(site_name, api_key_name)?;
 
-        if site.get_api_key(api_key_name).is_none() {
-            return Err(format!(
-                "API Key '{}' 不存在于站点 '{}'",
-                api_key_name, site_name
-            ));
-        }
+        // 记录使用次数与最近使用时间
+        self.gemini_manager.record_usage(site_name, api_key_name)?;
 
-        // 创建激活引用
-        let reference = GeminiActiveReference {
-            site: site_name.to_string(),
-            api_key_name: api_key_name.to_string(),
-        };
+        // 同步到 ~/.gemini/
+        self.sync_gemini(&active_config)
+    }
+
+    /// 当前激活的 Gemini 是否为该站点的该 API Key（只比较 config.json 中的引用）
+    pub fn is_active_gemini_key(&self, site_name: &str, api_key_name: &str) -> Result<bool> {
+        let active = self.read_global_config()?.active.gemini;
+        Ok(active.is_some_and(|r| r.site == site_name && r.api_key_name == api_key_name))
+    }
+
+    /// 仅更新 config.json 中的 Gemini 激活引用，不写入 ~/.gemini/
+    pub fn set_active_gemini(&mut self, site_name: &str, api_key_name: &str) -> Result<GeminiActiveConfig> {
+        // 验证站点和 API Key 能否组成有效的激活配置
+        let active_config = self.resolve_gemini_switch(site_name, api_key_name)?;
 
-        // 更新全局配置
         let mut global_config = self.read_global_config()?;
-        global_config.active.gemini = Some(reference.clone());
+        global_config.active.gemini = Some(GeminiActiveReference {
+            site: site_name.to_string(),
+            api_key_name: api_key_name.to_string(),
+        });
         global_config.update_timestamp();
         self.write_global_config(&global_config)?;
 
-        // 构建完整配置并同步到 ~/.gemini/
-        let active_config = GeminiActiveConfig::from_reference(&reference, site)?;
-        self.gemini_manager.sync_to_gemini(&active_config)?;
+        Ok(active_config)
+    }
+
+    /// 将激活配置写入 ~/.gemini/
+    pub fn sync_gemini(&self, active_config: &GeminiActiveConfig) -> Result<Vec<PathBuf>> {
+        self.gemini_manager.sync_to_gemini(active_config)
+    }
 
-        Ok(())
+    /// 重新写入当前激活的 Gemini 配置，`project` 为 true 时写入当前目录下的 .gemini/
+    pub fn apply_gemini(&self, project: bool) -> Result<Vec<PathBuf>> {
+        let active_config = self.require_active_gemini()?;
+        if project {
+            self.gemini_manager.sync_to_dir(&project_dir(".gemini")?, &active_config)
+        } else {
+            self.sync_gemini(&active_config)
+        }
+    }
+
+    /// 预览 [`Self::apply_gemini`] 将写入的文件内容（不修改任何文件）
+    pub fn preview_gemini_apply(&self, project: bool) -> Result<Vec<(PathBuf, String)>> {
+        let active_config = self.require_active_gemini()?;
+        if project {
+            self.gemini_manager.render_files_in(&project_dir(".gemini")?, &active_config)
+        } else {
+            self.gemini_manager.render_files(&active_config)
+        }
+    }
+
+    /// 当前激活配置对应的环境变量（用于 `gemini env` 导出到 shell）
+    pub fn gemini_env_vars(&self) -> Result<Vec<(&'static str, String)>> {
+        let active_config = self.require_active_gemini()?;
+        Ok(self.gemini_manager.env_vars(&active_config))
+    }
+
+    /// 当前激活的 Gemini 配置，没有激活时返回 NotFound
+    pub fn require_active_gemini(&self) -> Result<GeminiActiveConfig> {
+        self.get_active_gemini_config()?
+            .ok_or_else(|| CliError::NotFound("当前没有激活的 Gemini 配置，请先切换".to_string()))
+    }
+
+    /// 按磁盘上的最新配置解析切换目标，站点或 API Key 不存在时返回 NotFound
+    pub fn resolve_gemini_switch(&self, site_name: &str, api_key_name: &str) -> Result<GeminiActiveConfig> {
+        let gemini_config = self.gemini_manager.read_config()?;
+        let reference = GeminiActiveReference {
+            site: site_name.to_string(),
+            api_key_name: api_key_name.to_string(),
+        };
+        GeminiActiveConfig::from_reference(&reference, &gemini_config)
+    }
+
+    /// 预览切换 Gemini 配置将写入的文件内容（不修改任何文件）
+    pub fn preview_gemini_switch(
+        &self,
+        site_name: &str,
+        api_key_name: &str,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let active_config = self.resolve_gemini_switch(site_name, api_key_name)?;
+        self.gemini_manager.render_files(&active_config)
     }
 
     // ========================================================================
@@ -316,7 +520,7 @@ impl ConfigManager {
     }
 
     /// 获取当前激活的 OpenCode 配置
-    pub fn get_active_opencode_config(&self) -> Result<Option<OpenCodeActiveConfig>, String> {
+    pub fn get_active_opencode_config(&self) -> Result<Option<OpenCodeActiveConfig>> {
         let global_config = self.read_global_config()?;
 
         if let Some(ref reference) = global_config.active.opencode {
@@ -331,14 +535,19 @@ impl ConfigManager {
         }
     }
 
+    /// 当前激活的 OpenCode 配置，没有激活时返回 NotFound
+    pub fn require_active_opencode(&self) -> Result<OpenCodeActiveConfig> {
+        self.get_active_opencode_config()?
+            .ok_or_else(|| CliError::NotFound("当前没有激活的 OpenCode 配置，请先应用".to_string()))
+    }
+
     /// 切换 OpenCode 配置(简化版:只需指定Provider)
-    #[allow(dead_code)]
-    pub fn switch_opencode_config(&mut self, provider: &str) -> Result<(), String> {
+    pub fn switch_opencode_config(&mut self, provider: &str) -> Result<Vec<PathBuf>> {
         // 1. 验证 Provider 是否存在
         let opencode_config = self.opencode_manager.read_config()?;
 
         if opencode_config.get_provider(provider).is_none() {
-            return Err(format!("Provider '{}' 不存在", provider));
+            return Err(CliError::NotFound(format!("Provider '{}' 不存在", provider)));
         }
 
         // 2. 创建激活引用
@@ -354,19 +563,17 @@ impl ConfigManager {
 
         // 4. 构建完整配置并同步到 ~/.opencode/
         let active_config = OpenCodeActiveConfig::from_reference(&reference, &opencode_config)?;
-        self.opencode_manager.sync_to_opencode(&active_config)?;
-
-        Ok(())
+        self.opencode_manager.sync_to_opencode(&active_config)
     }
 
     /// 应用 OpenCode 配置到项目级
     #[allow(dead_code)]
-    pub fn apply_opencode_to_project(&mut self, provider: &str) -> Result<(), String> {
+    pub fn apply_opencode_to_project(&mut self, provider: &str) -> Result<Vec<PathBuf>> {
         // 1. 验证 Provider 是否存在
         let opencode_config = self.opencode_manager.read_config()?;
 
         if opencode_config.get_provider(provider).is_none() {
-            return Err(format!("Provider '{}' 不存在", provider));
+            return Err(CliError::NotFound(format!("Provider '{}' 不存在", provider)));
         }
 
         // 2. 创建激活引用
@@ -376,19 +583,17 @@ impl ConfigManager {
 
         // 3. 构建完整配置并同步到项目 .opencode/
         let active_config = OpenCodeActiveConfig::from_reference(&reference, &opencode_config)?;
-        self.opencode_manager.sync_to_project(&active_config)?;
-
-        Ok(())
+        self.opencode_manager.sync_to_project(&active_config)
     }
 
     /// 应用多个 OpenCode Provider 配置到全局
-    pub fn apply_multiple_opencode_to_global(&mut self, provider_names: &[String]) -> Result<(), String> {
+    pub fn apply_multiple_opencode_to_global(&mut self, provider_names: &[String]) -> Result<Vec<PathBuf>> {
         // 1. 验证所有 Provider 是否存在
         let opencode_config = self.opencode_manager.read_config()?;
 
         for provider_name in provider_names {
             if opencode_config.get_provider(provider_name).is_none() {
-                return Err(format!("Provider '{}' 不存在", provider_name));
+                return Err(CliError::NotFound(format!("Provider '{}' 不存在", provider_name)));
             }
         }
 
@@ -405,27 +610,405 @@ impl ConfigManager {
         }
 
         // 3. 同步所有Provider到 ~/.opencode/
-        self.opencode_manager.sync_multiple_providers_to_opencode(provider_names)?;
+        self.opencode_manager.sync_multiple_providers_to_opencode(provider_names)
+    }
 
-        Ok(())
+    /// 预览应用这些 Provider 后 ~/.opencode/opencode.json 的内容 (项目写入的内容仅 `$schema` 可能不同，不写入任何文件)
+    pub fn preview_opencode(&self, provider_names: &[String]) -> Result<serde_json::Value> {
+        let opencode_config = self.opencode_manager.read_config()?;
+
+        for provider_name in provider_names {
+            if opencode_config.get_provider(provider_name).is_none() {
+                return Err(CliError::NotFound(format!("Provider '{}' 不存在", provider_name)));
+            }
+        }
+
+        let mut data = self.opencode_manager.build_sync_data(provider_names)?;
+        OpenCodeConfigManager::keep_existing_schema(&mut data, self.opencode_manager.global_opencode_json());
+        Ok(data)
     }
 
     /// 应用多个 OpenCode Provider 配置到项目级
-    pub fn apply_multiple_opencode_to_project(&mut self, provider_names: &[String]) -> Result<(), String> {
+    pub fn apply_multiple_opencode_to_project(&mut self, provider_names: &[String]) -> Result<Vec<PathBuf>> {
         // 1. 验证所有 Provider 是否存在
         let opencode_config = self.opencode_manager.read_config()?;
 
         for provider_name in provider_names {
             if opencode_config.get_provider(provider_name).is_none() {
-                return Err(format!("Provider '{}' 不存在", provider_name));
+                return Err(CliError::NotFound(format!("Provider '{}' 不存在", provider_name)));
             }
         }
 
         // 2. 同步所有Provider到项目 .opencode/
-        self.opencode_manager.sync_multiple_providers_to_project(provider_names)?;
+        self.opencode_manager.sync_multiple_providers_to_project(provider_names)
+    }
+    // ========================================================================
+    // Profile（激活引用快照）
+    // ========================================================================
 
-        Ok(())
+    /// 所有已保存的 profile（按保存顺序）
+    pub fn profiles(&self) -> Result<IndexMap<String, ActiveConfigs>> {
+        Ok(self.read_global_config()?.profiles)
+    }
+
+    /// 将当前的激活引用保存为 profile（同名覆盖），返回是否覆盖了已有的 profile
+    pub fn save_profile(&self, name: &str) -> Result<bool> {
+        let mut global_config = self.read_global_config()?;
+        let snapshot = global_config.active.clone();
+        if snapshot.entries().is_empty() {
+            return Err(CliError::Config("当前没有任何激活配置，无法保存 profile".to_string()));
+        }
+
+        let replaced = global_config.profiles.insert(name.to_string(), snapshot).is_some();
+        global_config.update_timestamp();
+        self.write_global_config(&global_config)?;
+
+        Ok(replaced)
+    }
+
+    /// 删除 profile（不影响当前的激活配置）
+    pub fn delete_profile(&self, name: &str) -> Result<()> {
+        let mut global_config = self.read_global_config()?;
+        if global_config.profiles.shift_remove(name).is_none() {
+            return Err(CliError::NotFound(format!("Profile '{}' 不存在", name)));
+        }
+        global_config.update_timestamp();
+        self.write_global_config(&global_config)
+    }
+
+    /// 切换到 profile：先校验其中的全部引用，再依次切换并同步各工具的配置文件
+    ///
+    /// profile 中未记录的工具保持当前的激活配置不变
+    pub fn use_profile(&mut self, name: &str) -> Result<Vec<PathBuf>> {
+        let profile = self
+            .profiles()?
+            .shift_remove(name)
+            .ok_or_else(|| CliError::NotFound(format!("Profile '{}' 不存在", name)))?;
+
+        // 任一引用失效时不做任何修改
+        if let Some(ref claude) = profile.claude {
+            self.resolve_claude_switch(&claude.site, &claude.token_name)?;
+        }
+        if let Some(ref codex) = profile.codex {
+            self.resolve_codex_switch(&codex.site, &codex.api_key_name)?;
+        }
+        if let Some(ref gemini) = profile.gemini {
+            self.resolve_gemini_switch(&gemini.site, &gemini.api_key_name)?;
+        }
+        if let Some(ref opencode) = profile.opencode {
+            if self.opencode_manager.read_config()?.get_provider(&opencode.provider).is_none() {
+                return Err(CliError::NotFound(format!("Provider '{}' 不存在", opencode.provider)));
+            }
+        }
+
+        let mut written = Vec::new();
+        if let Some(ref claude) = profile.claude {
+            written.extend(self.switch_claude_config(&claude.site, &claude.token_name)?);
+        }
+        if let Some(ref codex) = profile.codex {
+            written.extend(self.switch_codex_config(&codex.site, &codex.api_key_name)?);
+        }
+        if let Some(ref gemini) = profile.gemini {
+            written.extend(self.switch_gemini_config(&gemini.site, &gemini.api_key_name)?);
+        }
+        if let Some(ref opencode) = profile.opencode {
+            written.extend(self.switch_opencode_config(&opencode.provider)?);
+        }
+
+        Ok(written)
+    }
+
+    // ========================================================================
+    // 按密钥名称跨工具切换
+    // ========================================================================
+
+    /// 在 Claude/Codex/Gemini 中查找拥有指定名称 Token/API Key 的站点，没有匹配的工具不在结果中
+    ///
+    /// 某个工具中没有该名称的密钥时，改为查找带有同名标签的站点；候选站点的选择见 [`pick_key_site`]
+    pub fn find_key_matches(&self, key_name: &str) -> Result<Vec<KeyMatch>> {
+        let active = self.read_global_config()?.active;
+        let mut matches = Vec::new();
+
+        let claude = self.claude_manager.read_config()?;
+        let active_claude = active.claude.as_ref().map(|r| (r.site.as_str(), r.token_name.as_str()));
+        if let Some(found) = match_key(&claude.sites, |site| (&site.tokens, &site.usage), key_name, active_claude) {
+            matches.push(found.with_tool("claude"));
+        }
+
+        let codex = self.codex_manager.read_config()?;
+        let active_codex = active.codex.as_ref().map(|r| (r.site.as_str(), r.api_key_name.as_str()));
+        if let Some(found) = match_key(&codex.sites, |site| (&site.api_keys, &site.usage), key_name, active_codex) {
+            matches.push(found.with_tool("codex"));
+        }
+
+        let gemini = self.gemini_manager.read_config()?;
+        let active_gemini = active.gemini.as_ref().map(|r| (r.site.as_str(), r.api_key_name.as_str()));
+        if let Some(found) = match_key(&gemini.sites, |site| (&site.api_keys, &site.usage), key_name, active_gemini) {
+            matches.push(found.with_tool("gemini"));
+        }
+
+        Ok(matches)
+    }
+
+    /// 切换到 [`Self::find_key_matches`] 找到的站点与密钥
+    pub fn switch_key_match(&mut self, key_match: &KeyMatch) -> Result<Vec<PathBuf>> {
+        match key_match.tool {
+            "claude" => self.switch_claude_config(&key_match.site, &key_match.key_name),
+            "codex" => self.switch_codex_config(&key_match.site, &key_match.key_name),
+            "gemini" => self.switch_gemini_config(&key_match.site, &key_match.key_name),
+            tool => Err(CliError::Config(format!("不支持按密钥名称切换 {}", tool))),
+        }
+    }
+
+    // ========================================================================
+    // 删除站点 / 密钥
+    // ========================================================================
+    // 删除的是当前激活的站点或密钥时同时清除 config.json 中的激活引用（不修改已同步的配置文件），
+    // 返回值表示是否清除了激活引用
+
+    /// 删除 Claude 站点
+    pub fn delete_claude_site(&mut self, site_name: &str) -> Result<bool> {
+        self.claude_manager.remove_site(site_name)?;
+        self.clear_active_if(|active| active.claude.take_if(|r| r.site == site_name).is_some())
+    }
+
+    /// 删除 Claude Token
+    pub fn delete_claude_token(&mut self, site_name: &str, token_name: &str) -> Result<bool> {
+        self.claude_manager.remove_token(site_name, token_name)?;
+        self.clear_active_if(|active| {
+            active
+                .claude
+                .take_if(|r| r.site == site_name && r.token_name == token_name)
+                .is_some()
+        })
+    }
+
+    /// 删除 Codex 站点
+    pub fn delete_codex_site(&mut self, site_name: &str) -> Result<bool> {
+        self.codex_manager.delete_site(site_name)?;
+        self.clear_active_if(|active| active.codex.take_if(|r| r.site == site_name).is_some())
+    }
+
+    /// 删除 Codex API Key
+    pub fn delete_codex_api_key(&mut self, site_name: &str, key_name: &str) -> Result<bool> {
+        self.codex_manager.delete_api_key(site_name, key_name)?;
+        self.clear_active_if(|active| {
+            active
+                .codex
+                .take_if(|r| r.site == site_name && r.api_key_name == key_name)
+                .is_some()
+        })
+    }
+
+    /// 删除 Gemini 站点
+    pub fn delete_gemini_site(&mut self, site_name: &str) -> Result<bool> {
+        self.gemini_manager.delete_site(site_name)?;
+        self.clear_active_if(|active| active.gemini.take_if(|r| r.site == site_name).is_some())
+    }
+
+    /// 删除 Gemini API Key
+    pub fn delete_gemini_api_key(&mut self, site_name: &str, key_name: &str) -> Result<bool> {
+        self.gemini_manager.delete_api_key(site_name, key_name)?;
+        self.clear_active_if(|active| {
+            active
+                .gemini
+                .take_if(|r| r.site == site_name && r.api_key_name == key_name)
+                .is_some()
+        })
+    }
+
+    /// 删除 OpenCode Provider
+    pub fn delete_opencode_provider(&mut self, provider_name: &str) -> Result<bool> {
+        self.opencode_manager.delete_provider(provider_name)?;
+        self.clear_active_if(|active| {
+            active
+                .opencode
+                .take_if(|r| r.provider == provider_name)
+                .is_some()
+        })
+    }
+
+    /// 按条件清除激活引用，有变化时写回 config.json
+    fn clear_active_if(&self, clear: impl FnOnce(&mut ActiveConfigs) -> bool) -> Result<bool> {
+        let mut global_config = self.read_global_config()?;
+        if !clear(&mut global_config.active) {
+            return Ok(false);
+        }
+
+        global_config.update_timestamp();
+        self.write_global_config(&global_config)?;
+        Ok(true)
+    }
+
+    // ========================================================================
+    // 站点导出
+    // ========================================================================
+
+    /// 指定工具切换后实际使用的配置文件 (如 ~/.claude/settings.json)，用于导出到当前目录
+    pub fn live_config_files(&self, provider: &str) -> Result<Vec<PathBuf>> {
+        match provider {
+            "claude" => Ok(self.claude_manager.live_config_files()),
+            "codex" => Ok(self.codex_manager.live_config_files()),
+            "gemini" => Ok(self.gemini_manager.live_config_files()),
+            "opencode" => Ok(self.opencode_manager.live_config_files()),
+            _ => Err(format!("不支持的供应商类型: {}", provider).into()),
+        }
+    }
+
+    /// 导出单个站点 / Provider
+    pub fn export_site(
+        &self,
+        provider: &str,
+        site_name: &str,
+        redact: bool,
+    ) -> Result<SiteExport> {
+        let not_found = || format!("站点 '{}' 不存在", site_name);
+
+        let payload = match provider {
+            "claude" => SiteExportPayload::Claude(
+                self.claude_manager.get_site(site_name)?.ok_or_else(not_found)?,
+            ),
+            "codex" => SiteExportPayload::Codex(
+                self.codex_manager.get_site(site_name)?.ok_or_else(not_found)?,
+            ),
+            "gemini" => SiteExportPayload::Gemini(
+                self.gemini_manager.get_site(site_name)?.ok_or_else(not_found)?,
+            ),
+            "opencode" => SiteExportPayload::OpenCode(
+                self.opencode_manager
+                    .get_provider(site_name)?
+                    .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", site_name)))?,
+            ),
+            _ => return Err(format!("不支持的供应商类型: {}", provider).into()),
+        };
+
+        Ok(SiteExport::new(site_name.to_string(), payload, redact))
+    }
+
+    /// 检查导入目标中是否已存在同名站点 / Provider
+    pub fn site_exists(&self, provider: &str, site_name: &str) -> Result<bool> {
+        match provider {
+            "claude" => Ok(self.claude_manager.get_site(site_name)?.is_some()),
+            "codex" => Ok(self.codex_manager.get_site(site_name)?.is_some()),
+            "gemini" => Ok(self.gemini_manager.get_site(site_name)?.is_some()),
+            "opencode" => Ok(self.opencode_manager.get_provider(site_name)?.is_some()),
+            _ => Err(format!("不支持的供应商类型: {}", provider).into()),
+        }
+    }
+
+    /// 导入站点 / Provider（同名时覆盖）
+    pub fn import_site(&mut self, export: &SiteExport, site_name: &str) -> Result<()> {
+        export.validate()?;
+
+        match &export.payload {
+            SiteExportPayload::Claude(site) => {
+                let mut config = self.claude_manager.read_config()?;
+                config.add_site(site_name.to_string(), site.clone());
+                self.claude_manager.write_config(&config)
+            }
+            SiteExportPayload::Codex(site) => {
+                let mut config = self.codex_manager.read_config()?;
+                config.add_site(site_name.to_string(), site.clone());
+                self.codex_manager.write_config(&config)
+            }
+            SiteExportPayload::Gemini(site) => {
+                let mut config = self.gemini_manager.read_config()?;
+                config.add_site(site_name.to_string(), site.clone());
+                self.gemini_manager.write_config(&config)
+            }
+            SiteExportPayload::OpenCode(provider) => {
+                let mut config = self.opencode_manager.read_config()?;
+                config.add_provider(site_name.to_string(), provider.clone());
+                self.opencode_manager.write_config(&config)
+            }
+        }
+    }
+}
+
+/// 按密钥名称在某个工具中找到的站点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMatch {
+    /// 工具 (claude/codex/gemini)
+    pub tool: &'static str,
+    /// 选中的站点
+    pub site: String,
+    /// 选中的 Token/API Key 名称
+    pub key_name: String,
+    /// 候选站点数
+    pub candidates: usize,
+    /// 是否通过站点标签匹配 (该工具中没有同名密钥)
+    pub by_tag: bool,
+}
+
+impl KeyMatch {
+    fn with_tool(mut self, tool: &'static str) -> Self {
+        self.tool = tool;
+        self
+    }
+}
+
+/// 在某个工具的站点中按密钥名称 (其次是同名标签) 查找要切换的站点与密钥
+///
+/// `keys` 取出站点的密钥与使用记录；`active` 为该工具当前激活的 (站点, 密钥)。
+/// 通过标签匹配时，当前激活的站点沿用当前密钥，其他站点使用最近使用过的密钥 (都未使用过时按名称取第一个)
+fn match_key<S: SiteSummary>(
+    sites: &HashMap<String, S>,
+    keys: impl Fn(&S) -> (&HashMap<String, String>, &HashMap<String, KeyUsage>),
+    key_name: &str,
+    active: Option<(&str, &str)>,
+) -> Option<KeyMatch> {
+    let active_site = active.map(|(site, _)| site);
+
+    let candidates: Vec<(String, Option<String>)> = sites
+        .iter()
+        .filter(|(_, site)| keys(site).0.contains_key(key_name))
+        .map(|(name, site)| (name.clone(), keys(site).1.get(key_name).and_then(|u| u.last_used.clone())))
+        .collect();
+    if let Some((site, candidates)) = pick_key_site(candidates, active_site) {
+        return Some(KeyMatch { tool: "", site, key_name: key_name.to_string(), candidates, by_tag: false });
+    }
+
+    // 没有同名密钥: 查找带有同名标签且至少有一个密钥的站点
+    let last_used = |site: &S| keys(site).1.values().filter_map(|u| u.last_used.clone()).max();
+    let candidates: Vec<(String, Option<String>)> = sites
+        .iter()
+        .filter(|(_, site)| site.has_tag(key_name) && !keys(site).0.is_empty())
+        .map(|(name, site)| (name.clone(), last_used(site)))
+        .collect();
+    let (site_name, candidates) = pick_key_site(candidates, active_site)?;
+
+    let (site_keys, usage) = keys(&sites[&site_name]);
+    let chosen = match active {
+        Some((site, key)) if site == site_name && site_keys.contains_key(key) => key.to_string(),
+        _ => {
+            let mut names: Vec<&String> = site_keys.keys().collect();
+            names.sort();
+            names.sort_by_key(|name| std::cmp::Reverse(usage.get(*name).and_then(|u| u.last_used.clone())));
+            names.first()?.to_string()
+        }
+    };
+
+    Some(KeyMatch { tool: "", site: site_name, key_name: chosen, candidates, by_tag: true })
+}
+
+/// 从拥有同名密钥的站点 (站点名, 该密钥最近使用时间) 中选择一个，返回 (站点, 候选数)
+///
+/// 当前激活的站点优先，其次是最近使用过该密钥的站点，都没有时按站点名排序取第一个
+fn pick_key_site(mut candidates: Vec<(String, Option<String>)>, active_site: Option<&str>) -> Option<(String, usize)> {
+    let count = candidates.len();
+    if let Some(active) = active_site.filter(|active| candidates.iter().any(|(name, _)| name == active)) {
+        return Some((active.to_string(), count));
     }
+
+    // RFC 3339 时间可以直接按字符串比较；未使用过的 (None) 排在最后
+    candidates.sort_by(|(a_name, a_used), (b_name, b_used)| b_used.cmp(a_used).then_with(|| a_name.cmp(b_name)));
+    candidates.into_iter().next().map(|(name, _)| (name, count))
+}
+
+/// 当前目录下的项目级工具配置目录（如 ./.claude）
+pub fn project_dir(name: &str) -> Result<PathBuf> {
+    let current_dir = std::env::current_dir().map_err(|e| format!("获取当前目录失败: {}", e))?;
+    Ok(current_dir.join(name))
 }
 
 #[cfg(test)]
@@ -449,4 +1032,298 @@ mod tests {
         let read_config = manager.read_global_config().unwrap();
         assert_eq!(read_config.version, "3.0.0");
     }
+
+    #[test]
+    fn test_pick_key_site() {
+        let used = |t: &str| Some(t.to_string());
+        let candidates = vec![
+            ("b".to_string(), None),
+            ("a".to_string(), None),
+            ("c".to_string(), used("2026-01-02T00:00:00Z")),
+            ("d".to_string(), used("2026-01-01T00:00:00Z")),
+        ];
+
+        assert_eq!(pick_key_site(candidates.clone(), Some("d")), Some(("d".to_string(), 4)));
+        assert_eq!(pick_key_site(candidates.clone(), Some("missing")), Some(("c".to_string(), 4)));
+        assert_eq!(pick_key_site(candidates[..2].to_vec(), None), Some(("a".to_string(), 2)));
+        assert_eq!(pick_key_site(Vec::new(), Some("a")), None);
+    }
+
+    #[test]
+    fn test_match_key_by_name_then_tag() {
+        use crate::config::ClaudeSite;
+
+        let site = |keys: &[&str], tags: &[&str]| {
+            let mut site = ClaudeSite::new("https://example.com".to_string(), None);
+            site.tokens = keys.iter().map(|k| (k.to_string(), "sk".to_string())).collect();
+            site.metadata.tags = tags.iter().map(|t| t.to_string()).collect();
+            site
+        };
+        let mut sites = HashMap::new();
+        sites.insert("a".to_string(), site(&["work", "home"], &[]));
+        sites.insert("fast-1".to_string(), site(&["x", "y"], &["fast"]));
+        sites.insert("fast-2".to_string(), site(&[], &["fast"]));
+        sites.get_mut("fast-1").unwrap().record_usage("y");
+        fn keys(site: &ClaudeSite) -> (&HashMap<String, String>, &HashMap<String, KeyUsage>) {
+            (&site.tokens, &site.usage)
+        }
+
+        let found = match_key(&sites, keys, "work", None).unwrap();
+        assert_eq!((found.site.as_str(), found.key_name.as_str(), found.by_tag), ("a", "work", false));
+
+        // 按标签匹配: 没有密钥的站点被排除，使用最近使用的密钥；当前激活的站点沿用当前密钥
+        let found = match_key(&sites, keys, "FAST", None).unwrap();
+        assert_eq!((found.site.as_str(), found.key_name.as_str(), found.by_tag), ("fast-1", "y", true));
+        let found = match_key(&sites, keys, "fast", Some(("fast-1", "x"))).unwrap();
+        assert_eq!(found.key_name, "x");
+
+        assert!(match_key(&sites, keys, "missing", None).is_none());
+    }
+
+    /// 在临时主目录中创建配置管理器
+    fn temp_manager(name: &str) -> (ConfigManager, PathBuf) {
+        let home_dir = std::env::temp_dir().join(format!("ca-switch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&home_dir);
+        (ConfigManager::with_dirs(home_dir.clone(), home_dir.join(".ca-switch")).unwrap(), home_dir)
+    }
+
+    /// 直接写入激活引用（不同步到工具配置文件）
+    fn set_active(manager: &ConfigManager, update: impl FnOnce(&mut ActiveConfigs)) {
+        let mut global_config = manager.read_global_config().unwrap();
+        update(&mut global_config.active);
+        manager.write_global_config(&global_config).unwrap();
+    }
+
+    #[test]
+    fn test_config_paths() {
+        let (manager, home_dir) = temp_manager("config-paths");
+        let config_dir = home_dir.join(".ca-switch");
+        let paths = manager.config_paths();
+
+        assert_eq!(paths.len(), 7);
+        assert!(paths.iter().all(|(_, path)| path.starts_with(&config_dir)));
+        assert_eq!(paths[5], ("prompts", config_dir.join("prompts")));
+        assert_eq!(paths[6], ("webdav", config_dir.join("webdav-config.json")));
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_is_active_key() {
+        let (manager, home_dir) = temp_manager("is-active-key");
+        set_active(&manager, |active| {
+            active.codex = Some(CodexActiveReference {
+                site: "a".to_string(),
+                api_key_name: "main".to_string(),
+            })
+        });
+
+        assert!(manager.is_active_codex_key("a", "main").unwrap());
+        assert!(!manager.is_active_codex_key("a", "backup").unwrap());
+        assert!(!manager.is_active_claude_token("a", "main").unwrap());
+        assert!(!manager.is_active_gemini_key("a", "main").unwrap());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_live_config_files() {
+        let (manager, home_dir) = temp_manager("live-files");
+
+        let codex = manager.live_config_files("codex").unwrap();
+        let names: Vec<_> = codex.iter().filter_map(|path| path.file_name()).collect();
+        assert_eq!(names, ["config.toml", "auth.json"]);
+        assert!(codex.iter().all(|path| path.parent().unwrap().ends_with(".codex")));
+        assert!(manager.live_config_files("claude").unwrap()[0].ends_with(".claude/settings.json"));
+        assert!(manager.live_config_files("unknown").is_err());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_config_dirs_are_isolated() {
+        let (manager, home_dir) = temp_manager("isolated");
+        let profile_dir = home_dir.join(".ca-switch").join("profiles").join("client-a");
+        let profile_manager = ConfigManager::with_dirs(home_dir.clone(), profile_dir.clone()).unwrap();
+
+        profile_manager
+            .claude()
+            .add_site("a".to_string(), "https://a.example.com".to_string(), None)
+            .unwrap();
+
+        assert!(profile_dir.join("claude.json").exists());
+        assert_eq!(profile_manager.claude().get_all_sites().unwrap().len(), 1);
+        assert!(manager.claude().get_all_sites().unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_delete_claude_clears_active() {
+        let (mut manager, home_dir) = temp_manager("delete-claude");
+        let claude = manager.claude();
+        claude.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        claude.add_token("a", "t1".to_string(), "sk-1".to_string()).unwrap();
+        claude.add_token("a", "t2".to_string(), "sk-2".to_string()).unwrap();
+        set_active(&manager, |active| {
+            active.claude = Some(ClaudeActiveReference {
+                site: "a".to_string(),
+                token_name: "t1".to_string(),
+            })
+        });
+
+        // 删除非激活的 Token 不影响引用
+        assert!(!manager.delete_claude_token("a", "t2").unwrap());
+        assert!(manager.read_global_config().unwrap().active.claude.is_some());
+
+        assert!(manager.delete_claude_token("a", "t1").unwrap());
+        assert!(manager.read_global_config().unwrap().active.claude.is_none());
+        assert!(manager.get_active_claude_config().unwrap().is_none());
+
+        set_active(&manager, |active| {
+            active.claude = Some(ClaudeActiveReference {
+                site: "a".to_string(),
+                token_name: "t1".to_string(),
+            })
+        });
+        assert!(manager.delete_claude_site("a").unwrap());
+        assert!(manager.read_global_config().unwrap().active.claude.is_none());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_set_active_claude_skips_sync() {
+        let (mut manager, home_dir) = temp_manager("record-only");
+        let claude = manager.claude();
+        claude.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        claude.add_token("a", "t1".to_string(), "sk-1".to_string()).unwrap();
+
+        assert!(manager.set_active_claude("a", "missing").is_err());
+
+        let active_config = manager.set_active_claude("a", "t1").unwrap();
+        assert_eq!(active_config.token_name, "t1");
+        assert_eq!(manager.get_active_claude_config().unwrap().unwrap().site, "a");
+        assert!(!home_dir.join(".claude").join("settings.json").exists());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_switch_claude_records_usage() {
+        let (mut manager, home_dir) = temp_manager("usage");
+        let claude = manager.claude();
+        claude.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        claude.add_token("a", "t1".to_string(), "sk-1".to_string()).unwrap();
+        claude.add_token("a", "t2".to_string(), "sk-2".to_string()).unwrap();
+
+        manager.switch_claude_config("a", "t1").unwrap();
+        manager.switch_claude_config("a", "t1").unwrap();
+        // 仅记录模式不计入使用次数
+        manager.set_active_claude("a", "t2").unwrap();
+
+        let site = manager.claude().get_site("a").unwrap().unwrap();
+        assert_eq!(site.usage["t1"].count, 2);
+        assert!(site.usage["t1"].last_used.is_some());
+        assert!(!site.usage.contains_key("t2"));
+
+        // 删除 Token 时一并清除使用记录
+        manager.delete_claude_token("a", "t1").unwrap();
+        assert!(manager.claude().get_site("a").unwrap().unwrap().usage.is_empty());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_profiles_save_use_delete() {
+        let (mut manager, home_dir) = temp_manager("profiles");
+        let claude = manager.claude();
+        claude.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        claude.add_token("a", "work".to_string(), "sk-1".to_string()).unwrap();
+        claude.add_token("a", "personal".to_string(), "sk-2".to_string()).unwrap();
+
+        // 没有激活配置时不能保存
+        assert!(manager.save_profile("empty").is_err());
+
+        manager.set_active_claude("a", "work").unwrap();
+        assert!(!manager.save_profile("work").unwrap());
+        manager.set_active_claude("a", "personal").unwrap();
+        assert!(!manager.save_profile("personal").unwrap());
+        assert!(manager.save_profile("personal").unwrap());
+
+        let written = manager.use_profile("work").unwrap();
+        assert_eq!(written, vec![home_dir.join(".claude").join("settings.json")]);
+        assert_eq!(manager.get_active_claude_config().unwrap().unwrap().token_name, "work");
+
+        // 引用失效时不做任何修改
+        manager.delete_claude_token("a", "personal").unwrap();
+        assert!(manager.use_profile("personal").is_err());
+        assert_eq!(manager.get_active_claude_config().unwrap().unwrap().token_name, "work");
+
+        manager.delete_profile("personal").unwrap();
+        assert!(manager.delete_profile("personal").is_err());
+        assert_eq!(manager.profiles().unwrap().keys().collect::<Vec<_>>(), vec!["work"]);
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_delete_codex_clears_active() {
+        let (mut manager, home_dir) = temp_manager("delete-codex");
+        let codex = manager.codex_mut();
+        codex.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        codex.add_site("b".to_string(), "https://b.example.com".to_string(), None).unwrap();
+        codex.add_api_key("a", "k1".to_string(), "sk-1".to_string()).unwrap();
+        set_active(&manager, |active| {
+            active.codex = Some(CodexActiveReference {
+                site: "a".to_string(),
+                api_key_name: "k1".to_string(),
+            })
+        });
+
+        assert!(!manager.delete_codex_site("b").unwrap());
+        assert!(manager.delete_codex_api_key("a", "k1").unwrap());
+        assert!(manager.read_global_config().unwrap().active.codex.is_none());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_delete_gemini_clears_active() {
+        let (mut manager, home_dir) = temp_manager("delete-gemini");
+        let gemini = manager.gemini_mut();
+        gemini.add_site("a".to_string(), "https://a.example.com".to_string(), None).unwrap();
+        gemini.add_api_key("a", "k1".to_string(), "key-1".to_string()).unwrap();
+        set_active(&manager, |active| {
+            active.gemini = Some(GeminiActiveReference {
+                site: "a".to_string(),
+                api_key_name: "k1".to_string(),
+            })
+        });
+
+        assert!(manager.delete_gemini_site("a").unwrap());
+        assert!(manager.read_global_config().unwrap().active.gemini.is_none());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn test_delete_opencode_provider_clears_active() {
+        let (mut manager, home_dir) = temp_manager("delete-opencode");
+        manager
+            .opencode_mut()
+            .add_provider("p".to_string(), "https://p.example.com/v1".to_string(), "sk-p".to_string(), None, None)
+            .unwrap();
+        set_active(&manager, |active| {
+            active.opencode = Some(OpenCodeActiveReference {
+                provider: "p".to_string(),
+            })
+        });
+
+        assert!(manager.delete_opencode_provider("p").unwrap());
+        assert!(manager.read_global_config().unwrap().active.opencode.is_none());
+        assert!(manager.delete_opencode_provider("p").is_err());
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
 }
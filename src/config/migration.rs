@@ -0,0 +1,162 @@
+// 配置文件版本迁移
+// 读取 claude.json / codex.json / gemini.json / opencode.json / config.json 时，
+// 按 version 字段依次应用升级迁移，再交给 serde 反序列化
+
+use crate::error::{CliError, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// 当前配置文件版本
+pub const CONFIG_VERSION: &str = "3.0.0";
+
+/// 缺少 version 字段的文件视为该版本
+const UNVERSIONED: &str = "2.0.0";
+
+/// 单步迁移: (起始主版本号, 迁移函数)，迁移后主版本号加一
+type Migration = (u64, fn(&mut Value));
+
+/// 迁移列表，按起始主版本号升序排列
+///
+/// 新增结构变更时在此追加一项，例如 `(3, migrate_3_to_4)`，并同步提升 [`CONFIG_VERSION`]
+const MIGRATIONS: &[Migration] = &[(2, migrate_2_to_3)];
+
+/// 2.x → 3.0: 结构未变化，仅更新版本号
+fn migrate_2_to_3(_value: &mut Value) {}
+
+/// 解析版本号中的主版本号
+fn major_version(version: &str) -> Option<u64> {
+    version.trim_start_matches('v').split('.').next()?.parse().ok()
+}
+
+/// 如有需要，将配置 JSON 升级到当前版本
+///
+/// 返回是否发生了迁移 (调用方应据此将升级后的配置写回文件)。
+/// 版本高于当前程序支持的版本时返回错误，避免旧程序覆盖新格式的文件
+pub fn migrate_if_needed(value: &mut Value) -> Result<bool> {
+    let Some(object) = value.as_object() else {
+        return Err(CliError::Parse("配置文件格式无效: 顶层不是 JSON 对象".to_string()));
+    };
+
+    let version = object
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or(UNVERSIONED)
+        .to_string();
+
+    let current = major_version(CONFIG_VERSION).unwrap_or_default();
+    let mut major = major_version(&version)
+        .ok_or_else(|| CliError::Parse(format!("无法识别的配置文件版本: {}", version)))?;
+
+    if major == current {
+        // 同一主版本内结构兼容，无需迁移
+        return Ok(false);
+    }
+    if major > current {
+        return Err(CliError::Parse(format!(
+            "配置文件版本 {} 高于当前支持的 {}，请升级 ca-switch",
+            version, CONFIG_VERSION
+        )));
+    }
+
+    for (from, migrate) in MIGRATIONS {
+        if major == *from {
+            migrate(value);
+            major += 1;
+        }
+    }
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), Value::String(CONFIG_VERSION.to_string()));
+    }
+
+    Ok(true)
+}
+
+/// 读取配置文件，按需迁移到当前版本后反序列化；文件不存在时返回 `None`
+///
+/// 发生迁移时通过 `write_back` 立即写回升级后的配置。`label` 为错误信息中的文件名 (如 `codex.json`)
+pub fn read_migrated<T, F>(path: &Path, label: &str, write_back: F) -> Result<Option<T>>
+where
+    T: DeserializeOwned,
+    F: FnOnce(&T) -> Result<()>,
+{
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", label, e))?;
+
+    let mut value: Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::Parse(format!("解析 {} 失败: {}", label, e)))?;
+    let migrated = migrate_if_needed(&mut value)?;
+
+    let config: T = serde_json::from_value(value)
+        .map_err(|e| CliError::Parse(format!("解析 {} 失败: {}", label, e)))?;
+
+    // 旧版本文件升级后写回
+    if migrated {
+        write_back(&config)?;
+    }
+
+    Ok(Some(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_older_versions() {
+        for old in [json!({"version": "2.1.0", "sites": {}}), json!({"sites": {}})] {
+            let mut value = old;
+            assert!(migrate_if_needed(&mut value).unwrap());
+            assert_eq!(value["version"], CONFIG_VERSION);
+            assert_eq!(value["sites"], json!({}));
+        }
+
+        // 已是当前版本时不做任何修改
+        let mut value = json!({"version": CONFIG_VERSION});
+        assert!(!migrate_if_needed(&mut value).unwrap());
+    }
+
+    #[test]
+    fn test_read_migrated_writes_back_once() {
+        let dir = std::env::temp_dir().join(format!("ca-switch-migration-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("codex.json");
+
+        let missing: Option<Value> = read_migrated(&path, "codex.json", |_| unreachable!()).unwrap();
+        assert!(missing.is_none());
+
+        fs::write(&path, r#"{"version": "2.0.0", "sites": {}}"#).unwrap();
+        let mut written = None;
+        let config: Value = read_migrated(&path, "codex.json", |config: &Value| {
+            written = Some(config.clone());
+            Ok(())
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(config["version"], CONFIG_VERSION);
+        assert_eq!(written, Some(config));
+
+        // 已是当前版本时不写回
+        fs::write(&path, format!(r#"{{"version": "{CONFIG_VERSION}"}}"#)).unwrap();
+        let _: Option<Value> = read_migrated(&path, "codex.json", |_| unreachable!()).unwrap();
+
+        fs::write(&path, "not json").unwrap();
+        let err = read_migrated::<Value, _>(&path, "codex.json", |_| unreachable!()).unwrap_err();
+        assert!(err.to_string().contains("codex.json"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_version() {
+        let mut value = json!({"version": "99.0.0"});
+        assert!(migrate_if_needed(&mut value).is_err());
+        assert_eq!(value["version"], "99.0.0");
+    }
+}
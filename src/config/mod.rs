@@ -2,6 +2,7 @@
 // 配置管理模块
 
 pub mod models;
+pub mod migration;
 pub mod claude_manager;
 pub mod codex_manager;
 pub mod gemini_manager;
@@ -10,8 +11,11 @@ pub mod manager;
 pub mod file_manager;
 pub mod webdav;
 pub mod detector;
+pub mod known_models;
+pub mod paths;
 
 // Re-export commonly used items
 pub use manager::*;
 pub use models::*;
 pub use detector::*;
+pub use paths::{config_dir, home_dir};
@@ -1,8 +1,12 @@
 // 配置数据结构模型
 // 统一使用 snake_case 命名风格
 
+use crate::error::{CliError, Result};
+use crate::config::migration::CONFIG_VERSION;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 // ============================================================================
 // 全局配置 (config.json)
@@ -14,9 +18,34 @@ pub struct GlobalConfig {
     pub version: String,
     pub active: ActiveConfigs,
     #[serde(default)]
+    pub settings: GlobalSettings,
+    /// 命名的激活引用快照 (profile 名称 -> 各工具的激活引用)
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub profiles: IndexMap<String, ActiveConfigs>,
+    #[serde(default)]
     pub metadata: ConfigMetadata,
 }
 
+/// 全局行为设置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GlobalSettings {
+    /// 切换时若会覆盖已有的工具配置文件，先显示 diff 再确认
+    #[serde(default)]
+    pub diff_before_overwrite: bool,
+
+    /// 切换成功后自动将对应类别备份到 WebDAV (需已配置 WebDAV)
+    #[serde(default)]
+    pub auto_backup_on_switch: bool,
+
+    /// 站点检测 (/v1/models) 超时秒数，未设置时为 10 秒
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detect_site_timeout_secs: Option<u64>,
+
+    /// 模型检测 (chat/completions) 超时秒数，未设置时为 60 秒
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detect_model_timeout_secs: Option<u64>,
+}
+
 /// 当前激活的配置引用
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ActiveConfigs {
@@ -33,6 +62,26 @@ pub struct ActiveConfigs {
     pub opencode: Option<OpenCodeActiveReference>,
 }
 
+impl ActiveConfigs {
+    /// 已设置的激活引用，按 (工具, 站点/密钥) 列出
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = Vec::new();
+        if let Some(ref claude) = self.claude {
+            entries.push(("claude", format!("{}/{}", claude.site, claude.token_name)));
+        }
+        if let Some(ref codex) = self.codex {
+            entries.push(("codex", format!("{}/{}", codex.site, codex.api_key_name)));
+        }
+        if let Some(ref gemini) = self.gemini {
+            entries.push(("gemini", format!("{}/{}", gemini.site, gemini.api_key_name)));
+        }
+        if let Some(ref opencode) = self.opencode {
+            entries.push(("opencode", opencode.provider.clone()));
+        }
+        entries
+    }
+}
+
 /// Claude 激活配置引用
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeActiveReference {
@@ -62,6 +111,141 @@ fn default_timestamp() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
+/// 站点摘要，用于站点列表的显示与关键字搜索
+pub trait SiteSummary {
+    fn url(&self) -> &str;
+    fn description(&self) -> Option<&str>;
+
+    /// 实际请求的 Base URL，未单独设置时为站点 URL
+    fn base_url(&self) -> &str {
+        self.url()
+    }
+
+    /// 站点标签，不支持标签的条目为空
+    fn tags(&self) -> &[String] {
+        &[]
+    }
+
+    /// 是否带有指定标签 (忽略大小写)
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags().iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+}
+
+impl<T: SiteSummary + ?Sized> SiteSummary for &T {
+    fn url(&self) -> &str {
+        (**self).url()
+    }
+
+    fn description(&self) -> Option<&str> {
+        (**self).description()
+    }
+
+    fn tags(&self) -> &[String] {
+        (**self).tags()
+    }
+
+    fn base_url(&self) -> &str {
+        (**self).base_url()
+    }
+}
+
+/// 解析逗号分隔的标签: 去除空白、忽略空项并按忽略大小写去重，保留首次出现的写法
+pub fn parse_site_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split([',', '，']).map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// 归一化 Base URL 以便比较: 忽略大小写、首尾空白以及末尾的 `/` 与 `/v1`
+pub fn normalize_base_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url.trim_end_matches('/');
+    url.strip_suffix("/v1").unwrap_or(url).trim_end_matches('/').to_string()
+}
+
+/// Base URL 归一化后与 `base_url` 相同的站点名称 (按名称排序)，`exclude` 为正在编辑的站点
+pub fn sites_with_base_url<S: SiteSummary>(sites: &HashMap<String, S>, base_url: &str, exclude: Option<&str>) -> Vec<String> {
+    let target = normalize_base_url(base_url);
+    if target.is_empty() {
+        return Vec::new();
+    }
+
+    let mut names: Vec<String> = sites
+        .iter()
+        .filter(|(name, site)| Some(name.as_str()) != exclude && normalize_base_url(site.base_url()) == target)
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// 按名称排序站点
+pub fn sort_sites<S>(sites: HashMap<String, S>) -> Vec<(String, S)> {
+    let mut sites: Vec<(String, S)> = sites.into_iter().collect();
+    sites.sort_by(|(a, _), (b, _)| a.cmp(b));
+    sites
+}
+
+/// 取已排序站点中的一页: 跳过前 `offset` 个，最多取 `limit` 个 (None 表示不限制)
+pub fn page_sites<S>(sites: Vec<(String, S)>, offset: usize, limit: Option<usize>) -> Vec<(String, S)> {
+    sites
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// API Key 的认证头格式
+///
+/// 站点未设置时按 provider 取默认值: Claude 为 `x-api-key`，Codex/Gemini 为 Bearer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthHeaderStyle {
+    /// `Authorization: Bearer <key>` (OpenAI 兼容接口)
+    #[default]
+    Bearer,
+    /// `x-api-key: <key>` (Anthropic 接口)
+    XApiKey,
+}
+
+impl AuthHeaderStyle {
+    /// 所有可选值
+    pub const ALL: [AuthHeaderStyle; 2] = [AuthHeaderStyle::Bearer, AuthHeaderStyle::XApiKey];
+}
+
+impl fmt::Display for AuthHeaderStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthHeaderStyle::Bearer => f.write_str("Authorization: Bearer"),
+            AuthHeaderStyle::XApiKey => f.write_str("x-api-key"),
+        }
+    }
+}
+
+/// 添加站点时可选的 Base URL 预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseUrlPreset {
+    pub name: &'static str,
+    pub url: &'static str,
+}
+
+impl BaseUrlPreset {
+    pub const fn new(name: &'static str, url: &'static str) -> Self {
+        Self { name, url }
+    }
+}
+
+impl fmt::Display for BaseUrlPreset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.url)
+    }
+}
+
 // ============================================================================
 // Claude 配置 (claude.json)
 // ============================================================================
@@ -79,6 +263,8 @@ pub struct ClaudeSite {
     pub metadata: SiteMetadata,
     pub tokens: HashMap<String, String>,
     pub config: ClaudeSiteConfig,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub usage: HashMap<String, KeyUsage>, // token_name -> 使用记录
 }
 
 /// 站点元数据
@@ -87,14 +273,49 @@ pub struct SiteMetadata {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// 标签 (如 fast、hk)，用于筛选与按标签切换
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     #[serde(default = "default_timestamp")]
     pub created_at: String,
     #[serde(default = "default_timestamp")]
     pub updated_at: String,
 }
 
+/// Token / API Key 的使用记录，每次切换时更新
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyUsage {
+    /// 累计激活次数
+    #[serde(default)]
+    pub count: u32,
+    /// 最近一次激活时间 (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+}
+
+impl KeyUsage {
+    /// 记录一次激活
+    pub fn record(&mut self) {
+        self.count = self.count.saturating_add(1);
+        self.last_used = Some(default_timestamp());
+    }
+
+    /// 选择列表中显示的简短说明，例如 "使用 3 次, 最近 2026-10-16"
+    pub fn summary(&self) -> String {
+        match &self.last_used {
+            Some(last_used) => {
+                let date = chrono::DateTime::parse_from_rfc3339(last_used)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|_| last_used.clone());
+                format!("使用 {} 次, 最近 {}", self.count, date)
+            }
+            None => format!("使用 {} 次", self.count),
+        }
+    }
+}
+
 /// Claude 站点配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClaudeSiteConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_url: Option<String>,
@@ -104,6 +325,13 @@ pub struct ClaudeSiteConfig {
 
     #[serde(default)]
     pub vertex: VertexConfig,
+
+    #[serde(default)]
+    pub bedrock: BedrockConfig,
+
+    /// 认证头格式，未设置时使用 provider 默认值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_header_style: Option<AuthHeaderStyle>,
 }
 
 /// Vertex AI 配置
@@ -122,6 +350,19 @@ pub struct VertexConfig {
     pub skip_auth: bool,
 }
 
+/// AWS Bedrock 配置（与 Vertex AI 互斥）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BedrockConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
 // ============================================================================
 // 运行时配置（从引用解析出的完整配置）
 // ============================================================================
@@ -138,6 +379,7 @@ pub struct ClaudeActiveConfig {
     pub base_url: Option<String>,
     pub model: Option<String>,
     pub vertex: VertexConfig,
+    pub bedrock: BedrockConfig,
 }
 
 // ============================================================================
@@ -148,8 +390,10 @@ impl GlobalConfig {
     /// 创建新的全局配置
     pub fn new() -> Self {
         Self {
-            version: "3.0.0".to_string(),
+            version: CONFIG_VERSION.to_string(),
             active: ActiveConfigs::default(),
+            settings: GlobalSettings::default(),
+            profiles: IndexMap::new(),
             metadata: ConfigMetadata::default(),
         }
     }
@@ -164,7 +408,7 @@ impl ClaudeConfig {
     /// 创建新的 Claude 配置
     pub fn new() -> Self {
         Self {
-            version: "3.0.0".to_string(),
+            version: CONFIG_VERSION.to_string(),
             sites: HashMap::new(),
         }
     }
@@ -190,6 +434,23 @@ impl ClaudeConfig {
     }
 }
 
+impl SiteSummary for ClaudeSite {
+    fn url(&self) -> &str {
+        &self.metadata.url
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.as_deref().unwrap_or(&self.metadata.url)
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.metadata.description.as_deref()
+    }
+    fn tags(&self) -> &[String] {
+        &self.metadata.tags
+    }
+}
+
 impl ClaudeSite {
     /// 创建新站点
     pub fn new(url: String, description: Option<String>) -> Self {
@@ -197,14 +458,34 @@ impl ClaudeSite {
             metadata: SiteMetadata {
                 url,
                 description,
+                tags: Vec::new(),
                 created_at: default_timestamp(),
                 updated_at: default_timestamp(),
             },
             tokens: HashMap::new(),
             config: ClaudeSiteConfig::default(),
+            usage: HashMap::new(),
         }
     }
 
+    /// 添加站点时可选的 Base URL 预设，第一项为官方地址
+    pub const BASE_URL_PRESETS: &'static [BaseUrlPreset] = &[
+        BaseUrlPreset::new("Anthropic 官方", "https://api.anthropic.com"),
+        BaseUrlPreset::new("OpenRouter", "https://openrouter.ai/api"),
+        BaseUrlPreset::new("DeepSeek", "https://api.deepseek.com/anthropic"),
+        BaseUrlPreset::new("Moonshot", "https://api.moonshot.cn/anthropic"),
+    ];
+
+    /// 未设置时使用的认证头格式
+    pub const DEFAULT_AUTH_HEADER_STYLE: AuthHeaderStyle = AuthHeaderStyle::XApiKey;
+
+    /// 站点实际使用的认证头格式
+    pub fn auth_header_style(&self) -> AuthHeaderStyle {
+        self.config
+            .auth_header_style
+            .unwrap_or(Self::DEFAULT_AUTH_HEADER_STYLE)
+    }
+
     /// 更新时间戳
     pub fn update_timestamp(&mut self) {
         self.metadata.updated_at = default_timestamp();
@@ -225,19 +506,15 @@ impl ClaudeSite {
     pub fn remove_token(&mut self, token_name: &str) -> Option<String> {
         let result = self.tokens.remove(token_name);
         if result.is_some() {
+            self.usage.remove(token_name);
             self.update_timestamp();
         }
         result
     }
-}
 
-impl Default for ClaudeSiteConfig {
-    fn default() -> Self {
-        Self {
-            base_url: None,
-            model: None,
-            vertex: VertexConfig::default(),
-        }
+    /// 记录一次 token 激活
+    pub fn record_usage(&mut self, token_name: &str) {
+        self.usage.entry(token_name.to_string()).or_default().record();
     }
 }
 
@@ -246,10 +523,12 @@ impl ClaudeActiveConfig {
     pub fn from_reference(
         reference: &ClaudeActiveReference,
         site: &ClaudeSite,
-    ) -> Result<Self, String> {
+    ) -> Result<Self> {
         let token = site
             .get_token(&reference.token_name)
-            .ok_or_else(|| format!("Token '{}' not found in site '{}'", reference.token_name, reference.site))?;
+            .ok_or_else(|| {
+                CliError::NotFound(format!("Token '{}' 不存在于站点 '{}'", reference.token_name, reference.site))
+            })?;
 
         Ok(Self {
             site: reference.site.clone(),
@@ -260,6 +539,7 @@ impl ClaudeActiveConfig {
             base_url: site.config.base_url.clone(),
             model: site.config.model.clone(),
             vertex: site.config.vertex.clone(),
+            bedrock: site.config.bedrock.clone(),
         })
     }
 }
@@ -281,6 +561,8 @@ pub struct CodexSite {
     pub metadata: SiteMetadata,
     pub api_keys: HashMap<String, String>, // key_name -> api_key
     pub config: CodexSiteConfig,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub usage: HashMap<String, KeyUsage>, // key_name -> 使用记录
 }
 
 /// Codex 站点级配置
@@ -308,8 +590,27 @@ pub struct CodexSiteConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wire_api: Option<String>, // "responses" 等
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_mode: Option<String>, // "read-only" / "workspace-write" / "danger-full-access"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<String>, // "untrusted" / "on-failure" / "on-request" / "never"
+
+    /// 认证头格式，未设置时使用 provider 默认值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_header_style: Option<AuthHeaderStyle>,
 }
 
+/// Codex sandbox_mode 可选值
+pub const CODEX_SANDBOX_MODES: &[&str] = &["read-only", "workspace-write", "danger-full-access"];
+
+/// Codex network_access 可选值
+pub const CODEX_NETWORK_ACCESS_OPTIONS: &[&str] = &["enabled", "disabled"];
+
+/// Codex approval_policy 可选值
+pub const CODEX_APPROVAL_POLICIES: &[&str] = &["untrusted", "on-failure", "on-request", "never"];
+
 /// Codex 激活配置引用（存储在 config.json）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodexActiveReference {
@@ -332,12 +633,15 @@ pub struct CodexActiveConfig {
     pub base_url: Option<String>,
     pub model: Option<String>,
     pub model_reasoning_effort: Option<String>, // 正确的字段名
-    pub model_provider: Option<String>, // 如果不填则默认继承站点名
+    /// 写入 config.toml 的 provider 名称，站点未设置（或为空）时为站点名
+    pub model_provider: String,
 
     // 额外配置
     pub network_access: Option<String>,
     pub disable_response_storage: Option<bool>,
     pub wire_api: Option<String>,
+    pub sandbox_mode: Option<String>,
+    pub approval_policy: Option<String>,
 }
 
 // ============================================================================
@@ -348,7 +652,7 @@ impl CodexConfig {
     /// 创建新的 Codex 配置
     pub fn new() -> Self {
         Self {
-            version: "3.0.0".to_string(),
+            version: CONFIG_VERSION.to_string(),
             sites: HashMap::new(),
         }
     }
@@ -374,6 +678,23 @@ impl CodexConfig {
     }
 }
 
+impl SiteSummary for CodexSite {
+    fn url(&self) -> &str {
+        &self.metadata.url
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.as_deref().unwrap_or(&self.metadata.url)
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.metadata.description.as_deref()
+    }
+    fn tags(&self) -> &[String] {
+        &self.metadata.tags
+    }
+}
+
 impl CodexSite {
     /// 创建新站点
     pub fn new(url: String, description: Option<String>) -> Self {
@@ -381,14 +702,33 @@ impl CodexSite {
             metadata: SiteMetadata {
                 url,
                 description,
+                tags: Vec::new(),
                 created_at: default_timestamp(),
                 updated_at: default_timestamp(),
             },
             api_keys: HashMap::new(),
             config: CodexSiteConfig::default(),
+            usage: HashMap::new(),
         }
     }
 
+    /// 添加站点时可选的 Base URL 预设，第一项为官方地址
+    pub const BASE_URL_PRESETS: &'static [BaseUrlPreset] = &[
+        BaseUrlPreset::new("OpenAI 官方", "https://api.openai.com/v1"),
+        BaseUrlPreset::new("OpenRouter", "https://openrouter.ai/api/v1"),
+        BaseUrlPreset::new("DeepSeek", "https://api.deepseek.com/v1"),
+    ];
+
+    /// 未设置时使用的认证头格式
+    pub const DEFAULT_AUTH_HEADER_STYLE: AuthHeaderStyle = AuthHeaderStyle::Bearer;
+
+    /// 站点实际使用的认证头格式
+    pub fn auth_header_style(&self) -> AuthHeaderStyle {
+        self.config
+            .auth_header_style
+            .unwrap_or(Self::DEFAULT_AUTH_HEADER_STYLE)
+    }
+
     /// 更新时间戳
     pub fn update_timestamp(&mut self) {
         self.metadata.updated_at = default_timestamp();
@@ -408,9 +748,15 @@ impl CodexSite {
     /// 删除 API key
     pub fn remove_api_key(&mut self, key_name: &str) -> Option<String> {
         let result = self.api_keys.remove(key_name);
+        self.usage.remove(key_name);
         self.update_timestamp();
         result
     }
+
+    /// 记录一次 API Key 激活
+    pub fn record_usage(&mut self, key_name: &str) {
+        self.usage.entry(key_name.to_string()).or_default().record();
+    }
 }
 
 impl CodexActiveConfig {
@@ -418,10 +764,12 @@ impl CodexActiveConfig {
     pub fn from_reference(
         reference: &CodexActiveReference,
         site: &CodexSite,
-    ) -> Result<Self, String> {
+    ) -> Result<Self> {
         let api_key = site
             .get_api_key(&reference.api_key_name)
-            .ok_or_else(|| format!("API Key '{}' not found in site '{}'", reference.api_key_name, reference.site))?;
+            .ok_or_else(|| {
+                CliError::NotFound(format!("API Key '{}' 不存在于站点 '{}'", reference.api_key_name, reference.site))
+            })?;
 
         Ok(Self {
             site: reference.site.clone(),
@@ -432,10 +780,17 @@ impl CodexActiveConfig {
             base_url: site.config.base_url.clone(),
             model: site.config.model.clone(),
             model_reasoning_effort: site.config.model_reasoning_effort.clone(),
-            model_provider: site.config.model_provider.clone(),
+            model_provider: site
+                .config
+                .model_provider
+                .clone()
+                .filter(|provider| !provider.trim().is_empty())
+                .unwrap_or_else(|| reference.site.clone()),
             network_access: site.config.network_access.clone(),
             disable_response_storage: site.config.disable_response_storage,
             wire_api: site.config.wire_api.clone(),
+            sandbox_mode: site.config.sandbox_mode.clone(),
+            approval_policy: site.config.approval_policy.clone(),
         })
     }
 }
@@ -448,15 +803,23 @@ impl CodexActiveConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeminiConfig {
     pub version: String,
+    /// 站点未指定模型时使用的默认模型
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
     pub sites: HashMap<String, GeminiSite>,
 }
 
+/// 未设置 default_model 时的内置默认模型
+pub const GEMINI_FALLBACK_MODEL: &str = "gemini-2.5-flash";
+
 /// Gemini 站点配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeminiSite {
     pub metadata: SiteMetadata,
     pub api_keys: HashMap<String, String>, // key_name -> api_key
     pub config: GeminiSiteConfig,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub usage: HashMap<String, KeyUsage>, // key_name -> 使用记录
 }
 
 /// Gemini 站点级配置
@@ -467,6 +830,56 @@ pub struct GeminiSiteConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+
+    /// 认证头格式，未设置时使用 provider 默认值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_header_style: Option<AuthHeaderStyle>,
+
+    /// 生成参数，写入 ~/.gemini/settings.json 的 generationConfig
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<GeminiGenerationConfig>,
+
+    /// 安全阈值（类别 -> 阈值），写入 ~/.gemini/settings.json 的 safetySettings
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub safety: HashMap<String, String>,
+}
+
+/// Gemini 生成参数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeminiGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+}
+
+impl GeminiSiteConfig {
+    /// 可在编辑菜单中设置阈值的安全类别
+    pub const SAFETY_CATEGORIES: &'static [&'static str] = &[
+        "HARM_CATEGORY_HARASSMENT",
+        "HARM_CATEGORY_HATE_SPEECH",
+        "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+        "HARM_CATEGORY_DANGEROUS_CONTENT",
+    ];
+
+    /// 可选的安全阈值，由宽到严
+    pub const SAFETY_THRESHOLDS: &'static [&'static str] = &[
+        "BLOCK_NONE",
+        "BLOCK_ONLY_HIGH",
+        "BLOCK_MEDIUM_AND_ABOVE",
+        "BLOCK_LOW_AND_ABOVE",
+    ];
+}
+
+impl GeminiGenerationConfig {
+    /// 是否未设置任何参数
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none() && self.top_p.is_none() && self.max_output_tokens.is_none()
+    }
 }
 
 /// Gemini 激活配置引用（存储在 config.json）
@@ -488,6 +901,10 @@ pub struct GeminiActiveConfig {
     pub api_key: String,
     pub base_url: Option<String>,
     pub model: Option<String>,
+    /// model 是否来自配置级默认模型（站点未指定）
+    pub model_is_default: bool,
+    pub generation: Option<GeminiGenerationConfig>,
+    pub safety: HashMap<String, String>,
 }
 
 // ============================================================================
@@ -498,11 +915,19 @@ impl GeminiConfig {
     /// 创建新的 Gemini 配置
     pub fn new() -> Self {
         Self {
-            version: "3.0.0".to_string(),
+            version: CONFIG_VERSION.to_string(),
+            default_model: None,
             sites: HashMap::new(),
         }
     }
 
+    /// 获取生效的默认模型
+    pub fn effective_default_model(&self) -> &str {
+        self.default_model
+            .as_deref()
+            .unwrap_or(GEMINI_FALLBACK_MODEL)
+    }
+
     /// 获取站点
     pub fn get_site(&self, site_name: &str) -> Option<&GeminiSite> {
         self.sites.get(site_name)
@@ -524,6 +949,23 @@ impl GeminiConfig {
     }
 }
 
+impl SiteSummary for GeminiSite {
+    fn url(&self) -> &str {
+        &self.metadata.url
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.as_deref().unwrap_or(&self.metadata.url)
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.metadata.description.as_deref()
+    }
+    fn tags(&self) -> &[String] {
+        &self.metadata.tags
+    }
+}
+
 impl GeminiSite {
     /// 创建新站点
     pub fn new(url: String, description: Option<String>) -> Self {
@@ -531,14 +973,31 @@ impl GeminiSite {
             metadata: SiteMetadata {
                 url,
                 description,
+                tags: Vec::new(),
                 created_at: default_timestamp(),
                 updated_at: default_timestamp(),
             },
             api_keys: HashMap::new(),
             config: GeminiSiteConfig::default(),
+            usage: HashMap::new(),
         }
     }
 
+    /// 添加站点时可选的 Base URL 预设，第一项为官方地址
+    pub const BASE_URL_PRESETS: &'static [BaseUrlPreset] = &[
+        BaseUrlPreset::new("Google 官方", "https://generativelanguage.googleapis.com"),
+    ];
+
+    /// 未设置时使用的认证头格式
+    pub const DEFAULT_AUTH_HEADER_STYLE: AuthHeaderStyle = AuthHeaderStyle::Bearer;
+
+    /// 站点实际使用的认证头格式
+    pub fn auth_header_style(&self) -> AuthHeaderStyle {
+        self.config
+            .auth_header_style
+            .unwrap_or(Self::DEFAULT_AUTH_HEADER_STYLE)
+    }
+
     /// 更新时间戳
     pub fn update_timestamp(&mut self) {
         self.metadata.updated_at = default_timestamp();
@@ -558,20 +1017,32 @@ impl GeminiSite {
     /// 删除 API key
     pub fn remove_api_key(&mut self, key_name: &str) -> Option<String> {
         let result = self.api_keys.remove(key_name);
+        self.usage.remove(key_name);
         self.update_timestamp();
         result
     }
+
+    /// 记录一次 API Key 激活
+    pub fn record_usage(&mut self, key_name: &str) {
+        self.usage.entry(key_name.to_string()).or_default().record();
+    }
 }
 
 impl GeminiActiveConfig {
-    /// 从引用和站点配置创建运行时配置
+    /// 从引用和 Gemini 配置创建运行时配置（站点未指定模型时使用默认模型）
     pub fn from_reference(
         reference: &GeminiActiveReference,
-        site: &GeminiSite,
-    ) -> Result<Self, String> {
+        config: &GeminiConfig,
+    ) -> Result<Self> {
+        let site = config
+            .get_site(&reference.site)
+            .ok_or_else(|| CliError::NotFound(format!("站点 '{}' 不存在", reference.site)))?;
+
         let api_key = site
             .get_api_key(&reference.api_key_name)
-            .ok_or_else(|| format!("API Key '{}' not found in site '{}'", reference.api_key_name, reference.site))?;
+            .ok_or_else(|| {
+                CliError::NotFound(format!("API Key '{}' 不存在于站点 '{}'", reference.api_key_name, reference.site))
+            })?;
 
         Ok(Self {
             site: reference.site.clone(),
@@ -580,7 +1051,15 @@ impl GeminiActiveConfig {
             api_key_name: reference.api_key_name.clone(),
             api_key: api_key.clone(),
             base_url: site.config.base_url.clone(),
-            model: site.config.model.clone(),
+            model: Some(
+                site.config
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| config.effective_default_model().to_string()),
+            ),
+            model_is_default: site.config.model.is_none(),
+            generation: site.config.generation.clone(),
+            safety: site.config.safety.clone(),
         })
     }
 }
@@ -594,12 +1073,24 @@ impl GeminiActiveConfig {
 pub struct OpenCodeConfig {
     #[serde(default = "default_opencode_version")]
     pub version: String,
+    // 使用 IndexMap 保持插入顺序，菜单与 opencode.json 顺序稳定
     #[serde(default)]
-    pub providers: HashMap<String, OpenCodeProvider>,
+    pub providers: IndexMap<String, OpenCodeProvider>,
+    /// 同步时在 opencode.json 中写入 `$schema` (编辑器据此提供补全)，设为 false 时不写入
+    #[serde(default = "default_write_schema", skip_serializing_if = "is_default_write_schema")]
+    pub write_schema: bool,
 }
 
 fn default_opencode_version() -> String {
-    "3.0.0".to_string()
+    CONFIG_VERSION.to_string()
+}
+
+fn default_write_schema() -> bool {
+    true
+}
+
+fn is_default_write_schema(write_schema: &bool) -> bool {
+    *write_schema
 }
 
 /// OpenCode Provider 配置 (匹配真实 opencode.json 格式)
@@ -609,7 +1100,7 @@ pub struct OpenCodeProvider {
     pub npm: Option<String>, // 如: "@ai-sdk/openai-compatible"
     pub name: String,
     pub options: OpenCodeProviderOptions,
-    pub models: HashMap<String, OpenCodeModelInfo>,
+    pub models: IndexMap<String, OpenCodeModelInfo>,
     // 内部元数据 (不同步到 opencode.json)
     #[serde(skip)]
     pub metadata: ProviderMetadata,
@@ -674,7 +1165,7 @@ pub struct OpenCodeActiveConfig {
     pub base_url: String,
     #[allow(dead_code)]
     pub api_key: String,
-    pub models: std::collections::HashMap<String, OpenCodeModelInfo>,
+    pub models: IndexMap<String, OpenCodeModelInfo>,
 }
 
 // ============================================================================
@@ -685,8 +1176,9 @@ impl OpenCodeConfig {
     /// 创建新的 OpenCode 配置
     pub fn new() -> Self {
         Self {
-            version: "3.0.0".to_string(),
-            providers: HashMap::new(),
+            version: CONFIG_VERSION.to_string(),
+            providers: IndexMap::new(),
+            write_schema: true,
         }
     }
 
@@ -705,9 +1197,9 @@ impl OpenCodeConfig {
         self.providers.insert(provider_name, provider);
     }
 
-    /// 删除 Provider
+    /// 删除 Provider（保持其余 Provider 的顺序）
     pub fn remove_provider(&mut self, provider_name: &str) -> Option<OpenCodeProvider> {
-        self.providers.remove(provider_name)
+        self.providers.shift_remove(provider_name)
     }
 }
 
@@ -717,6 +1209,16 @@ impl Default for OpenCodeConfig {
     }
 }
 
+impl SiteSummary for OpenCodeProvider {
+    fn url(&self) -> &str {
+        &self.options.base_url
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.metadata.description.as_deref()
+    }
+}
+
 impl OpenCodeProvider {
     /// 创建新的 Provider
     pub fn new(name: String, base_url: String, api_key: String, npm: Option<String>, description: Option<String>) -> Self {
@@ -727,7 +1229,7 @@ impl OpenCodeProvider {
                 base_url,
                 api_key,
             },
-            models: HashMap::new(),
+            models: IndexMap::new(),
             metadata: ProviderMetadata {
                 description,
                 created_at: default_timestamp(),
@@ -774,7 +1276,7 @@ impl OpenCodeProvider {
 
     /// 删除模型
     pub fn remove_model(&mut self, model_id: &str) -> Option<OpenCodeModelInfo> {
-        let result = self.models.remove(model_id);
+        let result = self.models.shift_remove(model_id);
         self.update_timestamp();
         result
     }
@@ -785,17 +1287,25 @@ impl OpenCodeProvider {
     }
 }
 
+/// 按给定的名称顺序重排集合，`order` 必须恰好包含集合中的全部名称
+pub fn apply_order<T>(items: &mut IndexMap<String, T>, order: &[String]) -> Result<()> {
+    if order.len() != items.len() || order.iter().any(|name| !items.contains_key(name)) {
+        return Err("新的顺序与现有条目不一致".into());
+    }
+
+    items.sort_by_cached_key(|name, _| order.iter().position(|o| o == name));
+    Ok(())
+}
+
 impl OpenCodeActiveConfig {
     /// 从引用和 Provider 配置创建完整运行时配置
     pub fn from_reference(
         reference: &OpenCodeActiveReference,
         config: &OpenCodeConfig,
-    ) -> Result<Self, String> {
+    ) -> Result<Self> {
         let provider = config
             .get_provider(&reference.provider)
-            .ok_or_else(|| {
-                format!("Provider '{}' not found", reference.provider)
-            })?;
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", reference.provider)))?;
 
         Ok(Self {
             provider: reference.provider.clone(),
@@ -826,6 +1336,10 @@ pub struct SiteDetectionResult {
     /// 检测到的模型列表
     pub available_models: Vec<String>,
 
+    /// 检测到的模型详情 (站点 `/models` 返回的上下文长度、所有者等)，与 available_models 顺序一致
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<DetectedModel>,
+
     /// 站点响应时间(毫秒)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_time_ms: Option<f64>,
@@ -833,6 +1347,44 @@ pub struct SiteDetectionResult {
     /// 错误信息(如果检测失败)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+
+    /// 是否因超时而失败
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+}
+
+/// 站点 `/models` 返回的单个模型
+///
+/// 不同站点的字段名不一致，上下文长度兼容 `context_length`、`context_window`、
+/// `max_context_length` 与 `max_model_len` (vLLM)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedModel {
+    /// 模型ID
+    pub id: String,
+
+    /// 上下文长度 (tokens)
+    #[serde(
+        default,
+        alias = "context_window",
+        alias = "max_context_length",
+        alias = "max_model_len",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub context_length: Option<u64>,
+
+    /// 模型所有者
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owned_by: Option<String>,
+}
+
+impl SiteDetectionResult {
+    /// 站点返回的模型上下文长度
+    pub fn context_length(&self, model_id: &str) -> Option<u64> {
+        self.models
+            .iter()
+            .find(|model| model.id == model_id)
+            .and_then(|model| model.context_length)
+    }
 }
 
 /// 模型检测结果
@@ -866,12 +1418,320 @@ pub struct ModelDetectionResult {
     /// 错误信息(如果检测失败)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+
+    /// 是否因超时而失败
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+}
+
+/// 检测历史 (~/.ca-switch/detection-history.json)
+///
+/// 与 opencode.json 分开保存，每个站点/模型只保留最近 [`DetectionHistory::MAX_ENTRIES`] 次，按时间从旧到新排列
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetectionHistory {
+    /// Provider 名称 -> 站点检测记录
+    #[serde(default)]
+    pub sites: IndexMap<String, Vec<SiteDetectionResult>>,
+
+    /// Provider 名称 -> 模型ID -> 模型检测记录
+    #[serde(default)]
+    pub models: IndexMap<String, IndexMap<String, Vec<ModelDetectionResult>>>,
+}
+
+impl DetectionHistory {
+    /// 每个站点/模型保留的记录数
+    pub const MAX_ENTRIES: usize = 20;
+
+    /// 追加一次站点检测
+    pub fn record_site(&mut self, provider: &str, result: SiteDetectionResult) {
+        let entries = self.sites.entry(provider.to_string()).or_default();
+        push_bounded(entries, result);
+    }
+
+    /// 追加一次模型检测
+    pub fn record_model(&mut self, provider: &str, result: ModelDetectionResult) {
+        let entries = self
+            .models
+            .entry(provider.to_string())
+            .or_default()
+            .entry(result.model_id.clone())
+            .or_default();
+        push_bounded(entries, result);
+    }
+
+    /// 有检测记录的 Provider (按首次记录顺序)
+    pub fn providers(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.sites.keys().collect();
+        for name in self.models.keys() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+/// 追加记录，超出上限时丢弃最旧的
+fn push_bounded<T>(entries: &mut Vec<T>, item: T) {
+    entries.push(item);
+    if entries.len() > DetectionHistory::MAX_ENTRIES {
+        let overflow = entries.len() - DetectionHistory::MAX_ENTRIES;
+        entries.drain(..overflow);
+    }
+}
+
+// ============================================================================
+// 站点导出 (可分享的单站点 JSON 文件)
+// ============================================================================
+
+/// 单站点导出文件结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteExport {
+    pub version: String,
+    /// 站点 / Provider 名称
+    pub name: String,
+    #[serde(default = "default_timestamp")]
+    pub exported_at: String,
+    /// 是否已移除 Token/API Key
+    #[serde(default)]
+    pub redacted: bool,
+    #[serde(flatten)]
+    pub payload: SiteExportPayload,
+}
+
+/// 导出内容，以 `provider` 字段区分供应商类型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "provider", content = "site", rename_all = "lowercase")]
+pub enum SiteExportPayload {
+    Claude(ClaudeSite),
+    Codex(CodexSite),
+    Gemini(GeminiSite),
+    OpenCode(OpenCodeProvider),
+}
+
+impl SiteExport {
+    /// 创建导出结构，`redact` 为 true 时移除所有密钥
+    pub fn new(name: String, payload: SiteExportPayload, redact: bool) -> Self {
+        let mut export = Self {
+            version: CONFIG_VERSION.to_string(),
+            name,
+            exported_at: default_timestamp(),
+            redacted: redact,
+            payload,
+        };
+
+        if redact {
+            export.redact();
+        }
+
+        export
+    }
+
+    /// 移除导出内容中的所有密钥
+    fn redact(&mut self) {
+        match &mut self.payload {
+            SiteExportPayload::Claude(site) => site.tokens.clear(),
+            SiteExportPayload::Codex(site) => site.api_keys.clear(),
+            SiteExportPayload::Gemini(site) => site.api_keys.clear(),
+            SiteExportPayload::OpenCode(provider) => provider.options.api_key.clear(),
+        }
+    }
+
+    /// 供应商类型名称
+    pub fn provider(&self) -> &'static str {
+        match self.payload {
+            SiteExportPayload::Claude(_) => "claude",
+            SiteExportPayload::Codex(_) => "codex",
+            SiteExportPayload::Gemini(_) => "gemini",
+            SiteExportPayload::OpenCode(_) => "opencode",
+        }
+    }
+
+    /// 导出内容中包含的密钥数量
+    pub fn key_count(&self) -> usize {
+        match &self.payload {
+            SiteExportPayload::Claude(site) => site.tokens.len(),
+            SiteExportPayload::Codex(site) => site.api_keys.len(),
+            SiteExportPayload::Gemini(site) => site.api_keys.len(),
+            SiteExportPayload::OpenCode(provider) => {
+                usize::from(!provider.options.api_key.is_empty())
+            }
+        }
+    }
+
+    /// 校验导出内容是否可以导入
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err("站点名称不能为空".into());
+        }
+
+        if let SiteExportPayload::OpenCode(provider) = &self.payload {
+            let base_url = url::Url::parse(&provider.options.base_url)
+                .map_err(|e| format!("Provider '{}' 的 baseURL 无效: {}", self.name, e))?;
+            if !matches!(base_url.scheme(), "http" | "https") {
+                return Err(format!(
+                    "Provider '{}' 的 baseURL 必须以 http:// 或 https:// 开头",
+                    self.name
+                ).into());
+            }
+            if provider.options.api_key.is_empty() && !self.redacted {
+                return Err(format!("Provider '{}' 缺少 apiKey", self.name).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 默认导出文件名: {provider}-{name}.json
+    pub fn file_name(&self) -> String {
+        let safe_name: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        format!("{}-{}.json", self.provider(), safe_name)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sites_with_base_url() {
+        let mut sites = HashMap::new();
+        sites.insert("a".to_string(), ClaudeSite::new("https://Relay.example.com/v1/".to_string(), None));
+        let mut b = ClaudeSite::new("https://b.example.com".to_string(), None);
+        b.config.base_url = Some("https://relay.example.com".to_string());
+        sites.insert("b".to_string(), b);
+        sites.insert("c".to_string(), ClaudeSite::new("https://other.example.com".to_string(), None));
+
+        assert_eq!(sites_with_base_url(&sites, " https://relay.example.com/ ", None), ["a", "b"]);
+        assert_eq!(sites_with_base_url(&sites, "https://relay.example.com", Some("a")), ["b"]);
+        assert!(sites_with_base_url(&sites, "https://b.example.com", None).is_empty());
+        assert!(sites_with_base_url(&sites, "", None).is_empty());
+    }
+
+    #[test]
+    fn test_sort_and_page_sites() {
+        let sites: HashMap<String, u32> = [("c", 3), ("a", 1), ("b", 2)]
+            .into_iter()
+            .map(|(name, n)| (name.to_string(), n))
+            .collect();
+        let sorted = sort_sites(sites);
+        assert_eq!(sorted.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["a", "b", "c"]);
+
+        assert_eq!(page_sites(sorted.clone(), 1, Some(1)), vec![("b".to_string(), 2)]);
+        assert_eq!(page_sites(sorted.clone(), 1, None).len(), 2);
+        assert!(page_sites(sorted, 5, Some(2)).is_empty());
+    }
+
+    #[test]
+    fn test_site_export_redact_and_roundtrip() {
+        let mut site = ClaudeSite::new("https://api.example.com".to_string(), None);
+        site.add_token("main".to_string(), "sk-xxx".to_string());
+
+        let export = SiteExport::new(
+            "my site".to_string(),
+            SiteExportPayload::Claude(site),
+            true,
+        );
+        assert_eq!(export.file_name(), "claude-my_site.json");
+
+        let json = serde_json::to_string(&export).unwrap();
+        assert!(json.contains("\"provider\":\"claude\""));
+        assert!(!json.contains("sk-xxx"));
+
+        let parsed: SiteExport = serde_json::from_str(&json).unwrap();
+        assert!(parsed.redacted);
+        match parsed.payload {
+            SiteExportPayload::Claude(site) => assert!(site.tokens.is_empty()),
+            _ => panic!("provider 类型错误"),
+        }
+    }
+
+    #[test]
+    fn test_from_reference_missing_key_is_not_found() {
+        let site = ClaudeSite::new("https://api.example.com".to_string(), None);
+        let reference = ClaudeActiveReference {
+            site: "demo".to_string(),
+            token_name: "gone".to_string(),
+        };
+        let err = ClaudeActiveConfig::from_reference(&reference, &site).unwrap_err();
+        assert!(matches!(err, CliError::NotFound(_)));
+
+        let reference = GeminiActiveReference {
+            site: "deleted".to_string(),
+            api_key_name: "main".to_string(),
+        };
+        let err = GeminiActiveConfig::from_reference(&reference, &GeminiConfig::new()).unwrap_err();
+        assert!(matches!(err, CliError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_base_url_presets() {
+        assert_eq!(ClaudeSite::BASE_URL_PRESETS[0].url, "https://api.anthropic.com");
+        assert_eq!(CodexSite::BASE_URL_PRESETS[0].url, "https://api.openai.com/v1");
+        assert_eq!(GeminiSite::BASE_URL_PRESETS[0].url, "https://generativelanguage.googleapis.com");
+
+        let all = ClaudeSite::BASE_URL_PRESETS
+            .iter()
+            .chain(CodexSite::BASE_URL_PRESETS)
+            .chain(GeminiSite::BASE_URL_PRESETS);
+        for preset in all {
+            assert!(reqwest::Url::parse(preset.url).is_ok(), "{}", preset.url);
+            assert!(!preset.url.ends_with('/'), "{}", preset.url);
+        }
+    }
+
+    #[test]
+    fn test_auth_header_style_default_and_serde() {
+        let mut site = ClaudeSite::new("https://api.example.com".to_string(), None);
+        assert_eq!(site.auth_header_style(), AuthHeaderStyle::XApiKey);
+        assert_eq!(
+            CodexSite::new("https://api.example.com".to_string(), None).auth_header_style(),
+            AuthHeaderStyle::Bearer
+        );
+
+        // 未设置时不写入文件
+        let json = serde_json::to_string(&site.config).unwrap();
+        assert!(!json.contains("auth_header_style"));
+
+        site.config.auth_header_style = Some(AuthHeaderStyle::Bearer);
+        let json = serde_json::to_string(&site.config).unwrap();
+        assert!(json.contains("\"auth_header_style\":\"bearer\""));
+
+        let parsed: ClaudeSiteConfig =
+            serde_json::from_str(r#"{"auth_header_style": "x-api-key"}"#).unwrap();
+        assert_eq!(parsed.auth_header_style, Some(AuthHeaderStyle::XApiKey));
+    }
+
+    #[test]
+    fn test_gemini_default_model_fallback() {
+        let mut config = GeminiConfig::new();
+        let mut site = GeminiSite::new("https://example.com".to_string(), None);
+        site.add_api_key("main".to_string(), "key".to_string());
+        config.add_site("test".to_string(), site);
+
+        let reference = GeminiActiveReference {
+            site: "test".to_string(),
+            api_key_name: "main".to_string(),
+        };
+
+        let active = GeminiActiveConfig::from_reference(&reference, &config).unwrap();
+        assert_eq!(active.model.as_deref(), Some(GEMINI_FALLBACK_MODEL));
+        assert!(active.model_is_default);
+
+        config.default_model = Some("gemini-custom".to_string());
+        let active = GeminiActiveConfig::from_reference(&reference, &config).unwrap();
+        assert_eq!(active.model.as_deref(), Some("gemini-custom"));
+
+        config.get_site_mut("test").unwrap().config.model = Some("gemini-site".to_string());
+        let active = GeminiActiveConfig::from_reference(&reference, &config).unwrap();
+        assert_eq!(active.model.as_deref(), Some("gemini-site"));
+        assert!(!active.model_is_default);
+    }
+
     #[test]
     fn test_global_config_creation() {
         let config = GlobalConfig::new();
@@ -1,24 +1,31 @@
 // OpenCode 配置管理器
 // 负责管理 ~/.ca-switch/opencode.json 和同步到 ~/.opencode/opencode.json
 
+use crate::error::{CliError, Result};
+use crate::logging::log_write;
+use crate::config::migration::read_migrated;
 use crate::config::models::{
-    OpenCodeActiveConfig, OpenCodeConfig, OpenCodeModelInfo, OpenCodeProvider,
+    apply_order, DetectionHistory, ModelDetectionResult, OpenCodeActiveConfig, OpenCodeConfig, OpenCodeModelInfo, OpenCodeModelLimit,
+    OpenCodeProvider, SiteDetectionResult,
 };
 use serde_json;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// 写入 opencode.json 的 `$schema` (OpenCode 官方配置 schema)
+pub const OPENCODE_SCHEMA_URL: &str = "https://opencode.ai/config.json";
 
 /// OpenCode 配置管理器
 pub struct OpenCodeConfigManager {
     opencode_config_file: PathBuf,  // ~/.ca-switch/opencode.json
-    opencode_dir: PathBuf,          // ~/.opencode
+    history_file: PathBuf,          // ~/.ca-switch/detection-history.json
     opencode_json: PathBuf,         // ~/.opencode/opencode.json
 }
 
 impl OpenCodeConfigManager {
     /// 创建新的 OpenCode 配置管理器
-    pub fn new(config_dir: PathBuf) -> Result<Self, String> {
+    pub fn new(config_dir: PathBuf) -> Result<Self> {
         // 确保 ~/.ca-switch 目录存在
         if !config_dir.exists() {
             fs::create_dir_all(&config_dir)
@@ -26,17 +33,16 @@ impl OpenCodeConfigManager {
         }
 
         let opencode_config_file = config_dir.join("opencode.json");
+        let history_file = config_dir.join("detection-history.json");
 
         // OpenCode 官方配置目录
-        let opencode_dir = dirs::home_dir()
-            .ok_or("无法获取用户主目录")?
-            .join(".opencode");
-
-        let opencode_json = opencode_dir.join("opencode.json");
+        let opencode_json = super::home_dir()?
+            .join(".opencode")
+            .join("opencode.json");
 
         Ok(Self {
             opencode_config_file,
-            opencode_dir,
+            history_file,
             opencode_json,
         })
     }
@@ -46,25 +52,64 @@ impl OpenCodeConfigManager {
     // ========================================================================
 
     /// 读取 opencode.json 配置
-    pub fn read_config(&self) -> Result<OpenCodeConfig, String> {
-        if !self.opencode_config_file.exists() {
-            return Ok(OpenCodeConfig::new());
-        }
-
-        let content = fs::read_to_string(&self.opencode_config_file)
-            .map_err(|e| format!("读取 opencode.json 失败: {}", e))?;
-
-        serde_json::from_str(&content)
-            .map_err(|e| format!("解析 opencode.json 失败: {}", e))
+    pub fn read_config(&self) -> Result<OpenCodeConfig> {
+        let config = read_migrated(&self.opencode_config_file, "opencode.json", |config| self.write_config(config))?;
+        // 文件不存在时返回空配置
+        Ok(config.unwrap_or_else(OpenCodeConfig::new))
     }
 
     /// 写入 opencode.json 配置
-    pub fn write_config(&self, config: &OpenCodeConfig) -> Result<(), String> {
+    pub fn write_config(&self, config: &OpenCodeConfig) -> Result<()> {
         let content = serde_json::to_string_pretty(config)
             .map_err(|e| format!("序列化 opencode.json 失败: {}", e))?;
 
+        log_write(&self.opencode_config_file);
         fs::write(&self.opencode_config_file, content)
-            .map_err(|e| format!("写入 opencode.json 失败: {}", e))
+            .map_err(|e| format!("写入 opencode.json 失败: {}", e))?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // 检测历史
+    // ========================================================================
+
+    /// 读取检测历史，文件不存在时返回空历史
+    pub fn read_detection_history(&self) -> Result<DetectionHistory> {
+        if !self.history_file.exists() {
+            return Ok(DetectionHistory::default());
+        }
+
+        let content = fs::read_to_string(&self.history_file)
+            .map_err(|e| format!("读取 detection-history.json 失败: {}", e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| CliError::Parse(format!("解析 detection-history.json 失败: {}", e)))
+    }
+
+    /// 写入检测历史
+    fn write_detection_history(&self, history: &DetectionHistory) -> Result<()> {
+        let content = serde_json::to_string_pretty(history)
+            .map_err(|e| format!("序列化 detection-history.json 失败: {}", e))?;
+
+        log_write(&self.history_file);
+        fs::write(&self.history_file, content)
+            .map_err(|e| format!("写入 detection-history.json 失败: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 追加一次站点检测到历史
+    pub fn record_site_detection(&self, provider_name: &str, result: &SiteDetectionResult) -> Result<()> {
+        let mut history = self.read_detection_history()?;
+        history.record_site(provider_name, result.clone());
+        self.write_detection_history(&history)
+    }
+
+    /// 追加一次模型检测到历史
+    pub fn record_model_detection(&self, provider_name: &str, result: &ModelDetectionResult) -> Result<()> {
+        let mut history = self.read_detection_history()?;
+        history.record_model(provider_name, result.clone());
+        self.write_detection_history(&history)
     }
 
     // ========================================================================
@@ -72,13 +117,13 @@ impl OpenCodeConfigManager {
     // ========================================================================
 
     /// 获取 Provider
-    pub fn get_provider(&self, provider_name: &str) -> Result<Option<OpenCodeProvider>, String> {
+    pub fn get_provider(&self, provider_name: &str) -> Result<Option<OpenCodeProvider>> {
         let config = self.read_config()?;
         Ok(config.get_provider(provider_name).cloned())
     }
 
     /// 获取所有 Provider
-    pub fn get_all_providers(&self) -> Result<HashMap<String, OpenCodeProvider>, String> {
+    pub fn get_all_providers(&self) -> Result<IndexMap<String, OpenCodeProvider>> {
         let config = self.read_config()?;
         Ok(config.providers.clone())
     }
@@ -91,12 +136,12 @@ impl OpenCodeConfigManager {
         api_key: String,
         npm: Option<String>,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         // 检查 Provider 是否已存在
         if config.get_provider(&provider_name).is_some() {
-            return Err(format!("Provider '{}' 已存在", provider_name));
+            return Err(format!("Provider '{}' 已存在", provider_name).into());
         }
 
         let provider = OpenCodeProvider::new(provider_name.clone(), base_url, api_key, npm, description);
@@ -113,12 +158,12 @@ impl OpenCodeConfigManager {
         api_key: Option<String>,
         npm: Option<String>,
         description: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let provider = config
             .get_provider_mut(provider_name)
-            .ok_or_else(|| format!("Provider '{}' 不存在", provider_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
 
         if let Some(url) = base_url {
             provider.set_base_url(url);
@@ -139,11 +184,11 @@ impl OpenCodeConfigManager {
     }
 
     /// 删除 Provider
-    pub fn delete_provider(&mut self, provider_name: &str) -> Result<(), String> {
+    pub fn delete_provider(&mut self, provider_name: &str) -> Result<()> {
         let mut config = self.read_config()?;
 
         if config.remove_provider(provider_name).is_none() {
-            return Err(format!("Provider '{}' 不存在", provider_name));
+            return Err(CliError::NotFound(format!("Provider '{}' 不存在", provider_name)));
         }
 
         self.write_config(&config)
@@ -154,11 +199,11 @@ impl OpenCodeConfigManager {
     // ========================================================================
 
     /// 获取模型
-    pub fn get_models(&self, provider_name: &str) -> Result<HashMap<String, OpenCodeModelInfo>, String> {
+    pub fn get_models(&self, provider_name: &str) -> Result<IndexMap<String, OpenCodeModelInfo>> {
         let config = self.read_config()?;
         let provider = config
             .get_provider(provider_name)
-            .ok_or_else(|| format!("Provider '{}' 不存在", provider_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
 
         Ok(provider.models.clone())
     }
@@ -169,19 +214,19 @@ impl OpenCodeConfigManager {
         provider_name: &str,
         model_id: String,
         model_info: OpenCodeModelInfo,
-    ) -> Result<(), String> {
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let provider = config
             .get_provider_mut(provider_name)
-            .ok_or_else(|| format!("Provider '{}' 不存在", provider_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
 
         // 检查模型是否已存在
         if provider.get_model(&model_id).is_some() {
             return Err(format!(
                 "模型 '{}' 已存在于 Provider '{}'",
                 model_id, provider_name
-            ));
+            ).into());
         }
 
         provider.add_model(model_id, model_info);
@@ -189,81 +234,81 @@ impl OpenCodeConfigManager {
         self.write_config(&config)
     }
 
-    /// 删除模型
-    pub fn delete_model(&mut self, provider_name: &str, model_id: &str) -> Result<(), String> {
+    /// 更新模型的显示名称和限制（保留模型检测缓存）
+    pub fn update_model(
+        &mut self,
+        provider_name: &str,
+        model_id: &str,
+        name: String,
+        limit: Option<OpenCodeModelLimit>,
+    ) -> Result<()> {
         let mut config = self.read_config()?;
 
         let provider = config
             .get_provider_mut(provider_name)
-            .ok_or_else(|| format!("Provider '{}' 不存在", provider_name))?;
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
 
-        if provider.remove_model(model_id).is_none() {
-            return Err(format!(
+        let model_info = provider.models.get_mut(model_id).ok_or_else(|| {
+            CliError::NotFound(format!(
                 "模型 '{}' 不存在于 Provider '{}'",
                 model_id, provider_name
-            ));
-        }
+            ))
+        })?;
+
+        model_info.name = name;
+        model_info.limit = limit;
+        provider.update_timestamp();
 
         self.write_config(&config)
     }
 
-    // ========================================================================
-    // 配置同步到 ~/.opencode/opencode.json
-    // ========================================================================
+    /// 调整 Provider 的显示顺序
+    pub fn reorder_providers(&mut self, order: &[String]) -> Result<()> {
+        let mut config = self.read_config()?;
+        apply_order(&mut config.providers, order)?;
+        self.write_config(&config)
+    }
 
-    /// 同步配置到 OpenCode 官方配置文件 (生成完整的 opencode.json)
-    #[allow(dead_code)]
-    pub fn sync_to_opencode(&self, active_config: &OpenCodeActiveConfig) -> Result<(), String> {
-        // 确保 ~/.opencode 目录存在
-        if !self.opencode_dir.exists() {
-            fs::create_dir_all(&self.opencode_dir)
-                .map_err(|e| format!("创建 .opencode 目录失败: {}", e))?;
-        }
+    /// 调整 Provider 下模型的显示顺序
+    pub fn reorder_models(&mut self, provider_name: &str, order: &[String]) -> Result<()> {
+        let mut config = self.read_config()?;
 
-        // 读取完整的 provider 配置
-        let opencode_config = self.read_config()?;
+        let provider = config
+            .get_provider_mut(provider_name)
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
 
-        // 构建 provider 对象（只包含当前激活的 provider）
-        let mut providers_map = serde_json::Map::new();
+        apply_order(&mut provider.models, order)?;
+        provider.update_timestamp();
 
-        if let Some(provider) = opencode_config.get_provider(&active_config.provider) {
-            providers_map.insert(
-                active_config.provider.clone(),
-                serde_json::to_value(provider)
-                    .map_err(|e| format!("序列化 Provider 失败: {}", e))?,
-            );
-        }
+        self.write_config(&config)
+    }
 
-        // 构建完整的 opencode.json 结构
-        // 注意: 不再设置 model 和 small_model,让 opencode 自己选择
-        let sync_data = serde_json::json!({
-            "$schema": "https://opencode.ai/config.json",
-            "theme": "tokyonight",
-            "autoupdate": false,
-            "provider": providers_map,
-            "tools": {
-                "get-current-session-id": true,
-                "webfetch": true
-            },
-            "agent": {},
-            "mcp": {}
-        });
+    /// 删除模型
+    pub fn delete_model(&mut self, provider_name: &str, model_id: &str) -> Result<()> {
+        let mut config = self.read_config()?;
 
-        let content = serde_json::to_string_pretty(&sync_data)
-            .map_err(|e| format!("序列化同步数据失败: {}", e))?;
+        let provider = config
+            .get_provider_mut(provider_name)
+            .ok_or_else(|| CliError::NotFound(format!("Provider '{}' 不存在", provider_name)))?;
+
+        if provider.remove_model(model_id).is_none() {
+            return Err(CliError::NotFound(format!(
+                "模型 '{}' 不存在于 Provider '{}'",
+                model_id, provider_name
+            )));
+        }
 
-        fs::write(&self.opencode_json, content)
-            .map_err(|e| format!("写入 ~/.opencode/opencode.json 失败: {}", e))
+        self.write_config(&config)
     }
 
-    /// 同步多个Provider配置到 OpenCode 官方配置文件
-    pub fn sync_multiple_providers_to_opencode(&self, provider_names: &[String]) -> Result<(), String> {
-        // 确保 ~/.opencode 目录存在
-        if !self.opencode_dir.exists() {
-            fs::create_dir_all(&self.opencode_dir)
-                .map_err(|e| format!("创建 .opencode 目录失败: {}", e))?;
-        }
+    // ========================================================================
+    // 配置同步到 ~/.opencode/opencode.json
+    // ========================================================================
 
+    /// 生成将写入 opencode.json 的完整内容 (只包含指定的 Provider，不存在的会被忽略)
+    ///
+    /// 同步与预览共用，预览时看到的即为实际写入的内容
+    pub fn build_sync_data(&self, provider_names: &[String]) -> Result<serde_json::Value> {
         // 读取完整的 provider 配置
         let opencode_config = self.read_config()?;
 
@@ -281,8 +326,9 @@ impl OpenCodeConfigManager {
         }
 
         // 构建完整的 opencode.json 结构
-        let sync_data = serde_json::json!({
-            "$schema": "https://opencode.ai/config.json",
+        // 注意: 不再设置 model 和 small_model,让 opencode 自己选择
+        let mut data = serde_json::json!({
+            "$schema": OPENCODE_SCHEMA_URL,
             "theme": "tokyonight",
             "autoupdate": false,
             "provider": providers_map,
@@ -293,114 +339,291 @@ impl OpenCodeConfigManager {
             "agent": {},
             "mcp": {}
         });
+        if !opencode_config.write_schema {
+            if let Some(obj) = data.as_object_mut() {
+                obj.shift_remove("$schema");
+            }
+        }
+        Ok(data)
+    }
+
+    /// 目标 opencode.json 中已有 `$schema` 时沿用其值 (如固定版本的 schema)，不写入 `$schema` 时不处理
+    pub fn keep_existing_schema(sync_data: &mut serde_json::Value, target: &Path) {
+        let Some(schema) = sync_data.get_mut("$schema") else {
+            return;
+        };
+        let existing = fs::read_to_string(target)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|value| value.get("$schema").and_then(|v| v.as_str()).map(str::to_string));
+        if let Some(existing) = existing.filter(|s| !s.is_empty()) {
+            *schema = serde_json::Value::String(existing);
+        }
+    }
+
+    /// 项目级 .opencode/opencode.json 路径 (当前目录下)
+    pub fn project_opencode_json(&self) -> Result<PathBuf> {
+        let current_dir = std::env::current_dir()
+            .map_err(|e| format!("获取当前目录失败: {}", e))?;
+        Ok(current_dir.join(".opencode").join("opencode.json"))
+    }
+
+    /// 全局 ~/.opencode/opencode.json 路径
+    pub fn global_opencode_json(&self) -> &PathBuf {
+        &self.opencode_json
+    }
+
+    /// 将同步内容写入目标 opencode.json，必要时创建所在目录
+    fn write_sync_data(path: &PathBuf, sync_data: &serde_json::Value, label: &str) -> Result<Vec<PathBuf>> {
+        let mut sync_data = sync_data.clone();
+        Self::keep_existing_schema(&mut sync_data, path);
+
+        if let Some(dir) = path.parent() {
+            if !dir.exists() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("创建 {} 所在目录失败: {}", label, e))?;
+            }
+        }
 
         let content = serde_json::to_string_pretty(&sync_data)
             .map_err(|e| format!("序列化同步数据失败: {}", e))?;
 
-        fs::write(&self.opencode_json, content)
-            .map_err(|e| format!("写入 ~/.opencode/opencode.json 失败: {}", e))
+        log_write(path);
+        fs::write(path, content)
+            .map_err(|e| format!("写入 {} 失败: {}", label, e))?;
+
+        Ok(vec![path.clone()])
+    }
+
+    /// 同步配置到 OpenCode 官方配置文件 (生成完整的 opencode.json)
+    pub fn sync_to_opencode(&self, active_config: &OpenCodeActiveConfig) -> Result<Vec<PathBuf>> {
+        self.sync_multiple_providers_to_opencode(std::slice::from_ref(&active_config.provider))
+    }
+
+    /// 同步多个Provider配置到 OpenCode 官方配置文件
+    pub fn sync_multiple_providers_to_opencode(&self, provider_names: &[String]) -> Result<Vec<PathBuf>> {
+        let sync_data = self.build_sync_data(provider_names)?;
+        Self::write_sync_data(&self.opencode_json, &sync_data, "~/.opencode/opencode.json")
     }
 
     /// 同步配置到项目级 .opencode/opencode.json
     #[allow(dead_code)]
-    pub fn sync_to_project(&self, active_config: &OpenCodeActiveConfig) -> Result<(), String> {
-        // 获取当前工作目录
-        let current_dir = std::env::current_dir()
-            .map_err(|e| format!("获取当前目录失败: {}", e))?;
+    pub fn sync_to_project(&self, active_config: &OpenCodeActiveConfig) -> Result<Vec<PathBuf>> {
+        self.sync_multiple_providers_to_project(std::slice::from_ref(&active_config.provider))
+    }
 
-        let project_opencode_dir = current_dir.join(".opencode");
-        let project_opencode_json = project_opencode_dir.join("opencode.json");
+    /// 同步多个Provider配置到项目级 .opencode/opencode.json
+    pub fn sync_multiple_providers_to_project(&self, provider_names: &[String]) -> Result<Vec<PathBuf>> {
+        let sync_data = self.build_sync_data(provider_names)?;
+        Self::write_sync_data(&self.project_opencode_json()?, &sync_data, "项目 .opencode/opencode.json")
+    }
 
-        // 确保 .opencode 目录存在
-        if !project_opencode_dir.exists() {
-            fs::create_dir_all(&project_opencode_dir)
-                .map_err(|e| format!("创建项目 .opencode 目录失败: {}", e))?;
-        }
+    /// 获取配置文件路径
+    pub fn get_config_file_path(&self) -> &PathBuf {
+        &self.opencode_config_file
+    }
 
-        // 读取完整的 provider 配置
-        let opencode_config = self.read_config()?;
+    /// 同步时写入的 OpenCode 配置文件（用于导出）
+    pub fn live_config_files(&self) -> Vec<PathBuf> {
+        vec![self.opencode_json.clone()]
+    }
+}
 
-        // 构建 provider 对象（只包含当前激活的 provider）
-        let mut providers_map = serde_json::Map::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detection_history_is_bounded_and_separate() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-opencode-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let manager = OpenCodeConfigManager::new(config_dir.clone()).unwrap();
+
+        for i in 0..DetectionHistory::MAX_ENTRIES + 5 {
+            let site = SiteDetectionResult {
+                detected_at: format!("{}", i),
+                is_available: true,
+                api_key_valid: true,
+                available_models: vec![],
+                models: vec![],
+                response_time_ms: Some(i as f64),
+                error_message: None,
+                timed_out: false,
+            };
+            manager.record_site_detection("provider", &site).unwrap();
+        }
+        let model = ModelDetectionResult {
+            detected_at: "now".to_string(),
+            model_id: "vendor/model".to_string(),
+            is_available: false,
+            first_token_time_ms: None,
+            tokens_per_second: None,
+            total_response_time_ms: None,
+            stream_available: None,
+            error_message: Some("请求超时".to_string()),
+            timed_out: true,
+        };
+        manager.record_model_detection("other", &model).unwrap();
+
+        // 只保留最近的记录，旧记录被丢弃
+        let history = manager.read_detection_history().unwrap();
+        let sites = &history.sites["provider"];
+        assert_eq!(sites.len(), DetectionHistory::MAX_ENTRIES);
+        assert_eq!(sites[0].detected_at, "5");
+        assert_eq!(sites.last().unwrap().response_time_ms, Some(24.0));
+        assert!(history.models["other"]["vendor/model"][0].timed_out);
+        assert_eq!(history.providers(), vec!["provider", "other"]);
+
+        // 历史不写入 opencode.json
+        assert!(!config_dir.join("opencode.json").exists());
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
 
-        if let Some(provider) = opencode_config.get_provider(&active_config.provider) {
-            providers_map.insert(
-                active_config.provider.clone(),
-                serde_json::to_value(provider)
-                    .map_err(|e| format!("序列化 Provider 失败: {}", e))?,
-            );
+    #[test]
+    fn test_build_sync_data_only_includes_selected_providers() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-opencode-preview-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let mut manager = OpenCodeConfigManager::new(config_dir.clone()).unwrap();
+        for name in ["a", "b"] {
+            manager
+                .add_provider(name.to_string(), format!("https://{}.example.com", name), "sk-test".to_string(), None, None)
+                .unwrap();
         }
 
-        // 构建完整的 opencode.json 结构
-        let sync_data = serde_json::json!({
-            "$schema": "https://opencode.ai/config.json",
-            "theme": "tokyonight",
-            "autoupdate": false,
-            "provider": providers_map,
-            "tools": {
-                "get-current-session-id": true,
-                "webfetch": true
-            },
-            "agent": {},
-            "mcp": {}
-        });
+        let data = manager
+            .build_sync_data(&["b".to_string(), "missing".to_string()])
+            .unwrap();
+        let providers = data["provider"].as_object().unwrap();
+        assert_eq!(providers.keys().collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(providers["b"]["options"]["baseURL"], "https://b.example.com");
+        assert_eq!(data["$schema"], "https://opencode.ai/config.json");
 
-        let content = serde_json::to_string_pretty(&sync_data)
-            .map_err(|e| format!("序列化同步数据失败: {}", e))?;
+        // 预览不写入任何 opencode.json
+        assert!(!config_dir.join(".opencode").exists());
 
-        fs::write(&project_opencode_json, content)
-            .map_err(|e| format!("写入 .opencode/opencode.json 失败: {}", e))
+        let _ = fs::remove_dir_all(&config_dir);
     }
 
-    /// 同步多个Provider配置到项目级 .opencode/opencode.json
-    pub fn sync_multiple_providers_to_project(&self, provider_names: &[String]) -> Result<(), String> {
-        // 获取当前工作目录
-        let current_dir = std::env::current_dir()
-            .map_err(|e| format!("获取当前目录失败: {}", e))?;
+    #[test]
+    fn test_sync_writes_and_keeps_schema() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-opencode-schema-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let mut manager = OpenCodeConfigManager::new(config_dir.clone()).unwrap();
+        manager
+            .add_provider("a".to_string(), "https://a.example.com".to_string(), "sk-test".to_string(), None, None)
+            .unwrap();
+        let target = config_dir.join(".opencode").join("opencode.json");
+        let providers = ["a".to_string()];
+        let written_schema = || {
+            let content = fs::read_to_string(&target).unwrap();
+            serde_json::from_str::<serde_json::Value>(&content).unwrap().get("$schema").cloned()
+        };
+
+        // 首次同步写入官方 schema
+        let data = manager.build_sync_data(&providers).unwrap();
+        OpenCodeConfigManager::write_sync_data(&target, &data, "opencode.json").unwrap();
+        assert_eq!(written_schema(), Some(serde_json::json!(OPENCODE_SCHEMA_URL)));
+
+        // 已有的 $schema 在再次同步时保留
+        fs::write(&target, r#"{"$schema": "https://example.com/pinned.json", "provider": {}}"#).unwrap();
+        OpenCodeConfigManager::write_sync_data(&target, &data, "opencode.json").unwrap();
+        assert_eq!(written_schema(), Some(serde_json::json!("https://example.com/pinned.json")));
+
+        // 关闭后不再写入
+        let mut config = manager.read_config().unwrap();
+        config.write_schema = false;
+        manager.write_config(&config).unwrap();
+        let data = manager.build_sync_data(&providers).unwrap();
+        OpenCodeConfigManager::write_sync_data(&target, &data, "opencode.json").unwrap();
+        assert_eq!(written_schema(), None);
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
+
+    #[test]
+    fn test_update_model() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-opencode-model-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let mut manager = OpenCodeConfigManager::new(config_dir.clone()).unwrap();
+        manager
+            .add_provider("provider".to_string(), "https://example.com".to_string(), "sk-test".to_string(), None, None)
+            .unwrap();
+        manager
+            .add_model(
+                "provider",
+                "model".to_string(),
+                OpenCodeModelInfo {
+                    name: "model".to_string(),
+                    limit: None,
+                    model_detection: None,
+                },
+            )
+            .unwrap();
+
+        let limit = OpenCodeModelLimit {
+            context: Some(200_000),
+            output: None,
+        };
+        manager
+            .update_model("provider", "model", "My Model".to_string(), Some(limit))
+            .unwrap();
+
+        let models = manager.get_models("provider").unwrap();
+        assert_eq!(models["model"].name, "My Model");
+        let limit = models["model"].limit.as_ref().unwrap();
+        assert_eq!((limit.context, limit.output), (Some(200_000), None));
+
+        assert!(matches!(
+            manager.update_model("provider", "missing", "x".to_string(), None),
+            Err(CliError::NotFound(_))
+        ));
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
 
-        let project_opencode_dir = current_dir.join(".opencode");
-        let project_opencode_json = project_opencode_dir.join("opencode.json");
+    #[test]
+    fn test_reorder_providers_and_models_persist() {
+        let config_dir = std::env::temp_dir().join(format!("ca-switch-opencode-order-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&config_dir);
+        let mut manager = OpenCodeConfigManager::new(config_dir.clone()).unwrap();
 
-        // 确保 .opencode 目录存在
-        if !project_opencode_dir.exists() {
-            fs::create_dir_all(&project_opencode_dir)
-                .map_err(|e| format!("创建项目 .opencode 目录失败: {}", e))?;
+        for name in ["zeta", "alpha", "mid"] {
+            manager
+                .add_provider(name.to_string(), "https://example.com".to_string(), "sk".to_string(), None, None)
+                .unwrap();
+        }
+        for model in ["m3", "m1", "m2"] {
+            let info = OpenCodeModelInfo {
+                name: model.to_string(),
+                limit: None,
+                model_detection: None,
+            };
+            manager.add_model("alpha", model.to_string(), info).unwrap();
         }
 
-        // 读取完整的 provider 配置
-        let opencode_config = self.read_config()?;
+        // 读回时保持插入顺序
+        let names: Vec<String> = manager.get_all_providers().unwrap().keys().cloned().collect();
+        assert_eq!(names, ["zeta", "alpha", "mid"]);
 
-        // 构建 provider 对象（包含所有指定的 provider）
-        let mut providers_map = serde_json::Map::new();
+        let order: Vec<String> = ["mid", "zeta", "alpha"].map(String::from).to_vec();
+        manager.reorder_providers(&order).unwrap();
+        let names: Vec<String> = manager.get_all_providers().unwrap().keys().cloned().collect();
+        assert_eq!(names, order);
 
-        for provider_name in provider_names {
-            if let Some(provider) = opencode_config.get_provider(provider_name) {
-                providers_map.insert(
-                    provider_name.clone(),
-                    serde_json::to_value(provider)
-                        .map_err(|e| format!("序列化 Provider '{}' 失败: {}", provider_name, e))?,
-                );
-            }
-        }
+        let order: Vec<String> = ["m1", "m2", "m3"].map(String::from).to_vec();
+        manager.reorder_models("alpha", &order).unwrap();
+        let ids: Vec<String> = manager.get_models("alpha").unwrap().keys().cloned().collect();
+        assert_eq!(ids, order);
 
-        // 构建完整的 opencode.json 结构
-        let sync_data = serde_json::json!({
-            "$schema": "https://opencode.ai/config.json",
-            "theme": "tokyonight",
-            "autoupdate": false,
-            "provider": providers_map,
-            "tools": {
-                "get-current-session-id": true,
-                "webfetch": true
-            },
-            "agent": {},
-            "mcp": {}
-        });
+        // 删除后其余条目顺序不变
+        manager.delete_model("alpha", "m2").unwrap();
+        let ids: Vec<String> = manager.get_models("alpha").unwrap().keys().cloned().collect();
+        assert_eq!(ids, ["m1", "m3"]);
 
-        let content = serde_json::to_string_pretty(&sync_data)
-            .map_err(|e| format!("序列化同步数据失败: {}", e))?;
+        // 顺序必须覆盖全部条目
+        assert!(manager.reorder_providers(&["mid".to_string()]).is_err());
 
-        fs::write(&project_opencode_json, content)
-            .map_err(|e| format!("写入项目 .opencode/opencode.json 失败: {}", e))
+        let _ = fs::remove_dir_all(&config_dir);
     }
 }
@@ -0,0 +1,116 @@
+// 主目录与数据目录解析
+// 所有配置路径 (~/.ca-switch、~/.claude、~/.codex 等) 都基于这里返回的主目录；
+// ca-switch 自身的数据 (站点列表、备份设置等) 位于 config_dir 返回的数据目录
+
+use crate::error::{CliError, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 覆盖主目录的环境变量
+pub const HOME_ENV: &str = "CA_SWITCH_HOME";
+
+/// 选择隔离数据目录的环境变量，数据位于 `~/.ca-switch/profiles/<名称>`
+pub const PROFILE_ENV: &str = "CA_SWITCH_PROFILE";
+
+/// 默认数据目录 (相对主目录)
+pub const DEFAULT_CONFIG_DIR: &str = ".ca-switch";
+
+/// 存放各 profile 数据目录的子目录 (相对默认数据目录)
+pub const PROFILES_DIR: &str = "profiles";
+
+/// 命令行 `--config-dir` 指定的数据目录
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// 设置命令行 `--config-dir` 指定的数据目录 (启动时调用一次)
+pub fn set_config_dir(dir: PathBuf) {
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
+
+/// 获取 ca-switch 的数据目录
+///
+/// 优先级: `--config-dir` > `$CA_SWITCH_PROFILE` (`~/.ca-switch/profiles/<名称>`) > `~/.ca-switch`
+pub fn config_dir(home: &Path) -> Result<PathBuf> {
+    resolve_config_dir(
+        home,
+        CONFIG_DIR_OVERRIDE.get().cloned(),
+        std::env::var(PROFILE_ENV).ok(),
+    )
+}
+
+/// 按优先级确定数据目录；profile 名称只能包含字母、数字、`-`、`_` 与 `.`，不能以 `.` 开头
+fn resolve_config_dir(home: &Path, override_dir: Option<PathBuf>, profile: Option<String>) -> Result<PathBuf> {
+    if let Some(dir) = override_dir.filter(|dir| !dir.as_os_str().is_empty()) {
+        return Ok(dir);
+    }
+
+    let default_dir = home.join(DEFAULT_CONFIG_DIR);
+    let Some(profile) = profile.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) else {
+        return Ok(default_dir);
+    };
+
+    let valid = !profile.starts_with('.')
+        && profile.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(CliError::Config(format!(
+            "{PROFILE_ENV} 的值 '{profile}' 无效，只能包含字母、数字、'-'、'_' 与 '.'，且不能以 '.' 开头"
+        )));
+    }
+
+    Ok(default_dir.join(PROFILES_DIR).join(profile))
+}
+
+/// 获取主目录
+///
+/// 依次使用 `$CA_SWITCH_HOME`、系统主目录与 `$HOME`；都无法确定时返回错误 (如无用户信息的精简容器)
+pub fn home_dir() -> Result<PathBuf> {
+    resolve_home(
+        std::env::var_os(HOME_ENV).map(PathBuf::from),
+        dirs::home_dir(),
+        std::env::var_os("HOME").map(PathBuf::from),
+    )
+}
+
+/// 按优先级选取第一个非空的候选目录
+fn resolve_home(override_dir: Option<PathBuf>, system_dir: Option<PathBuf>, home_env: Option<PathBuf>) -> Result<PathBuf> {
+    [override_dir, system_dir, home_env]
+        .into_iter()
+        .flatten()
+        .find(|dir| !dir.as_os_str().is_empty())
+        .ok_or_else(|| CliError::Config(format!("无法获取用户主目录，请设置 {HOME_ENV} 或 HOME 环境变量")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_home_order() {
+        let dir = |p: &str| Some(PathBuf::from(p));
+
+        assert_eq!(resolve_home(dir("/override"), dir("/sys"), dir("/env")).unwrap(), PathBuf::from("/override"));
+        assert_eq!(resolve_home(dir(""), dir("/sys"), dir("/env")).unwrap(), PathBuf::from("/sys"));
+        assert_eq!(resolve_home(None, None, dir("/env")).unwrap(), PathBuf::from("/env"));
+        assert!(matches!(resolve_home(None, None, dir("")), Err(CliError::Config(_))));
+    }
+
+    #[test]
+    fn test_resolve_config_dir_precedence() {
+        let home = Path::new("/home/me");
+        let profile = |p: &str| Some(p.to_string());
+
+        assert_eq!(resolve_config_dir(home, None, None).unwrap(), home.join(".ca-switch"));
+        assert_eq!(resolve_config_dir(home, None, profile(" ")).unwrap(), home.join(".ca-switch"));
+        assert_eq!(
+            resolve_config_dir(home, None, profile("client-a")).unwrap(),
+            home.join(".ca-switch/profiles/client-a")
+        );
+        // --config-dir 优先于环境变量
+        assert_eq!(
+            resolve_config_dir(home, Some(PathBuf::from("/data/cas")), profile("client-a")).unwrap(),
+            PathBuf::from("/data/cas")
+        );
+        for bad in ["../x", "a/b", ".hidden"] {
+            assert!(matches!(resolve_config_dir(home, None, profile(bad)), Err(CliError::Config(_))));
+        }
+    }
+}
@@ -18,6 +18,18 @@ pub enum CliError {
     #[error("配置错误: {0}")]
     Config(String),
 
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("请求超时: {0}")]
+    Timeout(String),
+
+    #[error("认证失败: {0}")]
+    AuthFailed(String),
+
+    #[error("{0}")]
+    Parse(String),
+
     #[error("WebDAV 错误: {0}")]
     WebDav(String),
 
@@ -25,10 +37,88 @@ pub enum CliError {
     UserCancelled,
 
     #[error("{0}")]
-    #[allow(dead_code)]
     Custom(String),
 }
 
+// ============================================================================
+// 进程退出码 (供脚本根据失败类型分支处理)
+// ============================================================================
+
+/// 一般错误
+pub const EXIT_FAILURE: i32 = 1;
+/// 站点、配置、备份等目标不存在
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// 认证失败 (API Key / WebDAV 密码错误)
+pub const EXIT_AUTH_FAILED: i32 = 4;
+/// 网络请求超时
+pub const EXIT_TIMEOUT: i32 = 5;
+/// 配置文件或响应内容解析失败
+pub const EXIT_PARSE: i32 = 6;
+/// 文件读写失败
+pub const EXIT_IO: i32 = 7;
+/// 用户取消操作 (Esc / Ctrl-C，与 shell 中 SIGINT 的约定一致)
+pub const EXIT_CANCELLED: i32 = 130;
+
+/// 去掉网络请求错误中 URL 携带的用户名和密码，避免错误信息泄露凭据
+pub fn strip_url_credentials(mut e: reqwest::Error) -> reqwest::Error {
+    if let Some(url) = e.url_mut() {
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+    }
+    e
+}
+
+impl CliError {
+    /// 将网络请求错误转换为带上下文的错误，超时单独归类
+    pub fn request(context: &str, e: reqwest::Error) -> Self {
+        let e = strip_url_credentials(e);
+        if e.is_timeout() {
+            CliError::Timeout(format!("{context}: {e}"))
+        } else if let Some(status @ (reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN)) =
+            e.status()
+        {
+            CliError::AuthFailed(format!("{context}: HTTP {status}"))
+        } else {
+            CliError::WebDav(format!("{context}: {e}"))
+        }
+    }
+
+    /// 是否为用户取消 (选择/输入提示中按 Esc 或 Ctrl-C)
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            CliError::UserCancelled => true,
+            CliError::Dialoguer(dialoguer::Error::IO(e)) | CliError::Io(e) => {
+                e.kind() == std::io::ErrorKind::Interrupted
+            }
+            _ => false,
+        }
+    }
+
+    /// 该错误对应的进程退出码
+    pub fn exit_code(&self) -> i32 {
+        if self.is_cancelled() {
+            return EXIT_CANCELLED;
+        }
+        match self {
+            CliError::NotFound(_) => EXIT_NOT_FOUND,
+            CliError::AuthFailed(_) => EXIT_AUTH_FAILED,
+            CliError::Timeout(_) => EXIT_TIMEOUT,
+            CliError::Parse(_) | CliError::Json(_) => EXIT_PARSE,
+            CliError::Io(_) => EXIT_IO,
+            CliError::Http(e) if e.is_timeout() => EXIT_TIMEOUT,
+            CliError::Http(e)
+                if matches!(
+                    e.status(),
+                    Some(reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN)
+                ) =>
+            {
+                EXIT_AUTH_FAILED
+            }
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
 // 实现 From<String> 转换，方便新架构使用
 impl From<String> for CliError {
     fn from(s: String) -> Self {
@@ -44,3 +134,36 @@ impl From<&str> for CliError {
 }
 
 pub type Result<T> = std::result::Result<T, CliError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = [
+            CliError::Custom("x".into()),
+            CliError::NotFound("x".into()),
+            CliError::AuthFailed("x".into()),
+            CliError::Timeout("x".into()),
+            CliError::Parse("x".into()),
+            CliError::Io(std::io::Error::other("x")),
+            CliError::UserCancelled,
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(CliError::exit_code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert_eq!(CliError::Config("x".into()).exit_code(), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn test_cancelled_errors() {
+        let interrupted = || std::io::Error::new(std::io::ErrorKind::Interrupted, "read interrupted");
+
+        assert!(CliError::UserCancelled.is_cancelled());
+        assert!(CliError::Dialoguer(dialoguer::Error::IO(interrupted())).is_cancelled());
+        assert_eq!(CliError::Io(interrupted()).exit_code(), EXIT_CANCELLED);
+        assert!(!CliError::Io(std::io::Error::other("x")).is_cancelled());
+    }
+}
@@ -2,48 +2,141 @@ mod cli;
 mod commands;
 mod config;
 mod error;
+mod logging;
 mod ui;
 
 use clap::Parser;
-use cli::{Cli, Commands, ExportType};
+use cli::{ApiAction, BackupAction, Cli, Commands, ConfigAction, ExportType, OpenCodeAction, ProfileAction, PromptAction};
 use error::Result;
 use ui::Menu;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
+    ui::init_output_mode(cli.no_color);
+    ui::set_quiet_mode(cli.quiet);
+    logging::init(cli.verbose);
+    ui::set_assume_yes(cli.yes);
+    if let Some(ref dir) = cli.config_dir {
+        config::paths::set_config_dir(dir.clone());
+    }
+
+    if let Err(e) = run(cli).await {
+        if e.is_cancelled() {
+            ui::report_cancelled();
+        } else {
+            ui::report_error(&e.to_string());
+        }
+        std::process::exit(e.exit_code());
+    }
+}
 
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Some(Commands::Claude) => {
+        Some(Commands::Claude { dry_run, action }) => {
             let mut cmd = commands::ClaudeCommand::new()?;
-            cmd.execute()?;
+            cmd.set_dry_run(dry_run);
+            match action {
+                Some(ApiAction::Delete { site }) => cmd.delete_site_by_name(&site)?,
+                Some(ApiAction::Apply { project }) => cmd.apply_active(project)?,
+                Some(ApiAction::Env { fish }) => cmd.print_env(fish)?,
+                Some(ApiAction::Current) => cmd.print_current()?,
+                Some(ApiAction::List { json, tag, offset, limit }) => cmd.list(json, tag.as_deref(), offset, limit)?,
+                Some(ApiAction::Switch { tag }) => cmd.switch_site(tag.as_deref())?,
+                Some(ApiAction::ImportExisting) => cmd.import_existing()?,
+                None => cmd.execute()?,
+            }
         }
-        Some(Commands::Codex) => {
+        Some(Commands::Codex { dry_run, action }) => {
             let mut cmd = commands::CodexCommand::new()?;
-            cmd.execute()?;
+            cmd.set_dry_run(dry_run);
+            match action {
+                Some(ApiAction::Delete { site }) => cmd.delete_site_by_name(&site)?,
+                Some(ApiAction::Apply { project }) => cmd.apply_active(project)?,
+                Some(ApiAction::Env { fish }) => cmd.print_env(fish)?,
+                Some(ApiAction::Current) => cmd.print_current()?,
+                Some(ApiAction::List { json, tag, offset, limit }) => cmd.list(json, tag.as_deref(), offset, limit)?,
+                Some(ApiAction::Switch { tag }) => cmd.switch_site(tag.as_deref())?,
+                Some(ApiAction::ImportExisting) => cmd.import_existing()?,
+                None => cmd.execute()?,
+            }
         }
-        Some(Commands::Gemini) => {
+        Some(Commands::Gemini { dry_run, action }) => {
             let mut cmd = commands::GeminiCommand::new()?;
-            cmd.execute()?;
+            cmd.set_dry_run(dry_run);
+            match action {
+                Some(ApiAction::Delete { site }) => cmd.delete_site_by_name(&site)?,
+                Some(ApiAction::Apply { project }) => cmd.apply_active(project)?,
+                Some(ApiAction::Env { fish }) => cmd.print_env(fish)?,
+                Some(ApiAction::Current) => cmd.print_current()?,
+                Some(ApiAction::List { json, tag, offset, limit }) => cmd.list(json, tag.as_deref(), offset, limit)?,
+                Some(ApiAction::Switch { tag }) => cmd.switch_site(tag.as_deref())?,
+                Some(ApiAction::ImportExisting) => return Err("gemini 暂不支持 import-existing，目前仅支持 claude 与 codex".into()),
+                None => cmd.execute()?,
+            }
         }
-        Some(Commands::OpenCode) => {
+        Some(Commands::OpenCode { action }) => {
             let mut cmd = commands::OpenCodeCommand::new()?;
-            cmd.execute()?;
+            match action {
+                Some(OpenCodeAction::Current) => cmd.print_current()?,
+                None => cmd.execute()?,
+            }
         }
-        Some(Commands::Backup) => {
+        Some(Commands::Backup { force, action }) => {
             let mut cmd = commands::BackupCommand::new()?;
-            cmd.execute().await?;
+            cmd.set_force(force);
+            match action {
+                Some(BackupAction::Test) => cmd.test_webdav().await?,
+                Some(BackupAction::Cleanup { keep, dry_run }) => cmd.cleanup(keep, dry_run).await?,
+                Some(BackupAction::Run { categories }) => cmd.run(&categories).await?,
+                None => cmd.execute().await?,
+            }
+        }
+        Some(Commands::Prompt { action }) => {
+            let mut cmd = commands::PromptCommand::new()?;
+            match action {
+                Some(PromptAction::Export { file }) => cmd.export_library(&file)?,
+                Some(PromptAction::Import { file }) => cmd.import_library(&file)?,
+                Some(PromptAction::Get { name }) => print!("{}", cmd.get_content(&name)?),
+                None => cmd.execute().await?,
+            }
         }
         Some(Commands::Status) => {
             show_status()?;
         }
-        Some(Commands::Export { config_type }) => {
-            match config_type {
-                ExportType::OpenCode => {
-                    export_opencode_config()?;
+        Some(Commands::Switch { key }) => {
+            commands::ProfileCommand::new()?.switch_key(&key)?;
+        }
+        Some(Commands::Profile { action }) => {
+            let mut cmd = commands::ProfileCommand::new()?;
+            match action {
+                ProfileAction::Save { name } => cmd.save(&name)?,
+                ProfileAction::Use { name } => cmd.use_profile(&name)?,
+                ProfileAction::List { json } => cmd.list(json)?,
+                ProfileAction::Delete { name } => cmd.delete(&name)?,
+            }
+        }
+        Some(Commands::Config { action }) => match action {
+            ConfigAction::Path { json } => show_config_paths(json)?,
+        },
+        Some(Commands::Export {
+            config_type,
+            provider,
+            site,
+            redact,
+            force,
+        }) => {
+            if let (Some(provider), Some(site)) = (provider, site) {
+                export_site(&provider, &site, redact, force)?;
+            } else {
+                if let Some(config_type) = config_type {
+                    export_live_config(&config_type, force)?;
                 }
             }
         }
+        Some(Commands::Import { file }) => {
+            import_sites(&file)?;
+        }
         None => {
             // 没有子命令时，显示交互式菜单
             let mut menu = Menu::new();
@@ -54,21 +147,115 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// 显示配置文件路径
+fn show_config_paths(json: bool) -> Result<()> {
+    use console::style;
+
+    let config_manager = config::ConfigManager::new()?;
+    let paths = config_manager.config_paths();
+
+    if json {
+        let map: serde_json::Map<String, serde_json::Value> = paths
+            .iter()
+            .map(|(name, path)| {
+                let entry = serde_json::json!({
+                    "path": path.display().to_string(),
+                    "exists": path.exists(),
+                });
+                (name.to_string(), entry)
+            })
+            .collect();
+        let content = serde_json::to_string_pretty(&map).map_err(|e| format!("序列化路径失败: {}", e))?;
+        println!("{}", content);
+        return Ok(());
+    }
+
+    let width = paths.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, path) in &paths {
+        let state = if path.exists() {
+            style("存在").green()
+        } else {
+            style("不存在").dim()
+        };
+        println!("{:<width$}  {}  ({})", name, path.display(), state, width = width);
+    }
+
+    Ok(())
+}
+
+/// 某个工具的激活状态
+enum ActiveState<T> {
+    Active(T),
+    Unset,
+    /// config.json 中的引用指向已删除的站点或密钥
+    Stale(String),
+}
+
+/// 区分未配置与引用失效，其他错误照常返回
+fn active_state<T>(result: Result<Option<T>>) -> Result<ActiveState<T>> {
+    match result {
+        Ok(Some(config)) => Ok(ActiveState::Active(config)),
+        Ok(None) => Ok(ActiveState::Unset),
+        Err(error::CliError::NotFound(msg)) => Ok(ActiveState::Stale(msg)),
+        Err(e) => Err(e),
+    }
+}
+
+/// 提示激活引用已失效
+fn show_stale_reference(tool: &str, msg: &str) {
+    ui::show_warning(&format!(
+        "{} 的激活配置已失效: {}，请重新切换 (ca-switch {})",
+        tool,
+        msg,
+        tool.to_lowercase()
+    ));
+}
+
 /// 显示状态
 fn show_status() -> Result<()> {
     use console::style;
     use config::ConfigManager;
     use ui::show_info;
 
-    println!("\n{}", style("📊 当前配置状态").cyan().bold());
-    println!("{}", style("═".repeat(40)).dim());
-
     let config_manager = ConfigManager::new()?;
+    let claude = active_state(config_manager.get_active_claude_config())?;
+    let codex = active_state(config_manager.get_active_codex_config())?;
+    let gemini = active_state(config_manager.get_active_gemini_config())?;
+    let opencode = active_state(config_manager.get_active_opencode_config())?;
+
+    // 安静模式: 每个已配置的工具输出一行 `<工具>\t<站点>/<密钥>`
+    // 引用失效时的提示输出到标准错误，不影响标准输出的解析
+    if ui::is_quiet_mode() {
+        match claude {
+            ActiveState::Active(config) => println!("claude\t{}/{}", config.site, config.token_name),
+            ActiveState::Stale(msg) => show_stale_reference("Claude", &msg),
+            ActiveState::Unset => {}
+        }
+        match codex {
+            ActiveState::Active(config) => println!("codex\t{}/{}", config.site, config.api_key_name),
+            ActiveState::Stale(msg) => show_stale_reference("Codex", &msg),
+            ActiveState::Unset => {}
+        }
+        match gemini {
+            ActiveState::Active(config) => println!("gemini\t{}/{}", config.site, config.api_key_name),
+            ActiveState::Stale(msg) => show_stale_reference("Gemini", &msg),
+            ActiveState::Unset => {}
+        }
+        match opencode {
+            ActiveState::Active(config) => println!("opencode\t{}", config.provider),
+            ActiveState::Stale(msg) => show_stale_reference("OpenCode", &msg),
+            ActiveState::Unset => {}
+        }
+        return Ok(());
+    }
+
+    println!("\n{}", style(ui::plain_text("📊 当前配置状态")).cyan().bold());
+    println!("{}", style(ui::separator(40)).dim());
 
     // 显示 Claude 配置
-    println!("\n{}", style("🤖 Claude 配置:").white().bold());
-    match config_manager.get_active_claude_config()? {
-        Some(config) => {
+    println!("\n{}", style(ui::plain_text("🤖 Claude 配置:")).white().bold());
+    match claude {
+        ActiveState::Active(config) => {
             println!("  {} {}", style("站点:").white(), style(&config.site).cyan());
             println!("  {} {}", style("URL:").white(), style(&config.site_url).dim());
             println!("  {} {}", style("Token:").white(), style(&config.token_name).cyan());
@@ -79,15 +266,16 @@ fn show_status() -> Result<()> {
                 println!("  {} {}", style("Model:").white(), style(model).yellow());
             }
         }
-        None => {
+        ActiveState::Stale(msg) => show_stale_reference("Claude", &msg),
+        ActiveState::Unset => {
             show_info("未配置 Claude API");
         }
     }
 
     // 显示 Codex 配置
-    println!("\n{}", style("💻 Codex 配置:").white().bold());
-    match config_manager.get_active_codex_config()? {
-        Some(config) => {
+    println!("\n{}", style(ui::plain_text("💻 Codex 配置:")).white().bold());
+    match codex {
+        ActiveState::Active(config) => {
             println!("  {} {}", style("站点:").white(), style(&config.site).cyan());
             if let Some(ref base_url) = config.base_url {
                 println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
@@ -96,42 +284,52 @@ fn show_status() -> Result<()> {
             if let Some(ref model) = config.model {
                 println!("  {} {}", style("Model:").white(), style(model).yellow());
             }
-            if let Some(ref provider) = config.model_provider {
-                println!("  {} {}", style("Model Provider:").white(), style(provider).green());
-            }
+            println!("  {} {}", style("Model Provider:").white(), style(&config.model_provider).green());
         }
-        None => {
+        ActiveState::Stale(msg) => show_stale_reference("Codex", &msg),
+        ActiveState::Unset => {
             show_info("未配置 Codex API");
         }
     }
 
     // 显示 Gemini 配置
-    println!("\n{}", style("🌟 Gemini 配置:").white().bold());
-    match config_manager.get_active_gemini_config()? {
-        Some(config) => {
+    println!("\n{}", style(ui::plain_text("🌟 Gemini 配置:")).white().bold());
+    match gemini {
+        ActiveState::Active(config) => {
             println!("  {} {}", style("站点:").white(), style(&config.site).cyan());
             if let Some(ref base_url) = config.base_url {
                 println!("  {} {}", style("Base URL:").white(), style(base_url).dim());
             }
             println!("  {} {}", style("API Key:").white(), style(&config.api_key_name).cyan());
             if let Some(ref model) = config.model {
-                println!("  {} {}", style("Model:").white(), style(model).yellow());
+                if config.model_is_default {
+                    println!(
+                        "  {} {} {}",
+                        style("Model:").white(),
+                        style(model).yellow(),
+                        style("(默认)").dim()
+                    );
+                } else {
+                    println!("  {} {}", style("Model:").white(), style(model).yellow());
+                }
             }
         }
-        None => {
+        ActiveState::Stale(msg) => show_stale_reference("Gemini", &msg),
+        ActiveState::Unset => {
             show_info("未配置 Gemini API");
         }
     }
 
-    println!("\n{}", style("🚀 OpenCode 配置:").white().bold());
-    match config_manager.get_active_opencode_config()? {
-        Some(config) => {
+    println!("\n{}", style(ui::plain_text("🚀 OpenCode 配置:")).white().bold());
+    match opencode {
+        ActiveState::Active(config) => {
             println!("  {} {}", style("Provider:").white(), style(&config.provider).cyan());
             println!("  {} {}", style("Base URL:").white(), style(&config.base_url).dim());
             let model_list: Vec<&str> = config.models.keys().map(|s| s.as_str()).collect();
             println!("  {} {}", style("可用模型:").white(), style(model_list.join(", ")).yellow());
         }
-        None => {
+        ActiveState::Stale(msg) => show_stale_reference("OpenCode", &msg),
+        ActiveState::Unset => {
             show_info("未配置 OpenCode");
         }
     }
@@ -168,57 +366,226 @@ fn show_help() -> Result<()> {
     Ok(())
 }
 
-/// 导出 OpenCode 配置到当前目录
-fn export_opencode_config() -> Result<()> {
+/// 将工具当前使用的配置文件导出到当前目录的同名目录下（如 ~/.codex/config.toml -> ./.codex/config.toml）
+///
+/// 目标文件已存在时需确认（`force` 时直接覆盖）；源文件部分缺失时只导出存在的文件
+fn export_live_config(config_type: &ExportType, force: bool) -> Result<()> {
     use console::style;
-    use ui::{show_error, show_info, show_success};
+    use ui::{show_error, show_info, show_success, show_warning};
 
-    println!("\n{}", style("📤 导出 OpenCode 配置").cyan().bold());
+    println!("\n{}", style(format!("📤 导出 {} 配置", config_type)).cyan().bold());
     println!("{}", style("═".repeat(40)).dim());
     println!();
 
-    // 获取源文件路径 ($HOME/.opencode/opencode.json)
-    let home_dir = dirs::home_dir().ok_or("无法获取用户主目录")?;
-    let source_path = home_dir.join(".opencode").join("opencode.json");
+    let config_manager = config::ConfigManager::new()?;
+    let sources = config_manager.live_config_files(&config_type.to_string())?;
+    let (sources, missing): (Vec<_>, Vec<_>) = sources.into_iter().partition(|path| path.exists());
 
     // 检查源文件是否存在
-    if !source_path.exists() {
+    if sources.is_empty() {
         show_error("源配置文件不存在");
-        show_info("请先切换配置以生成 ~/.opencode/opencode.json");
+        for path in &missing {
+            show_info(&format!("请先切换配置以生成 {}", path.display()));
+        }
         return Ok(());
     }
+    for path in &missing {
+        show_warning(&format!("跳过不存在的文件: {}", path.display()));
+    }
 
-    // 获取目标文件路径 (当前目录/.opencode/opencode.json)
+    // 目标文件: 当前目录/<源文件所在目录名>/<文件名>
     let current_dir = std::env::current_dir()
         .map_err(|e| format!("无法获取当前目录: {}", e))?;
-    let target_dir = current_dir.join(".opencode");
-    let target_path = target_dir.join("opencode.json");
+    let files: Vec<(std::path::PathBuf, std::path::PathBuf)> = sources
+        .into_iter()
+        .map(|source| {
+            let dir_name = source.parent().and_then(|dir| dir.file_name()).unwrap_or_default();
+            let target = current_dir.join(dir_name).join(source.file_name().unwrap_or_default());
+            (source, target)
+        })
+        .collect();
 
     // 显示路径信息
-    println!("{}", style("源文件:").white());
-    println!("  {}", style(source_path.display()).cyan());
-    println!();
-    println!("{}", style("目标文件:").white());
-    println!("  {}", style(target_path.display()).cyan());
-    println!();
-
-    // 如果目标文件已存在，显示警告
-    if target_path.exists() {
-        println!("{}", style("⚠️  目标文件已存在，将被覆盖").yellow());
+    for (source, target) in &files {
+        println!("{}", style("源文件:").white());
+        println!("  {}", style(source.display()).cyan());
+        println!("{}", style("目标文件:").white());
+        println!("  {}", style(target.display()).cyan());
         println!();
     }
 
-    // 创建目标目录
-    std::fs::create_dir_all(&target_dir)
-        .map_err(|e| format!("创建目标目录失败: {}", e))?;
+    let targets: Vec<&std::path::Path> = files.iter().map(|(_, target)| target.as_path()).collect();
+    confirm_export_overwrite(&targets, force)?;
+
+    for (source, target) in &files {
+        // 创建目标目录
+        if let Some(target_dir) = target.parent() {
+            std::fs::create_dir_all(target_dir)
+                .map_err(|e| format!("创建目标目录失败: {}", e))?;
+        }
 
-    // 复制文件
-    std::fs::copy(&source_path, &target_path)
-        .map_err(|e| format!("复制文件失败: {}", e))?;
+        // 复制文件
+        std::fs::copy(source, target)
+            .map_err(|e| format!("复制文件失败: {}", e))?;
+    }
 
     show_success("✨ 配置已成功导出到当前目录！");
     println!();
+    for (_, target) in &files {
+        show_info(&format!("目标路径: {}", target.display()));
+    }
+    show_warning("导出的配置文件可能包含明文密钥，请勿提交到版本库");
+    println!();
+
+    Ok(())
+}
+
+/// 导出单个站点到当前目录
+fn export_site(provider: &ExportType, site_name: &str, redact: bool, force: bool) -> Result<()> {
+    use config::ConfigManager;
+    use console::style;
+    use ui::{show_info, show_success, show_warning};
+
+    println!("\n{}", style("📤 导出站点配置").cyan().bold());
+    println!("{}", style("═".repeat(40)).dim());
+    println!();
+
+    let config_manager = ConfigManager::new()?;
+    let export = config_manager.export_site(&provider.to_string(), site_name, redact)?;
+
+    let current_dir = std::env::current_dir()
+        .map_err(|e| format!("无法获取当前目录: {}", e))?;
+    let target_path = current_dir.join(export.file_name());
+    confirm_export_overwrite(&[target_path.as_path()], force)?;
+
+    let content = serde_json::to_string_pretty(&export)?;
+    std::fs::write(&target_path, content)
+        .map_err(|e| format!("写入导出文件失败: {}", e))?;
+
+    show_success(&format!("站点 '{}' 已导出", site_name));
     show_info(&format!("目标路径: {}", target_path.display()));
+    if redact {
+        show_info("已移除 Token/API Key，导入后需重新添加");
+    } else {
+        show_warning("导出文件包含明文密钥，请妥善保管");
+    }
+    println!();
+
+    Ok(())
+}
+
+/// 导出目标已存在时确认是否覆盖，`force` 时跳过确认；拒绝覆盖时返回取消
+fn confirm_export_overwrite(targets: &[&std::path::Path], force: bool) -> Result<()> {
+    let existing: Vec<String> = targets
+        .iter()
+        .filter(|target| target.exists())
+        .map(|target| target.display().to_string())
+        .collect();
+    if existing.is_empty() {
+        return Ok(());
+    }
+
+    if force {
+        ui::show_warning(&format!("目标文件已存在，将被覆盖 (--force): {}", existing.join(", ")));
+        return Ok(());
+    }
+
+    ui::show_warning("目标文件已存在");
+    if !ui::confirm_destructive(&format!("确认覆盖 {}", existing.join(", ")))? {
+        return Err(error::CliError::UserCancelled);
+    }
+    Ok(())
+}
+
+/// 从导出文件导入站点（支持单个站点或站点数组）
+fn import_sites(file: &std::path::Path) -> Result<()> {
+    use config::{ConfigManager, SiteExport};
+    use console::style;
+    use dialoguer::{theme::ColorfulTheme, Input, Select};
+    use ui::{show_info, show_success, show_warning};
+
+    println!("\n{}", style("📥 导入站点配置").cyan().bold());
+    println!("{}", style("═".repeat(40)).dim());
+    println!();
+
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| format!("读取导入文件失败: {}", e))?;
+
+    let exports: Vec<SiteExport> = match serde_json::from_str::<Vec<SiteExport>>(&content) {
+        Ok(list) => list,
+        Err(_) => vec![serde_json::from_str::<SiteExport>(&content)
+            .map_err(|e| format!("解析导入文件失败: {}", e))?],
+    };
+
+    let mut config_manager = ConfigManager::new()?;
+    let mut imported_sites = 0;
+    let mut imported_keys = 0;
+    let mut skipped: Vec<String> = Vec::new();
+
+    for export in &exports {
+        let provider = export.provider();
+        let label = format!("{}/{}", provider, export.name);
+
+        if let Err(e) = export.validate() {
+            show_warning(&format!("跳过 {}: {}", label, e));
+            skipped.push(label);
+            continue;
+        }
+
+        let mut target_name = export.name.clone();
+        if config_manager.site_exists(provider, &target_name)? {
+            let choice = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("{} 已存在，如何处理?", label))
+                .items(&["跳过", "覆盖", "重命名"])
+                .default(0)
+                .interact()
+                .map_err(|_| error::CliError::UserCancelled)?;
+
+            match choice {
+                1 => {}
+                2 => loop {
+                    let new_name: String = Input::with_theme(&ColorfulTheme::default())
+                        .with_prompt("新的名称")
+                        .interact_text()
+                        .map_err(|_| error::CliError::UserCancelled)?;
+                    let new_name = new_name.trim().to_string();
+
+                    if new_name.is_empty() {
+                        show_warning("名称不能为空");
+                    } else if config_manager.site_exists(provider, &new_name)? {
+                        show_warning(&format!("'{}' 也已存在，请换一个名称", new_name));
+                    } else {
+                        target_name = new_name;
+                        break;
+                    }
+                },
+                _ => {
+                    skipped.push(label);
+                    continue;
+                }
+            }
+        }
+
+        config_manager.import_site(export, &target_name)?;
+        imported_sites += 1;
+        imported_keys += export.key_count();
+        show_success(&format!("已导入 {}/{}", provider, target_name));
+
+        if export.redacted {
+            show_info(&format!("{}/{} 的密钥已在导出时移除，请重新添加", provider, target_name));
+        }
+    }
+
+    println!();
+    show_info(&format!(
+        "共导入 {} 个站点、{} 个密钥，跳过 {} 个",
+        imported_sites,
+        imported_keys,
+        skipped.len()
+    ));
+    for label in &skipped {
+        println!("  {} {}", style("-").dim(), style(label).dim());
+    }
     println!();
 
     Ok(())
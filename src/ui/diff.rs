@@ -0,0 +1,225 @@
+// 行级 diff 显示
+// 用于 dry-run 预览将要写入的配置文件变更，以及覆盖已有文件前的确认
+
+use crate::ui::redact;
+use console::style;
+use std::fs;
+use std::path::PathBuf;
+
+/// 预览中需要脱敏的密钥字段 (JSON 键或 .env 变量名)
+const SECRET_FIELDS: &[&str] = &[
+    "OPENAI_API_KEY",
+    "ANTHROPIC_AUTH_TOKEN",
+    "ANTHROPIC_API_KEY",
+    "AWS_BEARER_TOKEN_BEDROCK",
+    "GEMINI_API_KEY",
+    "GOOGLE_API_KEY",
+];
+
+/// diff 中的一行
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+/// 计算两段文本的行级 diff（基于最长公共子序列）
+pub fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j] = old_lines[i..] 与 new_lines[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            result.push(DiffLine::Same(old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old_lines[i]));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new_lines[j]));
+            j += 1;
+        }
+    }
+    result.extend(old_lines[i..].iter().map(|l| DiffLine::Removed(l)));
+    result.extend(new_lines[j..].iter().map(|l| DiffLine::Added(l)));
+
+    result
+}
+
+/// 显示单个文件的 diff
+pub fn show_line_diff(old: &str, new: &str) {
+    for line in line_diff(old, new) {
+        match line {
+            DiffLine::Same(l) => println!("{}", style(format!("  {}", l)).dim()),
+            DiffLine::Added(l) => println!("{}", style(format!("+ {}", l)).green()),
+            DiffLine::Removed(l) => println!("{}", style(format!("- {}", l)).red()),
+        }
+    }
+}
+
+/// 对文件内容中的密钥字段脱敏，支持 `"KEY": "value"` 与 `KEY=value` 两种写法
+///
+/// dry-run 与覆盖确认的输出常被粘贴到 issue 或 CI 日志中，不能包含明文密钥
+pub fn redact_secrets(content: &str) -> String {
+    content.lines().map(redact_secret_line).collect::<Vec<_>>().join("\n")
+}
+
+/// 对单行中第一个密钥字段的值脱敏
+fn redact_secret_line(line: &str) -> String {
+    let Some(start) = SECRET_FIELDS
+        .iter()
+        .find_map(|field| line.find(field).map(|index| index + field.len()))
+    else {
+        return line.to_string();
+    };
+
+    let rest = &line[start..];
+    let value = rest.trim_start_matches(['"', ' ', ':', '=']);
+    let value_start = start + rest.len() - value.len();
+    let value_end = value_start + value.find('"').unwrap_or(value.len());
+    if value_end == value_start {
+        return line.to_string();
+    }
+
+    format!(
+        "{}{}{}",
+        &line[..value_start],
+        redact(&line[value_start..value_end]),
+        &line[value_end..]
+    )
+}
+
+/// 显示将要写入的文件与当前文件之间的 diff（不写入任何文件），密钥字段脱敏显示
+pub fn show_file_diffs(files: &[(PathBuf, String)]) {
+    for (path, new_content) in files {
+        let old_content = fs::read_to_string(path).ok();
+
+        println!();
+        match old_content {
+            Some(_) => println!("{}", style(format!("📄 {}", path.display())).cyan().bold()),
+            None => println!(
+                "{} {}",
+                style(format!("📄 {}", path.display())).cyan().bold(),
+                style("(新文件)").yellow()
+            ),
+        }
+
+        let old_content = old_content.unwrap_or_default();
+        if old_content == *new_content {
+            println!("{}", style("  (无变化)").dim());
+        } else {
+            show_line_diff(&redact_secrets(&old_content), &redact_secrets(new_content));
+        }
+    }
+    println!();
+}
+
+/// 显示将被覆盖的已有文件的 diff（新文件与无变化的文件不显示），密钥字段脱敏显示
+///
+/// 返回是否有已有文件会被修改
+pub fn show_overwrite_diffs(files: &[(PathBuf, String)]) -> bool {
+    let mut changed = false;
+    for (path, new_content) in files {
+        let Ok(old_content) = fs::read_to_string(path) else {
+            continue;
+        };
+        if old_content == *new_content {
+            continue;
+        }
+
+        println!();
+        println!("{}", style(format!("📄 {}", path.display())).cyan().bold());
+        show_line_diff(&redact_secrets(&old_content), &redact_secrets(new_content));
+        changed = true;
+    }
+    if changed {
+        println!();
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        let old = "a\nb\nc\n";
+        let new = "a\nx\nc\nd\n";
+
+        assert_eq!(
+            line_diff(old, new),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_show_overwrite_diffs_only_existing_changed_files() {
+        let dir = std::env::temp_dir().join(format!("ca-switch-overwrite-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("settings.json");
+        fs::write(&existing, "a\nb\n").unwrap();
+
+        let new_file = (dir.join("missing.json"), "x".to_string());
+        assert!(!show_overwrite_diffs(std::slice::from_ref(&new_file)));
+        assert!(!show_overwrite_diffs(&[(existing.clone(), "a\nb\n".to_string())]));
+        assert!(show_overwrite_diffs(&[new_file, (existing, "a\nc\n".to_string())]));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_redact_secrets_hides_keys() {
+        let key = "sk-proj-1234567890abcdefghij";
+        let auth_json = format!("{{\n  \"OPENAI_API_KEY\": \"{key}\"\n}}");
+        let settings = format!("{{\"env\": {{\"ANTHROPIC_AUTH_TOKEN\": \"{key}\", \"X\": \"1\"}}}}");
+        let env = format!("GOOGLE_GEMINI_BASE_URL=https://api.example.com\nGEMINI_API_KEY={key}\n");
+
+        for content in [&auth_json, &settings, &env] {
+            let redacted = redact_secrets(content);
+            assert!(!redacted.contains(key), "{redacted}");
+            assert!(redacted.contains(&redact(key)), "{redacted}");
+        }
+
+        assert_eq!(
+            redact_secrets(&auth_json),
+            "{\n  \"OPENAI_API_KEY\": \"sk-pro****ghij\"\n}"
+        );
+        assert_eq!(
+            redact_secrets(&env),
+            "GOOGLE_GEMINI_BASE_URL=https://api.example.com\nGEMINI_API_KEY=sk-pro****ghij"
+        );
+        assert_eq!(redact_secrets("model = \"gpt-5\""), "model = \"gpt-5\"");
+    }
+
+    #[test]
+    fn test_line_diff_empty_old() {
+        assert_eq!(
+            line_diff("", "a\nb"),
+            vec![DiffLine::Added("a"), DiffLine::Added("b")]
+        );
+    }
+}
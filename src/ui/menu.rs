@@ -1,6 +1,6 @@
-use crate::commands::{BackupCommand, ClaudeCommand, CodexCommand, GeminiCommand, OpenCodeCommand};
+use crate::commands::{BackupCommand, ClaudeCommand, CodexCommand, GeminiCommand, OpenCodeCommand, PromptCommand};
 use crate::error::Result;
-use crate::ui::{show_banner, show_main_menu, MainMenuChoice};
+use crate::ui::{plain_text, select_theme_preset, show_banner, show_main_menu, MainMenuChoice};
 
 /// 菜单管理器
 pub struct Menu;
@@ -37,8 +37,15 @@ impl Menu {
                     let mut cmd = BackupCommand::new()?;
                     cmd.execute().await?;
                 }
+                MainMenuChoice::Prompt => {
+                    let mut cmd = PromptCommand::new()?;
+                    cmd.execute().await?;
+                }
+                MainMenuChoice::Theme => {
+                    select_theme_preset()?;
+                }
                 MainMenuChoice::Exit => {
-                    println!("\n👋 再见喵～");
+                    println!("\n{}", plain_text("👋 再见喵～"));
                     break;
                 }
             }
@@ -1,9 +1,16 @@
 // UI module
 // 用户界面模块
 
+pub mod diff;
 pub mod menu;
+pub mod shell;
+pub mod spinner;
 pub mod style;
+pub mod theme;
 
 // Re-export commonly used items
+pub use diff::*;
 pub use menu::*;
+pub use shell::*;
+pub use spinner::*;
 pub use style::*;
@@ -0,0 +1,51 @@
+// Shell 环境变量导出
+// 生成可被 `eval` 的 export / set -gx 语句
+
+/// 按目标 shell 的规则给值加单引号
+pub fn shell_quote(value: &str, fish: bool) -> String {
+    if fish {
+        // fish 的单引号内只有 \' 与 \\ 需要转义
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+    } else {
+        // POSIX shell 的单引号内不能转义，需要先结束引号再拼接 \'
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// 生成导出环境变量的语句，每行一个变量
+pub fn env_export_lines(vars: &[(&str, String)], fish: bool) -> String {
+    vars.iter()
+        .map(|(key, value)| {
+            let value = shell_quote(value, fish);
+            if fish {
+                format!("set -gx {} {}\n", key, value)
+            } else {
+                format!("export {}={}\n", key, value)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_export_lines() {
+        let vars = [
+            ("ANTHROPIC_BASE_URL", "https://api.example.com".to_string()),
+            ("ANTHROPIC_AUTH_TOKEN", "sk-it's $HOME \\n".to_string()),
+        ];
+
+        assert_eq!(
+            env_export_lines(&vars, false),
+            "export ANTHROPIC_BASE_URL='https://api.example.com'\n\
+             export ANTHROPIC_AUTH_TOKEN='sk-it'\\''s $HOME \\n'\n"
+        );
+        assert_eq!(
+            env_export_lines(&vars, true),
+            "set -gx ANTHROPIC_BASE_URL 'https://api.example.com'\n\
+             set -gx ANTHROPIC_AUTH_TOKEN 'sk-it\\'s $HOME \\\\n'\n"
+        );
+    }
+}
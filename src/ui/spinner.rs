@@ -0,0 +1,73 @@
+// 加载动画
+// 在站点检测、WebDAV 请求等耗时操作期间显示 spinner
+
+use super::style::{is_plain_mode, is_quiet_mode, plain_text, show_info};
+use super::theme::current_theme;
+use console::Term;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
+
+/// 加载动画
+///
+/// 结束时 (包括提前 return / `?` 出错导致的 drop) 会自动清除，不会残留在终端上。
+/// 标准错误不是终端时不显示动画，只输出一行普通提示
+pub struct Spinner {
+    bar: Option<ProgressBar>,
+}
+
+impl Spinner {
+    /// 开始显示加载动画
+    pub fn start(message: &str) -> Self {
+        if is_quiet_mode() || !Term::stderr().is_term() {
+            show_info(message);
+            return Self { bar: None };
+        }
+
+        let accent = current_theme().accent;
+        let template = if accent.is_empty() || is_plain_mode() {
+            "{spinner} {msg}".to_string()
+        } else {
+            format!("{{spinner:.{}}} {{msg}}", accent)
+        };
+        let style = ProgressStyle::with_template(&template)
+            .unwrap_or_else(|_| ProgressStyle::default_spinner());
+        let style = if is_plain_mode() {
+            style.tick_chars("|/-\\ ")
+        } else {
+            style
+        };
+
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(style);
+        bar.set_message(plain_text(message).into_owned());
+        bar.enable_steady_tick(Duration::from_millis(100));
+
+        Self { bar: Some(bar) }
+    }
+
+    /// 更新提示文字
+    pub fn set_message(&self, message: &str) {
+        match &self.bar {
+            Some(bar) => bar.set_message(plain_text(message).into_owned()),
+            None => show_info(message),
+        }
+    }
+
+    /// 结束并清除加载动画
+    pub fn finish(self) {
+        // 清除逻辑在 Drop 中完成
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// 显示加载动画 (`ui::spinner("正在检测站点...")`)
+pub fn spinner(message: &str) -> Spinner {
+    Spinner::start(message)
+}
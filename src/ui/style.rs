@@ -1,25 +1,284 @@
-use console::style;
-use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use console::{style, Term};
+use super::theme::{current_theme, set_theme, Theme, THEME_PRESETS};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme as PromptTheme};
+use crate::config::{KeyUsage, SiteSummary};
+use dialoguer::{Confirm, FuzzySelect, Input, Select};
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ============================================================================
+// 输出模式 (纯文本模式下不输出 emoji 与 ANSI 颜色)
+// ============================================================================
+
+static PLAIN_MODE: AtomicBool = AtomicBool::new(false);
+static QUIET_MODE: AtomicBool = AtomicBool::new(false);
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// 初始化输出模式
+///
+/// `force_plain` 对应命令行的 `--no-color` / `--plain`；此外当标准输出不是
+/// 支持颜色的终端 (管道、重定向、TERM=dumb、设置了 NO_COLOR) 或终端编码
+/// 不是 UTF-8 时，也会自动切换到纯文本模式
+pub fn init_output_mode(force_plain: bool) {
+    let plain = force_plain
+        || !Term::stdout().features().colors_supported()
+        || !terminal_is_utf8();
+    set_plain_mode(plain);
+}
+
+/// 设置是否使用纯文本模式
+pub fn set_plain_mode(plain: bool) {
+    PLAIN_MODE.store(plain, Ordering::Relaxed);
+    console::set_colors_enabled(!plain);
+    console::set_colors_enabled_stderr(!plain);
+}
+
+/// 当前是否为纯文本模式
+pub fn is_plain_mode() -> bool {
+    PLAIN_MODE.load(Ordering::Relaxed)
+}
+
+/// 设置安静模式 (`--quiet`): 不输出提示与装饰信息，警告和错误改写到标准错误
+pub fn set_quiet_mode(quiet: bool) {
+    QUIET_MODE.store(quiet, Ordering::Relaxed);
+}
+
+/// 当前是否为安静模式
+pub fn is_quiet_mode() -> bool {
+    QUIET_MODE.load(Ordering::Relaxed)
+}
+
+/// 设置 `--yes`: 确认提示不再等待输入
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// 当前是否自动确认
+pub fn is_assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+}
+
+/// 检测终端编码是否为 UTF-8
+fn terminal_is_utf8() -> bool {
+    if cfg!(windows) {
+        // 旧版控制台默认使用 GBK 等代码页，仅在 Windows Terminal / VS Code 等环境下认为支持
+        return std::env::var_os("WT_SESSION").is_some()
+            || std::env::var_os("TERM_PROGRAM").is_some();
+    }
+
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))
+        .map(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+        .unwrap_or(false)
+}
+
+/// 判断字符是否为 emoji / 图形符号
+fn is_emoji_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // emoji、国旗等
+            | 0x2600..=0x27BF // 杂项符号与装饰符号 (✨ ❌ ✔ ⚠)
+            | 0x2B00..=0x2BFF // 箭头 (⬅)
+            | 0x2190..=0x21FF
+            | 0x2300..=0x23FF
+            | 0x2139 // ℹ
+            | 0xFE0F // 变体选择符
+            | 0x200D // 零宽连接符
+    )
+}
+
+/// 去除文本中的 emoji，并去掉因此留下的行首空白
+pub fn strip_emoji(text: &str) -> String {
+    let stripped: String = text.chars().filter(|c| !is_emoji_char(*c)).collect();
+    stripped.trim_start().to_string()
+}
+
+/// 纯文本模式下去除 emoji，否则原样返回
+pub fn plain_text(text: &str) -> Cow<'_, str> {
+    if is_plain_mode() {
+        Cow::Owned(strip_emoji(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// 消息前缀：正常模式使用 emoji，纯文本模式使用 ASCII 标记
+fn icon(emoji: &'static str, plain: &'static str) -> &'static str {
+    if is_plain_mode() {
+        plain
+    } else {
+        emoji
+    }
+}
+
+/// 按字符数截断文本，超出时以 `...` 结尾
+///
+/// 按字符而非字节截取，不会在多字节字符 (中文、emoji 等) 中间切断
+pub fn truncate_chars(text: &str, max_chars: usize) -> Cow<'_, str> {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => Cow::Owned(format!("{}...", &text[..end])),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// 密钥脱敏时保留的开头与结尾字符数
+const REDACT_HEAD: usize = 6;
+const REDACT_TAIL: usize = 4;
+
+/// 显示密钥时脱敏: 保留前 6 位和后 4 位，中间用 `****` 代替
+///
+/// 按字符而非字节截取，短密钥整体隐藏
+pub fn redact(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= REDACT_HEAD + REDACT_TAIL + 4 {
+        return "****".to_string();
+    }
+
+    let head: String = chars[..REDACT_HEAD].iter().collect();
+    let tail: String = chars[chars.len() - REDACT_TAIL..].iter().collect();
+    format!("{}****{}", head, tail)
+}
+
+/// 迷你趋势图: 每个值映射为一个高度字符，`None` (检测失败) 显示为 `×`
+///
+/// 纯文本模式下使用 ASCII 字符
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    const PLAIN_BARS: [char; 8] = ['_', '.', ',', '-', '=', '+', '*', '#'];
+    let (bars, missing) = if is_plain_mode() { (PLAIN_BARS, 'x') } else { (BARS, '×') };
+
+    let present = values.iter().flatten();
+    let min = present.clone().copied().fold(f64::INFINITY, f64::min);
+    let max = present.copied().fold(f64::NEG_INFINITY, f64::max);
+
+    values
+        .iter()
+        .map(|value| match value {
+            Some(v) if max > min => {
+                let level = ((v - min) / (max - min) * (bars.len() - 1) as f64).round() as usize;
+                bars[level.min(bars.len() - 1)]
+            }
+            Some(_) => bars[bars.len() / 2],
+            None => missing,
+        })
+        .collect()
+}
+
+/// 分隔线
+pub fn separator(width: usize) -> String {
+    if is_plain_mode() {
+        "=".repeat(width)
+    } else {
+        "═".repeat(width)
+    }
+}
+
+/// 交互提示使用的主题 (纯文本模式下不使用彩色符号)
+pub fn prompt_theme() -> Box<dyn PromptTheme> {
+    if is_plain_mode() {
+        Box::new(SimpleTheme)
+    } else {
+        Box::new(ColorfulTheme::default())
+    }
+}
+
+/// 输出命令的最终结果 (如切换后的配置名)
+///
+/// 仅在安静模式下输出一行纯文本，便于脚本读取；正常模式下结果已由成功提示展示
+pub fn show_result(message: &str) {
+    if is_quiet_mode() {
+        println!("{}", message);
+    }
+}
 
 /// 显示成功消息
 pub fn show_success(message: &str) {
-    println!("{} {}", style("✨").green(), style(message).green());
+    if is_quiet_mode() {
+        return;
+    }
+    let color = current_theme().success_style();
+    println!(
+        "{} {}",
+        color.apply_to(icon("✨", "[OK]")),
+        color.apply_to(plain_text(message))
+    );
 }
 
 /// 显示警告消息
 pub fn show_warning(message: &str) {
-    println!("{} {}", style("⚠️ ").yellow(), style(message).yellow());
+    if is_quiet_mode() {
+        let color = current_theme().warning_style().for_stderr();
+        eprintln!("{} {}", color.apply_to(icon("⚠️ ", "[WARN]")), color.apply_to(plain_text(message)));
+        return;
+    }
+    let color = current_theme().warning_style();
+    println!(
+        "{} {}",
+        color.apply_to(icon("⚠️ ", "[WARN]")),
+        color.apply_to(plain_text(message))
+    );
 }
 
 /// 显示错误消息
 pub fn show_error(message: &str) {
-    println!("{} {}", style("❌").red(), style(message).red());
+    if is_quiet_mode() {
+        report_error(message);
+        return;
+    }
+    let color = current_theme().error_style();
+    println!(
+        "{} {}",
+        color.apply_to(icon("❌", "[ERROR]")),
+        color.apply_to(plain_text(message))
+    );
+}
+
+/// 输出错误消息到标准错误（命令失败退出前使用，不干扰标准输出的内容）
+pub fn report_error(message: &str) {
+    let color = current_theme().error_style().for_stderr();
+    eprintln!(
+        "{} {}",
+        color.apply_to(icon("❌", "[ERROR]")),
+        color.apply_to(plain_text(message))
+    );
+}
+
+/// 在标准错误输出用户取消的提示 (取代错误信息)
+pub fn report_cancelled() {
+    // 提示中途取消时光标可能仍处于隐藏状态
+    let _ = Term::stderr().show_cursor();
+    eprintln!("{}", style(plain_text("已取消")).dim().for_stderr());
+}
+
+/// 菜单中的操作失败时显示错误并等待返回；用户取消时只提示已取消
+pub fn show_action_error(context: &str, e: &crate::error::CliError) {
+    if e.is_cancelled() {
+        show_info("已取消");
+        return;
+    }
+    show_error(&format!("{context}: {e}"));
+    wait_for_back();
 }
 
 /// 显示信息消息
 pub fn show_info(message: &str) {
-    println!("{} {}", style("ℹ️ ").blue(), style(message).blue());
+    if is_quiet_mode() {
+        return;
+    }
+    let color = current_theme().info_style();
+    println!(
+        "{} {}",
+        color.apply_to(icon("ℹ️ ", "[INFO]")),
+        color.apply_to(plain_text(message))
+    );
 }
 
 /// 显示启动 Banner
@@ -56,18 +315,22 @@ pub enum MainMenuChoice {
     GeminiApi,
     OpenCodeApi,
     Backup,
+    Prompt,
+    Theme,
     Exit,
 }
 
 impl fmt::Display for MainMenuChoice {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            MainMenuChoice::Api => write!(f, "📡 ClaudeCode"),
-            MainMenuChoice::CodexApi => write!(f, "💻 Codex"),
-            MainMenuChoice::GeminiApi => write!(f, "🌟 Gemini-cli"),
-            MainMenuChoice::OpenCodeApi => write!(f, "🚀 OpenCode"),
-            MainMenuChoice::Backup => write!(f, "🔄 Backup"),
-            MainMenuChoice::Exit => write!(f, "🚪 Exit"),
+            MainMenuChoice::Api => f.write_str(&plain_text("📡 ClaudeCode")),
+            MainMenuChoice::CodexApi => f.write_str(&plain_text("💻 Codex")),
+            MainMenuChoice::GeminiApi => f.write_str(&plain_text("🌟 Gemini-cli")),
+            MainMenuChoice::OpenCodeApi => f.write_str(&plain_text("🚀 OpenCode")),
+            MainMenuChoice::Backup => f.write_str(&plain_text("🔄 Backup")),
+            MainMenuChoice::Prompt => f.write_str(&plain_text("📝 Prompt")),
+            MainMenuChoice::Theme => f.write_str(&plain_text("🎨 Theme")),
+            MainMenuChoice::Exit => f.write_str(&plain_text("🚪 Exit")),
         }
     }
 }
@@ -80,10 +343,12 @@ pub fn show_main_menu() -> crate::error::Result<MainMenuChoice> {
         MainMenuChoice::CodexApi,
         MainMenuChoice::GeminiApi,
         MainMenuChoice::Backup,
+        MainMenuChoice::Prompt,
+        MainMenuChoice::Theme,
         MainMenuChoice::Exit,
     ];
 
-    let selection = Select::with_theme(&ColorfulTheme::default())
+    let selection = Select::with_theme(&*prompt_theme())
         .with_prompt("请选择功能模块")
         .items(&choices)
         .default(0)
@@ -101,26 +366,36 @@ pub enum ApiMenuChoice {
     Add,
     Edit,
     Delete,
+    CopyKey,
+    ToggleVerify,
+    ToggleRecordOnly,
     Back,
 }
 
 impl fmt::Display for ApiMenuChoice {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ApiMenuChoice::Switch => write!(f, "🔄 切换配置 - 切换API配置"),
-            ApiMenuChoice::List => write!(f, "📋 查看配置 - 列出所有配置"),
-            ApiMenuChoice::Add => write!(f, "➕ 添加配置 - 添加新的API配置"),
-            ApiMenuChoice::Edit => write!(f, "📝 编辑配置 - 修改现有配置"),
-            ApiMenuChoice::Delete => write!(f, "❌ 删除配置 - 删除API配置"),
-            ApiMenuChoice::Back => write!(f, "⬅️  返回上一级菜单"),
+            ApiMenuChoice::Switch => f.write_str(&plain_text("🔄 切换配置 - 切换API配置")),
+            ApiMenuChoice::List => f.write_str(&plain_text("📋 查看配置 - 列出所有配置")),
+            ApiMenuChoice::Add => f.write_str(&plain_text("➕ 添加配置 - 添加新的API配置")),
+            ApiMenuChoice::Edit => f.write_str(&plain_text("📝 编辑配置 - 修改现有配置")),
+            ApiMenuChoice::Delete => f.write_str(&plain_text("❌ 删除配置 - 删除API配置")),
+            ApiMenuChoice::CopyKey => f.write_str(&plain_text("🔑 复制当前密钥 - 将当前激活的 Token/Key 复制到剪贴板")),
+            ApiMenuChoice::ToggleVerify => {
+                f.write_str(&plain_text("🩺 切换前验证 - 开启/关闭切换前的 Token 验证"))
+            }
+            ApiMenuChoice::ToggleRecordOnly => {
+                f.write_str(&plain_text("🔖 仅记录，不写入 - 切换时只更新激活记录，不写入工具配置文件"))
+            }
+            ApiMenuChoice::Back => f.write_str(&plain_text("⬅️  返回上一级菜单")),
         }
     }
 }
 
 /// 显示 API 菜单 (ClaudeCode/Codex/Gemini 通用菜单，不包含 OpenCode 专属功能)
 pub fn show_api_menu(title: &str) -> crate::error::Result<ApiMenuChoice> {
-    println!("\n{}", style(title).cyan().bold());
-    println!("{}", style("═".repeat(40)).dim());
+    println!("\n{}", current_theme().accent_style().bold().apply_to(title));
+    println!("{}", style(separator(40)).dim());
 
     let choices = [
         ApiMenuChoice::Switch,
@@ -128,10 +403,13 @@ pub fn show_api_menu(title: &str) -> crate::error::Result<ApiMenuChoice> {
         ApiMenuChoice::Add,
         ApiMenuChoice::Edit,
         ApiMenuChoice::Delete,
+        ApiMenuChoice::CopyKey,
+        ApiMenuChoice::ToggleVerify,
+        ApiMenuChoice::ToggleRecordOnly,
         ApiMenuChoice::Back,
     ];
 
-    let selection = Select::with_theme(&ColorfulTheme::default())
+    let selection = Select::with_theme(&*prompt_theme())
         .with_prompt("请选择操作")
         .items(&choices)
         .default(0)
@@ -141,70 +419,473 @@ pub fn show_api_menu(title: &str) -> crate::error::Result<ApiMenuChoice> {
     Ok(choices[selection])
 }
 
+/// 列表一次最多显示的条目数，超出部分滚动分页
+pub const SELECT_PAGE_SIZE: usize = 10;
+
+/// 条目数超过该值时改用可输入关键字筛选的模糊选择
+pub const FUZZY_SELECT_THRESHOLD: usize = 15;
+
+/// 从列表中选择一项（站点/Token/模型等可能很长的列表）
+///
+/// 列表按 [`SELECT_PAGE_SIZE`] 分页显示，条目较多时切换为模糊搜索
+pub fn select_item<T: ToString>(
+    prompt: &str,
+    items: &[T],
+    default: usize,
+) -> dialoguer::Result<usize> {
+    let theme = prompt_theme();
+    if items.len() > FUZZY_SELECT_THRESHOLD {
+        FuzzySelect::with_theme(&*theme)
+            .with_prompt(format!("{} (输入关键字筛选)", prompt))
+            .items(items)
+            .default(default)
+            .max_length(SELECT_PAGE_SIZE)
+            .interact()
+    } else {
+        Select::with_theme(&*theme)
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .max_length(SELECT_PAGE_SIZE)
+            .interact()
+    }
+}
+
+/// 站点数量超过该值时，选择前先输入关键字筛选
+pub const SITE_SEARCH_THRESHOLD: usize = 5;
+
+/// 按名称排序返回集合的键，保证列表与选择项在每次运行时顺序一致
+pub fn sorted_keys<T>(map: &HashMap<String, T>) -> Vec<&String> {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    keys
+}
+
+/// 以名称为键的集合，`names` 决定选择列表中的显示顺序
+pub trait NamedItems {
+    type Item;
+
+    /// 按显示顺序返回全部名称
+    fn names(&self) -> Vec<&String>;
+
+    /// 按名称获取条目
+    fn item(&self, name: &str) -> Option<&Self::Item>;
+}
+
+/// HashMap 本身无序，按名称排序显示
+impl<T> NamedItems for HashMap<String, T> {
+    type Item = T;
+
+    fn names(&self) -> Vec<&String> {
+        sorted_keys(self)
+    }
+
+    fn item(&self, name: &str) -> Option<&T> {
+        self.get(name)
+    }
+}
+
+/// IndexMap 按插入（或用户调整后的）顺序显示
+impl<T> NamedItems for IndexMap<String, T> {
+    type Item = T;
+
+    fn names(&self) -> Vec<&String> {
+        self.keys().collect()
+    }
+
+    fn item(&self, name: &str) -> Option<&T> {
+        self.get(name)
+    }
+}
+
+/// 按关键字筛选站点（名称/URL/描述/标签，不区分大小写），关键字为空时返回全部
+pub fn filter_sites<'a, M>(sites: &'a M, keyword: &str) -> Vec<&'a String>
+where
+    M: NamedItems,
+    M::Item: SiteSummary,
+{
+    let keyword = keyword.trim().to_lowercase();
+    sites
+        .names()
+        .into_iter()
+        .filter(|name| {
+            let Some(site) = sites.item(name) else {
+                return false;
+            };
+            keyword.is_empty()
+                || name.to_lowercase().contains(&keyword)
+                || site.url().to_lowercase().contains(&keyword)
+                || site
+                    .description()
+                    .is_some_and(|d| d.to_lowercase().contains(&keyword))
+                || site.tags().iter().any(|t| t.to_lowercase().contains(&keyword))
+        })
+        .collect()
+}
+
+/// 带有指定标签 (忽略大小写) 的站点，按显示顺序返回
+pub fn sites_with_tag<'a, M>(sites: &'a M, tag: &str) -> IndexMap<String, &'a M::Item>
+where
+    M: NamedItems,
+    M::Item: SiteSummary,
+{
+    sites
+        .names()
+        .into_iter()
+        .filter_map(|name| sites.item(name).map(|site| (name, site)))
+        .filter(|(_, site)| site.has_tag(tag))
+        .map(|(name, site)| (name.clone(), site))
+        .collect()
+}
+
+/// 站点标签的显示文字 (如 ` [fast, hk]`)，没有标签时为空
+pub fn tags_label(tags: &[String]) -> String {
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", tags.join(", "))
+    }
+}
+
+/// 从以名称为键的集合中选择一项，返回选中的名称
+///
+/// `label` 生成每一项的显示文字；集合为空时返回 [`CliError::NotFound`](crate::error::CliError::NotFound)
+pub fn select_named<M: NamedItems>(
+    prompt: &str,
+    items: &M,
+    label: impl Fn(&str, &M::Item) -> String,
+) -> crate::error::Result<String> {
+    pick_named(prompt, items, label, |labels| {
+        select_item(prompt, labels, 0).map_err(|_| crate::error::CliError::UserCancelled)
+    })
+}
+
+/// [`select_named`] 的实现，`pick` 负责从显示文字列表中选出下标
+fn pick_named<M: NamedItems>(
+    prompt: &str,
+    items: &M,
+    label: impl Fn(&str, &M::Item) -> String,
+    pick: impl FnOnce(&[String]) -> crate::error::Result<usize>,
+) -> crate::error::Result<String> {
+    let names: Vec<(&String, &M::Item)> = items
+        .names()
+        .into_iter()
+        .filter_map(|name| items.item(name).map(|item| (name, item)))
+        .collect();
+    if names.is_empty() {
+        return Err(crate::error::CliError::NotFound(format!("{}: 没有可选择的条目", prompt)));
+    }
+
+    let labels: Vec<String> = names.iter().map(|(name, item)| label(name, item)).collect();
+
+    let idx = pick(&labels)?;
+    names
+        .get(idx)
+        .map(|(name, _)| (*name).clone())
+        .ok_or(crate::error::CliError::UserCancelled)
+}
+
+/// 选择站点，返回站点名称
+///
+/// 站点较多时先输入关键字筛选，再从匹配的站点中选择
+pub fn select_site<M>(prompt: &str, sites: &M) -> crate::error::Result<String>
+where
+    M: NamedItems,
+    M::Item: SiteSummary,
+{
+    let total = sites.names().len();
+    let matched: IndexMap<String, &M::Item> = loop {
+        let keyword = if total > SITE_SEARCH_THRESHOLD {
+            Input::<String>::with_theme(&*prompt_theme())
+                .with_prompt("搜索站点（名称/URL/描述，留空显示全部）")
+                .allow_empty(true)
+                .interact_text()
+                .map_err(|_| crate::error::CliError::UserCancelled)?
+        } else {
+            String::new()
+        };
+
+        let names = filter_sites(sites, &keyword);
+        if !names.is_empty() || total == 0 {
+            break names
+                .into_iter()
+                .filter_map(|name| sites.item(name).map(|site| (name.clone(), site)))
+                .collect();
+        }
+        show_warning(&format!("没有匹配 '{}' 的站点", keyword.trim()));
+    };
+
+    select_named(prompt, &matched, |name, site| format!("🌐 {} ({}){}", name, site.url(), tags_label(site.tags())))
+}
+
+/// 选择站点，指定标签时只在带有该标签的站点中选择
+///
+/// 只有一个站点带有该标签时直接使用；没有站点带有该标签时返回 [`CliError::NotFound`](crate::error::CliError::NotFound)
+pub fn select_site_with_tag<M>(prompt: &str, sites: &M, tag: Option<&str>) -> crate::error::Result<String>
+where
+    M: NamedItems,
+    M::Item: SiteSummary,
+{
+    let Some(tag) = tag else {
+        return select_site(prompt, sites);
+    };
+
+    let tagged = sites_with_tag(sites, tag);
+    match tagged.len() {
+        0 => Err(crate::error::CliError::NotFound(format!("没有带标签 '{}' 的站点", tag.trim()))),
+        1 => {
+            let name = tagged.keys().next().cloned().unwrap_or_default();
+            show_info(&format!("标签 '{}' 只匹配站点: {}", tag.trim(), name));
+            Ok(name)
+        }
+        _ => select_site(prompt, &tagged),
+    }
+}
+
+/// 当前激活配置的单行摘要，如 `codex: openai-relay / 主账号 / gpt-4o` (不含密钥内容，未设置模型时省略)
+pub fn current_line(tool: &str, site: &str, key_name: &str, model: Option<&str>) -> String {
+    match model.filter(|m| !m.is_empty()) {
+        Some(model) => format!("{}: {} / {} / {}", tool, site, key_name, model),
+        None => format!("{}: {} / {}", tool, site, key_name),
+    }
+}
+
+/// 其他站点已使用相同的 Base URL 时提示（不阻止添加或保存）
+pub fn warn_duplicate_base_url(base_url: &str, sites: &[String]) {
+    if sites.is_empty() {
+        return;
+    }
+    let names: Vec<String> = sites.iter().map(|name| format!("'{}'", name)).collect();
+    show_warning(&format!(
+        "Base URL {} 已被站点 {} 使用，请确认是否重复添加了同一站点",
+        base_url.trim(),
+        names.join(", ")
+    ));
+}
+
+/// 输入站点标签（逗号分隔，留空则不设置），默认值为当前标签
+pub fn input_tags(current: &[String]) -> crate::error::Result<Vec<String>> {
+    let input: String = Input::with_theme(&*prompt_theme())
+        .with_prompt("标签（逗号分隔，如 fast,hk，留空则不设置）")
+        .with_initial_text(current.join(","))
+        .allow_empty(true)
+        .interact_text()
+        .map_err(|_| crate::error::CliError::UserCancelled)?;
+
+    Ok(crate::config::parse_site_tags(&input))
+}
+
+/// 选择站点下的 Token / API Key，返回选中的名称
+///
+/// 每项附带使用次数与最近使用日期；存在使用记录时可改为按最近使用排序
+pub fn select_key(
+    prompt: &str,
+    keys: &HashMap<String, String>,
+    usage: &HashMap<String, KeyUsage>,
+) -> crate::error::Result<String> {
+    let mut names = sorted_keys(keys);
+    if names.len() > 1 && !usage.is_empty() {
+        let orders = ["🔤 按名称排序", "🕒 按最近使用排序"];
+        if select_item("排序方式", &orders, 1).map_err(|_| crate::error::CliError::UserCancelled)? == 1 {
+            sort_by_recent_use(&mut names, usage);
+        }
+    }
+
+    let ordered: IndexMap<String, &String> = names.into_iter().map(|name| (name.clone(), &keys[name])).collect();
+    select_named(prompt, &ordered, |name, key| match usage.get(name) {
+        Some(record) => format!("🔑 {} ({}) - {}", name, redact(key), record.summary()),
+        None => format!("🔑 {} ({})", name, redact(key)),
+    })
+}
+
+/// 按最近使用时间倒序排列，从未使用的排在最后（同一时间保持原顺序）
+pub fn sort_by_recent_use(names: &mut [&String], usage: &HashMap<String, KeyUsage>) {
+    let last_used = |name: &str| usage.get(name).and_then(|record| record.last_used.clone());
+    names.sort_by_key(|name| std::cmp::Reverse(last_used(name)));
+}
+
+/// 在列表中移动一项，返回移动后的新下标；越界移动保持不变
+pub fn move_entry<T>(items: &mut Vec<T>, idx: usize, direction: MoveDirection) -> usize {
+    if idx >= items.len() {
+        return idx;
+    }
+
+    let target = match direction {
+        MoveDirection::Up => idx.saturating_sub(1),
+        MoveDirection::Down => (idx + 1).min(items.len() - 1),
+        MoveDirection::Top => 0,
+        MoveDirection::Bottom => items.len() - 1,
+    };
+
+    let item = items.remove(idx);
+    items.insert(target, item);
+    target
+}
+
+/// 调整顺序时的移动方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveDirection {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
+impl fmt::Display for MoveDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveDirection::Up => f.write_str(&plain_text("⬆️  上移")),
+            MoveDirection::Down => f.write_str(&plain_text("⬇️  下移")),
+            MoveDirection::Top => f.write_str(&plain_text("⏫ 移到顶部")),
+            MoveDirection::Bottom => f.write_str(&plain_text("⏬ 移到底部")),
+        }
+    }
+}
+
+/// 交互式调整名称顺序，返回调整后的顺序；未做任何调整时返回 None
+pub fn reorder_names(title: &str, names: Vec<String>) -> crate::error::Result<Option<Vec<String>>> {
+    let original = names.clone();
+    let mut names = names;
+    let mut cursor = 0;
+
+    loop {
+        println!("\n{}", current_theme().accent_style().bold().apply_to(title));
+
+        let mut items: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| format!("{:>2}. {}", i + 1, name))
+            .collect();
+        items.push(plain_text("✅ 完成").into_owned());
+
+        let idx = Select::with_theme(&*prompt_theme())
+            .with_prompt("选择要移动的条目")
+            .items(&items)
+            .default(cursor.min(items.len() - 1))
+            .max_length(SELECT_PAGE_SIZE)
+            .interact()
+            .map_err(|_| crate::error::CliError::UserCancelled)?;
+
+        if idx >= names.len() {
+            break;
+        }
+
+        let directions = [MoveDirection::Up, MoveDirection::Down, MoveDirection::Top, MoveDirection::Bottom];
+        let direction = Select::with_theme(&*prompt_theme())
+            .with_prompt(format!("移动 '{}'", names[idx]))
+            .items(&directions)
+            .default(0)
+            .interact()
+            .map_err(|_| crate::error::CliError::UserCancelled)?;
+
+        cursor = move_entry(&mut names, idx, directions[direction]);
+    }
+
+    Ok((names != original).then_some(names))
+}
+
 /// 确认操作
+///
+/// 使用 `--yes` 时不再提示，直接采用默认值
 pub fn confirm(message: &str, default: bool) -> crate::error::Result<bool> {
-    Confirm::with_theme(&ColorfulTheme::default())
+    if is_assume_yes() {
+        show_info(&format!("{}: {} (--yes)", message, if default { "是" } else { "否" }));
+        return Ok(default);
+    }
+
+    Confirm::with_theme(&*prompt_theme())
         .with_prompt(message)
         .default(default)
         .interact()
         .map_err(|_| crate::error::CliError::UserCancelled)
 }
 
-/// 等待返回确认
-#[allow(dead_code)]
-pub fn wait_for_back_confirm(message: &str) -> crate::error::Result<()> {
-    let items = vec!["⬅️  返回上一级菜单"];
-    Select::with_theme(&ColorfulTheme::default())
-        .with_prompt(message)
-        .items(&items)
-        .default(0)
-        .interact()
-        .map_err(|_| crate::error::CliError::UserCancelled)?;
-    Ok(())
+/// 确认删除等危险操作，默认选择「否」
+///
+/// 使用 `--yes` 时自动确认，并始终输出一条警告 (安静模式下写到标准错误) 记录执行的操作
+pub fn confirm_destructive(message: &str) -> crate::error::Result<bool> {
+    if is_assume_yes() {
+        show_warning(&format!("{}: 是 (--yes 自动确认)", message));
+        return Ok(true);
+    }
+
+    confirm(message, false)
+}
+
+/// 标准输入与标准错误都是终端时才能交互提问 (dialoguer 在标准错误上绘制提示)
+pub fn is_interactive_terminal() -> bool {
+    use std::io::IsTerminal;
+
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// 等待用户按回车返回上一级菜单
+///
+/// 标准输入不是终端（如管道输入）时直接返回；读到 EOF 或读取失败时同样直接返回，不会反复等待
+pub fn wait_for_back() {
+    use std::io::{BufRead, IsTerminal};
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return;
+    }
+
+    println!();
+    println!("{}", style("按回车键返回...").dim());
+    let _ = stdin.lock().read_line(&mut String::new());
 }
 
 /// OpenCode 菜单选项 (去除 Switch 和 List)
 #[derive(Debug, Clone, Copy)]
 pub enum OpenCodeMenuChoice {
     Apply,
+    Preview,
     Add,
     Edit,
     Delete,
+    Reorder,
     DetectSite,
     DetectModel,
+    DetectionHistory,
     Back,
 }
 
 impl fmt::Display for OpenCodeMenuChoice {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            OpenCodeMenuChoice::Apply => write!(f, "🚀 应用配置 - 应用到项目或全局"),
-            OpenCodeMenuChoice::Add => write!(f, "➕ 添加配置 - 添加新的API配置"),
-            OpenCodeMenuChoice::Edit => write!(f, "📝 编辑配置 - 修改现有配置"),
-            OpenCodeMenuChoice::Delete => write!(f, "❌ 删除配置 - 删除API配置"),
-            OpenCodeMenuChoice::DetectSite => write!(f, "🌐 站点检测 - 检测站点并获取模型列表"),
-            OpenCodeMenuChoice::DetectModel => write!(f, "🤖 模型检测 - 测试模型性能和可用性"),
-            OpenCodeMenuChoice::Back => write!(f, "⬅️  返回上一级菜单"),
+            OpenCodeMenuChoice::Apply => f.write_str(&plain_text("🚀 应用配置 - 应用到项目或全局")),
+            OpenCodeMenuChoice::Preview => f.write_str(&plain_text("👀 预览配置 - 查看将写入的 opencode.json")),
+            OpenCodeMenuChoice::Add => f.write_str(&plain_text("➕ 添加配置 - 添加新的API配置")),
+            OpenCodeMenuChoice::Edit => f.write_str(&plain_text("📝 编辑配置 - 修改现有配置")),
+            OpenCodeMenuChoice::Delete => f.write_str(&plain_text("❌ 删除配置 - 删除API配置")),
+            OpenCodeMenuChoice::Reorder => f.write_str(&plain_text("🔃 调整顺序 - 调整 Provider 显示顺序")),
+            OpenCodeMenuChoice::DetectSite => f.write_str(&plain_text("🌐 站点检测 - 检测站点并获取模型列表")),
+            OpenCodeMenuChoice::DetectModel => f.write_str(&plain_text("🤖 模型检测 - 测试模型性能和可用性")),
+            OpenCodeMenuChoice::DetectionHistory => f.write_str(&plain_text("📈 检测历史 - 查看延迟与可用性趋势")),
+            OpenCodeMenuChoice::Back => f.write_str(&plain_text("⬅️  返回上一级菜单")),
         }
     }
 }
 
 /// 显示 OpenCode 专用菜单
 pub fn show_opencode_menu(title: &str) -> crate::error::Result<OpenCodeMenuChoice> {
-    println!("\n{}", style(title).cyan().bold());
-    println!("{}", style("═".repeat(40)).dim());
+    println!("\n{}", current_theme().accent_style().bold().apply_to(title));
+    println!("{}", style(separator(40)).dim());
 
     let choices = [
         OpenCodeMenuChoice::Apply,
+        OpenCodeMenuChoice::Preview,
         OpenCodeMenuChoice::Add,
         OpenCodeMenuChoice::Edit,
         OpenCodeMenuChoice::Delete,
+        OpenCodeMenuChoice::Reorder,
         OpenCodeMenuChoice::DetectSite,
         OpenCodeMenuChoice::DetectModel,
+        OpenCodeMenuChoice::DetectionHistory,
         OpenCodeMenuChoice::Back,
     ];
 
-    let selection = Select::with_theme(&ColorfulTheme::default())
+    let selection = Select::with_theme(&*prompt_theme())
         .with_prompt("请选择操作")
         .items(&choices)
         .default(0)
@@ -214,6 +895,112 @@ pub fn show_opencode_menu(title: &str) -> crate::error::Result<OpenCodeMenuChoic
     Ok(choices[selection])
 }
 
+/// 选择颜色主题预设并保存到 ~/.ca-switch/theme.json
+pub fn select_theme_preset() -> crate::error::Result<()> {
+    let current = current_theme();
+    let default_idx = THEME_PRESETS
+        .iter()
+        .position(|name| *name == current.name)
+        .unwrap_or(0);
+
+    let selection = Select::with_theme(&*prompt_theme())
+        .with_prompt("选择颜色主题")
+        .items(THEME_PRESETS)
+        .default(default_idx)
+        .interact()
+        .map_err(|_| crate::error::CliError::UserCancelled)?;
+
+    let theme = Theme::preset(THEME_PRESETS[selection]).unwrap_or_default();
+    let name = theme.name.clone();
+    match set_theme(theme) {
+        Ok(()) => show_success(&format!("已切换到主题: {}", name)),
+        Err(e) => show_error(&e.to_string()),
+    }
+
+    Ok(())
+}
+
+/// 复制文本到系统剪贴板（调用系统自带的剪贴板工具）
+pub fn copy_to_clipboard(content: &str) -> crate::error::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+
+    for (program, args) in candidates {
+        let Ok(mut child) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(content.as_bytes())
+                .map_err(|e| crate::error::CliError::Custom(format!("复制到剪贴板失败: {e}")))?;
+        }
+
+        let status = child
+            .wait()
+            .map_err(|e| crate::error::CliError::Custom(format!("复制到剪贴板失败: {e}")))?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+
+    Err(crate::error::CliError::NotFound(
+        "未找到可用的剪贴板工具 (pbcopy / clip / wl-copy / xclip / xsel)".to_string(),
+    ))
+}
+
+/// 显示切换后写入的文件路径
+pub fn show_written_paths(paths: &[PathBuf]) {
+    if paths.is_empty() || is_quiet_mode() {
+        return;
+    }
+
+    println!("{}", style(plain_text("📝 已写入文件:")).white());
+    for path in paths {
+        println!("  {}", style(path.display()).cyan());
+    }
+    println!();
+}
+
+/// 显示写入的文件路径，并询问是否将主配置文件路径复制到剪贴板 (仅用于交互式菜单)
+///
+/// 非终端或 `--yes` 时不询问；提示失败视为不复制，文件已写入，不应报告为取消
+pub fn show_written_paths_with_copy(paths: &[PathBuf]) {
+    show_written_paths(paths);
+
+    let Some(primary) = paths.first() else {
+        return;
+    };
+    if is_quiet_mode() || is_assume_yes() || !is_interactive_terminal() {
+        return;
+    }
+
+    if confirm("是否复制主配置文件路径到剪贴板", false).unwrap_or(false) {
+        match copy_to_clipboard(&primary.display().to_string()) {
+            Ok(()) => show_success("路径已复制到剪贴板"),
+            Err(e) => show_warning(&e.to_string()),
+        }
+    }
+}
+
 /// 获取地区图标
 #[allow(dead_code)]
 pub fn get_region_icon(region_name: &str) -> &'static str {
@@ -232,3 +1019,168 @@ pub fn get_region_icon(region_name: &str) -> &'static str {
         "🌍"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_line() {
+        assert_eq!(current_line("codex", "openai-relay", "主账号", Some("gpt-4o")), "codex: openai-relay / 主账号 / gpt-4o");
+        assert_eq!(current_line("claude", "relay", "work", None), "claude: relay / work");
+        assert_eq!(current_line("claude", "relay", "work", Some("")), "claude: relay / work");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[Some(1.0), Some(5.0), None, Some(3.0)]), "▁█×▅");
+        assert_eq!(sparkline(&[Some(2.0), Some(2.0)]), "▅▅");
+        assert_eq!(sparkline(&[None]), "×");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        assert_eq!(strip_emoji("📡 ClaudeCode"), "ClaudeCode");
+        assert_eq!(strip_emoji("⬅️  返回上一级菜单"), "返回上一级菜单");
+        assert_eq!(strip_emoji("ℹ️ 已切换到站点 ✨"), "已切换到站点 ");
+        assert_eq!(strip_emoji("无 emoji 的文本"), "无 emoji 的文本");
+    }
+
+    #[test]
+    fn test_sort_by_recent_use() {
+        let names = ["alpha".to_string(), "beta".to_string(), "gamma".to_string()];
+        let mut usage = HashMap::new();
+        usage.insert(
+            "alpha".to_string(),
+            KeyUsage { count: 5, last_used: Some("2026-01-01T00:00:00+00:00".to_string()) },
+        );
+        usage.insert(
+            "gamma".to_string(),
+            KeyUsage { count: 1, last_used: Some("2026-03-01T00:00:00+00:00".to_string()) },
+        );
+
+        let mut sorted: Vec<&String> = names.iter().collect();
+        sort_by_recent_use(&mut sorted, &usage);
+        assert_eq!(sorted, vec!["gamma", "alpha", "beta"]);
+    }
+
+    #[test]
+    fn test_filter_sites() {
+        use crate::config::ClaudeSite;
+
+        let mut sites = HashMap::new();
+        sites.insert(
+            "relay-a".to_string(),
+            ClaudeSite::new("https://a.example.com".to_string(), Some("主力中转".to_string())),
+        );
+        sites.insert(
+            "official".to_string(),
+            ClaudeSite::new("https://api.anthropic.com".to_string(), None),
+        );
+
+        assert_eq!(filter_sites(&sites, "").len(), 2);
+        assert_eq!(filter_sites(&sites, "  RELAY ")[0], "relay-a");
+        assert_eq!(filter_sites(&sites, "anthropic")[0], "official");
+        assert_eq!(filter_sites(&sites, "中转")[0], "relay-a");
+        assert!(filter_sites(&sites, "missing").is_empty());
+
+        sites.get_mut("official").unwrap().metadata.tags = vec!["Fast".to_string(), "us".to_string()];
+        assert_eq!(filter_sites(&sites, "fast")[0], "official");
+        let tagged = sites_with_tag(&sites, " FAST ");
+        assert_eq!(tagged.keys().collect::<Vec<_>>(), vec!["official"]);
+        assert!(sites_with_tag(&sites, "fas").is_empty());
+        assert_eq!(tags_label(&sites["official"].metadata.tags), " [Fast, us]");
+    }
+
+    fn labels_of(items: &HashMap<String, u32>) -> Vec<String> {
+        let mut labels = Vec::new();
+        let picked = pick_named("选择", items, |name, n| format!("{}={}", name, n), |l| {
+            labels = l.to_vec();
+            Ok(l.len() - 1)
+        });
+        assert!(picked.is_ok());
+        labels
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("sk-short", 20), "sk-short");
+        assert_eq!(truncate_chars("abcdef", 3), "abc...");
+        // 第 20 个字节落在多字节字符中间时不会 panic
+        let key = "sk-密钥密钥密钥密钥密钥密钥密钥";
+        assert!(!key.is_char_boundary(20));
+        assert_eq!(truncate_chars(key, 7), "sk-密钥密钥...");
+        assert_eq!(truncate_chars("🔑🔑🔑", 2), "🔑🔑...");
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact("sk-ant-REDACTED"), "sk-ant****WXYZ");
+        assert_eq!(redact(""), "****");
+        assert_eq!(redact("short-key"), "****");
+        // 多字节字符不会在字符中间截断
+        assert_eq!(redact("密钥密钥密钥-中间部分很长很长-尾部尾部"), "密钥密钥密钥****尾部尾部");
+    }
+
+    #[test]
+    fn test_sorted_keys_stable_order() {
+        let map: HashMap<String, u32> = ["zeta", "alpha", "Beta", "mid"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i as u32))
+            .collect();
+
+        assert_eq!(sorted_keys(&map), ["Beta", "alpha", "mid", "zeta"]);
+        // HashMap 在选择列表中按名称排序，IndexMap 保持插入顺序
+        assert_eq!(map.names(), sorted_keys(&map));
+        let index: IndexMap<String, u32> = ["zeta", "alpha"].iter().map(|n| (n.to_string(), 0)).collect();
+        assert_eq!(index.names(), ["zeta", "alpha"]);
+    }
+
+    #[test]
+    fn test_move_entry() {
+        let mut items = vec!["a", "b", "c", "d"];
+
+        assert_eq!(move_entry(&mut items, 2, MoveDirection::Up), 1);
+        assert_eq!(items, ["a", "c", "b", "d"]);
+
+        assert_eq!(move_entry(&mut items, 0, MoveDirection::Bottom), 3);
+        assert_eq!(items, ["c", "b", "d", "a"]);
+
+        assert_eq!(move_entry(&mut items, 2, MoveDirection::Top), 0);
+        assert_eq!(items, ["d", "c", "b", "a"]);
+
+        // 已在边界时保持不变
+        assert_eq!(move_entry(&mut items, 3, MoveDirection::Down), 3);
+        assert_eq!(move_entry(&mut items, 0, MoveDirection::Up), 0);
+        assert_eq!(items, ["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_pick_named() {
+        // 空集合: 不进入选择，直接返回 NotFound
+        let empty: HashMap<String, u32> = HashMap::new();
+        let result = pick_named("选择站点", &empty, |name, _| name.to_string(), |_| unreachable!());
+        assert!(matches!(result, Err(crate::error::CliError::NotFound(_))));
+
+        // 单个条目
+        let single = HashMap::from([("only".to_string(), 1)]);
+        assert_eq!(labels_of(&single), vec!["only=1"]);
+        let picked = pick_named("选择", &single, |name, _| name.to_string(), |_| Ok(0)).unwrap();
+        assert_eq!(picked, "only");
+
+        // 多个条目: 选中的下标与显示文字一一对应
+        let multi = HashMap::from([("a".to_string(), 1), ("b".to_string(), 2), ("c".to_string(), 3)]);
+        let labels = labels_of(&multi);
+        assert_eq!(labels.len(), 3);
+        for (idx, label) in labels.iter().enumerate() {
+            let picked = pick_named("选择", &multi, |name, n| format!("{}={}", name, n), |_| Ok(idx)).unwrap();
+            assert_eq!(label, &format!("{}={}", picked, multi[&picked]));
+        }
+
+        // 越界下标视为取消
+        let result = pick_named("选择", &multi, |name, _| name.to_string(), |_| Ok(3));
+        assert!(matches!(result, Err(crate::error::CliError::UserCancelled)));
+    }
+}
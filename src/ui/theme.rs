@@ -0,0 +1,143 @@
+// 颜色主题
+// 从 ~/.ca-switch/theme.json 加载 ui::style 使用的颜色，文件不存在时使用默认配色
+
+use crate::error::Result;
+use console::Style;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+/// 内置主题预设名称
+pub const THEME_PRESETS: &[&str] = &["default", "solarized", "mono"];
+
+/// 颜色主题
+///
+/// 每个字段是 console 的点分样式字符串，例如 `"cyan.bold"`、`"33"` (256 色)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    pub info: String,
+    pub success: String,
+    pub warning: String,
+    pub error: String,
+    /// 菜单标题等强调色
+    pub accent: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset("default").unwrap()
+    }
+}
+
+impl Theme {
+    /// 获取内置预设主题
+    pub fn preset(name: &str) -> Option<Self> {
+        let (info, success, warning, error, accent) = match name {
+            "default" => ("blue", "green", "yellow", "red", "cyan"),
+            "solarized" => ("33", "64", "136", "160", "37"),
+            "mono" => ("", "bold", "bold", "bold.underlined", "bold"),
+            _ => return None,
+        };
+
+        Some(Self {
+            name: name.to_string(),
+            info: info.to_string(),
+            success: success.to_string(),
+            warning: warning.to_string(),
+            error: error.to_string(),
+            accent: accent.to_string(),
+        })
+    }
+
+    /// 主题文件路径 (~/.ca-switch/theme.json)
+    pub fn file_path() -> Result<PathBuf> {
+        let home_dir = crate::config::home_dir()?;
+        let config_dir = crate::config::config_dir(&home_dir)?;
+        Ok(config_dir.join("theme.json"))
+    }
+
+    /// 从主题文件加载，文件不存在或无法解析时使用默认主题
+    pub fn load() -> Self {
+        Self::file_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 保存到主题文件
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建配置目录失败: {}", e))?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("序列化主题失败: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("写入主题文件失败: {}", e))?;
+        Ok(())
+    }
+
+    pub fn info_style(&self) -> Style {
+        Style::from_dotted_str(&self.info)
+    }
+
+    pub fn success_style(&self) -> Style {
+        Style::from_dotted_str(&self.success)
+    }
+
+    pub fn warning_style(&self) -> Style {
+        Style::from_dotted_str(&self.warning)
+    }
+
+    pub fn error_style(&self) -> Style {
+        Style::from_dotted_str(&self.error)
+    }
+
+    pub fn accent_style(&self) -> Style {
+        Style::from_dotted_str(&self.accent)
+    }
+}
+
+fn current_theme_lock() -> &'static RwLock<Theme> {
+    static CURRENT_THEME: OnceLock<RwLock<Theme>> = OnceLock::new();
+    CURRENT_THEME.get_or_init(|| RwLock::new(Theme::load()))
+}
+
+/// 当前使用的主题
+pub fn current_theme() -> Theme {
+    current_theme_lock()
+        .read()
+        .map(|theme| theme.clone())
+        .unwrap_or_default()
+}
+
+/// 切换当前主题并保存到主题文件
+pub fn set_theme(theme: Theme) -> Result<()> {
+    theme.save()?;
+    if let Ok(mut current) = current_theme_lock().write() {
+        *current = theme;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_presets_and_partial_file() {
+        for name in THEME_PRESETS {
+            assert_eq!(Theme::preset(name).unwrap().name, *name);
+        }
+        assert!(Theme::preset("unknown").is_none());
+
+        // 主题文件中缺少的字段使用默认配色
+        let theme: Theme = serde_json::from_str(r#"{"name": "custom", "error": "magenta"}"#).unwrap();
+        assert_eq!(theme.error, "magenta");
+        assert_eq!(theme.success, Theme::default().success);
+    }
+}